@@ -308,6 +308,7 @@ class NymBackendManager @Inject constructor(
 				title = context.getString(R.string.bandwidth_alert),
 				description = context.getString(R.string.low_bandwidth) + " ${bandwidthEvent.v1.toMB()} MB",
 			)
+			is BandwidthEvent.DepletionForecast -> Unit
 		}
 	}
 
@@ -53,11 +53,14 @@ fn group_by_country(gateways: Vec<Gateway>, gw_type: GatewayType) -> Vec<Gateway
         .collect()
 }
 
+/// Sort the gateways of each country, favourites first then by performance
 fn sort_by_perf(mut gw_by_countries: Vec<GatewaysByCountry>) -> Vec<GatewaysByCountry> {
     for group in gw_by_countries.iter_mut() {
-        group.gateways.sort_by(|a, b| match a.kind {
-            GatewayType::Wg => a.wg_score.cmp(&b.wg_score).reverse(),
-            _ => a.mx_score.cmp(&b.mx_score).reverse(),
+        group.gateways.sort_by(|a, b| {
+            b.favourite.cmp(&a.favourite).then_with(|| match a.kind {
+                GatewayType::Wg => a.wg_score.cmp(&b.wg_score).reverse(),
+                _ => a.mx_score.cmp(&b.mx_score).reverse(),
+            })
         });
     }
     gw_by_countries
@@ -85,6 +88,8 @@ pub async fn get_gateways(
         });
 
     gateways
+        // gateways blocked in the daemon are never offered
+        .map(|gws| gws.into_iter().filter(|gw| !gw.blocked).collect())
         .map(|gws| group_by_country(gws, node_type))
         .map(sort_by_perf)
         .inspect(|list| {
@@ -2,6 +2,8 @@ use crate::db::{Db, Key};
 use crate::error::ErrorKey;
 use crate::grpc::client::{GrpcClient, NodeConnect, VpndError};
 use crate::grpc::tunnel::TunnelState;
+use crate::locations::{self, Location};
+use crate::tray;
 use crate::{
     error::BackendError,
     events::{AppHandleEventEmitter, ConnectProgressMsg},
@@ -26,6 +28,7 @@ pub async fn connect(
     app: tauri::AppHandle,
     state: State<'_, SharedAppState>,
     grpc: State<'_, GrpcClient>,
+    db: State<'_, Db>,
     entry: NodeConnect,
     exit: NodeConnect,
 ) -> Result<TunnelState, BackendError> {
@@ -45,6 +48,7 @@ pub async fn connect(
 
     app.emit_connecting();
     app.emit_connection_progress(ConnectProgressMsg::Initializing);
+    tray::refresh(&app);
 
     let app_state = state.lock().await;
     let vpn_mode = app_state.vpn_mode.clone();
@@ -74,6 +78,7 @@ pub async fn connect(
 
     let use_netstack_wireguard = false;
 
+    let location = Location::new(vpn_mode, entry.clone(), exit.clone());
     app.emit_connection_progress(ConnectProgressMsg::InitDone);
     match grpc
         .vpn_connect(
@@ -86,13 +91,20 @@ pub async fn connect(
         )
         .await
     {
-        Ok(_) => Ok(TunnelState::Connecting(None)),
+        Ok(_) => {
+            locations::add_recent(&db, location)
+                .inspect_err(|e| warn!("failed to save recent location: {e}"))
+                .ok();
+            tray::refresh(&app);
+            Ok(TunnelState::Connecting(None))
+        }
         Err(vpnd_err) => {
             warn!("grpc vpn_connect: {}", vpnd_err);
             debug!("update connection state [Disconnected]");
             let mut app_state = state.lock().await;
             app_state.tunnel = TunnelState::Disconnected;
             drop(app_state);
+            tray::refresh(&app);
             match vpnd_err {
                 VpndError::Response(ref e) => {
                     app.emit_disconnected(Some(e.clone()));
@@ -130,6 +142,7 @@ pub async fn disconnect(
     debug!("update connection state [Disconnecting]");
     drop(app_state);
     app.emit_disconnecting();
+    tray::refresh(&app);
 
     grpc.vpn_disconnect().await?;
     Ok(TunnelState::Disconnecting(None))
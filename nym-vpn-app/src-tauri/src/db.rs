@@ -35,6 +35,8 @@ pub enum Key {
     WelcomeScreenSeen,
     DesktopNotifications,
     LastNetworkEnv,
+    RecentLocations,
+    FavouriteLocations,
     // some data cache (no semantic difference)
     CacheMxEntryGateways,
     CacheMxExitGateways,
@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::oneshot;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, error, info, instrument, warn};
+
+use crate::commands::account as cmd_account;
+use crate::commands::tunnel as cmd_tunnel;
+use crate::country::Country;
+use crate::db::Db;
+use crate::events::AppHandleEventEmitter;
+use crate::grpc::client::{GrpcClient, NodeConnect, VpndStatus};
+use crate::grpc::gateway::GatewayType;
+use crate::grpc::tunnel::TunnelState;
+use crate::locations::{self, Location};
+use crate::state::app::VpnMode;
+use crate::state::SharedAppState;
+use crate::window::focus_main_window;
+use crate::APP_NAME;
+
+const SCHEME: &str = "nymvpn";
+/// How long a link which started the app waits for the daemon
+const VPND_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+const VPND_WAIT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Action requested by a `nymvpn://` link
+///
+/// - `nymvpn://connect?entry=CH&exit=IS&mode=wg`, each parameter is optional
+///   and defaults to the current selection
+/// - `nymvpn://disconnect`
+/// - `nymvpn://login?mnemonic=…`
+enum Action {
+    Connect {
+        entry: Option<Node>,
+        exit: Option<Node>,
+        mode: Option<VpnMode>,
+    },
+    Disconnect,
+    Login {
+        mnemonic: String,
+    },
+}
+
+/// Entry or exit point given in a link, either a country code or a gateway
+/// identity, as the daemon's `EntryPoint` and `ExitPoint` locations and gateways
+enum Node {
+    Country(Country),
+    Gateway(String),
+}
+
+// never log the mnemonic
+impl fmt::Debug for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Connect { entry, exit, mode } => f
+                .debug_struct("Connect")
+                .field("entry", entry)
+                .field("exit", exit)
+                .field("mode", mode)
+                .finish(),
+            Action::Disconnect => write!(f, "Disconnect"),
+            Action::Login { .. } => write!(f, "Login"),
+        }
+    }
+}
+
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Node::Country(country) => write!(f, "{}", country.code),
+            Node::Gateway(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+impl Node {
+    fn parse(value: &str) -> Result<Self> {
+        if value.len() == 2 && value.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Country::try_new_from_code(&value.to_ascii_uppercase())
+                .map(Node::Country)
+                .ok_or_else(|| anyhow!("unknown country code `{value}`"));
+        }
+        // gateway identities are base58 encoded ed25519 public keys
+        let base58 = |c: char| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l');
+        if !(32..=44).contains(&value.len()) || !value.chars().all(base58) {
+            bail!("`{value}` is neither a country code nor a gateway identity");
+        }
+        Ok(Node::Gateway(value.to_string()))
+    }
+
+    /// Check the node against the gateways known by the daemon
+    async fn resolve(self, grpc: &GrpcClient, gw_type: GatewayType) -> Result<NodeConnect> {
+        let gateways = grpc.gateways(gw_type).await?;
+        match self {
+            Node::Country(country) => {
+                if !gateways.iter().any(|gw| gw.country.code == country.code) {
+                    bail!("No gateway available in {} for this mode", country.name);
+                }
+                Ok(NodeConnect::Country(country))
+            }
+            Node::Gateway(id) => gateways
+                .into_iter()
+                .find(|gw| gw.id == id)
+                .map(NodeConnect::Gateway)
+                .ok_or_else(|| anyhow!("Unknown gateway `{id}` for this mode")),
+        }
+    }
+}
+
+impl Action {
+    fn parse(url: &Url) -> Result<Self> {
+        if url.scheme() != SCHEME {
+            bail!("unsupported scheme `{}`", url.scheme());
+        }
+        // `nymvpn://connect?…` has the action as host, `nymvpn:connect?…` as path
+        let name = url
+            .host_str()
+            .unwrap_or_else(|| url.path())
+            .trim_matches('/');
+        let mut params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let mut take = |key: &str| params.remove(key).filter(|v| !v.is_empty());
+
+        let action = match name {
+            "connect" => {
+                let mode = take("mode")
+                    .map(|mode| match mode.as_str() {
+                        "wg" => Ok(VpnMode::Wg),
+                        "mixnet" => Ok(VpnMode::Mixnet),
+                        _ => Err(anyhow!("unknown mode `{mode}`")),
+                    })
+                    .transpose()?;
+                Action::Connect {
+                    entry: take("entry").as_deref().map(Node::parse).transpose()?,
+                    exit: take("exit").as_deref().map(Node::parse).transpose()?,
+                    mode,
+                }
+            }
+            "disconnect" => Action::Disconnect,
+            "login" => Action::Login {
+                mnemonic: take("mnemonic").ok_or_else(|| anyhow!("missing mnemonic"))?,
+            },
+            _ => bail!("unknown action `{name}`"),
+        };
+        if let Some(key) = params.keys().next() {
+            bail!("unknown parameter `{key}`");
+        }
+        Ok(action)
+    }
+}
+
+/// Register the handler for the links opened while the app runs, and handle
+/// the ones the app has been started with
+#[instrument(skip_all)]
+pub fn setup(app: &AppHandle) -> Result<()> {
+    // the installers register the scheme, but not the AppImage or dev builds
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link()
+        .register_all()
+        .inspect_err(|e| warn!("failed to register deep link schemes: {e}"))
+        .ok();
+
+    let c_app = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            tokio::spawn(handle(c_app.clone(), url, false));
+        }
+    });
+
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            tokio::spawn(handle(app.clone(), url, true));
+        }
+    }
+    Ok(())
+}
+
+#[instrument(skip(app, url))]
+async fn handle(app: AppHandle, url: Url, at_startup: bool) {
+    info!(
+        "opening deep link [{}://{}]",
+        url.scheme(),
+        url.host_str().unwrap_or_default()
+    );
+    let action = match Action::parse(&url) {
+        Ok(action) => action,
+        Err(e) => {
+            warn!("invalid deep link: {e}");
+            message(
+                &app,
+                &format!("Invalid link: {e}"),
+                MessageDialogKind::Error,
+            );
+            return;
+        }
+    };
+    debug!("deep link action {action:?}");
+    focus_main_window(&app);
+
+    if at_startup && !wait_for_daemon(&app).await {
+        warn!("daemon not running, dropping deep link");
+        message(
+            &app,
+            "The link can't be opened, the NymVPN daemon is not running",
+            MessageDialogKind::Error,
+        );
+        return;
+    }
+
+    let result = match action {
+        Action::Connect { entry, exit, mode } => connect(&app, entry, exit, mode).await,
+        Action::Disconnect => disconnect(&app).await,
+        Action::Login { mnemonic } => login(&app, mnemonic).await,
+    };
+    if let Err(e) = result {
+        warn!("failed to handle deep link: {e}");
+        message(&app, &e.to_string(), MessageDialogKind::Error);
+    }
+}
+
+async fn connect(
+    app: &AppHandle,
+    entry: Option<Node>,
+    exit: Option<Node>,
+    mode: Option<VpnMode>,
+) -> Result<()> {
+    let state = app.state::<SharedAppState>();
+    let grpc = app.state::<GrpcClient>();
+    let db = app.state::<Db>();
+
+    let app_state = state.lock().await;
+    if app_state.tunnel != TunnelState::Disconnected {
+        bail!("Disconnect first to connect through the link");
+    }
+    let vpn_mode = mode.unwrap_or_else(|| app_state.vpn_mode.clone());
+    drop(app_state);
+
+    let (entry_type, exit_type) = match vpn_mode {
+        VpnMode::Mixnet => (GatewayType::MxEntry, GatewayType::MxExit),
+        VpnMode::Wg => (GatewayType::Wg, GatewayType::Wg),
+    };
+    let selected = locations::selected(&db, vpn_mode.clone());
+    let entry = match entry {
+        Some(node) => node.resolve(&grpc, entry_type).await?,
+        None => selected
+            .as_ref()
+            .map(|l| l.entry.clone())
+            .ok_or_else(|| anyhow!("The link has no entry location and none is selected"))?,
+    };
+    let exit = match exit {
+        Some(node) => node.resolve(&grpc, exit_type).await?,
+        None => selected
+            .map(|l| l.exit)
+            .ok_or_else(|| anyhow!("The link has no exit location and none is selected"))?,
+    };
+    let location = Location::new(vpn_mode, entry, exit);
+
+    if !confirm(
+        app,
+        &format!("Connect to {}?", location.label()),
+        MessageDialogKind::Info,
+        "Connect",
+    )
+    .await
+    {
+        info!("deep link connect cancelled");
+        return Ok(());
+    }
+
+    locations::select(app, &location).await;
+    cmd_tunnel::connect(
+        app.clone(),
+        app.state(),
+        app.state(),
+        app.state(),
+        location.entry,
+        location.exit,
+    )
+    .await
+    .map_err(|e| anyhow!("Failed to connect: {}", e.message))?;
+    Ok(())
+}
+
+async fn disconnect(app: &AppHandle) -> Result<()> {
+    let tunnel = app.state::<SharedAppState>().lock().await.tunnel.clone();
+    if matches!(
+        tunnel,
+        TunnelState::Disconnected | TunnelState::Disconnecting(_)
+    ) {
+        info!("not connected, ignoring deep link disconnect");
+        return Ok(());
+    }
+
+    if !confirm(
+        app,
+        "Disconnect from the VPN?",
+        MessageDialogKind::Warning,
+        "Disconnect",
+    )
+    .await
+    {
+        info!("deep link disconnect cancelled");
+        return Ok(());
+    }
+
+    cmd_tunnel::disconnect(app.clone(), app.state(), app.state())
+        .await
+        .map_err(|e| anyhow!("Failed to disconnect: {}", e.message))?;
+    Ok(())
+}
+
+async fn login(app: &AppHandle, mnemonic: String) -> Result<()> {
+    let grpc = app.state::<GrpcClient>();
+    if grpc.is_account_stored().await? {
+        bail!("An account is already stored, log out first to use another one");
+    }
+
+    if !confirm(
+        app,
+        "Log in with the account from the link? Only continue if you trust where the link comes from.",
+        MessageDialogKind::Warning,
+        "Log in",
+    )
+    .await
+    {
+        info!("deep link login cancelled");
+        return Ok(());
+    }
+
+    cmd_account::add_account(mnemonic, app.state(), app.state())
+        .await
+        .map_err(|e| anyhow!("Failed to log in: {}", e.message))?;
+    app.emit_account_stored();
+    Ok(())
+}
+
+/// Wait for the daemon to be up, the app being just started
+async fn wait_for_daemon(app: &AppHandle) -> bool {
+    let deadline = Instant::now() + VPND_WAIT_TIMEOUT;
+    let state = app.state::<SharedAppState>();
+    while Instant::now() < deadline {
+        if matches!(state.lock().await.vpnd_status, VpndStatus::Ok(_)) {
+            return true;
+        }
+        sleep(VPND_WAIT_INTERVAL).await;
+    }
+    false
+}
+
+async fn confirm(app: &AppHandle, text: &str, kind: MessageDialogKind, ok: &str) -> bool {
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .message(text)
+        .title(APP_NAME)
+        .kind(kind)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            ok.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            tx.send(confirmed).ok();
+        });
+    rx.await
+        .inspect_err(|e| error!("failed to get dialog answer: {e}"))
+        .unwrap_or(false)
+}
+
+fn message(app: &AppHandle, text: &str, kind: MessageDialogKind) {
+    app.dialog()
+        .message(text)
+        .title(APP_NAME)
+        .kind(kind)
+        .show(|_| {});
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GATEWAY_ID: &str = "qj3GgGYgGZZ3HkFrtD1GU9UJ5oNXME9eD2xtmPLqYYw";
+
+    fn parse(link: &str) -> Result<Action> {
+        Action::parse(&Url::parse(link).unwrap())
+    }
+
+    fn parse_err(link: &str) -> String {
+        parse(link).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parse_connect() {
+        let link = format!("nymvpn://connect?entry=ch&exit={GATEWAY_ID}&mode=mixnet");
+        let Action::Connect { entry, exit, mode } = parse(&link).unwrap() else {
+            panic!("expected a connect action");
+        };
+        assert!(matches!(entry, Some(Node::Country(c)) if c.code == "CH"));
+        assert!(matches!(exit, Some(Node::Gateway(id)) if id == GATEWAY_ID));
+        assert_eq!(mode, Some(VpnMode::Mixnet));
+
+        // every parameter is optional, empty ones included
+        let Action::Connect { entry, exit, mode } = parse("nymvpn:connect?entry=").unwrap() else {
+            panic!("expected a connect action");
+        };
+        assert!(entry.is_none() && exit.is_none() && mode.is_none());
+        assert!(matches!(
+            parse("nymvpn://disconnect").unwrap(),
+            Action::Disconnect
+        ));
+    }
+
+    #[test]
+    fn reject_unknown_action() {
+        assert_eq!(
+            parse_err("nymvpn://reconnect"),
+            "unknown action `reconnect`"
+        );
+        assert!(parse_err("https://connect").starts_with("unsupported scheme"));
+    }
+
+    #[test]
+    fn reject_unknown_param() {
+        assert_eq!(
+            parse_err("nymvpn://connect?entry=CH&country=IS"),
+            "unknown parameter `country`"
+        );
+        assert_eq!(
+            parse_err("nymvpn://disconnect?now=1"),
+            "unknown parameter `now`"
+        );
+        assert_eq!(
+            parse_err("nymvpn://connect?mode=fast"),
+            "unknown mode `fast`"
+        );
+    }
+
+    #[test]
+    fn reject_bad_country_code() {
+        assert_eq!(
+            Node::parse("ZZ").unwrap_err().to_string(),
+            "unknown country code `ZZ`"
+        );
+        assert!(parse("nymvpn://connect?exit=zz").is_err());
+        assert!(Node::parse("CHE").is_err());
+    }
+
+    #[test]
+    fn reject_bad_gateway_identity() {
+        // valid length, but `0`, `O`, `I` and `l` aren't in the base58 alphabet
+        for bad in ['0', 'O', 'I', 'l'] {
+            let id = format!("{bad}{}", &GATEWAY_ID[1..]);
+            assert!(
+                Node::parse(&id).is_err(),
+                "`{id}` is not a gateway identity"
+            );
+        }
+        assert!(Node::parse(&GATEWAY_ID[..31]).is_err());
+        assert!(matches!(Node::parse(GATEWAY_ID), Ok(Node::Gateway(_))));
+    }
+
+    #[test]
+    fn login_requires_mnemonic() {
+        assert_eq!(parse_err("nymvpn://login"), "missing mnemonic");
+        assert_eq!(parse_err("nymvpn://login?mnemonic="), "missing mnemonic");
+        let Action::Login { mnemonic } = parse("nymvpn://login?mnemonic=a%20b%20c").unwrap() else {
+            panic!("expected a login action");
+        };
+        assert_eq!(mnemonic, "a b c");
+    }
+}
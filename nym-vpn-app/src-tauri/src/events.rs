@@ -5,11 +5,14 @@ use ts_rs::TS;
 
 use crate::error::{BackendError, ErrorKey};
 use crate::grpc::{client::VpndStatus, events::MixnetEvent, tunnel::TunnelState};
+use crate::locations::Location;
 
 pub const EVENT_VPND_STATUS: &str = "vpnd-status";
 pub const EVENT_TUNNEL_STATE: &str = "tunnel-state";
 pub const EVENT_MIXNET: &str = "mixnet-event";
 pub const EVENT_CONNECTION_PROGRESS: &str = "connection-progress";
+pub const EVENT_LOCATION_CHANGED: &str = "location-changed";
+pub const EVENT_ACCOUNT_STORED: &str = "account-stored";
 
 #[derive(Clone, Debug, Serialize)]
 pub enum ConnectProgressMsg {
@@ -58,6 +61,7 @@ impl MixnetEventPayload {
             MixnetEvent::ConnectedIpv6 => Self::Event(event),
             MixnetEvent::NoBandwidth => Self::Error(ErrorKey::NoBandwidth),
             MixnetEvent::RemainingBandwidth(_) => Self::Event(event),
+            MixnetEvent::BandwidthForecast(_) => Self::Event(event),
             MixnetEvent::SphinxPacketMetrics => Self::Event(event),
         }
     }
@@ -71,6 +75,8 @@ pub trait AppHandleEventEmitter {
     fn emit_disconnected(&self, error: Option<BackendError>);
     fn emit_mixnet_event(&self, event: MixnetEvent);
     fn emit_connection_progress(&self, key: ConnectProgressMsg);
+    fn emit_location_changed(&self, location: &Location);
+    fn emit_account_stored(&self);
 }
 
 impl AppHandleEventEmitter for tauri::AppHandle {
@@ -120,4 +126,18 @@ impl AppHandleEventEmitter for tauri::AppHandle {
         self.emit(EVENT_CONNECTION_PROGRESS, ProgressEventPayload { key })
             .ok();
     }
+
+    fn emit_location_changed(&self, location: &Location) {
+        debug!(
+            "sending event [{}]: {}",
+            EVENT_LOCATION_CHANGED,
+            location.label()
+        );
+        self.emit(EVENT_LOCATION_CHANGED, location).ok();
+    }
+
+    fn emit_account_stored(&self) {
+        debug!("sending event [{}]", EVENT_ACCOUNT_STORED);
+        self.emit(EVENT_ACCOUNT_STORED, ()).ok();
+    }
 }
@@ -7,8 +7,8 @@ use nym_vpn_proto::{
     get_account_links_response::Res as AccountLinkRes,
     get_device_identity_response::Id as DeviceIdRes,
     is_account_stored_response::Resp as IsAccountStoredResp, nym_vpnd_client::NymVpndClient,
-    tunnel_event::Event, ConnectRequest, Dns, GetAccountLinksRequest, ListGatewaysRequest,
-    Location, SetNetworkRequest, StoreAccountRequest, UserAgent,
+    tunnel_event::Event, ConnectRequest, Dns, GetAccountLinksRequest, IpFamily,
+    ListGatewaysRequest, Location, SetNetworkRequest, StoreAccountRequest, UserAgent,
 };
 use tauri::{AppHandle, Manager, PackageInfo};
 use tokio::sync::mpsc;
@@ -273,6 +273,9 @@ impl GrpcClient {
             min_mixnode_performance: None,
             min_gateway_mixnet_performance: None,
             min_gateway_vpn_performance: None,
+            ip_family: IpFamily::DualStack.into(),
+            wireguard_mtu: None,
+            precheck: false,
         });
         let response = vpnd
             .vpn_connect(request)
@@ -3,7 +3,10 @@ use ts_rs::TS;
 
 use nym_vpn_proto as p;
 use p::mixnet_event::{
-    bandwidth_event::Event as BandwidthEventEvent, BandwidthEvent, ConnectionEvent, Event,
+    bandwidth_event::{
+        depletion_forecast::Gateway as ForecastGateway, Event as BandwidthEventEvent,
+    },
+    BandwidthEvent, ConnectionEvent, Event,
 };
 use tracing::{error, instrument};
 
@@ -21,9 +24,29 @@ pub enum MixnetEvent {
     ConnectedIpv6,
     NoBandwidth,
     RemainingBandwidth(i64),
+    BandwidthForecast(BandwidthForecast),
     SphinxPacketMetrics, // TODO include metrics
 }
 
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "kebab-case")]
+pub enum BandwidthForecastGateway {
+    Entry,
+    Exit,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthForecast {
+    pub gateway: BandwidthForecastGateway,
+    pub remaining_bandwidth: i64,
+    // bytes per second
+    pub depletion_rate: u64,
+    pub time_to_exhaustion_secs: Option<u64>,
+}
+
 impl MixnetEvent {
     #[instrument(skip(mixnet_event))]
     pub fn from_proto(mixnet_event: p::MixnetEvent) -> Option<Self> {
@@ -47,6 +70,18 @@ impl MixnetEvent {
         match event {
             BandwidthEventEvent::NoBandwidth(_) => Some(Self::NoBandwidth),
             BandwidthEventEvent::RemainingBandwidth(b) => Some(Self::RemainingBandwidth(b.value)),
+            BandwidthEventEvent::DepletionForecast(f) => {
+                let gateway = match f.gateway() {
+                    ForecastGateway::Entry => BandwidthForecastGateway::Entry,
+                    ForecastGateway::Exit => BandwidthForecastGateway::Exit,
+                };
+                Some(Self::BandwidthForecast(BandwidthForecast {
+                    gateway,
+                    remaining_bandwidth: f.remaining_bandwidth,
+                    depletion_rate: f.depletion_rate,
+                    time_to_exhaustion_secs: f.time_to_exhaustion_secs,
+                }))
+            }
         }
     }
 
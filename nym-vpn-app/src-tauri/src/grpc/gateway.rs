@@ -37,6 +37,10 @@ pub struct Gateway {
     pub country: Country,
     pub mx_score: Score,
     pub wg_score: Score,
+    /// Marked as favourite in the daemon, directly or through its country
+    pub favourite: bool,
+    /// Marked as blocked in the daemon, directly or through its country
+    pub blocked: bool,
 }
 
 impl Gateway {
@@ -76,6 +80,8 @@ impl Gateway {
             country: Country::try_from(&location)?,
             mx_score: Score::from(mx_score),
             wg_score: Score::from(wg_score),
+            favourite: gateway.favourite,
+            blocked: gateway.blocked,
         })
     }
 }
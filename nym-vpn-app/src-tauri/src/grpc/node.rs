@@ -17,6 +17,29 @@ pub enum NodeConnect {
     Gateway(Gateway),
 }
 
+impl NodeConnect {
+    /// Country code or gateway ID
+    pub fn id(&self) -> &str {
+        match self {
+            NodeConnect::Country(country) => &country.code,
+            NodeConnect::Gateway(gateway) => &gateway.id,
+        }
+    }
+
+    /// Short human readable name, used in the tray menu
+    pub fn label(&self) -> String {
+        match self {
+            NodeConnect::Country(country) => country.name.clone(),
+            NodeConnect::Gateway(gateway) if gateway.name.is_empty() => {
+                format!("{} ({})", gateway.id, gateway.country.code)
+            }
+            NodeConnect::Gateway(gateway) => {
+                format!("{} ({})", gateway.name, gateway.country.code)
+            }
+        }
+    }
+}
+
 impl From<Country> for EntryNode {
     fn from(country: Country) -> Self {
         EntryNode {
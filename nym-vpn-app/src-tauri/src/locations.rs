@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, instrument, warn};
+use ts_rs::TS;
+
+use crate::db::{Db, DbError, Key};
+use crate::events::AppHandleEventEmitter;
+use crate::grpc::client::NodeConnect;
+use crate::state::app::VpnMode;
+use crate::state::SharedAppState;
+
+const MAX_RECENT_LOCATIONS: usize = 5;
+
+/// Entry and exit locations connected to, as listed in the tray quick-connect menu
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub vpn_mode: VpnMode,
+    pub entry: NodeConnect,
+    pub exit: NodeConnect,
+}
+
+impl Location {
+    pub fn new(vpn_mode: VpnMode, entry: NodeConnect, exit: NodeConnect) -> Self {
+        Location {
+            vpn_mode,
+            entry,
+            exit,
+        }
+    }
+
+    fn same_as(&self, other: &Location) -> bool {
+        self.vpn_mode == other.vpn_mode
+            && self.entry.id() == other.entry.id()
+            && self.exit.id() == other.exit.id()
+    }
+
+    pub fn label(&self) -> String {
+        let mode = match self.vpn_mode {
+            VpnMode::Mixnet => "Anonymous",
+            VpnMode::Wg => "Fast",
+        };
+        format!("{mode}: {} → {}", self.entry.label(), self.exit.label())
+    }
+}
+
+/// The entry and exit locations selected in the app, used to connect
+pub fn selected(db: &Db, vpn_mode: VpnMode) -> Option<Location> {
+    let get_node = |key: Key| {
+        db.get_typed::<NodeConnect>(key.as_ref())
+            .inspect_err(|e| warn!("failed to get [{key}] from db: {e}"))
+            .ok()
+            .flatten()
+    };
+    Some(Location::new(
+        vpn_mode,
+        get_node(Key::EntryNode)?,
+        get_node(Key::ExitNode)?,
+    ))
+}
+
+/// Make the location the selected one and let the UI know about it
+#[instrument(skip_all)]
+pub async fn select(app: &AppHandle, location: &Location) {
+    let db = app.state::<Db>();
+    let state = app.state::<SharedAppState>();
+    state.lock().await.vpn_mode = location.vpn_mode.clone();
+    for (key, value) in [
+        (Key::EntryNode, &location.entry),
+        (Key::ExitNode, &location.exit),
+    ] {
+        db.insert(key.as_ref(), value)
+            .inspect_err(|e| error!("failed to save [{key}] to db: {e}"))
+            .ok();
+    }
+    db.insert(Key::VpnMode.as_ref(), &location.vpn_mode)
+        .inspect_err(|e| error!("failed to save [{}] to db: {e}", Key::VpnMode))
+        .ok();
+    app.emit_location_changed(location);
+}
+
+fn get_list(db: &Db, key: Key) -> Vec<Location> {
+    db.get_typed::<Vec<Location>>(key.as_ref())
+        .inspect_err(|e| error!("failed to get [{key}] from db: {e}"))
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Most recently used locations, latest first
+pub fn recent(db: &Db) -> Vec<Location> {
+    get_list(db, Key::RecentLocations)
+}
+
+pub fn favourites(db: &Db) -> Vec<Location> {
+    get_list(db, Key::FavouriteLocations)
+}
+
+pub fn is_favourite(db: &Db, location: &Location) -> bool {
+    favourites(db).iter().any(|l| l.same_as(location))
+}
+
+/// Move the location to the top of the recent locations
+#[instrument(skip(db))]
+pub fn add_recent(db: &Db, location: Location) -> Result<(), DbError> {
+    let mut recent = recent(db);
+    recent.retain(|l| !l.same_as(&location));
+    recent.insert(0, location);
+    recent.truncate(MAX_RECENT_LOCATIONS);
+    db.insert(Key::RecentLocations.as_ref(), &recent)?;
+    Ok(())
+}
+
+/// Add the location to the favourites, or remove it if already there.
+/// Returns whether it is a favourite now
+#[instrument(skip(db))]
+pub fn toggle_favourite(db: &Db, location: Location) -> Result<bool, DbError> {
+    let mut favourites = favourites(db);
+    let count = favourites.len();
+    favourites.retain(|l| !l.same_as(&location));
+    let added = favourites.len() == count;
+    if added {
+        favourites.push(location);
+    }
+    debug!("favourite locations #{}", favourites.len());
+    db.insert(Key::FavouriteLocations.as_ref(), &favourites)?;
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::country::Country;
+
+    fn temp_db() -> Db {
+        Db {
+            db: sled::Config::new().temporary(true).open().unwrap(),
+            path: Default::default(),
+        }
+    }
+
+    fn country(code: &str) -> NodeConnect {
+        NodeConnect::Country(Country {
+            name: code.to_owned(),
+            code: code.to_owned(),
+        })
+    }
+
+    fn location(vpn_mode: VpnMode, entry: &str, exit: &str) -> Location {
+        Location::new(vpn_mode, country(entry), country(exit))
+    }
+
+    fn ids(locations: &[Location]) -> Vec<(VpnMode, String, String)> {
+        locations
+            .iter()
+            .map(|l| {
+                (
+                    l.vpn_mode.clone(),
+                    l.entry.id().to_owned(),
+                    l.exit.id().to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn add_recent_moves_duplicates_to_the_top() {
+        let db = temp_db();
+        add_recent(&db, location(VpnMode::Wg, "DE", "FR")).unwrap();
+        add_recent(&db, location(VpnMode::Wg, "CH", "US")).unwrap();
+        // same nodes in another mode is another location
+        add_recent(&db, location(VpnMode::Mixnet, "DE", "FR")).unwrap();
+        add_recent(&db, location(VpnMode::Wg, "DE", "FR")).unwrap();
+
+        assert_eq!(
+            ids(&recent(&db)),
+            vec![
+                (VpnMode::Wg, "DE".into(), "FR".into()),
+                (VpnMode::Mixnet, "DE".into(), "FR".into()),
+                (VpnMode::Wg, "CH".into(), "US".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn add_recent_keeps_the_latest_locations() {
+        let db = temp_db();
+        let exits = ["AT", "BE", "CH", "DE", "ES", "FR", "GB"];
+        for exit in exits {
+            add_recent(&db, location(VpnMode::Wg, "NL", exit)).unwrap();
+        }
+
+        let recent = recent(&db);
+        assert_eq!(recent.len(), MAX_RECENT_LOCATIONS);
+        let recent_exits: Vec<_> = recent.iter().map(|l| l.exit.id()).collect();
+        assert_eq!(recent_exits, vec!["GB", "FR", "ES", "DE", "CH"]);
+    }
+
+    #[tokio::test]
+    async fn toggle_favourite_adds_and_removes() {
+        let db = temp_db();
+        let favourite = location(VpnMode::Mixnet, "DE", "FR");
+        let other = location(VpnMode::Wg, "CH", "US");
+
+        assert!(toggle_favourite(&db, favourite.clone()).unwrap());
+        assert!(toggle_favourite(&db, other.clone()).unwrap());
+        assert!(is_favourite(&db, &favourite));
+        assert!(!is_favourite(&db, &location(VpnMode::Wg, "DE", "FR")));
+
+        assert!(!toggle_favourite(&db, favourite.clone()).unwrap());
+        assert!(!is_favourite(&db, &favourite));
+        assert_eq!(
+            ids(&favourites(&db)),
+            vec![(VpnMode::Wg, "CH".into(), "US".into())]
+        );
+    }
+}
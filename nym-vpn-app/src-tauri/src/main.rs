@@ -36,11 +36,13 @@ mod cli;
 mod commands;
 mod country;
 mod db;
+mod deep_link;
 mod env;
 mod error;
 mod events;
 mod fs;
 mod grpc;
+mod locations;
 mod log;
 mod misc;
 mod startup_error;
@@ -116,6 +118,7 @@ async fn main() -> Result<()> {
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_deep_link::init())
         .setup(move |app| {
             info!("app setup");
 
@@ -176,6 +179,7 @@ async fn main() -> Result<()> {
                 app_window.no_splash();
             }
             tray::setup(app.handle())?;
+            deep_link::setup(app.handle())?;
 
             let handle = app.handle().clone();
             let mut c_grpc = grpc.clone();
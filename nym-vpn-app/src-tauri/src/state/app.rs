@@ -14,6 +14,7 @@ use crate::{
     db::{Db, Key},
     fs::config::AppConfig,
     grpc::client::{VpndInfo, VpndStatus},
+    tray,
 };
 
 const NETWORK_VERSION_REQ_OP: &str = ">=";
@@ -75,6 +76,7 @@ impl AppState {
     ) -> Result<()> {
         self.tunnel = state;
         app.emit_tunnel_update(&self.tunnel);
+        tray::refresh(app);
         Ok(())
     }
 
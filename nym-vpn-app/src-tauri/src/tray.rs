@@ -1,15 +1,21 @@
 use anyhow::Result;
 use strum::AsRefStr;
 use tauri::image::Image;
-use tauri::menu::MenuEvent;
+use tauri::menu::{Menu, MenuEvent, MenuItemBuilder, SubmenuBuilder};
 use tauri::tray::TrayIconEvent;
 use tauri::tray::{MouseButton, MouseButtonState};
 use tauri::tray::{TrayIcon, TrayIconBuilder};
-use tauri::{include_image, Manager};
+use tauri::{include_image, Manager, Wry};
 use tauri::{menu::MenuBuilder, AppHandle};
+use tokio::sync::Mutex;
 use tracing::{debug, error, info, instrument, trace, warn};
 
-use crate::grpc::tunnel::TunnelState;
+use crate::commands::tunnel as cmd_tunnel;
+use crate::db::Db;
+use crate::grpc::gateway::GatewayType;
+use crate::grpc::tunnel::{Tunnel, TunnelState};
+use crate::locations::{self, Location};
+use crate::state::app::VpnMode;
 #[cfg(not(target_os = "linux"))]
 use crate::APP_NAME;
 use crate::{
@@ -19,13 +25,31 @@ use crate::{
 pub const TRAY_ICON_ID: &str = "main";
 pub const TRAY_MENU_ID: &str = "tray_menu";
 const APP_ICON: Image<'_> = include_image!("icons/tray_icon.png");
+const RECENT_ITEM_PREFIX: &str = "Recent:";
+const FAVOURITE_ITEM_PREFIX: &str = "Favourite:";
 
 #[derive(AsRefStr, Debug)]
 enum MenuItemId {
     ShowHide,
+    Status,
+    Connection,
+    Connect,
+    Disconnect,
+    QuickConnect,
+    ToggleFavourite,
     Quit,
 }
 
+/// Serializes the menu updates and caches the description of the current connection,
+/// which takes a gateway lookup
+static MENU_CACHE: Mutex<Option<ConnectionLabel>> = Mutex::const_new(None);
+
+struct ConnectionLabel {
+    entry_gw_id: String,
+    exit_gw_id: String,
+    label: String,
+}
+
 #[instrument(skip_all)]
 fn on_tray_event(tray_icon: &TrayIcon, event: TrayIconEvent) {
     if let TrayIconEvent::Click {
@@ -48,6 +72,57 @@ fn on_menu_event(app: &AppHandle, event: MenuEvent) {
             trace!("show/hide menu clicked");
             show_window(app, true).ok();
         }
+        x if x == MenuItemId::Connect.as_ref() => {
+            trace!("connect menu clicked");
+            tokio::spawn(quick_connect(app.clone(), None));
+        }
+        x if x == MenuItemId::Disconnect.as_ref() => {
+            trace!("disconnect menu clicked");
+            let c_app = app.clone();
+            tokio::spawn(async move {
+                cmd_tunnel::disconnect(c_app.clone(), c_app.state(), c_app.state())
+                    .await
+                    .inspect_err(|e| warn!("failed to disconnect: {e:?}"))
+                    .ok();
+            });
+        }
+        x if x == MenuItemId::ToggleFavourite.as_ref() => {
+            trace!("favourite menu clicked");
+            let c_app = app.clone();
+            tokio::spawn(async move {
+                let db = c_app.state::<Db>();
+                let vpn_mode = c_app
+                    .state::<SharedAppState>()
+                    .lock()
+                    .await
+                    .vpn_mode
+                    .clone();
+                if let Some(location) = locations::selected(&db, vpn_mode) {
+                    locations::toggle_favourite(&db, location).ok();
+                    refresh(&c_app);
+                }
+            });
+        }
+        x if x.starts_with(RECENT_ITEM_PREFIX) || x.starts_with(FAVOURITE_ITEM_PREFIX) => {
+            trace!("quick connect menu clicked");
+            let db = app.state::<Db>();
+            let location = if let Some(index) = x.strip_prefix(RECENT_ITEM_PREFIX) {
+                index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| locations::recent(&db).into_iter().nth(i))
+            } else {
+                x.strip_prefix(FAVOURITE_ITEM_PREFIX)
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(|i| locations::favourites(&db).into_iter().nth(i))
+            };
+            match location {
+                Some(location) => {
+                    tokio::spawn(quick_connect(app.clone(), Some(location)));
+                }
+                None => warn!("no location for menu item {x}"),
+            }
+        }
         x if x == MenuItemId::Quit.as_ref() => {
             trace!("quit menu clicked");
             let c_app = app.clone();
@@ -72,15 +147,229 @@ fn on_menu_event(app: &AppHandle, event: MenuEvent) {
     }
 }
 
+/// Connect to the given location, or to the one selected in the app.
+/// A location from the quick-connect menu becomes the selected one
+#[instrument(skip(app))]
+async fn quick_connect(app: AppHandle, location: Option<Location>) {
+    let db = app.state::<Db>();
+    let state = app.state::<SharedAppState>();
+
+    let location = match location {
+        Some(location) => {
+            let tunnel = state.lock().await.tunnel.clone();
+            if tunnel != TunnelState::Disconnected {
+                warn!("cannot quick connect from state {}", tunnel);
+                return;
+            }
+            locations::select(&app, &location).await;
+            location
+        }
+        None => {
+            let vpn_mode = state.lock().await.vpn_mode.clone();
+            let Some(location) = locations::selected(&db, vpn_mode) else {
+                info!("no location selected yet, showing main window");
+                show_window(&app, false).ok();
+                return;
+            };
+            location
+        }
+    };
+
+    cmd_tunnel::connect(
+        app.clone(),
+        app.state(),
+        app.state(),
+        db,
+        location.entry,
+        location.exit,
+    )
+    .await
+    .inspect_err(|e| warn!("failed to connect: {e:?}"))
+    .ok();
+}
+
+/// Rebuild the tray menu in the background, to reflect the tunnel state
+/// and the recent and favourite locations
+pub fn refresh(app: &AppHandle) {
+    let app = app.clone();
+    tokio::spawn(async move {
+        update_menu(&app)
+            .await
+            .inspect_err(|e| warn!("failed to update tray menu: {e}"))
+            .ok();
+    });
+}
+
 #[instrument(skip_all)]
-pub fn setup(app: &AppHandle) -> Result<()> {
-    debug!("building system tray");
-    let menu = MenuBuilder::with_id(app, TRAY_MENU_ID)
+async fn update_menu(app: &AppHandle) -> Result<()> {
+    let mut cache = MENU_CACHE.lock().await;
+    let (tunnel, vpn_mode) = {
+        let state = app.state::<SharedAppState>();
+        let app_state = state.lock().await;
+        (app_state.tunnel.clone(), app_state.vpn_mode.clone())
+    };
+
+    let connection = match &tunnel {
+        TunnelState::Connected(tunnel) => {
+            let cached = cache.as_ref().filter(|c| {
+                c.entry_gw_id == tunnel.entry_gw_id && c.exit_gw_id == tunnel.exit_gw_id
+            });
+            match cached {
+                Some(cached) => Some(cached.label.clone()),
+                None => {
+                    let label = connection_label(app, tunnel, &vpn_mode).await;
+                    *cache = Some(ConnectionLabel {
+                        entry_gw_id: tunnel.entry_gw_id.clone(),
+                        exit_gw_id: tunnel.exit_gw_id.clone(),
+                        label: label.clone(),
+                    });
+                    Some(label)
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let menu = build_menu(app, &tunnel, vpn_mode, connection)?;
+    if let Some(tray) = app.tray_by_id(TRAY_ICON_ID) {
+        tray.set_menu(Some(menu))?;
+    }
+    Ok(())
+}
+
+/// Describe the gateways of the connection with their country,
+/// falling back to their ID when they aren't listed by the daemon
+async fn connection_label(app: &AppHandle, tunnel: &Tunnel, vpn_mode: &VpnMode) -> String {
+    let grpc = app.state::<GrpcClient>();
+    let (entry_type, exit_type) = match vpn_mode {
+        VpnMode::Mixnet => (GatewayType::MxEntry, GatewayType::MxExit),
+        VpnMode::Wg => (GatewayType::Wg, GatewayType::Wg),
+    };
+
+    let mut labels = Vec::with_capacity(2);
+    for (id, gw_type) in [
+        (&tunnel.entry_gw_id, entry_type),
+        (&tunnel.exit_gw_id, exit_type),
+    ] {
+        let gateway = grpc
+            .gateways(gw_type)
+            .await
+            .inspect_err(|e| warn!("failed to get gateways: {e}"))
+            .ok()
+            .and_then(|gateways| gateways.into_iter().find(|gw| &gw.id == id));
+        labels.push(match gateway {
+            Some(gw) if !gw.name.is_empty() => format!("{} ({})", gw.name, gw.country.name),
+            Some(gw) => format!("{} ({})", short_id(id), gw.country.name),
+            None => short_id(id),
+        });
+    }
+    labels.join(" → ")
+}
+
+fn short_id(id: &str) -> String {
+    match id.char_indices().nth(8) {
+        Some((i, _)) => format!("{}…", &id[..i]),
+        None => id.to_string(),
+    }
+}
+
+fn build_menu(
+    app: &AppHandle,
+    tunnel: &TunnelState,
+    vpn_mode: VpnMode,
+    connection: Option<String>,
+) -> Result<Menu<Wry>> {
+    let db = app.state::<Db>();
+    let disconnected = *tunnel == TunnelState::Disconnected;
+    let can_disconnect = !matches!(
+        tunnel,
+        TunnelState::Disconnected | TunnelState::Disconnecting(_)
+    );
+    let status = match tunnel {
+        TunnelState::Disconnected => "Disconnected",
+        TunnelState::Connected(_) => "Connected",
+        TunnelState::Connecting(_) => "Connecting…",
+        TunnelState::Disconnecting(_) => "Disconnecting…",
+        TunnelState::Error(_) => "Error",
+        TunnelState::Offline { .. } => "Offline",
+    };
+
+    let status_item = MenuItemBuilder::with_id(MenuItemId::Status.as_ref(), status)
+        .enabled(false)
+        .build(app)?;
+    let connect = MenuItemBuilder::with_id(MenuItemId::Connect.as_ref(), "Connect")
+        .enabled(disconnected)
+        .build(app)?;
+    let disconnect = MenuItemBuilder::with_id(MenuItemId::Disconnect.as_ref(), "Disconnect")
+        .enabled(can_disconnect)
+        .build(app)?;
+
+    let recent = locations::recent(&db);
+    let favourites = locations::favourites(&db);
+    let mut quick_connect =
+        SubmenuBuilder::with_id(app, MenuItemId::QuickConnect.as_ref(), "Quick connect")
+            .enabled(disconnected && !(recent.is_empty() && favourites.is_empty()));
+    if !favourites.is_empty() {
+        quick_connect = quick_connect.item(
+            &MenuItemBuilder::new("Favourites")
+                .enabled(false)
+                .build(app)?,
+        );
+        for (i, location) in favourites.iter().enumerate() {
+            quick_connect =
+                quick_connect.text(format!("{FAVOURITE_ITEM_PREFIX}{i}"), location.label());
+        }
+    }
+    if !recent.is_empty() {
+        if !favourites.is_empty() {
+            quick_connect = quick_connect.separator();
+        }
+        quick_connect =
+            quick_connect.item(&MenuItemBuilder::new("Recent").enabled(false).build(app)?);
+        for (i, location) in recent.iter().enumerate() {
+            quick_connect =
+                quick_connect.text(format!("{RECENT_ITEM_PREFIX}{i}"), location.label());
+        }
+    }
+    let quick_connect = quick_connect.build()?;
+
+    let mut menu = MenuBuilder::with_id(app, TRAY_MENU_ID).item(&status_item);
+    if let Some(connection) = connection {
+        menu = menu.item(
+            &MenuItemBuilder::with_id(MenuItemId::Connection.as_ref(), connection)
+                .enabled(false)
+                .build(app)?,
+        );
+    }
+    menu = menu
+        .separator()
+        .item(&connect)
+        .item(&disconnect)
+        .item(&quick_connect);
+    if let (TunnelState::Connected(_), Some(location)) =
+        (tunnel, locations::selected(&db, vpn_mode))
+    {
+        let text = if locations::is_favourite(&db, &location) {
+            "Remove from favourites"
+        } else {
+            "Add to favourites"
+        };
+        menu = menu.text(MenuItemId::ToggleFavourite.as_ref(), text);
+    }
+    let menu = menu
+        .separator()
         .text(MenuItemId::ShowHide.as_ref(), "Show/Hide")
         .separator()
         .text(MenuItemId::Quit.as_ref(), "Quit (disconnect)")
         .build()
         .inspect_err(|e| error!("failed to build tray menu: {e}"))?;
+    Ok(menu)
+}
+
+#[instrument(skip_all)]
+pub fn setup(app: &AppHandle) -> Result<()> {
+    debug!("building system tray");
+    let menu = build_menu(app, &TunnelState::Disconnected, VpnMode::default(), None)?;
 
     #[allow(unused_variables)]
     let tray = TrayIconBuilder::with_id(TRAY_ICON_ID)
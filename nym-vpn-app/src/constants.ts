@@ -8,6 +8,8 @@ export const TunnelStateEvent = 'tunnel-state';
 export const ProgressEvent = 'connection-progress';
 export const DaemonEvent = 'vpnd-status';
 export const MixnetEvent = 'mixnet-event';
+export const LocationChangedEvent = 'location-changed';
+export const AccountStoredEvent = 'account-stored';
 // ⚠ keep this value in sync with the one declared in `index.html`
 export const DefaultRootFontSize = 14; // in px
 // NOTE: when fresh country data is get from daemon, the selected countries
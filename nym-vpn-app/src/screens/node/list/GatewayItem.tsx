@@ -63,7 +63,15 @@ const GatewayItem = ({
             />
           </div>
           <div className="flex flex-col text-start overflow-hidden">
-            <p className="truncate">{gateway.name}</p>
+            <div className="flex flex-row items-center gap-1 overflow-hidden">
+              <p className="truncate">{gateway.name}</p>
+              {gateway.favourite && (
+                <MsIcon
+                  className="text-base text-malachite shrink-0"
+                  icon="star"
+                />
+              )}
+            </div>
             <p className="text-sm text-iron dark:text-bombay truncate">
               {truncateId(gateway.id)}
             </p>
@@ -5,6 +5,7 @@ import i18n from 'i18next';
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import {
   AccountLinks,
+  Location,
   MixnetEventPayload,
   ProgressEventPayload,
   StateDispatch,
@@ -15,7 +16,9 @@ import {
   isVpndOk,
 } from '../types';
 import {
+  AccountStoredEvent,
   DaemonEvent,
+  LocationChangedEvent,
   MixnetEvent,
   ProgressEvent,
   TunnelStateEvent,
@@ -101,6 +104,30 @@ export function useTauriEvents(
     });
   }, [dispatch]);
 
+  const registerLocationChangedListener = useCallback(() => {
+    return listen<Location>(LocationChangedEvent, ({ event, payload }) => {
+      console.info(`received event [${event}]`, payload);
+      dispatch({ type: 'set-vpn-mode', mode: payload.vpnMode });
+      dispatch({
+        type: 'set-node',
+        payload: { hop: 'entry', node: payload.entry },
+      });
+      dispatch({
+        type: 'set-node',
+        payload: { hop: 'exit', node: payload.exit },
+      });
+    });
+  }, [dispatch]);
+
+  const registerAccountStoredListener = useCallback(() => {
+    return listen(AccountStoredEvent, async ({ event }) => {
+      console.info(`received event [${event}]`);
+      await CCache.del('cache-account-id');
+      await CCache.del('cache-device-id');
+      dispatch({ type: 'set-account', stored: true });
+    });
+  }, [dispatch]);
+
   const registerThemeChangedListener = useCallback(() => {
     const window = getCurrentWebviewWindow();
     return window.onThemeChanged(({ payload }) => {
@@ -118,6 +145,8 @@ export function useTauriEvents(
     const unlistenState = registerStateListener();
     const unlistenMixnetEvent = registerMixnetEventListener();
     const unlistenProgress = registerProgressListener();
+    const unlistenLocationChanged = registerLocationChangedListener();
+    const unlistenAccountStored = registerAccountStoredListener();
     const unlistenThemeChanges = registerThemeChangedListener();
 
     return () => {
@@ -125,6 +154,8 @@ export function useTauriEvents(
       unlistenState.then((f) => f());
       unlistenMixnetEvent.then((f) => f());
       unlistenProgress.then((f) => f());
+      unlistenLocationChanged.then((f) => f());
+      unlistenAccountStored.then((f) => f());
       unlistenThemeChanges.then((f) => f());
     };
   }, [
@@ -132,6 +163,8 @@ export function useTauriEvents(
     registerStateListener,
     registerMixnetEventListener,
     registerProgressListener,
+    registerLocationChangedListener,
+    registerAccountStoredListener,
     registerThemeChangedListener,
   ]);
 }
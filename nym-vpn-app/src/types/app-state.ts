@@ -67,6 +67,13 @@ export type AppState = {
   networkCompat?: NetworkCompat | null;
 };
 
+// entry and exit locations picked from the tray quick-connect menu
+export type Location = {
+  vpnMode: VpnMode;
+  entry: Country | Gateway;
+  exit: Country | Gateway;
+};
+
 export type ConnectProgressMsg = 'Initializing' | 'InitDone' | 'Canceling';
 
 export type ProgressEventPayload = {
@@ -25,6 +25,8 @@ export type DbKey =
   | 'welcome-screen-seen'
   | 'desktop-notifications'
   | 'last-network-env'
+  | 'recent-locations'
+  | 'favourite-locations'
   | 'cache-mx-entry-gateways'
   | 'cache-mx-exit-gateways'
   | 'cache-wg-gateways'
@@ -101,6 +103,8 @@ export type Gateway = {
   country: Country;
   mxScore: Score;
   wgScore: Score;
+  favourite: boolean;
+  blocked: boolean;
 };
 
 export type GatewaysByCountry = {
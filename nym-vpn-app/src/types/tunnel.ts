@@ -122,6 +122,15 @@ export type WgNode = {
 export type RemainingBandwidth = {
   'remaining-bandwidth': bigint;
 };
+export type BandwidthForecast = {
+  'bandwidth-forecast': {
+    gateway: 'entry' | 'exit';
+    remainingBandwidth: bigint;
+    // bytes per second
+    depletionRate: bigint;
+    timeToExhaustionSecs: bigint | null;
+  };
+};
 export type MixnetEvent =
   | 'entry-gw-down'
   | 'exit-gw-down-ipv4'
@@ -132,6 +141,7 @@ export type MixnetEvent =
   | 'connected-ipv6'
   | 'no-bandwidth'
   | RemainingBandwidth
+  | BandwidthForecast
   | 'sphinx-packet-metrics';
 
 export function isRemainingBandwidth(
@@ -140,6 +150,12 @@ export function isRemainingBandwidth(
   return (event as RemainingBandwidth)['remaining-bandwidth'] !== undefined;
 }
 
+export function isBandwidthForecast(
+  event: MixnetEvent,
+): event is BandwidthForecast {
+  return (event as BandwidthForecast)['bandwidth-forecast'] !== undefined;
+}
+
 export type MixnetEventPayload =
   | { event: MixnetEvent }
   | {
@@ -41,6 +41,7 @@ struct IcmpConnectionBeacon {
     ipr_address: Recipient,
     sequence_number: u16,
     icmp_identifier: u16,
+    ipv6_enabled: bool,
 }
 
 impl IcmpConnectionBeacon {
@@ -49,6 +50,7 @@ impl IcmpConnectionBeacon {
         our_ips: IpPair,
         ipr_address: Recipient,
         icmp_identifier: u16,
+        ipv6_enabled: bool,
     ) -> Self {
         IcmpConnectionBeacon {
             mixnet_client_sender,
@@ -56,6 +58,7 @@ impl IcmpConnectionBeacon {
             ipr_address,
             sequence_number: 0,
             icmp_identifier,
+            ipv6_enabled,
         }
     }
 
@@ -144,12 +147,15 @@ impl IcmpConnectionBeacon {
                         if let Err(err) = self.ping_v4_ipr_tun_device_over_the_mixnet().await {
                             error!("Failed to send ICMP ping: {err}");
                         }
-                        if let Err(err) = self.ping_v6_ipr_tun_device_over_the_mixnet().await {
-                            error!("Failed to send ICMPv6 ping: {err}");
-                        }
                         if let Err(err) = self.ping_v4_some_external_ip_over_the_mixnet().await {
                             error!("Failed to send ICMP ping: {err}");
                         }
+                        if !self.ipv6_enabled {
+                            return;
+                        }
+                        if let Err(err) = self.ping_v6_ipr_tun_device_over_the_mixnet().await {
+                            error!("Failed to send ICMPv6 ping: {err}");
+                        }
                         if let Err(err) = self.ping_v6_some_external_ip_over_the_mixnet().await {
                             error!("Failed to send ICMPv6 ping: {err}");
                         }
@@ -236,11 +242,17 @@ pub fn start_icmp_connection_beacon(
     our_ips: IpPair,
     ipr_address: Recipient,
     icmp_identifier: u16,
+    ipv6_enabled: bool,
     shutdown_listener: TaskClient,
 ) -> JoinHandle<Result<()>> {
     debug!("Creating icmp connection beacon");
-    let beacon =
-        IcmpConnectionBeacon::new(mixnet_client_sender, our_ips, ipr_address, icmp_identifier);
+    let beacon = IcmpConnectionBeacon::new(
+        mixnet_client_sender,
+        our_ips,
+        ipr_address,
+        icmp_identifier,
+        ipv6_enabled,
+    );
     tokio::spawn(async move {
         beacon.run(shutdown_listener).await.inspect_err(|err| {
             error!("Icmp connection beacon error: {err}");
@@ -34,16 +34,19 @@ fn create_icmp_beacon_identifier() -> u16 {
 // monitor.
 pub struct ConnectionMonitorTask {
     icmp_beacon_identifier: u16,
+    ipv6_enabled: bool,
     connection_event_tx: mpsc::UnboundedSender<monitor::ConnectionStatusEvent>,
     connection_event_rx: mpsc::UnboundedReceiver<monitor::ConnectionStatusEvent>,
 }
 
 impl ConnectionMonitorTask {
-    pub fn setup() -> ConnectionMonitorTask {
+    /// Sets up the monitor. IPv6 connectivity is only checked when `ipv6_enabled` is set.
+    pub fn setup(ipv6_enabled: bool) -> ConnectionMonitorTask {
         let (connection_event_tx, connection_event_rx) = mpsc::unbounded();
         let icmp_beacon_identifier = create_icmp_beacon_identifier();
         ConnectionMonitorTask {
             icmp_beacon_identifier,
+            ipv6_enabled,
             connection_event_tx,
             connection_event_rx,
         }
@@ -78,12 +81,14 @@ impl ConnectionMonitorTask {
             our_ips,
             exit_router_address,
             self.icmp_beacon_identifier,
+            self.ipv6_enabled,
             task_manager.subscribe_named("icmp_beacon"),
         );
 
         info!("Setting up connection monitor");
         monitor::start_connection_monitor(
             self.connection_event_rx,
+            self.ipv6_enabled,
             task_manager.subscribe_named("connection_monitor"),
         );
     }
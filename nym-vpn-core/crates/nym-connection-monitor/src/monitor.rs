@@ -97,6 +97,7 @@ impl ConnectionStats {
 struct ConnectionMonitor {
     connection_event_rx: mpsc::UnboundedReceiver<ConnectionStatusEvent>,
     stats: ConnectionStats,
+    ipv6_enabled: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -127,10 +128,14 @@ struct ConnectivityState {
 }
 
 impl ConnectionMonitor {
-    fn new(connection_event_rx: mpsc::UnboundedReceiver<ConnectionStatusEvent>) -> Self {
+    fn new(
+        connection_event_rx: mpsc::UnboundedReceiver<ConnectionStatusEvent>,
+        ipv6_enabled: bool,
+    ) -> Self {
         ConnectionMonitor {
             connection_event_rx,
             stats: ConnectionStats::default(),
+            ipv6_enabled,
         }
     }
 
@@ -178,7 +183,7 @@ impl ConnectionMonitor {
                 _ = report_interval.tick() => {
                     self.stats.log_status();
                     let connectivity = self.stats.evaluate_connectivity();
-                    report_connectivity(&connectivity, &mut task_client);
+                    report_connectivity(&connectivity, self.ipv6_enabled, &mut task_client);
                 }
             }
         }
@@ -187,7 +192,11 @@ impl ConnectionMonitor {
     }
 }
 
-fn report_connectivity(connectivity: &ConnectivityState, task_client: &mut TaskClient) {
+fn report_connectivity(
+    connectivity: &ConnectivityState,
+    ipv6_enabled: bool,
+    task_client: &mut TaskClient,
+) {
     if connectivity.entry == ConnectivityStatus::Fail {
         tracing::error!("Entry gateway not routing our mixnet traffic");
         task_client.send_status_msg(Box::new(ConnectionMonitorStatus::EntryGatewayDown));
@@ -210,6 +219,11 @@ fn report_connectivity(connectivity: &ConnectivityState, task_client: &mut TaskC
         tracing::error!("Unexpected connectivity state - exit gateway ipv4 connectivity is ok, but routing is not?");
     }
 
+    // No IPv6 beacons are sent when IPv6 is disabled in the tunnel
+    if !ipv6_enabled {
+        return;
+    }
+
     if connectivity.exit_routing.ipv6 == ConnectivityStatus::Ok {
         tracing::debug!("ConnectionMonitor: connection success over ipv6");
         task_client.send_status_msg(Box::new(ConnectionMonitorStatus::ConnectedIpv6));
@@ -282,10 +296,11 @@ impl nym_task::TaskStatusEvent for ConnectionMonitorStatus {
 
 pub fn start_connection_monitor(
     connection_event_rx: futures::channel::mpsc::UnboundedReceiver<ConnectionStatusEvent>,
+    ipv6_enabled: bool,
     shutdown_listener: TaskClient,
 ) -> JoinHandle<Result<()>> {
     tracing::debug!("Creating connection monitor");
-    let monitor = ConnectionMonitor::new(connection_event_rx);
+    let monitor = ConnectionMonitor::new(connection_event_rx, ipv6_enabled);
     tokio::spawn(async move {
         monitor.run(shutdown_listener).await.inspect_err(|err| {
             tracing::error!("Connection monitor error: {err}");
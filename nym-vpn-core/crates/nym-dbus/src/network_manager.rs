@@ -47,6 +47,11 @@ const NM_SETTINGS_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings";
 const NM_SETTINGS_CONNECTION_INTERFACE: &str = "org.freedesktop.NetworkManager.Settings.Connection";
 const NM_SETTINGS_PATH: &str = "/org/freedesktop/NetworkManager/Settings";
 const NM_CONNECTION_ACTIVE: &str = "org.freedesktop.NetworkManager.Connection.Active";
+const NM_ACCESS_POINT: &str = "org.freedesktop.NetworkManager.AccessPoint";
+
+const NM_CONNECTION_TYPE_WIFI: &str = "802-11-wireless";
+// Connections of these types are tunnels, never the network the host joined.
+const NM_VIRTUAL_CONNECTION_TYPES: [&str; 3] = ["wireguard", "tun", "vpn"];
 
 const NM_ADD_CONNECTION_VOLATILE: u32 = 0x2;
 
@@ -647,6 +652,83 @@ impl NetworkManager {
         Err(Error::DeviceNotFound)
     }
 
+    /// Describe the active connection providing the default route, skipping tunnels. Returns
+    /// `None` when the host isn't connected to any network.
+    pub fn active_network(&self) -> Result<Option<ActiveNetwork>> {
+        let primary: dbus::Path<'static> =
+            self.as_manager().get(NM_MANAGER, "PrimaryConnection")?;
+        let active: Vec<dbus::Path<'static>> =
+            self.as_manager().get(NM_MANAGER, "ActiveConnections")?;
+
+        // The primary connection comes first, the other ones only matter when it's a tunnel.
+        let candidates = std::iter::once(primary.clone())
+            .chain(active.into_iter().filter(|path| *path != primary))
+            .filter(|path| &**path != "/");
+        for path in candidates {
+            let connection = self.as_path(&path);
+            let connection_type: String = connection.get(NM_CONNECTION_ACTIVE, "Type")?;
+            if NM_VIRTUAL_CONNECTION_TYPES.contains(&connection_type.as_str()) {
+                continue;
+            }
+            let is_default: bool = connection.get(NM_CONNECTION_ACTIVE, "Default")?;
+            let is_default6: bool = connection.get(NM_CONNECTION_ACTIVE, "Default6")?;
+            if path != primary && !is_default && !is_default6 {
+                continue;
+            }
+            return self.read_active_network(&path, connection_type).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn read_active_network(
+        &self,
+        path: &dbus::Path<'static>,
+        connection_type: String,
+    ) -> Result<ActiveNetwork> {
+        let connection = self.as_path(path);
+        let id: String = connection.get(NM_CONNECTION_ACTIVE, "Id")?;
+
+        let devices: Vec<dbus::Path<'static>> = connection.get(NM_CONNECTION_ACTIVE, "Devices")?;
+        let device = devices.first().ok_or(Error::NoDevice)?;
+        let interface: String = self.as_path(device).get(NM_DEVICE, "Interface")?;
+
+        // The specific object of an active Wi-Fi connection is its access point.
+        let ssid = if connection_type == NM_CONNECTION_TYPE_WIFI {
+            let access_point: dbus::Path<'static> =
+                connection.get(NM_CONNECTION_ACTIVE, "SpecificObject")?;
+            let ssid: Vec<u8> = self.as_path(&access_point).get(NM_ACCESS_POINT, "Ssid")?;
+            Some(String::from_utf8_lossy(&ssid).into_owned())
+        } else {
+            None
+        };
+
+        let mut addresses = Vec::new();
+        for (config_property, config_interface) in
+            [("Ip4Config", NM_IP4_CONFIG), ("Ip6Config", NM_IP6_CONFIG)]
+        {
+            let config: dbus::Path<'static> =
+                connection.get(NM_CONNECTION_ACTIVE, config_property)?;
+            if &*config == "/" {
+                continue;
+            }
+            let address_data: Vec<VariantMap> =
+                self.as_path(&config).get(config_interface, "AddressData")?;
+            addresses.extend(address_data.iter().filter_map(|data| {
+                let address = data.get("address")?.0.as_str()?.parse().ok()?;
+                let prefix = u8::try_from(data.get("prefix")?.0.as_u64()?).ok()?;
+                Some((address, prefix))
+            }));
+        }
+
+        Ok(ActiveNetwork {
+            connection_type,
+            id,
+            interface,
+            ssid,
+            addresses,
+        })
+    }
+
     pub fn convert_address_to_dbus(address: &IpAddr) -> VariantMap {
         let mut map: VariantMap = HashMap::new();
         map.insert(
@@ -682,6 +764,20 @@ impl dbus::message::SignalArgs for DeviceStateChange {
     const INTERFACE: &'static str = NM_DEVICE;
 }
 
+/// Active connection of NetworkManager, as returned by [`NetworkManager::active_network`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveNetwork {
+    /// Connection type, e.g. `802-11-wireless` or `802-3-ethernet`
+    pub connection_type: String,
+    /// Name of the connection profile
+    pub id: String,
+    pub interface: String,
+    /// SSID of Wi-Fi connections
+    pub ssid: Option<String>,
+    /// Addresses of the interface with their prefix length
+    pub addresses: Vec<(IpAddr, u8)>,
+}
+
 #[derive(Debug)]
 pub struct WireguardTunnel {
     config_path: dbus::Path<'static>,
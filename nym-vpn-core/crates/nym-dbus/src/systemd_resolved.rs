@@ -575,6 +575,32 @@ impl AsyncHandle {
             .map_err(Error::AsyncTaskError)?
     }
 
+    pub async fn get_domains(&self, interface_index: u32) -> Result<Vec<(String, bool)>> {
+        let interface = self.dbus_interface.clone();
+        tokio::task::spawn_blocking(move || interface.get_domains(interface_index))
+            .await
+            .map_err(Error::AsyncTaskError)?
+    }
+
+    /// Replaces the search and routing domains of the link. Routing domains are flagged with
+    /// `true`.
+    pub async fn replace_domains(
+        &self,
+        interface_index: u32,
+        domains: Vec<(String, bool)>,
+    ) -> Result<()> {
+        let interface = self.dbus_interface.clone();
+        tokio::task::spawn_blocking(move || {
+            let domains = domains
+                .iter()
+                .map(|(domain, routing)| (domain.as_str(), *routing))
+                .collect::<Vec<_>>();
+            interface.set_domains(interface_index, &domains)
+        })
+        .await
+        .map_err(Error::AsyncTaskError)?
+    }
+
     pub async fn revert_link(&self, state: DnsState) -> Result<()> {
         let mut interface = self.dbus_interface.clone();
         tokio::task::spawn_blocking(move || interface.revert_link(&state))
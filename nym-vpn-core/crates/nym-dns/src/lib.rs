@@ -2,7 +2,7 @@
 // Copyright 2024 Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{fmt, net::IpAddr};
+use std::{fmt, net::IpAddr, str::FromStr};
 
 #[cfg(target_os = "linux")]
 use nym_routing::RouteManagerHandle;
@@ -28,16 +28,141 @@ mod imp;
 
 pub use self::imp::Error;
 
+/// The mechanism used to manage the system DNS.
+///
+/// Only Linux has multiple backends to choose from, other platforms ignore this setting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DnsBackend {
+    /// Detect the backend in use by the system.
+    #[default]
+    Auto,
+    SystemdResolved,
+    NetworkManager,
+    Resolvconf,
+    /// Write `/etc/resolv.conf` directly.
+    StaticFile,
+}
+
+impl fmt::Display for DnsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Auto => "auto",
+            Self::SystemdResolved => "systemd-resolved",
+            Self::NetworkManager => "network-manager",
+            Self::Resolvconf => "resolvconf",
+            Self::StaticFile => "static-file",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for DnsBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "systemd-resolved" | "systemd" => Ok(Self::SystemdResolved),
+            "network-manager" => Ok(Self::NetworkManager),
+            "resolvconf" => Ok(Self::Resolvconf),
+            "static-file" => Ok(Self::StaticFile),
+            other => Err(format!("unknown DNS backend: {other}")),
+        }
+    }
+}
+
+/// Snapshot of the DNS configuration managed by a [`DnsMonitor`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DnsStatus {
+    /// The backend requested in the settings.
+    pub configured_backend: DnsBackend,
+    /// The backend that is or would be used, `None` if no usable backend was detected or the
+    /// platform only has a single one.
+    pub active_backend: Option<DnsBackend>,
+    /// The interface the DNS servers are applied to.
+    pub interface: Option<String>,
+    /// The DNS servers currently applied to the tunnel interface.
+    pub servers: Vec<IpAddr>,
+}
+
+/// Result of checking whether the system resolver uses the applied DNS servers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsVerification {
+    /// The system resolver uses the applied servers.
+    Verified,
+    /// The system resolver uses other servers than the applied ones.
+    Mismatch { resolver_servers: Vec<IpAddr> },
+    /// The check could not be performed.
+    Inconclusive(String),
+}
+
+/// Checks whether the system resolver actually uses the servers described by `status`.
+pub async fn verify(status: &DnsStatus) -> DnsVerification {
+    if status.servers.is_empty() {
+        return DnsVerification::Inconclusive("no DNS servers are applied".to_owned());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        imp::verify(status).await
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        DnsVerification::Inconclusive("verification is not supported on this platform".to_owned())
+    }
+}
+
+/// Resolve a domain and its subdomains using specific servers outside the tunnel, e.g. a
+/// corporate resolver on the LAN.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DomainRoute {
+    /// The domain, `corp.example` and `*.corp.example` are equivalent.
+    pub domain: String,
+    pub servers: Vec<IpAddr>,
+}
+
+impl DomainRoute {
+    /// Returns the route with the domain in canonical form, or `None` if it can't be used.
+    fn normalize(&self) -> Option<Self> {
+        let domain = self
+            .domain
+            .trim()
+            .trim_start_matches("*.")
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        if domain.is_empty() || self.servers.is_empty() {
+            return None;
+        }
+        Some(Self {
+            domain,
+            servers: self.servers.clone(),
+        })
+    }
+
+    /// Returns true if `name` is the domain or one of its subdomains.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        name == self.domain
+            || name
+                .strip_suffix(self.domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    }
+}
+
 /// DNS configuration
 #[derive(Debug, Clone, PartialEq)]
 pub struct DnsConfig {
     config: InnerDnsConfig,
+    domain_routes: Vec<DomainRoute>,
 }
 
 impl Default for DnsConfig {
     fn default() -> Self {
         Self {
             config: InnerDnsConfig::Default,
+            domain_routes: Vec::new(),
         }
     }
 }
@@ -50,8 +175,25 @@ impl DnsConfig {
                 tunnel_config: tunnel_config.to_owned(),
                 non_tunnel_config: non_tunnel_config.to_owned(),
             },
+            domain_routes: Vec::new(),
         }
     }
+
+    /// Resolve the given domains using their own servers instead of the tunnel DNS. Routes
+    /// without a domain or servers are ignored.
+    pub fn with_domain_routes(mut self, domain_routes: &[DomainRoute]) -> Self {
+        self.domain_routes = domain_routes
+            .iter()
+            .filter_map(|route| {
+                let normalized = route.normalize();
+                if normalized.is_none() {
+                    tracing::warn!("Ignoring invalid DNS domain route: {route:?}");
+                }
+                normalized
+            })
+            .collect();
+        self
+    }
 }
 
 impl DnsConfig {
@@ -64,6 +206,7 @@ impl DnsConfig {
             InnerDnsConfig::Default => ResolvedDnsConfig {
                 tunnel_config: default_tun_config.to_owned(),
                 non_tunnel_config: vec![],
+                domain_routes: self.domain_routes.clone(),
                 #[cfg(target_os = "macos")]
                 port,
             },
@@ -73,6 +216,7 @@ impl DnsConfig {
             } => ResolvedDnsConfig {
                 tunnel_config: tunnel_config.to_owned(),
                 non_tunnel_config: non_tunnel_config.to_owned(),
+                domain_routes: self.domain_routes.clone(),
                 #[cfg(target_os = "macos")]
                 port,
             },
@@ -104,6 +248,8 @@ pub struct ResolvedDnsConfig {
     /// For the most part, the tunnel state machine will not handle any of this configuration
     /// on non-tunnel interface, only allow them in the firewall.
     non_tunnel_config: Vec<IpAddr>,
+    /// Domains resolved by their own servers outside the tunnel
+    domain_routes: Vec<DomainRoute>,
     /// Port to use
     #[cfg(target_os = "macos")]
     port: u16,
@@ -117,6 +263,11 @@ impl fmt::Display for ResolvedDnsConfig {
         f.write_str(" Non-tunnel DNS: ")?;
         Self::fmt_addr_set(f, &self.non_tunnel_config)?;
 
+        for route in &self.domain_routes {
+            write!(f, " {}: ", route.domain)?;
+            Self::fmt_addr_set(f, &route.servers)?;
+        }
+
         #[cfg(target_os = "macos")]
         write!(f, " Port: {}", self.port)?;
 
@@ -148,6 +299,22 @@ impl ResolvedDnsConfig {
         &self.non_tunnel_config
     }
 
+    /// Domains resolved by their own servers outside the tunnel
+    pub fn domain_routes(&self) -> &[DomainRoute] {
+        &self.domain_routes
+    }
+
+    /// Addresses to allow on non-tunnel interface, including the servers of domain routes.
+    pub fn non_tunnel_servers(&self) -> Vec<IpAddr> {
+        let mut servers = self.non_tunnel_config.clone();
+        for server in self.domain_routes.iter().flat_map(|route| &route.servers) {
+            if !servers.contains(server) {
+                servers.push(*server);
+            }
+        }
+        servers
+    }
+
     /// Consume `self` and return a vector of all addresses
     pub fn addresses(self) -> impl Iterator<Item = IpAddr> {
         self.non_tunnel_config.into_iter().chain(self.tunnel_config)
@@ -170,31 +337,53 @@ impl ResolvedDnsConfig {
 /// Sets and monitors system DNS settings. Makes sure the desired DNS servers are being used.
 pub struct DnsMonitor {
     inner: imp::DnsMonitor,
+    backend: DnsBackend,
+    applied: Option<(String, Vec<IpAddr>)>,
 }
 
 impl DnsMonitor {
     /// Returns a new `DnsMonitor` that can set and monitor the system DNS.
     pub fn new(
+        backend: DnsBackend,
         #[cfg(target_os = "linux")] route_manager: RouteManagerHandle,
     ) -> Result<Self, Error> {
         Ok(DnsMonitor {
             inner: imp::DnsMonitor::new(
+                #[cfg(target_os = "linux")]
+                backend,
                 #[cfg(target_os = "linux")]
                 route_manager,
             )?,
+            backend,
+            applied: None,
         })
     }
 
     /// Set DNS to the given servers. And start monitoring the system for changes.
     pub async fn set(&mut self, interface: &str, config: ResolvedDnsConfig) -> Result<(), Error> {
         tracing::info!("Setting DNS servers: {config}");
-        self.inner.set(interface, config).await
+        let servers = config.tunnel_config().to_vec();
+        self.applied = None;
+        self.inner.set(interface, config).await?;
+        self.applied = Some((interface.to_owned(), servers));
+
+        match verify(&self.status()).await {
+            DnsVerification::Verified => tracing::debug!("System resolver uses the tunnel DNS"),
+            DnsVerification::Mismatch { resolver_servers } => tracing::warn!(
+                "System resolver does not use the tunnel DNS, it uses: {resolver_servers:?}"
+            ),
+            DnsVerification::Inconclusive(reason) => {
+                tracing::debug!("Could not verify the system resolver: {reason}")
+            }
+        }
+        Ok(())
     }
 
     /// Reset system DNS settings to what it was before being set by this instance.
     /// This succeeds if the interface does not exist.
     pub async fn reset(&mut self) -> Result<(), Error> {
         tracing::info!("Resetting DNS");
+        self.applied = None;
         self.inner.reset().await
     }
 
@@ -203,14 +392,27 @@ impl DnsMonitor {
     /// as the interface will be destroyed.
     pub async fn reset_before_interface_removal(&mut self) -> Result<(), Error> {
         tracing::info!("Resetting DNS");
+        self.applied = None;
         self.inner.reset_before_interface_removal().await
     }
+
+    /// Returns the backend in use and the DNS servers currently applied.
+    pub fn status(&self) -> DnsStatus {
+        let (interface, servers) = self.applied.clone().unzip();
+        DnsStatus {
+            configured_backend: self.backend,
+            active_backend: self.inner.active_backend(),
+            interface,
+            servers: servers.unwrap_or_default(),
+        }
+    }
 }
 
 trait DnsMonitorT: Sized {
     type Error: std::error::Error;
 
     fn new(
+        #[cfg(target_os = "linux")] backend: DnsBackend,
         #[cfg(target_os = "linux")] route_manager: RouteManagerHandle,
     ) -> Result<Self, Self::Error>;
 
@@ -222,4 +424,66 @@ trait DnsMonitorT: Sized {
     async fn reset_before_interface_removal(&mut self) -> Result<(), Self::Error> {
         self.reset().await
     }
+
+    /// The backend used to manage DNS, if the platform has more than one.
+    fn active_backend(&self) -> Option<DnsBackend> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_backend_round_trip() {
+        for backend in [
+            DnsBackend::Auto,
+            DnsBackend::SystemdResolved,
+            DnsBackend::NetworkManager,
+            DnsBackend::Resolvconf,
+            DnsBackend::StaticFile,
+        ] {
+            assert_eq!(backend.to_string().parse::<DnsBackend>(), Ok(backend));
+        }
+        assert_eq!("systemd".parse(), Ok(DnsBackend::SystemdResolved));
+        assert!("dnsmasq".parse::<DnsBackend>().is_err());
+    }
+
+    #[test]
+    fn domain_routes() {
+        let lan: IpAddr = "192.168.1.1".parse().unwrap();
+        let tunnel: IpAddr = "10.64.0.1".parse().unwrap();
+        let routes = [
+            DomainRoute {
+                domain: "*.Corp.Example.".to_owned(),
+                servers: vec![lan],
+            },
+            DomainRoute {
+                domain: "empty.example".to_owned(),
+                servers: vec![],
+            },
+        ];
+
+        let config = DnsConfig::from_addresses(&[tunnel], &[])
+            .with_domain_routes(&routes)
+            .resolve(
+                &[],
+                #[cfg(target_os = "macos")]
+                53,
+            );
+
+        let [route] = config.domain_routes() else {
+            panic!("expected a single route: {:?}", config.domain_routes());
+        };
+        assert_eq!(route.domain, "corp.example");
+        assert!(route.matches("corp.example"));
+        assert!(route.matches("git.CORP.example."));
+        assert!(!route.matches("notcorp.example"));
+        assert!(!route.matches("example"));
+
+        assert_eq!(config.tunnel_config(), &[tunnel]);
+        assert!(config.non_tunnel_config().is_empty());
+        assert_eq!(config.non_tunnel_servers(), vec![lan]);
+    }
 }
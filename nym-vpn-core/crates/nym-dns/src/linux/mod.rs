@@ -7,7 +7,10 @@ mod resolvconf;
 mod static_resolv_conf;
 mod systemd_resolved;
 
-use std::{env, fmt, net::IpAddr};
+use std::{
+    env, fmt,
+    net::{IpAddr, Ipv4Addr},
+};
 
 use nym_routing::RouteManagerHandle;
 
@@ -15,7 +18,10 @@ use self::{
     network_manager::NetworkManager, resolvconf::Resolvconf, static_resolv_conf::StaticResolvConf,
     systemd_resolved::SystemdResolved,
 };
-use super::ResolvedDnsConfig;
+use super::{DnsBackend, DnsStatus, DnsVerification, DomainRoute, ResolvedDnsConfig};
+
+const RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
+const SYSTEMD_RESOLVED_STUB: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 53));
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -44,17 +50,24 @@ pub enum Error {
 }
 
 pub struct DnsMonitor {
+    backend: DnsBackend,
     route_manager: RouteManagerHandle,
     inner: Option<DnsMonitorHolder>,
+    // Detecting the backend probes several services over D-Bus, so it's only redone when the DNS
+    // is set.
+    detected_backend: Option<DnsBackend>,
 }
 
 impl super::DnsMonitorT for DnsMonitor {
     type Error = Error;
 
-    fn new(route_manager: RouteManagerHandle) -> Result<Self> {
+    fn new(backend: DnsBackend, route_manager: RouteManagerHandle) -> Result<Self> {
+        let backend = backend_with_env_override(backend);
         Ok(DnsMonitor {
+            backend,
             route_manager,
             inner: None,
+            detected_backend: detect_backend(backend),
         })
     }
 
@@ -62,9 +75,17 @@ impl super::DnsMonitorT for DnsMonitor {
         let servers = config.tunnel_config();
         self.reset().await?;
         // Creating a new DNS monitor for each set, in case the system changed how it manages DNS.
-        let mut inner = DnsMonitorHolder::new()?;
+        let mut inner = DnsMonitorHolder::new(self.backend)?;
+        self.detected_backend = Some(inner.backend());
         if !servers.is_empty() {
-            inner.set(&self.route_manager, interface, servers).await?;
+            inner
+                .set(
+                    &self.route_manager,
+                    interface,
+                    servers,
+                    config.domain_routes(),
+                )
+                .await?;
             self.inner = Some(inner);
         }
         Ok(())
@@ -76,6 +97,135 @@ impl super::DnsMonitorT for DnsMonitor {
         }
         Ok(())
     }
+
+    fn active_backend(&self) -> Option<DnsBackend> {
+        match &self.inner {
+            Some(inner) => Some(inner.backend()),
+            None => self.detected_backend,
+        }
+    }
+}
+
+// `NYM_DNS_MODULE` used to be the only way of picking the backend, keep honoring it when the
+// setting is left on auto.
+fn backend_with_env_override(backend: DnsBackend) -> DnsBackend {
+    if backend != DnsBackend::Auto {
+        return backend;
+    }
+
+    let Some(dns_module) = env::var_os("NYM_DNS_MODULE") else {
+        return backend;
+    };
+    match dns_module.to_str().map(str::parse::<DnsBackend>) {
+        Some(Ok(backend)) => {
+            tracing::warn!(
+                "NYM_DNS_MODULE is deprecated, set the DNS backend in the daemon config instead"
+            );
+            backend
+        }
+        _ => {
+            tracing::warn!("Ignoring unknown NYM_DNS_MODULE value: {:?}", dns_module);
+            DnsBackend::Auto
+        }
+    }
+}
+
+// Returns the backend that would be used for the given setting, without touching the system
+// configuration.
+fn detect_backend(backend: DnsBackend) -> Option<DnsBackend> {
+    match backend {
+        DnsBackend::Auto => {
+            if SystemdResolved::new().is_ok() {
+                Some(DnsBackend::SystemdResolved)
+            } else if NetworkManager::new().is_ok() {
+                Some(DnsBackend::NetworkManager)
+            } else if Resolvconf::new().is_ok() {
+                Some(DnsBackend::Resolvconf)
+            } else {
+                Some(DnsBackend::StaticFile)
+            }
+        }
+        backend => Some(backend),
+    }
+}
+
+pub async fn verify(status: &DnsStatus) -> DnsVerification {
+    match (status.active_backend, status.interface.as_deref()) {
+        (Some(DnsBackend::SystemdResolved), Some(interface)) => {
+            verify_systemd_resolved(interface, &status.servers).await
+        }
+        _ => verify_resolv_conf(&status.servers),
+    }
+}
+
+async fn verify_systemd_resolved(interface: &str, servers: &[IpAddr]) -> DnsVerification {
+    let nameservers = match read_resolv_conf_nameservers() {
+        Ok(nameservers) => nameservers,
+        Err(reason) => return DnsVerification::Inconclusive(reason),
+    };
+    // Link DNS is only used if queries go through the resolved stub, otherwise the listed
+    // nameservers are queried directly.
+    if !nameservers.contains(&SYSTEMD_RESOLVED_STUB) {
+        return compare_servers(servers, nameservers);
+    }
+
+    let link_servers = async {
+        let index = nym_common::linux::iface_index(interface).map_err(|e| e.to_string())?;
+        let handle = nym_dbus::systemd_resolved::SystemdResolved::new()
+            .map_err(|e| e.to_string())?
+            .async_handle();
+        handle
+            .get_dns(index)
+            .await
+            .map(|state| state.set_servers)
+            .map_err(|e| e.to_string())
+    };
+
+    match link_servers.await {
+        Ok(link_servers) => compare_servers(servers, link_servers),
+        Err(reason) => DnsVerification::Inconclusive(format!(
+            "failed to query systemd-resolved for {interface}: {reason}"
+        )),
+    }
+}
+
+fn verify_resolv_conf(servers: &[IpAddr]) -> DnsVerification {
+    match read_resolv_conf_nameservers() {
+        Ok(nameservers) if nameservers.contains(&SYSTEMD_RESOLVED_STUB) => {
+            DnsVerification::Inconclusive(format!(
+                "{RESOLV_CONF_PATH} points at the systemd-resolved stub"
+            ))
+        }
+        Ok(nameservers) => compare_servers(servers, nameservers),
+        Err(reason) => DnsVerification::Inconclusive(reason),
+    }
+}
+
+fn compare_servers(expected: &[IpAddr], resolver_servers: Vec<IpAddr>) -> DnsVerification {
+    let uses_expected = !resolver_servers.is_empty()
+        && resolver_servers
+            .iter()
+            .all(|server| expected.contains(server));
+    if uses_expected {
+        DnsVerification::Verified
+    } else {
+        DnsVerification::Mismatch { resolver_servers }
+    }
+}
+
+fn read_resolv_conf_nameservers() -> std::result::Result<Vec<IpAddr>, String> {
+    let contents = std::fs::read_to_string(RESOLV_CONF_PATH)
+        .map_err(|e| format!("failed to read {RESOLV_CONF_PATH}: {e}"))?;
+    let config = resolv_conf::Config::parse(contents)
+        .map_err(|e| format!("failed to parse {RESOLV_CONF_PATH}: {e}"))?;
+    Ok(config
+        .nameservers
+        .into_iter()
+        .map(|ip| match ip {
+            resolv_conf::ScopedIp::V4(ip) => IpAddr::V4(ip),
+            resolv_conf::ScopedIp::V6(ip, _) => IpAddr::V6(ip),
+        })
+        .collect())
 }
 
 pub enum DnsMonitorHolder {
@@ -99,20 +249,29 @@ impl fmt::Display for DnsMonitorHolder {
 }
 
 impl DnsMonitorHolder {
-    fn new() -> Result<Self> {
-        let dns_module = env::var_os("NYM_DNS_MODULE");
-
-        let manager = match dns_module.as_ref().and_then(|value| value.to_str()) {
-            Some("static-file") => DnsMonitorHolder::StaticResolvConf(StaticResolvConf::new()?),
-            Some("resolvconf") => DnsMonitorHolder::Resolvconf(Resolvconf::new()?),
-            Some("systemd") => DnsMonitorHolder::SystemdResolved(SystemdResolved::new()?),
-            Some("network-manager") => DnsMonitorHolder::NetworkManager(NetworkManager::new()?),
-            Some(_) | None => Self::with_detected_dns_manager()?,
+    fn new(backend: DnsBackend) -> Result<Self> {
+        let manager = match backend {
+            DnsBackend::StaticFile => DnsMonitorHolder::StaticResolvConf(StaticResolvConf::new()?),
+            DnsBackend::Resolvconf => DnsMonitorHolder::Resolvconf(Resolvconf::new()?),
+            DnsBackend::SystemdResolved => {
+                DnsMonitorHolder::SystemdResolved(SystemdResolved::new()?)
+            }
+            DnsBackend::NetworkManager => DnsMonitorHolder::NetworkManager(NetworkManager::new()?),
+            DnsBackend::Auto => Self::with_detected_dns_manager()?,
         };
         tracing::debug!("Managing DNS via {}", manager);
         Ok(manager)
     }
 
+    fn backend(&self) -> DnsBackend {
+        match self {
+            DnsMonitorHolder::SystemdResolved(..) => DnsBackend::SystemdResolved,
+            DnsMonitorHolder::NetworkManager(..) => DnsBackend::NetworkManager,
+            DnsMonitorHolder::Resolvconf(..) => DnsBackend::Resolvconf,
+            DnsMonitorHolder::StaticResolvConf(..) => DnsBackend::StaticFile,
+        }
+    }
+
     fn with_detected_dns_manager() -> Result<Self> {
         SystemdResolved::new()
             .map(DnsMonitorHolder::SystemdResolved)
@@ -137,8 +296,14 @@ impl DnsMonitorHolder {
         route_manager: &RouteManagerHandle,
         interface: &str,
         servers: &[IpAddr],
+        domain_routes: &[DomainRoute],
     ) -> Result<()> {
         use self::DnsMonitorHolder::*;
+        if !domain_routes.is_empty() && !matches!(self, SystemdResolved(_)) {
+            tracing::warn!(
+                "Per-domain DNS routes are only supported with systemd-resolved, ignoring them"
+            );
+        }
         match self {
             Resolvconf(ref mut resolvconf) => resolvconf.set_dns(interface, servers)?,
             StaticResolvConf(ref mut static_resolv_conf) => {
@@ -146,7 +311,7 @@ impl DnsMonitorHolder {
             }
             SystemdResolved(ref mut systemd_resolved) => {
                 systemd_resolved
-                    .set_dns(route_manager.clone(), interface, servers)
+                    .set_dns(route_manager.clone(), interface, servers, domain_routes)
                     .await?
             }
             NetworkManager(ref mut network_manager) => {
@@ -172,3 +337,33 @@ impl DnsMonitorHolder {
 pub fn will_use_nm() -> bool {
     crate::imp::SystemdResolved::new().is_err() && crate::imp::NetworkManager::new().is_ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_resolver_servers() {
+        let tunnel_dns: Vec<IpAddr> = vec!["10.64.0.1".parse().unwrap()];
+
+        assert_eq!(
+            compare_servers(&tunnel_dns, tunnel_dns.clone()),
+            DnsVerification::Verified
+        );
+
+        let leaking: Vec<IpAddr> = vec!["10.64.0.1".parse().unwrap(), "1.1.1.1".parse().unwrap()];
+        assert_eq!(
+            compare_servers(&tunnel_dns, leaking.clone()),
+            DnsVerification::Mismatch {
+                resolver_servers: leaking
+            }
+        );
+
+        assert_eq!(
+            compare_servers(&tunnel_dns, vec![]),
+            DnsVerification::Mismatch {
+                resolver_servers: vec![]
+            }
+        );
+    }
+}
@@ -2,15 +2,17 @@
 // Copyright 2024 Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::net::IpAddr;
+use std::{collections::BTreeMap, net::IpAddr};
 
 use nym_common::{
     linux::{iface_index, IfaceIndexLookupError},
     ErrorExt,
 };
-use nym_dbus::systemd_resolved::{AsyncHandle, SystemdResolved as DbusInterface};
+use nym_dbus::systemd_resolved::{AsyncHandle, DnsState, SystemdResolved as DbusInterface};
 use nym_routing::RouteManagerHandle;
 
+use crate::DomainRoute;
+
 pub(crate) use nym_dbus::systemd_resolved::Error as SystemdDbusError;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -22,11 +24,16 @@ pub enum Error {
 
     #[error("Failed to resolve interface index with error {0}")]
     InterfaceNameError(#[from] IfaceIndexLookupError),
+
+    #[error("Failed to look up the route to a DNS server")]
+    RouteLookup(#[from] nym_routing::Error),
 }
 
 pub struct SystemdResolved {
     pub dbus_interface: AsyncHandle,
     tunnel_index: u32,
+    /// Links given routing domains for split DNS, with the servers and domains they had before.
+    split_links: Vec<(DnsState, Vec<(String, bool)>)>,
 }
 
 impl SystemdResolved {
@@ -36,6 +43,7 @@ impl SystemdResolved {
         let systemd_resolved = SystemdResolved {
             dbus_interface,
             tunnel_index: 0,
+            split_links: Vec::new(),
         };
 
         Ok(systemd_resolved)
@@ -43,9 +51,10 @@ impl SystemdResolved {
 
     pub async fn set_dns(
         &mut self,
-        _route_manager: RouteManagerHandle,
+        route_manager: RouteManagerHandle,
         interface_name: &str,
         servers: &[IpAddr],
+        domain_routes: &[DomainRoute],
     ) -> Result<()> {
         let tunnel_index = iface_index(interface_name)?;
         self.tunnel_index = tunnel_index;
@@ -67,10 +76,99 @@ impl SystemdResolved {
             .set_dns(self.tunnel_index, servers.to_vec())
             .await?;
 
+        if let Err(error) = self.set_domain_routes(&route_manager, domain_routes).await {
+            tracing::error!("Failed to set DNS domain routes: {}", error.display_chain());
+        }
+
         Ok(())
     }
 
+    /// Adds the domains as routing domains of the links the servers of each route are reachable
+    /// through, next to the servers and domains the links already have. systemd-resolved sends
+    /// queries to the link with the longest matching routing domain, so these take precedence
+    /// over the catch-all domain of the tunnel.
+    async fn set_domain_routes(
+        &mut self,
+        route_manager: &RouteManagerHandle,
+        domain_routes: &[DomainRoute],
+    ) -> Result<()> {
+        let mut links: BTreeMap<u32, (Vec<IpAddr>, Vec<String>)> = BTreeMap::new();
+        for route in domain_routes {
+            let Some(server) = route.servers.first() else {
+                continue;
+            };
+            let interface = route_manager
+                .get_destination_route(*server, None)
+                .await?
+                .and_then(|route| route.get_node().get_device().map(ToOwned::to_owned));
+            let Some(interface) = interface else {
+                tracing::warn!("No route to {server}, not routing {} to it", route.domain);
+                continue;
+            };
+
+            let index = iface_index(&interface)?;
+            // Giving the tunnel link the route servers would make it use them for all queries
+            if index == self.tunnel_index {
+                tracing::warn!(
+                    "{server} is reached through the tunnel, not routing {} to it",
+                    route.domain
+                );
+                continue;
+            }
+
+            let (servers, domains) = links.entry(index).or_default();
+            for server in &route.servers {
+                if !servers.contains(server) {
+                    servers.push(*server);
+                }
+            }
+            domains.push(route.domain.clone());
+        }
+
+        for (index, (servers, domains)) in links {
+            let previous_state = self.dbus_interface.get_dns(index).await?;
+            let previous_domains = self.dbus_interface.get_domains(index).await?;
+
+            let mut new_servers = previous_state.set_servers.clone();
+            for server in servers {
+                if !new_servers.contains(&server) {
+                    new_servers.push(server);
+                }
+            }
+            let mut new_domains = previous_domains.clone();
+            new_domains.extend(domains.into_iter().map(|domain| (domain, true)));
+
+            self.split_links.push((previous_state, previous_domains));
+            let _ = self.dbus_interface.set_dns(index, new_servers).await?;
+            self.dbus_interface
+                .replace_domains(index, new_domains)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn reset_domain_routes(&mut self) {
+        for (state, domains) in self.split_links.drain(..) {
+            let index = state.interface_index;
+            if let Err(error) = self.dbus_interface.set_dns_state(state).await {
+                tracing::error!(
+                    "Failed to restore DNS servers of link {index}: {}",
+                    error.display_chain()
+                );
+            }
+            if let Err(error) = self.dbus_interface.replace_domains(index, domains).await {
+                tracing::error!(
+                    "Failed to restore DNS domains of link {index}: {}",
+                    error.display_chain()
+                );
+            }
+        }
+    }
+
     pub async fn reset(&mut self) -> Result<()> {
+        self.reset_domain_routes().await;
+
         if let Err(error) = self
             .dbus_interface
             .set_domains(self.tunnel_index, &[])
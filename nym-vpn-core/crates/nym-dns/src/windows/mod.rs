@@ -26,6 +26,10 @@ pub enum Error {
     /// Failed to set DNS config using the tcpip module.
     #[error("Error in tcpip module")]
     Tcpip(#[from] tcpip::Error),
+
+    /// Per-domain DNS routes were requested, which can't be applied on Windows.
+    #[error("Per-domain DNS routes are not supported on Windows")]
+    DomainRoutesNotSupported,
 }
 
 pub struct DnsMonitor {
@@ -51,6 +55,9 @@ impl DnsMonitorT for DnsMonitor {
     }
 
     async fn set(&mut self, interface: &str, config: ResolvedDnsConfig) -> Result<(), Error> {
+        if !config.domain_routes().is_empty() {
+            return Err(Error::DomainRoutesNotSupported);
+        }
         match self.inner {
             DnsMonitorHolder::Auto(ref mut inner) => inner.set(interface, config).await?,
             DnsMonitorHolder::Iphlpapi(ref mut inner) => inner.set(interface, config).await?,
@@ -35,8 +35,8 @@ mod net;
 mod split_tunnel;
 use net::ALLOWED_LAN_NETS;
 pub use net::{
-    AllowedClients, AllowedEndpoint, AllowedTunnelTraffic, Endpoint, TransportProtocol,
-    TunnelInterface, TunnelMetadata,
+    AllowedClients, AllowedEndpoint, AllowedNonTunnelTraffic, AllowedTunnelTraffic, Endpoint,
+    TransportProtocol, TunnelInterface, TunnelMetadata,
 };
 
 pub use self::imp::Error;
@@ -146,6 +146,13 @@ pub enum FirewallPolicy {
         dns_config: ResolvedDnsConfig,
         /// Hosts that should be reachable outside of tunnel when connected.
         allowed_endpoints: Vec<AllowedEndpoint>,
+        /// Networks that should be reachable outside of tunnel when connected.
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        allowed_non_tunnel_traffic: AllowedNonTunnelTraffic,
+        /// Drop all IPv6 traffic except to the peer and allowed endpoints, e.g. when the tunnel
+        /// is IPv4 only.
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        block_ipv6: bool,
         /// Interface to redirect (VPN tunnel) traffic to
         #[cfg(target_os = "macos")]
         redirect_interface: Option<String>,
@@ -286,6 +293,10 @@ impl fmt::Display for FirewallPolicy {
                 #[cfg(not(target_os = "android"))]
                 dns_config,
                 allowed_endpoints,
+                #[cfg(any(target_os = "linux", target_os = "macos"))]
+                allowed_non_tunnel_traffic,
+                #[cfg(any(target_os = "linux", target_os = "macos"))]
+                block_ipv6,
                 ..
             } => {
                 #[cfg(not(target_os = "android"))]
@@ -293,14 +304,26 @@ impl fmt::Display for FirewallPolicy {
                 #[cfg(target_os = "android")]
                 let dns_str = "none".to_owned();
 
+                #[cfg(any(target_os = "linux", target_os = "macos"))]
+                let non_tunnel_str = allowed_non_tunnel_traffic.to_string();
+                #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+                let non_tunnel_str = "none".to_owned();
+
+                #[cfg(any(target_os = "linux", target_os = "macos"))]
+                let block_ipv6 = *block_ipv6;
+                #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+                let block_ipv6 = false;
+
                 write!(
                 f,
-                "Connected to {} over {}, {} LAN. Allowing endpoints: {}. Allowing non-tunnel DNS: {}",
+                "Connected to {} over {}, {} LAN, {} IPv6. Allowing endpoints: {}. Allowing non-tunnel DNS: {}. Allowing non-tunnel networks: {}",
                 display_peer_endpoints(peer_endpoints),
                 display_tunnel_interface(tunnel),
                 if *allow_lan { "Allowing" } else { "Blocking" },
+                if block_ipv6 { "Blocking" } else { "Allowing" },
                 display_allowed_endpoints(allowed_endpoints),
-                dns_str
+                dns_str,
+                non_tunnel_str
             )
             }
             FirewallPolicy::Blocked {
@@ -319,11 +342,11 @@ impl fmt::Display for FirewallPolicy {
 
 #[cfg(not(target_os = "android"))]
 fn display_allowed_non_tunnel_dns(dns_config: &ResolvedDnsConfig) -> String {
-    if dns_config.non_tunnel_config().is_empty() {
+    let servers = dns_config.non_tunnel_servers();
+    if servers.is_empty() {
         "none".to_owned()
     } else {
-        dns_config
-            .non_tunnel_config()
+        servers
             .iter()
             .map(|ip| ip.to_string())
             .collect::<Vec<_>>()
@@ -25,7 +25,9 @@ use super::{
     split_tunnel, FirewallArguments, FirewallPolicy,
 };
 
-use crate::{AllowedClients, TunnelInterface, DNS_TCP_PORTS, DNS_UDP_PORTS};
+use crate::{
+    AllowedClients, AllowedNonTunnelTraffic, TunnelInterface, DNS_TCP_PORTS, DNS_UDP_PORTS,
+};
 
 /// Priority for rules that tag split tunneling packets. Equals NF_IP_PRI_MANGLE.
 const MANGLE_CHAIN_PRIORITY: i32 = libc::NF_IP_PRI_MANGLE;
@@ -591,9 +593,9 @@ impl<'a> PolicyBatch<'a> {
                 allowed_exit_tunnel_traffic,
             } => {
                 let dns_endpoints = dns_config
-                    .non_tunnel_config()
-                    .iter()
-                    .flat_map(|server| get_allow_dns_endpoints_when_connecting(*server))
+                    .non_tunnel_servers()
+                    .into_iter()
+                    .flat_map(get_allow_dns_endpoints_when_connecting)
                     .collect::<Vec<_>>();
 
                 // todo: we should do fwmark, at some point
@@ -675,6 +677,8 @@ impl<'a> PolicyBatch<'a> {
                 allow_lan,
                 allowed_endpoints,
                 dns_config,
+                allowed_non_tunnel_traffic,
+                block_ipv6,
             } => {
                 peer_endpoints
                     .iter()
@@ -683,6 +687,12 @@ impl<'a> PolicyBatch<'a> {
                     .iter()
                     .for_each(|endpoint| self.add_allow_endpoint_rules(endpoint));
 
+                // Must come before the DNS, tunnel and LAN rules, so that no IPv6 traffic is
+                // accepted by them.
+                if *block_ipv6 {
+                    self.add_drop_ipv6_rules();
+                }
+
                 for server in dns_config.tunnel_config() {
                     self.add_allow_tunnel_dns_rule(
                         &tunnel.exit_metadata().interface,
@@ -695,7 +705,7 @@ impl<'a> PolicyBatch<'a> {
                         *server,
                     )?;
                 }
-                for server in dns_config.non_tunnel_config() {
+                for server in &dns_config.non_tunnel_servers() {
                     self.add_allow_local_dns_rule(
                         &tunnel.exit_metadata().interface,
                         TransportProtocol::Udp,
@@ -719,6 +729,10 @@ impl<'a> PolicyBatch<'a> {
                     }
                 }
 
+                // Must come after the tunnel rules, so that traffic which is only allowed through
+                // the tunnel is accepted before the remaining traffic is matched.
+                self.add_allow_non_tunnel_traffic_rules(allowed_non_tunnel_traffic);
+
                 *allow_lan
             }
             FirewallPolicy::Blocked {
@@ -904,6 +918,17 @@ impl<'a> PolicyBatch<'a> {
         }
     }
 
+    /// Drops all IPv6 traffic which has not been accepted by an earlier rule.
+    fn add_drop_ipv6_rules(&mut self) {
+        for chain in &[&self.out_chain, &self.forward_chain, &self.in_chain] {
+            let mut drop_rule = Rule::new(chain);
+            drop_rule.add_expr(&nft_expr!(meta nfproto));
+            drop_rule.add_expr(&nft_expr!(cmp == libc::NFPROTO_IPV6 as u8));
+            add_verdict(&mut drop_rule, &Verdict::Drop);
+            self.batch.add(&drop_rule, nftnl::MsgType::Add);
+        }
+    }
+
     fn add_allow_in_tunnel_endpoint_rules(
         &mut self,
         tunnel_interface: &str,
@@ -965,6 +990,61 @@ impl<'a> PolicyBatch<'a> {
         }
     }
 
+    fn add_allow_non_tunnel_traffic_rules(&mut self, traffic: &AllowedNonTunnelTraffic) {
+        match traffic {
+            AllowedNonTunnelTraffic::None => (),
+            AllowedNonTunnelTraffic::Networks(networks) => {
+                for chain in &[&self.out_chain, &self.forward_chain] {
+                    for net in networks {
+                        let mut out_rule = Rule::new(chain);
+                        check_net(&mut out_rule, End::Dst, *net);
+                        add_verdict(&mut out_rule, &Verdict::Accept);
+                        self.batch.add(&out_rule, nftnl::MsgType::Add);
+                    }
+                }
+                for net in networks {
+                    let mut in_rule = Rule::new(&self.in_chain);
+                    check_net(&mut in_rule, End::Src, *net);
+                    add_verdict(&mut in_rule, &Verdict::Accept);
+                    self.batch.add(&in_rule, nftnl::MsgType::Add);
+                }
+            }
+            AllowedNonTunnelTraffic::AllExcept(networks) => {
+                // Traffic to these networks is only allowed through the tunnel
+                for chain in &[&self.out_chain, &self.forward_chain] {
+                    for net in networks {
+                        let mut drop_rule = Rule::new(chain);
+                        check_net(&mut drop_rule, End::Dst, *net);
+                        add_verdict(&mut drop_rule, &Verdict::Drop);
+                        self.batch.add(&drop_rule, nftnl::MsgType::Add);
+                    }
+
+                    let mut out_rule = Rule::new(chain);
+                    add_verdict(&mut out_rule, &Verdict::Accept);
+                    self.batch.add(&out_rule, nftnl::MsgType::Add);
+                }
+
+                for net in networks {
+                    let mut drop_rule = Rule::new(&self.in_chain);
+                    check_net(&mut drop_rule, End::Src, *net);
+                    add_verdict(&mut drop_rule, &Verdict::Drop);
+                    self.batch.add(&drop_rule, nftnl::MsgType::Add);
+                }
+
+                // Only accept incoming traffic of connections made outside of the tunnel
+                let mut in_rule = Rule::new(&self.in_chain);
+                in_rule.add_expr(&nft_expr!(ct state));
+                let allowed_states = (nftnl::expr::ct::States::ESTABLISHED
+                    | nftnl::expr::ct::States::RELATED)
+                    .bits();
+                in_rule.add_expr(&nft_expr!(bitwise mask allowed_states, xor 0u32));
+                in_rule.add_expr(&nft_expr!(cmp != 0u32));
+                add_verdict(&mut in_rule, &Verdict::Accept);
+                self.batch.add(&in_rule, nftnl::MsgType::Add);
+            }
+        }
+    }
+
     fn add_allow_lan_rules(&mut self) {
         // Output and forward chains
         for chain in &[&self.out_chain, &self.forward_chain] {
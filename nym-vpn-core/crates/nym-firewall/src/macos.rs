@@ -15,8 +15,8 @@ use pfctl::{DropAction, FilterRuleAction, Ip, RedirectRule, Uid};
 
 use super::{
     net::{
-        AllowedEndpoint, AllowedTunnelTraffic, TransportProtocol, TunnelInterface, TunnelMetadata,
-        ALLOWED_LAN_MULTICAST_NETS, ALLOWED_LAN_NETS,
+        AllowedEndpoint, AllowedNonTunnelTraffic, AllowedTunnelTraffic, TransportProtocol,
+        TunnelInterface, TunnelMetadata, ALLOWED_LAN_MULTICAST_NETS, ALLOWED_LAN_NETS,
     },
     FirewallArguments, FirewallPolicy, DNS_TCP_PORTS, DNS_UDP_PORTS,
 };
@@ -469,7 +469,7 @@ impl Firewall {
             } => {
                 let mut rules = Vec::new();
 
-                for server in dns_config.non_tunnel_config() {
+                for server in &dns_config.non_tunnel_servers() {
                     rules.append(&mut self.get_allow_dns_rules_when_connecting(*server)?);
                 }
 
@@ -531,6 +531,8 @@ impl Firewall {
                 allow_lan,
                 dns_config,
                 allowed_endpoints,
+                allowed_non_tunnel_traffic,
+                block_ipv6,
                 redirect_interface,
                 dns_redirect_port: _,
             } => {
@@ -546,7 +548,7 @@ impl Firewall {
                             .get_allow_tunnel_dns_rules_when_connected(exit_tunnel, *server)?,
                     );
                 }
-                for server in dns_config.non_tunnel_config() {
+                for server in &dns_config.non_tunnel_servers() {
                     rules.append(
                         &mut self.get_allow_local_dns_rules_when_connected(exit_tunnel, *server)?,
                     );
@@ -560,6 +562,12 @@ impl Firewall {
                     rules.push(self.get_allowed_endpoint_rule(allowed_endpoint)?);
                 }
 
+                // Must come before the tunnel and LAN rules, so that no IPv6 traffic is passed
+                // by them.
+                if *block_ipv6 {
+                    rules.push(self.get_block_ipv6_rule()?);
+                }
+
                 // Important to block DNS *before* we allow the tunnel and allow LAN. So DNS
                 // can't leak to the wrong IPs in the tunnel or on the LAN.
                 rules.append(&mut self.get_block_dns_rules()?);
@@ -568,6 +576,10 @@ impl Firewall {
                     rules.append(&mut self.get_allow_lan_rules()?);
                 }
 
+                if let AllowedNonTunnelTraffic::Networks(networks) = allowed_non_tunnel_traffic {
+                    rules.append(&mut self.get_allow_networks_rules(networks)?);
+                }
+
                 if let Some(redirect_interface) = redirect_interface {
                     enable_forwarding();
 
@@ -588,7 +600,12 @@ impl Firewall {
                         &AllowedTunnelTraffic::All,
                     )?);
 
-                    if *NAT_WORKAROUND {
+                    // In include-only mode the default route stays outside of the tunnel, so
+                    // traffic must not be forced into it
+                    if let AllowedNonTunnelTraffic::AllExcept(networks) = allowed_non_tunnel_traffic
+                    {
+                        rules.append(&mut self.get_allow_all_networks_except_rules(networks)?);
+                    } else if *NAT_WORKAROUND {
                         rules.push(self.route_everything_to(&exit_tunnel.interface)?);
                     }
                 }
@@ -811,6 +828,14 @@ impl Firewall {
         Ok(vec![block_tcp_dns_rule, block_udp_dns_rule])
     }
 
+    /// Drops all IPv6 traffic which has not been passed by an earlier rule.
+    fn get_block_ipv6_rule(&self) -> Result<pfctl::FilterRule> {
+        self.create_rule_builder(FilterRuleAction::Drop(DropAction::Drop))
+            .quick(true)
+            .af(pfctl::AddrFamily::Ipv6)
+            .build()
+    }
+
     fn get_allow_tunnel_rules(
         &self,
         tunnel_interface: &str,
@@ -925,6 +950,54 @@ impl Firewall {
         Ok(rules)
     }
 
+    fn get_allow_networks_rules(&self, networks: &[IpNetwork]) -> Result<Vec<pfctl::FilterRule>> {
+        let mut rules = vec![];
+        for net in networks {
+            let mut rule_builder = self.create_rule_builder(FilterRuleAction::Pass);
+            rule_builder.quick(true);
+            let allow_out = rule_builder
+                .direction(pfctl::Direction::Out)
+                .from(pfctl::Ip::Any)
+                .keep_state(pfctl::StatePolicy::Keep)
+                .to(pfctl::Ip::from(*net))
+                .build()?;
+            let allow_in = rule_builder
+                .direction(pfctl::Direction::In)
+                .from(pfctl::Ip::from(*net))
+                .to(pfctl::Ip::Any)
+                .build()?;
+            rules.push(allow_out);
+            rules.push(allow_in);
+        }
+        Ok(rules)
+    }
+
+    /// Allow outgoing traffic outside of the tunnel, except to `networks`. Must come after the
+    /// tunnel rules, which accept traffic to `networks` over the tunnel interface.
+    fn get_allow_all_networks_except_rules(
+        &self,
+        networks: &[IpNetwork],
+    ) -> Result<Vec<pfctl::FilterRule>> {
+        let mut rules = vec![];
+        for net in networks {
+            let block_out = self
+                .create_rule_builder(FilterRuleAction::Drop(DropAction::Drop))
+                .quick(true)
+                .direction(pfctl::Direction::Out)
+                .to(pfctl::Ip::from(*net))
+                .build()?;
+            rules.push(block_out);
+        }
+        let allow_out = self
+            .create_rule_builder(FilterRuleAction::Pass)
+            .quick(true)
+            .direction(pfctl::Direction::Out)
+            .keep_state(pfctl::StatePolicy::Keep)
+            .build()?;
+        rules.push(allow_out);
+        Ok(rules)
+    }
+
     fn get_split_tunnel_rules(
         &self,
         from_interface: &str,
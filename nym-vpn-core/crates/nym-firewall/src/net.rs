@@ -87,6 +87,37 @@ impl fmt::Display for AllowedTunnelTraffic {
     }
 }
 
+/// What traffic to allow outside of the tunnel while connected.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub enum AllowedNonTunnelTraffic {
+    /// All traffic has to go through the tunnel.
+    #[default]
+    None,
+    /// Allow traffic to these networks outside of the tunnel.
+    Networks(Vec<IpNetwork>),
+    /// Allow traffic outside of the tunnel to all networks but these.
+    AllExcept(Vec<IpNetwork>),
+}
+
+impl fmt::Display for AllowedNonTunnelTraffic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let join = |networks: &[IpNetwork]| {
+            networks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match self {
+            AllowedNonTunnelTraffic::None => "none".fmt(f),
+            AllowedNonTunnelTraffic::Networks(networks) => join(networks).fmt(f),
+            AllowedNonTunnelTraffic::AllExcept(networks) => {
+                write!(f, "all except {}", join(networks))
+            }
+        }
+    }
+}
+
 /// Represents a network layer IP address together with the transport layer protocol and port.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Endpoint {
@@ -197,6 +197,25 @@ impl From<nym_vpn_api_client::response::ProbeOutcome> for ProbeOutcome {
     }
 }
 
+impl From<nym_harbour_master_client::ProbeOutcome> for ProbeOutcome {
+    fn from(outcome: nym_harbour_master_client::ProbeOutcome) -> Self {
+        ProbeOutcome {
+            as_entry: Entry {
+                can_connect: outcome.as_entry.can_connect,
+                can_route: outcome.as_entry.can_route,
+            },
+            as_exit: outcome.as_exit.map(|exit| Exit {
+                can_connect: exit.can_connect,
+                can_route_ip_v4: exit.can_route_ip_v4,
+                can_route_ip_external_v4: exit.can_route_ip_external_v4,
+                can_route_ip_v6: exit.can_route_ip_v6,
+                can_route_ip_external_v6: exit.can_route_ip_external_v6,
+            }),
+            wg: None,
+        }
+    }
+}
+
 impl From<nym_vpn_api_client::response::Entry> for Entry {
     fn from(entry: nym_vpn_api_client::response::Entry) -> Self {
         Entry {
@@ -428,6 +447,14 @@ impl GatewayList {
             .retain(|gateway| gateway.identity() != entry_gateway.identity());
     }
 
+    /// Add the gateways to the list, replacing listed gateways with the same identity.
+    pub fn insert_or_replace(&mut self, gateways: impl IntoIterator<Item = Gateway>) {
+        for gateway in gateways {
+            self.remove_gateway(&gateway);
+            self.gateways.push(gateway);
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.gateways.len()
     }
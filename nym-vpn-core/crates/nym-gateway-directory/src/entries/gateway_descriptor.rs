@@ -0,0 +1,180 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt, net::IpAddr};
+
+use nym_sdk::mixnet::NodeIdentity;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Result, AuthAddress, Error, Gateway, IpPacketRouterAddress, Location};
+
+/// A gateway provided manually instead of through the directory, e.g. a self-hosted nym-node
+/// that isn't listed yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayDescriptor {
+    /// Base58 encoded identity key of the gateway.
+    pub identity: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub moniker: String,
+
+    /// Hostname of the gateway, defaults to the first IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    /// IP addresses the WireGuard tunnel is established with.
+    pub ips: Vec<IpAddr>,
+
+    /// Nym address of the authenticator, required to register in WireGuard mode.
+    pub authenticator_address: String,
+
+    /// Nym address of the IP packet router, required to use the gateway as a mixnet exit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipr_address: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clients_ws_port: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clients_wss_port: Option<u16>,
+
+    /// Two letter ISO country code, used when selecting gateways by location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    /// Version of the nym-node, used to pick the authenticator protocol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl fmt::Display for GatewayDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.moniker.is_empty() {
+            write!(f, "{}", self.identity)
+        } else {
+            write!(f, "{} ({})", self.identity, self.moniker)
+        }
+    }
+}
+
+impl TryFrom<GatewayDescriptor> for Gateway {
+    type Error = Error;
+
+    fn try_from(descriptor: GatewayDescriptor) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidGatewayDescriptor {
+            identity: descriptor.identity.clone(),
+            reason: reason.to_string(),
+        };
+
+        let identity =
+            NodeIdentity::from_base58_string(&descriptor.identity).map_err(|source| {
+                Error::NodeIdentityFormattingError {
+                    identity: descriptor.identity.clone(),
+                    source,
+                }
+            })?;
+
+        if descriptor.ips.is_empty() {
+            return Err(invalid("at least one ip address is required"));
+        }
+
+        let authenticator_address =
+            AuthAddress::try_from_base58_string(&descriptor.authenticator_address)?;
+        if authenticator_address
+            .0
+            .is_some_and(|recipient| recipient.gateway() != identity)
+        {
+            return Err(invalid("authenticator address belongs to another gateway"));
+        }
+
+        let ipr_address = descriptor
+            .ipr_address
+            .as_deref()
+            .map(IpPacketRouterAddress::try_from_base58_string)
+            .transpose()?;
+        if ipr_address.is_some_and(|ipr_address| ipr_address.gateway() != identity) {
+            return Err(invalid(
+                "ip packet router address belongs to another gateway",
+            ));
+        }
+
+        let location = match &descriptor.country {
+            Some(code) if code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) => {
+                Some(Location {
+                    two_letter_iso_country_code: code.to_ascii_uppercase(),
+                    ..Default::default()
+                })
+            }
+            Some(_) => return Err(invalid("country must be a two letter ISO code")),
+            None => None,
+        };
+
+        let host = descriptor
+            .host
+            .or_else(|| descriptor.ips.first().map(ToString::to_string));
+
+        Ok(Gateway {
+            identity,
+            moniker: descriptor.moniker,
+            location,
+            ipr_address,
+            authenticator_address: Some(authenticator_address),
+            last_probe: None,
+            ips: descriptor.ips,
+            host,
+            clients_ws_port: descriptor.clients_ws_port,
+            clients_wss_port: descriptor.clients_wss_port,
+            mixnet_performance: None,
+            wg_performance: None,
+            wg_score: None,
+            mixnet_score: None,
+            version: descriptor.version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{self, ADDRESS, IDENTITY};
+
+    fn descriptor() -> GatewayDescriptor {
+        GatewayDescriptor {
+            moniker: "office".to_string(),
+            ipr_address: Some(ADDRESS.to_string()),
+            clients_ws_port: Some(9000),
+            country: Some("ch".to_string()),
+            version: Some("1.6.2".to_string()),
+            ..test_utils::descriptor(IDENTITY)
+        }
+    }
+
+    #[test]
+    fn valid_descriptor() {
+        let gateway = Gateway::try_from(descriptor()).unwrap();
+        assert_eq!(gateway.identity().to_base58_string(), IDENTITY);
+        assert_eq!(gateway.host(), Some(&"192.0.2.1".to_string()));
+        assert!(gateway.has_authenticator_address());
+        assert!(gateway.has_ipr_address());
+        assert!(gateway.is_two_letter_iso_country_code("CH"));
+    }
+
+    #[test]
+    fn invalid_descriptors() {
+        let mut no_ips = descriptor();
+        no_ips.ips.clear();
+        assert!(Gateway::try_from(no_ips).is_err());
+
+        let mut bad_identity = descriptor();
+        bad_identity.identity = "not-base58".to_string();
+        assert!(Gateway::try_from(bad_identity).is_err());
+
+        let mut other_gateway = descriptor();
+        other_gateway.identity = "FJDUECYAeosXhNGjxf8w5MJM7N2DfDwQznvWwTxJz6ft".to_string();
+        assert!(Gateway::try_from(other_gateway).is_err());
+
+        let mut bad_country = descriptor();
+        bad_country.country = Some("Switzerland".to_string());
+        assert!(Gateway::try_from(bad_country).is_err());
+    }
+}
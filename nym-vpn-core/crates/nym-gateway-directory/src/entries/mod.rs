@@ -3,5 +3,6 @@ pub(crate) mod country;
 pub(crate) mod entry_point;
 pub(crate) mod exit_point;
 pub(crate) mod gateway;
+pub(crate) mod gateway_descriptor;
 pub(crate) mod ipr_addresses;
 pub(crate) mod score;
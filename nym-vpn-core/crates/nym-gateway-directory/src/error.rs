@@ -85,6 +85,9 @@ pub enum Error {
 
     #[error("the provided gateway information is malformed")]
     MalformedGateway,
+
+    #[error("invalid gateway descriptor for {identity}: {reason}")]
+    InvalidGatewayDescriptor { identity: String, reason: String },
 }
 
 // Result type based on our error type
@@ -17,8 +17,10 @@ use crate::{
     entries::{
         country::Country,
         gateway::{Gateway, GatewayList, GatewayType, NymNodeList},
+        gateway_descriptor::GatewayDescriptor,
     },
     error::Result,
+    harbour_master::HarbourMasterProbes,
     Error, NymNode,
 };
 
@@ -30,6 +32,8 @@ pub struct Config {
     pub min_gateway_performance: Option<GatewayMinPerformance>,
     pub mix_score_thresholds: Option<ScoreThresholds>,
     pub wg_score_thresholds: Option<ScoreThresholds>,
+    pub custom_gateways: Vec<GatewayDescriptor>,
+    pub harbour_master_url: Option<Url>,
 }
 
 fn to_string<T: fmt::Display>(value: &Option<T>) -> String {
@@ -86,6 +90,21 @@ impl Config {
         self.min_gateway_performance = Some(min_gateway_performance);
         self
     }
+
+    pub fn with_custom_gateways(mut self, custom_gateways: Vec<GatewayDescriptor>) -> Self {
+        self.custom_gateways = custom_gateways;
+        self
+    }
+
+    pub fn harbour_master_url(&self) -> Option<&Url> {
+        self.harbour_master_url.as_ref()
+    }
+
+    /// Filter the gateways on the probe results of the harbour master at the given URL.
+    pub fn with_harbour_master_url(mut self, harbour_master_url: Url) -> Self {
+        self.harbour_master_url = Some(harbour_master_url);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -93,6 +112,7 @@ pub struct ResolvedConfig {
     pub nyxd_socket_addrs: Vec<SocketAddr>,
     pub api_socket_addrs: Vec<SocketAddr>,
     pub nym_vpn_api_socket_addrs: Option<Vec<SocketAddr>>,
+    pub harbour_master_socket_addrs: Option<Vec<SocketAddr>>,
 }
 
 impl ResolvedConfig {
@@ -103,6 +123,9 @@ impl ResolvedConfig {
         if let Some(vpn_api_socket_addrs) = &self.nym_vpn_api_socket_addrs {
             socket_addrs.extend(vpn_api_socket_addrs.iter());
         }
+        if let Some(harbour_master_socket_addrs) = &self.harbour_master_socket_addrs {
+            socket_addrs.extend(harbour_master_socket_addrs.iter());
+        }
         socket_addrs
     }
 }
@@ -113,6 +136,9 @@ pub struct GatewayClient {
     min_gateway_performance: Option<GatewayMinPerformance>,
     mix_score_thresholds: Option<ScoreThresholds>,
     wg_score_thresholds: Option<ScoreThresholds>,
+    custom_gateways: Vec<Gateway>,
+    harbour_master_url: Option<Url>,
+    harbour_master_socket_addrs: Option<Vec<SocketAddr>>,
 }
 
 impl GatewayClient {
@@ -136,6 +162,16 @@ impl GatewayClient {
                 )
             })
             .transpose()?;
+        let custom_gateways = config
+            .custom_gateways
+            .into_iter()
+            .filter_map(|descriptor| {
+                let name = descriptor.to_string();
+                Gateway::try_from(descriptor)
+                    .inspect_err(|err| warn!("Skipping invalid custom gateway {name}: {err}"))
+                    .ok()
+            })
+            .collect();
 
         Ok(GatewayClient {
             api_client,
@@ -143,9 +179,21 @@ impl GatewayClient {
             min_gateway_performance: config.min_gateway_performance,
             mix_score_thresholds: config.mix_score_thresholds,
             wg_score_thresholds: config.wg_score_thresholds,
+            custom_gateways,
+            harbour_master_url: config.harbour_master_url,
+            harbour_master_socket_addrs: None,
         })
     }
 
+    /// Resolve the harbour master to the given addresses instead of using DNS.
+    pub fn with_harbour_master_socket_addrs(
+        mut self,
+        harbour_master_socket_addrs: Option<Vec<SocketAddr>>,
+    ) -> Self {
+        self.harbour_master_socket_addrs = harbour_master_socket_addrs;
+        self
+    }
+
     pub fn mixnet_min_performance(&self) -> Option<Percent> {
         self.min_gateway_performance
             .as_ref()
@@ -296,6 +344,17 @@ impl GatewayClient {
     }
 
     pub async fn lookup_gateway_ip(&self, gateway_identity: &str) -> Result<IpAddr> {
+        if let Some(gateway) = self
+            .custom_gateways
+            .iter()
+            .find(|gw| gw.identity().to_base58_string() == gateway_identity)
+        {
+            debug!("Using the ip of custom gateway {gateway_identity}");
+            return gateway
+                .lookup_ip()
+                .ok_or(Error::FailedToLookupIp(gateway_identity.to_string()));
+        }
+
         if let Some(nym_vpn_api_client) = &self.nym_vpn_api_client {
             debug!("Fetching gateway ip from nym-vpn-api...");
             let gateway = nym_vpn_api_client
@@ -349,6 +408,25 @@ impl GatewayClient {
     }
 
     pub async fn lookup_gateways(&self, gw_type: GatewayType) -> Result<GatewayList> {
+        let mut gateways = self.lookup_directory_gateways(gw_type.clone()).await?;
+        if let Some(harbour_master_url) = &self.harbour_master_url {
+            if let Some(probes) = HarbourMasterProbes::fetch(
+                harbour_master_url,
+                self.harbour_master_socket_addrs.as_deref(),
+            )
+            .await
+            {
+                gateways = GatewayList::new(probes.apply(gateways.into_inner(), &gw_type));
+            }
+        }
+        if matches!(gw_type, GatewayType::Wg) && !self.custom_gateways.is_empty() {
+            debug!("Adding {} custom gateways", self.custom_gateways.len());
+            gateways.insert_or_replace(self.custom_gateways.iter().cloned());
+        }
+        Ok(gateways)
+    }
+
+    async fn lookup_directory_gateways(&self, gw_type: GatewayType) -> Result<GatewayList> {
         if let Some(nym_vpn_api_client) = &self.nym_vpn_api_client {
             debug!("Fetching {gw_type} gateways from nym-vpn-api...");
             let gateways: Vec<_> = nym_vpn_api_client
@@ -472,6 +550,8 @@ mod test {
             min_gateway_performance: None,
             mix_score_thresholds: None,
             wg_score_thresholds: None,
+            custom_gateways: Vec::new(),
+            harbour_master_url: None,
         }
     }
 
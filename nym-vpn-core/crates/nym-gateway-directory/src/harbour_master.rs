@@ -0,0 +1,168 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::HashMap, net::SocketAddr};
+
+use nym_harbour_master_client::{HarbourMasterApiClientExt, ProbeResult};
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::entries::gateway::{Gateway, GatewayType, Probe, ProbeOutcome};
+
+/// Recent probe results published by the harbour master, by gateway identity.
+#[derive(Debug)]
+pub(crate) struct HarbourMasterProbes {
+    probes: HashMap<String, HarbourMasterProbe>,
+}
+
+#[derive(Debug)]
+struct HarbourMasterProbe {
+    last_updated_utc: String,
+    result: ProbeResult,
+}
+
+impl HarbourMasterProbe {
+    fn is_operational(&self, gw_type: &GatewayType) -> bool {
+        match gw_type {
+            GatewayType::MixnetExit => self.result.is_fully_operational_exit(),
+            // The harbour master doesn't probe WireGuard, so WireGuard gateways are held to the
+            // entry checks.
+            GatewayType::MixnetEntry | GatewayType::Wg => self.result.is_fully_operational_entry(),
+        }
+    }
+
+    fn to_probe(&self) -> Probe {
+        Probe {
+            last_updated_utc: self.last_updated_utc.clone(),
+            outcome: ProbeOutcome::from(self.result.outcome.clone()),
+        }
+    }
+}
+
+impl HarbourMasterProbes {
+    /// Fetch the probe results. Returns `None` when the harbour master can't be reached, in which
+    /// case the gateways are used as listed by the directory.
+    pub(crate) async fn fetch(url: &Url, static_addresses: Option<&[SocketAddr]>) -> Option<Self> {
+        debug!("Fetching probe results from the harbour master at {url}");
+        let client = nym_harbour_master_client::new_client(url.clone(), static_addresses)
+            .inspect_err(|err| warn!("Failed to create harbour master client: {err}"))
+            .ok()?;
+        let gateways = client
+            .get_gateways()
+            .await
+            .inspect_err(|err| {
+                warn!("Failed to fetch probe results from the harbour master: {err}")
+            })
+            .ok()?;
+
+        let probes = gateways
+            .into_iter()
+            .filter_map(|gateway| {
+                let result = gateway.recent_probe_result()?;
+                let probe = HarbourMasterProbe {
+                    last_updated_utc: gateway.last_testrun_utc.unwrap_or(gateway.last_updated_utc),
+                    result,
+                };
+                Some((gateway.gateway_identity_key, probe))
+            })
+            .collect::<HashMap<_, _>>();
+        debug!(
+            "Fetched {} recent probe results from the harbour master",
+            probes.len()
+        );
+        Some(Self { probes })
+    }
+
+    /// Fill in the missing probes of the gateways and drop the ones which aren't fully
+    /// operational in the given role. Gateways without a recent probe result are kept, and so
+    /// are all gateways if none of them would be left.
+    pub(crate) fn apply(&self, gateways: Vec<Gateway>, gw_type: &GatewayType) -> Vec<Gateway> {
+        let (operational, failing): (Vec<_>, Vec<_>) = gateways
+            .into_iter()
+            .map(|mut gateway| {
+                let probe = self.probes.get(&gateway.identity().to_base58_string());
+                if let Some(probe) = probe.filter(|_| gateway.last_probe.is_none()) {
+                    gateway.last_probe = Some(probe.to_probe());
+                }
+                let is_operational = probe.is_none_or(|probe| probe.is_operational(gw_type));
+                (gateway, is_operational)
+            })
+            .partition(|(_, is_operational)| *is_operational);
+
+        if operational.is_empty() && !failing.is_empty() {
+            warn!("No {gw_type} gateway passed the harbour master probes, ignoring them");
+            return failing.into_iter().map(|(gateway, _)| gateway).collect();
+        }
+        if !failing.is_empty() {
+            debug!(
+                "Dropping {} {gw_type} gateways failing the harbour master probes",
+                failing.len()
+            );
+        }
+        operational
+            .into_iter()
+            .map(|(gateway, _)| gateway)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nym_harbour_master_client::ProbeOutcome as HarbourMasterOutcome;
+
+    use super::*;
+    use crate::test_utils::{gateway, IDENTITY as OPERATIONAL};
+
+    const FAILING: &str = "FJDUECYAeosXhNGjxf8w5MJM7N2DfDwQznvWwTxJz6ft";
+    const UNKNOWN: &str = "4SPdxfBYsuARBw6REQQa5vFiKcvmYiet9sSWqb751i3Z";
+
+    fn probe(identity: &str, can_route: bool) -> (String, HarbourMasterProbe) {
+        let outcome: HarbourMasterOutcome = serde_json::from_value(serde_json::json!({
+            "as_entry": { "can_connect": true, "can_route": can_route },
+            "as_exit": null,
+        }))
+        .unwrap();
+        let probe = HarbourMasterProbe {
+            last_updated_utc: "2025-01-01T00:00:00Z".to_owned(),
+            result: ProbeResult {
+                gateway: identity.to_owned(),
+                outcome,
+            },
+        };
+        (identity.to_owned(), probe)
+    }
+
+    fn identities(gateways: &[Gateway]) -> Vec<String> {
+        gateways
+            .iter()
+            .map(|gateway| gateway.identity().to_base58_string())
+            .collect()
+    }
+
+    #[test]
+    fn drops_failing_gateways() {
+        let probes = HarbourMasterProbes {
+            probes: HashMap::from([probe(OPERATIONAL, true), probe(FAILING, false)]),
+        };
+        let gateways = vec![gateway(OPERATIONAL), gateway(FAILING), gateway(UNKNOWN)];
+
+        let gateways = probes.apply(gateways, &GatewayType::Wg);
+        assert_eq!(identities(&gateways), [OPERATIONAL, UNKNOWN]);
+        assert!(gateways[0].last_probe.is_some());
+        assert!(gateways[1].last_probe.is_none());
+
+        // Exits also have to route through the ip packet router.
+        let gateways = probes.apply(gateways, &GatewayType::MixnetExit);
+        assert_eq!(identities(&gateways), [UNKNOWN]);
+    }
+
+    #[test]
+    fn keeps_gateways_when_all_fail() {
+        let probes = HarbourMasterProbes {
+            probes: HashMap::from([probe(FAILING, false)]),
+        };
+
+        let gateways = probes.apply(vec![gateway(FAILING)], &GatewayType::MixnetEntry);
+        assert_eq!(identities(&gateways), [FAILING]);
+    }
+}
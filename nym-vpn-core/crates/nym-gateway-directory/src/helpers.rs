@@ -54,10 +54,21 @@ pub async fn resolve_config(config: &Config) -> Result<ResolvedConfig> {
     } else {
         None
     };
+    // The harbour master is optional, so failing to resolve it doesn't prevent connecting.
+    let harbour_master_socket_addrs = if let Some(harbour_master_url) = config.harbour_master_url()
+    {
+        url_to_socket_addr(harbour_master_url)
+            .await
+            .inspect_err(|err| tracing::warn!("Failed to resolve the harbour master: {err}"))
+            .ok()
+    } else {
+        None
+    };
 
     Ok(ResolvedConfig {
         nyxd_socket_addrs,
         api_socket_addrs,
         nym_vpn_api_socket_addrs,
+        harbour_master_socket_addrs,
     })
 }
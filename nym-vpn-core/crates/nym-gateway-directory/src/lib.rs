@@ -4,7 +4,10 @@
 mod entries;
 mod error;
 mod gateway_client;
+mod harbour_master;
 mod helpers;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub use nym_sdk::mixnet::{NodeIdentity, Recipient};
 pub use nym_vpn_api_client::types::{GatewayMinPerformance, Percent};
@@ -18,6 +21,7 @@ pub use crate::{
         gateway::{
             Entry, Exit, Gateway, GatewayList, GatewayType, Location, NymNode, Probe, ProbeOutcome,
         },
+        gateway_descriptor::GatewayDescriptor,
         ipr_addresses::IpPacketRouterAddress,
         score::Score,
     },
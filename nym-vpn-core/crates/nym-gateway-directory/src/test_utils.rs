@@ -0,0 +1,29 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Gateway fixtures shared by the tests of this crate and the crates depending on it.
+
+use crate::{Gateway, GatewayDescriptor};
+
+pub const IDENTITY: &str = "8wH1ScVTGnBVxLjrA3hzZ8m55dvpkiNrpqTet6ccchFV";
+pub const ADDRESS: &str = "6Yu1b6cb3TJNProLHSL1kAiDcpiRxBrhiqUbP9uDz3xz.8boeihWTpiMNzCzdWmeDgc77yUZio47kdRRaLiqvXqyC@8wH1ScVTGnBVxLjrA3hzZ8m55dvpkiNrpqTet6ccchFV";
+
+/// Descriptor of a gateway with an authenticator and no ip packet router.
+pub fn descriptor(identity: &str) -> GatewayDescriptor {
+    GatewayDescriptor {
+        identity: identity.to_owned(),
+        moniker: String::new(),
+        host: None,
+        ips: vec!["192.0.2.1".parse().unwrap()],
+        authenticator_address: ADDRESS.to_owned(),
+        ipr_address: None,
+        clients_ws_port: None,
+        clients_wss_port: None,
+        country: None,
+        version: None,
+    }
+}
+
+pub fn gateway(identity: &str) -> Gateway {
+    Gateway::try_from(descriptor(identity)).unwrap()
+}
@@ -4,6 +4,7 @@
 use vergen::EmitBuilder;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "netstack")]
     rust2go::Builder::new()
         .with_go_src("./netstack_ping")
         .build();
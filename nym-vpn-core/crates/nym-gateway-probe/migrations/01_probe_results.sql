@@ -0,0 +1,16 @@
+/*
+ * Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+CREATE TABLE probe_results
+(
+    id                  INTEGER     NOT NULL PRIMARY KEY AUTOINCREMENT,
+    gateway             TEXT        NOT NULL,
+    -- ProbeResult serialized as JSON, unset when the probe failed
+    result              TEXT,
+    error               TEXT,
+    timestamp           TIMESTAMP   WITHOUT TIME ZONE NOT NULL
+);
+
+CREATE INDEX probe_results_gateway_timestamp ON probe_results (gateway, timestamp);
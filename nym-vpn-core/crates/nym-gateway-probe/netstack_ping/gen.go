@@ -8,16 +8,16 @@ package main
 #include <stdint.h>
 #include <stdlib.h>
 
-typedef struct StringRef {
-  const uint8_t *ptr;
-  uintptr_t len;
-} StringRef;
-
 typedef struct ListRef {
   const void *ptr;
   uintptr_t len;
 } ListRef;
 
+typedef struct StringRef {
+  const uint8_t *ptr;
+  uintptr_t len;
+} StringRef;
+
 typedef struct NetstackRequestGoRef {
   struct StringRef wg_ip;
   struct StringRef private_key;
@@ -32,6 +32,9 @@ typedef struct NetstackRequestGoRef {
   uint64_t recv_timeout_sec;
   uint64_t download_timeout_sec;
   struct StringRef awg_args;
+  struct StringRef benchmark_server;
+  uint64_t benchmark_duration_sec;
+  uint32_t benchmark_rtt_probes;
 } NetstackRequestGoRef;
 
 typedef struct NetstackResponseRef {
@@ -44,6 +47,11 @@ typedef struct NetstackResponseRef {
   struct StringRef downloaded_file;
   uint64_t download_duration_sec;
   struct StringRef download_error;
+  uint64_t benchmark_upload_kbps;
+  uint64_t benchmark_download_kbps;
+  uint32_t benchmark_packets_sent;
+  struct ListRef benchmark_rtts_us;
+  struct StringRef benchmark_error;
 } NetstackResponseRef;
 
 // hack from: https://stackoverflow.com/a/69904977
@@ -221,36 +229,42 @@ func refC_float(p *float32, _ *[]byte) C.float      { return C.float(*p) }
 func refC_double(p *float64, _ *[]byte) C.double    { return C.double(*p) }
 
 type NetstackRequestGo struct {
-	wg_ip                string
-	private_key          string
-	public_key           string
-	endpoint             string
-	dns                  string
-	ip_version           uint8
-	ping_hosts           []string
-	ping_ips             []string
-	num_ping             uint8
-	send_timeout_sec     uint64
-	recv_timeout_sec     uint64
-	download_timeout_sec uint64
-	awg_args             string
+	wg_ip                  string
+	private_key            string
+	public_key             string
+	endpoint               string
+	dns                    string
+	ip_version             uint8
+	ping_hosts             []string
+	ping_ips               []string
+	num_ping               uint8
+	send_timeout_sec       uint64
+	recv_timeout_sec       uint64
+	download_timeout_sec   uint64
+	awg_args               string
+	benchmark_server       string
+	benchmark_duration_sec uint64
+	benchmark_rtt_probes   uint32
 }
 
 func newNetstackRequestGo(p C.NetstackRequestGoRef) NetstackRequestGo {
 	return NetstackRequestGo{
-		wg_ip:                newString(p.wg_ip),
-		private_key:          newString(p.private_key),
-		public_key:           newString(p.public_key),
-		endpoint:             newString(p.endpoint),
-		dns:                  newString(p.dns),
-		ip_version:           newC_uint8_t(p.ip_version),
-		ping_hosts:           new_list_mapper(newString)(p.ping_hosts),
-		ping_ips:             new_list_mapper(newString)(p.ping_ips),
-		num_ping:             newC_uint8_t(p.num_ping),
-		send_timeout_sec:     newC_uint64_t(p.send_timeout_sec),
-		recv_timeout_sec:     newC_uint64_t(p.recv_timeout_sec),
-		download_timeout_sec: newC_uint64_t(p.download_timeout_sec),
-		awg_args:             newString(p.awg_args),
+		wg_ip:                  newString(p.wg_ip),
+		private_key:            newString(p.private_key),
+		public_key:             newString(p.public_key),
+		endpoint:               newString(p.endpoint),
+		dns:                    newString(p.dns),
+		ip_version:             newC_uint8_t(p.ip_version),
+		ping_hosts:             new_list_mapper(newString)(p.ping_hosts),
+		ping_ips:               new_list_mapper(newString)(p.ping_ips),
+		num_ping:               newC_uint8_t(p.num_ping),
+		send_timeout_sec:       newC_uint64_t(p.send_timeout_sec),
+		recv_timeout_sec:       newC_uint64_t(p.recv_timeout_sec),
+		download_timeout_sec:   newC_uint64_t(p.download_timeout_sec),
+		awg_args:               newString(p.awg_args),
+		benchmark_server:       newString(p.benchmark_server),
+		benchmark_duration_sec: newC_uint64_t(p.benchmark_duration_sec),
+		benchmark_rtt_probes:   newC_uint32_t(p.benchmark_rtt_probes),
 	}
 }
 func cntNetstackRequestGo(s *NetstackRequestGo, cnt *uint) [0]C.NetstackRequestGoRef {
@@ -260,45 +274,58 @@ func cntNetstackRequestGo(s *NetstackRequestGo, cnt *uint) [0]C.NetstackRequestG
 }
 func refNetstackRequestGo(p *NetstackRequestGo, buffer *[]byte) C.NetstackRequestGoRef {
 	return C.NetstackRequestGoRef{
-		wg_ip:                refString(&p.wg_ip, buffer),
-		private_key:          refString(&p.private_key, buffer),
-		public_key:           refString(&p.public_key, buffer),
-		endpoint:             refString(&p.endpoint, buffer),
-		dns:                  refString(&p.dns, buffer),
-		ip_version:           refC_uint8_t(&p.ip_version, buffer),
-		ping_hosts:           ref_list_mapper(refString)(&p.ping_hosts, buffer),
-		ping_ips:             ref_list_mapper(refString)(&p.ping_ips, buffer),
-		num_ping:             refC_uint8_t(&p.num_ping, buffer),
-		send_timeout_sec:     refC_uint64_t(&p.send_timeout_sec, buffer),
-		recv_timeout_sec:     refC_uint64_t(&p.recv_timeout_sec, buffer),
-		download_timeout_sec: refC_uint64_t(&p.download_timeout_sec, buffer),
-		awg_args:             refString(&p.awg_args, buffer),
+		wg_ip:                  refString(&p.wg_ip, buffer),
+		private_key:            refString(&p.private_key, buffer),
+		public_key:             refString(&p.public_key, buffer),
+		endpoint:               refString(&p.endpoint, buffer),
+		dns:                    refString(&p.dns, buffer),
+		ip_version:             refC_uint8_t(&p.ip_version, buffer),
+		ping_hosts:             ref_list_mapper(refString)(&p.ping_hosts, buffer),
+		ping_ips:               ref_list_mapper(refString)(&p.ping_ips, buffer),
+		num_ping:               refC_uint8_t(&p.num_ping, buffer),
+		send_timeout_sec:       refC_uint64_t(&p.send_timeout_sec, buffer),
+		recv_timeout_sec:       refC_uint64_t(&p.recv_timeout_sec, buffer),
+		download_timeout_sec:   refC_uint64_t(&p.download_timeout_sec, buffer),
+		awg_args:               refString(&p.awg_args, buffer),
+		benchmark_server:       refString(&p.benchmark_server, buffer),
+		benchmark_duration_sec: refC_uint64_t(&p.benchmark_duration_sec, buffer),
+		benchmark_rtt_probes:   refC_uint32_t(&p.benchmark_rtt_probes, buffer),
 	}
 }
 
 type NetstackResponse struct {
-	can_handshake         bool
-	sent_ips              uint16
-	received_ips          uint16
-	sent_hosts            uint16
-	received_hosts        uint16
-	can_resolve_dns       bool
-	downloaded_file       string
-	download_duration_sec uint64
-	download_error        string
+	can_handshake           bool
+	sent_ips                uint16
+	received_ips            uint16
+	sent_hosts              uint16
+	received_hosts          uint16
+	can_resolve_dns         bool
+	downloaded_file         string
+	download_duration_sec   uint64
+	download_error          string
+	benchmark_upload_kbps   uint64
+	benchmark_download_kbps uint64
+	benchmark_packets_sent  uint32
+	benchmark_rtts_us       []uint64
+	benchmark_error         string
 }
 
 func newNetstackResponse(p C.NetstackResponseRef) NetstackResponse {
 	return NetstackResponse{
-		can_handshake:         newC_bool(p.can_handshake),
-		sent_ips:              newC_uint16_t(p.sent_ips),
-		received_ips:          newC_uint16_t(p.received_ips),
-		sent_hosts:            newC_uint16_t(p.sent_hosts),
-		received_hosts:        newC_uint16_t(p.received_hosts),
-		can_resolve_dns:       newC_bool(p.can_resolve_dns),
-		downloaded_file:       newString(p.downloaded_file),
-		download_duration_sec: newC_uint64_t(p.download_duration_sec),
-		download_error:        newString(p.download_error),
+		can_handshake:           newC_bool(p.can_handshake),
+		sent_ips:                newC_uint16_t(p.sent_ips),
+		received_ips:            newC_uint16_t(p.received_ips),
+		sent_hosts:              newC_uint16_t(p.sent_hosts),
+		received_hosts:          newC_uint16_t(p.received_hosts),
+		can_resolve_dns:         newC_bool(p.can_resolve_dns),
+		downloaded_file:         newString(p.downloaded_file),
+		download_duration_sec:   newC_uint64_t(p.download_duration_sec),
+		download_error:          newString(p.download_error),
+		benchmark_upload_kbps:   newC_uint64_t(p.benchmark_upload_kbps),
+		benchmark_download_kbps: newC_uint64_t(p.benchmark_download_kbps),
+		benchmark_packets_sent:  newC_uint32_t(p.benchmark_packets_sent),
+		benchmark_rtts_us:       new_list_mapper_primitive(newC_uint64_t)(p.benchmark_rtts_us),
+		benchmark_error:         newString(p.benchmark_error),
 	}
 }
 func cntNetstackResponse(s *NetstackResponse, cnt *uint) [0]C.NetstackResponseRef {
@@ -306,15 +333,20 @@ func cntNetstackResponse(s *NetstackResponse, cnt *uint) [0]C.NetstackResponseRe
 }
 func refNetstackResponse(p *NetstackResponse, buffer *[]byte) C.NetstackResponseRef {
 	return C.NetstackResponseRef{
-		can_handshake:         refC_bool(&p.can_handshake, buffer),
-		sent_ips:              refC_uint16_t(&p.sent_ips, buffer),
-		received_ips:          refC_uint16_t(&p.received_ips, buffer),
-		sent_hosts:            refC_uint16_t(&p.sent_hosts, buffer),
-		received_hosts:        refC_uint16_t(&p.received_hosts, buffer),
-		can_resolve_dns:       refC_bool(&p.can_resolve_dns, buffer),
-		downloaded_file:       refString(&p.downloaded_file, buffer),
-		download_duration_sec: refC_uint64_t(&p.download_duration_sec, buffer),
-		download_error:        refString(&p.download_error, buffer),
+		can_handshake:           refC_bool(&p.can_handshake, buffer),
+		sent_ips:                refC_uint16_t(&p.sent_ips, buffer),
+		received_ips:            refC_uint16_t(&p.received_ips, buffer),
+		sent_hosts:              refC_uint16_t(&p.sent_hosts, buffer),
+		received_hosts:          refC_uint16_t(&p.received_hosts, buffer),
+		can_resolve_dns:         refC_bool(&p.can_resolve_dns, buffer),
+		downloaded_file:         refString(&p.downloaded_file, buffer),
+		download_duration_sec:   refC_uint64_t(&p.download_duration_sec, buffer),
+		download_error:          refString(&p.download_error, buffer),
+		benchmark_upload_kbps:   refC_uint64_t(&p.benchmark_upload_kbps, buffer),
+		benchmark_download_kbps: refC_uint64_t(&p.benchmark_download_kbps, buffer),
+		benchmark_packets_sent:  refC_uint32_t(&p.benchmark_packets_sent, buffer),
+		benchmark_rtts_us:       ref_list_mapper_primitive(refC_uint64_t)(&p.benchmark_rtts_us, buffer),
+		benchmark_error:         refString(&p.benchmark_error, buffer),
 	}
 }
 func main() {}
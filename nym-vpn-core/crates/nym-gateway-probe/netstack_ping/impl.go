@@ -3,6 +3,7 @@ package main
 import (
 	"bytes"
 	"context"
+	"encoding/binary"
 	"fmt"
 	"io"
 	"log"
@@ -31,6 +32,19 @@ var fileUrlsV6 = []string{
 	"https://proof.ovh.net/files/10Mb.dat",
 }
 
+// Protocol of the nym-tunnel-benchmark server.
+const (
+	benchmarkModeUpload   = 'U'
+	benchmarkModeDownload = 'D'
+	benchmarkModeLatency  = 'L'
+	benchmarkTokenLen     = 16
+	benchmarkChunkSize    = 64 * 1024
+	benchmarkPacketSize   = 64
+	benchmarkRttInterval  = 50 * time.Millisecond
+	benchmarkRttTimeout   = 2 * time.Second
+	benchmarkIdleTimeout  = 30 * time.Second
+)
+
 type Netstack struct{}
 
 func init() {
@@ -71,7 +85,7 @@ func (Netstack) ping(req NetstackRequestGo) NetstackResponse {
 		ipc.WriteString("\nallowed_ip=::/0\n")
 	}
 
-	response := NetstackResponse{false, 0, 0, 0, 0, false, "", 0, ""}
+	response := NetstackResponse{false, 0, 0, 0, 0, false, "", 0, "", 0, 0, 0, nil, ""}
 
 	dev.IpcSet(ipc.String())
 
@@ -147,9 +161,185 @@ func (Netstack) ping(req NetstackRequestGo) NetstackResponse {
 		response.download_error = ""
 	}
 
+	if req.benchmark_server != "" {
+		runBenchmark(req, tnet, &response)
+	}
+
 	return response
 }
 
+func runBenchmark(req NetstackRequestGo, tnet *netstack.Net, response *NetstackResponse) {
+	tcp, udp := "tcp4", "udp4"
+	if req.ip_version == 6 {
+		tcp, udp = "tcp6", "udp6"
+	}
+	duration := time.Second * time.Duration(req.benchmark_duration_sec)
+
+	log.Printf("Measuring latency to %s", req.benchmark_server)
+	sent, rtts, err := benchmarkLatency(tnet, tcp, udp, req.benchmark_server, req.benchmark_rtt_probes)
+	if err != nil {
+		response.benchmark_error = fmt.Sprintf("latency measurement failed: %v", err)
+		return
+	}
+	response.benchmark_packets_sent = sent
+	response.benchmark_rtts_us = rtts
+
+	log.Printf("Measuring upload to %s", req.benchmark_server)
+	upload, err := benchmarkUpload(tnet, tcp, req.benchmark_server, duration)
+	if err != nil {
+		response.benchmark_error = fmt.Sprintf("upload failed: %v", err)
+		return
+	}
+	response.benchmark_upload_kbps = upload
+
+	log.Printf("Measuring download from %s", req.benchmark_server)
+	download, err := benchmarkDownload(tnet, tcp, req.benchmark_server, duration)
+	if err != nil {
+		response.benchmark_error = fmt.Sprintf("download failed: %v", err)
+		return
+	}
+	response.benchmark_download_kbps = download
+}
+
+func benchmarkConnect(tnet *netstack.Net, network string, server string, mode byte, duration time.Duration) (net.Conn, error) {
+	conn, err := tnet.Dial(network, server)
+	if err != nil {
+		return nil, err
+	}
+
+	header := make([]byte, 9)
+	header[0] = mode
+	binary.BigEndian.PutUint64(header[1:], uint64(duration.Milliseconds()))
+	conn.SetWriteDeadline(time.Now().Add(benchmarkIdleTimeout))
+	if _, err := conn.Write(header); err != nil {
+		conn.Close()
+		return nil, err
+	}
+	return conn, nil
+}
+
+func benchmarkUpload(tnet *netstack.Net, network string, server string, duration time.Duration) (uint64, error) {
+	conn, err := benchmarkConnect(tnet, network, server, benchmarkModeUpload, duration)
+	if err != nil {
+		return 0, err
+	}
+	defer conn.Close()
+
+	buf := make([]byte, benchmarkChunkSize)
+	start := time.Now()
+	for time.Since(start) < duration {
+		conn.SetWriteDeadline(time.Now().Add(benchmarkIdleTimeout))
+		if _, err := conn.Write(buf); err != nil {
+			return 0, err
+		}
+	}
+
+	halfCloser, ok := conn.(interface{ CloseWrite() error })
+	if !ok {
+		return 0, fmt.Errorf("connection can't be half closed")
+	}
+	if err := halfCloser.CloseWrite(); err != nil {
+		return 0, err
+	}
+
+	// The server answers once it read everything, so this includes the data in flight.
+	var received [8]byte
+	conn.SetReadDeadline(time.Now().Add(benchmarkIdleTimeout))
+	if _, err := io.ReadFull(conn, received[:]); err != nil {
+		return 0, err
+	}
+	return kbps(binary.BigEndian.Uint64(received[:]), time.Since(start)), nil
+}
+
+func benchmarkDownload(tnet *netstack.Net, network string, server string, duration time.Duration) (uint64, error) {
+	conn, err := benchmarkConnect(tnet, network, server, benchmarkModeDownload, duration)
+	if err != nil {
+		return 0, err
+	}
+	defer conn.Close()
+
+	start := time.Now()
+	conn.SetReadDeadline(start.Add(duration + benchmarkIdleTimeout))
+	received, err := io.Copy(io.Discard, conn)
+	if err != nil {
+		return 0, err
+	}
+	return kbps(uint64(received), time.Since(start)), nil
+}
+
+func benchmarkLatency(tnet *netstack.Net, tcp string, udp string, server string, probes uint32) (uint32, []uint64, error) {
+	// The server only echoes datagrams carrying the token of a session open over TCP.
+	sessionDuration := time.Duration(probes)*benchmarkRttInterval + benchmarkRttTimeout
+	session, err := benchmarkConnect(tnet, tcp, server, benchmarkModeLatency, sessionDuration)
+	if err != nil {
+		return 0, nil, err
+	}
+	defer session.Close()
+
+	token := make([]byte, benchmarkTokenLen)
+	session.SetReadDeadline(time.Now().Add(benchmarkIdleTimeout))
+	if _, err := io.ReadFull(session, token); err != nil {
+		return 0, nil, err
+	}
+
+	conn, err := tnet.Dial(udp, server)
+	if err != nil {
+		return 0, nil, err
+	}
+	defer conn.Close()
+
+	start := time.Now()
+	rttsCh := make(chan []uint64)
+
+	go func() {
+		echoed := make([]bool, probes)
+		rtts := []uint64{}
+		buf := make([]byte, 2048)
+		conn.SetReadDeadline(start.Add(time.Duration(probes)*benchmarkRttInterval + benchmarkRttTimeout))
+		for uint32(len(rtts)) < probes {
+			n, err := conn.Read(buf)
+			if err != nil {
+				break
+			}
+			if n < benchmarkTokenLen+12 || !bytes.Equal(buf[:benchmarkTokenLen], token) {
+				continue
+			}
+			payload := buf[benchmarkTokenLen:n]
+			seq := binary.BigEndian.Uint32(payload[:4])
+			if seq >= probes || echoed[seq] {
+				continue
+			}
+			echoed[seq] = true
+			sentAt := time.Duration(binary.BigEndian.Uint64(payload[4:12])) * time.Microsecond
+			rtts = append(rtts, uint64((time.Since(start) - sentAt).Microseconds()))
+		}
+		rttsCh <- rtts
+	}()
+
+	packet := make([]byte, benchmarkPacketSize)
+	copy(packet, token)
+	sent := uint32(0)
+	for seq := uint32(0); seq < probes; seq++ {
+		binary.BigEndian.PutUint32(packet[benchmarkTokenLen:], seq)
+		binary.BigEndian.PutUint64(packet[benchmarkTokenLen+4:], uint64(time.Since(start).Microseconds()))
+		if _, err := conn.Write(packet); err != nil {
+			log.Printf("Failed to send benchmark packet: %v\n", err)
+			break
+		}
+		sent += 1
+		time.Sleep(benchmarkRttInterval)
+	}
+
+	return sent, <-rttsCh, nil
+}
+
+func kbps(count uint64, elapsed time.Duration) uint64 {
+	if elapsed <= 0 {
+		return 0
+	}
+	return uint64(float64(count) * 8 / elapsed.Seconds() / 1000)
+}
+
 func sendPing(address string, seq uint8, send_timeout_secs uint64, recieve_timout_secs uint64, tnet *netstack.Net, ip_version uint8) (time.Duration, error) {
 	var socket net.Conn
 	var err error
@@ -0,0 +1,359 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use nym_gateway_directory::{Config as GatewayDirectoryConfig, EntryPoint, GatewayList};
+use nym_sdk::mixnet::NodeIdentity;
+use serde::{Deserialize, Serialize};
+use tokio::time::error::Elapsed;
+use tracing::*;
+
+use crate::{
+    types::{Entry, ProbeResult},
+    CredentialArgs, NetstackArgs, Probe, TestedNode,
+};
+
+/// Probes a list of gateways, each as its own entry, with a bounded number of probes running at
+/// the same time.
+pub struct BatchProbe {
+    gateways: Vec<NodeIdentity>,
+    concurrency: usize,
+    probe_timeout: Duration,
+    amnezia_args: Option<String>,
+    netstack_args: NetstackArgs,
+    credentials_args: CredentialArgs,
+}
+
+impl BatchProbe {
+    pub fn new(
+        gateways: Vec<NodeIdentity>,
+        netstack_args: NetstackArgs,
+        credentials_args: CredentialArgs,
+    ) -> Self {
+        Self {
+            gateways,
+            concurrency: 4,
+            probe_timeout: Duration::from_secs(300),
+            amnezia_args: None,
+            netstack_args,
+            credentials_args,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+
+    pub fn with_amnezia(mut self, args: &str) -> Self {
+        self.amnezia_args = Some(args.to_string());
+        self
+    }
+
+    pub async fn run(
+        self,
+        gateway_config: GatewayDirectoryConfig,
+        gateways: &GatewayList,
+        ignore_egress_epoch_role: bool,
+        only_wireguard: bool,
+    ) -> BatchReport {
+        let total = self.gateways.len();
+        info!("probing {total} gateways, {} at a time", self.concurrency);
+
+        let probe_timeout = self.probe_timeout;
+        let probes = self.gateways.iter().map(|identity| {
+            let mut probe = Probe::new(
+                EntryPoint::Gateway {
+                    identity: *identity,
+                },
+                TestedNode::SameAsEntry,
+                self.netstack_args.clone(),
+                self.credentials_args.clone(),
+            );
+            if let Some(awg_args) = &self.amnezia_args {
+                probe.with_amnezia(awg_args);
+            }
+            let gateway_config = gateway_config.clone();
+
+            async move {
+                let started = Instant::now();
+                let result = tokio::time::timeout(
+                    probe_timeout,
+                    probe.probe_with_gateways(
+                        gateway_config,
+                        gateways,
+                        ignore_egress_epoch_role,
+                        only_wireguard,
+                    ),
+                )
+                .await;
+                let duration_sec = started.elapsed().as_secs_f64();
+
+                let (result, error, timed_out) = match result {
+                    Ok(Ok(result)) => (Some(result), None, false),
+                    Ok(Err(err)) => (None, Some(err.to_string()), false),
+                    Err(Elapsed { .. }) => (None, Some("timed out".to_string()), true),
+                };
+                if let Some(error) = &error {
+                    warn!("probe of {identity} failed: {error}");
+                }
+
+                BatchProbeResult {
+                    gateway: identity.to_base58_string(),
+                    duration_sec,
+                    result,
+                    error,
+                    timed_out,
+                }
+            }
+        });
+
+        let mut results = Vec::with_capacity(total);
+        let mut stream = futures::stream::iter(probes).buffer_unordered(self.concurrency);
+        while let Some(result) = stream.next().await {
+            results.push(result);
+            info!("{}/{total} gateways probed", results.len());
+        }
+        results.sort_by(|a, b| a.gateway.cmp(&b.gateway));
+
+        BatchReport::new(results)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProbeResult {
+    pub gateway: String,
+    pub duration_sec: f64,
+    pub result: Option<ProbeResult>,
+    pub error: Option<String>,
+    /// Whether the probe was cancelled after running for longer than the probe timeout.
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+    pub entry_can_connect: usize,
+    pub entry_can_route: usize,
+    pub exit_can_route_ip_v4: usize,
+    pub exit_can_route_ip_v6: usize,
+    pub wg_can_register: usize,
+    pub wg_can_handshake_v4: usize,
+    pub wg_can_handshake_v6: usize,
+    pub average_duration_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub summary: BatchSummary,
+    pub results: Vec<BatchProbeResult>,
+}
+
+impl BatchReport {
+    pub fn new(results: Vec<BatchProbeResult>) -> Self {
+        let mut summary = BatchSummary {
+            total: results.len(),
+            ..Default::default()
+        };
+
+        for probe in &results {
+            if probe.timed_out {
+                summary.timed_out += 1;
+            } else if probe.error.is_some() {
+                summary.failed += 1;
+            }
+            let Some(result) = &probe.result else {
+                continue;
+            };
+            summary.completed += 1;
+
+            let outcome = &result.outcome;
+            if let Entry::Tested(entry) = &outcome.as_entry {
+                summary.entry_can_connect += usize::from(entry.can_connect);
+                summary.entry_can_route += usize::from(entry.can_route);
+            }
+            if let Some(exit) = &outcome.as_exit {
+                summary.exit_can_route_ip_v4 += usize::from(exit.can_route_ip_v4);
+                summary.exit_can_route_ip_v6 += usize::from(exit.can_route_ip_v6);
+            }
+            if let Some(wg) = &outcome.wg {
+                summary.wg_can_register += usize::from(wg.can_register);
+                summary.wg_can_handshake_v4 += usize::from(wg.can_handshake_v4);
+                summary.wg_can_handshake_v6 += usize::from(wg.can_handshake_v6);
+            }
+        }
+
+        if !results.is_empty() {
+            summary.average_duration_sec =
+                results.iter().map(|probe| probe.duration_sec).sum::<f64>() / results.len() as f64;
+        }
+
+        Self { summary, results }
+    }
+
+    /// One row per gateway. Cells of checks that weren't run are left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "gateway,duration_sec,entry_can_connect,entry_can_route,exit_can_connect,\
+             exit_can_route_ip_v4,exit_can_route_ip_external_v4,exit_can_route_ip_v6,\
+             exit_can_route_ip_external_v6,wg_can_register,wg_can_handshake_v4,\
+             wg_can_handshake_v6,wg_ping_ips_performance_v4,wg_ping_ips_performance_v6,error\n",
+        );
+
+        for probe in &self.results {
+            let outcome = probe.result.as_ref().map(|result| &result.outcome);
+            let entry = outcome.and_then(|outcome| match &outcome.as_entry {
+                Entry::Tested(entry) => Some(entry),
+                Entry::NotTested | Entry::EntryFailure => None,
+            });
+            let exit = outcome.and_then(|outcome| outcome.as_exit.as_ref());
+            let wg = outcome.and_then(|outcome| outcome.wg.as_ref());
+
+            let cells = [
+                probe.gateway.clone(),
+                format!("{:.1}", probe.duration_sec),
+                cell(entry.map(|entry| entry.can_connect)),
+                cell(entry.map(|entry| entry.can_route)),
+                cell(exit.map(|exit| exit.can_connect)),
+                cell(exit.map(|exit| exit.can_route_ip_v4)),
+                cell(exit.map(|exit| exit.can_route_ip_external_v4)),
+                cell(exit.map(|exit| exit.can_route_ip_v6)),
+                cell(exit.map(|exit| exit.can_route_ip_external_v6)),
+                cell(wg.map(|wg| wg.can_register)),
+                cell(wg.map(|wg| wg.can_handshake_v4)),
+                cell(wg.map(|wg| wg.can_handshake_v6)),
+                cell(wg.map(|wg| wg.ping_ips_performance_v4)),
+                cell(wg.map(|wg| wg.ping_ips_performance_v6)),
+                escape(probe.error.as_deref().unwrap_or_default()),
+            ];
+            let _ = writeln!(csv, "{}", cells.join(","));
+        }
+        csv
+    }
+}
+
+fn cell<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Exit, ProbeOutcome, WgProbeResults};
+
+    fn probe_result(gateway: &str, outcome: ProbeOutcome) -> BatchProbeResult {
+        BatchProbeResult {
+            gateway: gateway.to_string(),
+            duration_sec: 10.0,
+            result: Some(ProbeResult {
+                node: gateway.to_string(),
+                used_entry: gateway.to_string(),
+                outcome,
+            }),
+            error: None,
+            timed_out: false,
+        }
+    }
+
+    fn failed(gateway: &str, error: &str) -> BatchProbeResult {
+        BatchProbeResult {
+            gateway: gateway.to_string(),
+            duration_sec: 20.0,
+            result: None,
+            error: Some(error.to_string()),
+            timed_out: false,
+        }
+    }
+
+    fn timed_out(gateway: &str) -> BatchProbeResult {
+        BatchProbeResult {
+            error: Some("timed out".to_string()),
+            timed_out: true,
+            ..failed(gateway, "")
+        }
+    }
+
+    fn report() -> BatchReport {
+        BatchReport::new(vec![
+            probe_result(
+                "a",
+                ProbeOutcome {
+                    as_entry: Entry::success(),
+                    as_exit: Some(Exit::fail_to_connect()),
+                    wg: Some(WgProbeResults {
+                        can_register: true,
+                        can_handshake_v4: true,
+                        ..Default::default()
+                    }),
+                },
+            ),
+            probe_result(
+                "b",
+                ProbeOutcome {
+                    as_entry: Entry::fail_to_route(),
+                    as_exit: None,
+                    wg: None,
+                },
+            ),
+            timed_out("c"),
+            failed("d", "no gateway, \"d\""),
+        ])
+    }
+
+    #[test]
+    fn summary_counts_outcomes() {
+        let summary = report().summary;
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.completed, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.timed_out, 1);
+        assert_eq!(summary.entry_can_connect, 2);
+        assert_eq!(summary.entry_can_route, 1);
+        assert_eq!(summary.exit_can_route_ip_v4, 0);
+        assert_eq!(summary.wg_can_register, 1);
+        assert_eq!(summary.wg_can_handshake_v4, 1);
+        assert_eq!(summary.average_duration_sec, 15.0);
+    }
+
+    #[test]
+    fn only_elapsed_probes_time_out() {
+        // A probe failing with a similar message still counts as failed
+        let summary = BatchReport::new(vec![failed("a", "timed out"), timed_out("b")]).summary;
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.timed_out, 1);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_gateway() {
+        let csv = report().to_csv();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        let columns = lines[0].split(',').count();
+        assert!(lines[1].starts_with("a,10.0,true,true,false,false,"));
+        assert_eq!(lines[2].split(',').count(), columns);
+        assert_eq!(lines[3], "c,20.0,,,,,,,,,,,,,timed out");
+        assert!(lines[4].ends_with(",\"no gateway, \"\"d\"\"\""));
+    }
+}
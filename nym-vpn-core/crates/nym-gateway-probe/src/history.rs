@@ -0,0 +1,251 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::types::{Entry, ProbeResult, WgProbeResults};
+
+/// A probe read back from the [`ProbeStore`](crate::ProbeStore).
+#[derive(Debug, Clone)]
+pub struct StoredProbe {
+    pub gateway: String,
+    pub timestamp: OffsetDateTime,
+    pub result: Option<ProbeResult>,
+    pub error: Option<String>,
+}
+
+impl StoredProbe {
+    /// The gateway could connect and route as an entry.
+    fn is_up(&self) -> bool {
+        self.result.as_ref().is_some_and(|result| {
+            matches!(&result.outcome.as_entry, Entry::Tested(entry) if entry.can_connect && entry.can_route)
+        })
+    }
+
+    fn wg(&self) -> Option<&WgProbeResults> {
+        self.result
+            .as_ref()
+            .and_then(|result| result.outcome.wg.as_ref())
+    }
+}
+
+/// Aggregated outcome of a set of probes. Rates are between 0 and 1, and WireGuard statistics are
+/// unset when none of the probes ran the WireGuard checks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProbeStats {
+    pub probes: usize,
+    /// Share of probes in which the gateway could connect and route as an entry.
+    pub uptime: f64,
+    pub handshake_success_rate_v4: Option<f64>,
+    pub handshake_success_rate_v6: Option<f64>,
+    pub ping_ips_performance_v4: Option<f64>,
+    pub ping_ips_performance_v6: Option<f64>,
+    /// Average duration of the downloads which completed.
+    pub download_duration_sec_v4: Option<f64>,
+    pub download_duration_sec_v6: Option<f64>,
+}
+
+impl ProbeStats {
+    fn new(probes: &[&StoredProbe]) -> Self {
+        let wg = probes
+            .iter()
+            .filter_map(|probe| probe.wg())
+            .collect::<Vec<_>>();
+        let wg_average = |value: fn(&WgProbeResults) -> Option<f64>| {
+            average(wg.iter().filter_map(|wg| value(wg)))
+        };
+
+        Self {
+            probes: probes.len(),
+            uptime: average(probes.iter().map(|probe| rate(probe.is_up()))).unwrap_or_default(),
+            handshake_success_rate_v4: wg_average(|wg| Some(rate(wg.can_handshake_v4))),
+            handshake_success_rate_v6: wg_average(|wg| Some(rate(wg.can_handshake_v6))),
+            ping_ips_performance_v4: wg_average(|wg| Some(f64::from(wg.ping_ips_performance_v4))),
+            ping_ips_performance_v6: wg_average(|wg| Some(f64::from(wg.ping_ips_performance_v6))),
+            download_duration_sec_v4: wg_average(|wg| {
+                download_duration(
+                    &wg.downloaded_file_v4,
+                    &wg.download_error_v4,
+                    wg.download_duration_sec_v4,
+                )
+            }),
+            download_duration_sec_v6: wg_average(|wg| {
+                download_duration(
+                    &wg.downloaded_file_v6,
+                    &wg.download_error_v6,
+                    wg.download_duration_sec_v6,
+                )
+            }),
+        }
+    }
+}
+
+fn rate(success: bool) -> f64 {
+    if success {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (count, sum) = values.fold((0u32, 0.0), |(count, sum), value| (count + 1, sum + value));
+    (count > 0).then(|| sum / f64::from(count))
+}
+
+fn download_duration(file: &str, error: &str, duration_sec: u64) -> Option<f64> {
+    (!file.is_empty() && error.is_empty()).then_some(duration_sec as f64)
+}
+
+/// Statistics of the probes started within one bucket of the report window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryBucket {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(flatten)]
+    pub stats: ProbeStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayHistory {
+    pub gateway: String,
+    #[serde(flatten)]
+    pub stats: ProbeStats,
+    /// Number of times the gateway went up or down between consecutive probes.
+    pub state_changes: usize,
+    /// Buckets without probes are left out.
+    pub buckets: Vec<HistoryBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryReport {
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+    pub bucket_sec: u64,
+    pub gateways: Vec<GatewayHistory>,
+}
+
+impl HistoryReport {
+    /// Aggregate the probes per gateway, splitting the window starting at `since` into buckets
+    /// of `bucket` length. The probes are expected to be sorted by time.
+    pub fn new(probes: &[StoredProbe], since: OffsetDateTime, bucket: Duration) -> Self {
+        let bucket_sec = bucket.as_secs().max(1);
+
+        let mut by_gateway = BTreeMap::<&str, Vec<&StoredProbe>>::new();
+        for probe in probes {
+            by_gateway.entry(&probe.gateway).or_default().push(probe);
+        }
+
+        let gateways = by_gateway
+            .into_iter()
+            .map(|(gateway, probes)| {
+                let mut buckets = BTreeMap::<u64, Vec<&StoredProbe>>::new();
+                for &probe in &probes {
+                    let offset = (probe.timestamp - since).whole_seconds().max(0) as u64;
+                    buckets.entry(offset / bucket_sec).or_default().push(probe);
+                }
+                let state_changes = probes
+                    .windows(2)
+                    .filter(|pair| pair[0].is_up() != pair[1].is_up())
+                    .count();
+
+                GatewayHistory {
+                    gateway: gateway.to_owned(),
+                    stats: ProbeStats::new(&probes),
+                    state_changes,
+                    buckets: buckets
+                        .into_iter()
+                        .map(|(index, probes)| HistoryBucket {
+                            start: since + Duration::from_secs(index * bucket_sec),
+                            stats: ProbeStats::new(&probes),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            since,
+            bucket_sec,
+            gateways,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ProbeOutcome;
+
+    fn probe(minutes: i64, entry: Entry, wg: Option<WgProbeResults>) -> StoredProbe {
+        StoredProbe {
+            gateway: "a".to_owned(),
+            timestamp: OffsetDateTime::UNIX_EPOCH + time::Duration::minutes(minutes),
+            result: Some(ProbeResult {
+                node: "a".to_owned(),
+                used_entry: "a".to_owned(),
+                outcome: ProbeOutcome {
+                    as_entry: entry,
+                    as_exit: None,
+                    wg,
+                },
+            }),
+            error: None,
+        }
+    }
+
+    fn handshake(ping_ips_performance_v4: f32) -> Option<WgProbeResults> {
+        Some(WgProbeResults {
+            can_register: true,
+            can_handshake_v4: true,
+            ping_ips_performance_v4,
+            downloaded_file_v4: "1MB.zip".to_owned(),
+            download_duration_sec_v4: 4,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn aggregates_per_gateway_and_bucket() {
+        let mut failed = probe(90, Entry::success(), None);
+        failed.gateway = "b".to_owned();
+        failed.result = None;
+        failed.error = Some("timed out".to_owned());
+        let probes = vec![
+            probe(0, Entry::success(), handshake(1.0)),
+            probe(30, Entry::fail_to_route(), Some(WgProbeResults::default())),
+            probe(70, Entry::success(), handshake(0.5)),
+            failed,
+        ];
+
+        let report = HistoryReport::new(
+            &probes,
+            OffsetDateTime::UNIX_EPOCH,
+            Duration::from_secs(3600),
+        );
+        assert_eq!(report.gateways.len(), 2);
+
+        let a = &report.gateways[0];
+        assert_eq!(a.stats.probes, 3);
+        assert_eq!(a.stats.uptime, 2.0 / 3.0);
+        assert_eq!(a.stats.handshake_success_rate_v4, Some(2.0 / 3.0));
+        assert_eq!(a.stats.handshake_success_rate_v6, Some(0.0));
+        assert_eq!(a.stats.ping_ips_performance_v4, Some(0.5));
+        assert_eq!(a.stats.download_duration_sec_v4, Some(4.0));
+        assert_eq!(a.stats.download_duration_sec_v6, None);
+        assert_eq!(a.state_changes, 2);
+        assert_eq!(a.buckets.len(), 2);
+        assert_eq!(a.buckets[0].stats.uptime, 0.5);
+        assert_eq!(
+            a.buckets[1].start,
+            OffsetDateTime::UNIX_EPOCH + time::Duration::hours(1)
+        );
+
+        let b = &report.gateways[1];
+        assert_eq!(b.stats.uptime, 0.0);
+        assert_eq!(b.stats.handshake_success_rate_v4, None);
+    }
+}
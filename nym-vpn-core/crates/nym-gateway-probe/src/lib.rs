@@ -12,7 +12,7 @@ use crate::types::Entry;
 use anyhow::{anyhow, bail};
 use base64::{engine::general_purpose, Engine as _};
 use bytes::BytesMut;
-use clap::Args;
+use clap::{Args, Parser};
 use futures::StreamExt;
 use nym_authenticator_client::{
     AuthClientMixnetListener, AuthenticatorResponse, AuthenticatorVersion, ClientMessage,
@@ -50,20 +50,34 @@ use crate::{
     types::Exit,
 };
 
+#[cfg(feature = "netstack")]
 use netstack::{
     ffi::{NetstackCall as _, NetstackCallImpl, NetstackRequestGo},
     NetstackRequest,
 };
 
+mod batch;
 mod error;
+#[cfg(feature = "store")]
+mod history;
 mod icmp;
+#[cfg(feature = "netstack")]
 mod netstack;
+#[cfg(feature = "store")]
+mod store;
 mod types;
 
+pub use batch::{BatchProbe, BatchProbeResult, BatchReport, BatchSummary};
 pub use error::{Error, Result};
-pub use types::{IpPingReplies, ProbeOutcome, ProbeResult};
+#[cfg(feature = "store")]
+pub use history::{GatewayHistory, HistoryBucket, HistoryReport, ProbeStats, StoredProbe};
+#[cfg(feature = "store")]
+pub use store::ProbeStore;
+pub use types::{
+    Entry, EntryTestResult, Exit, IpPingReplies, ProbeOutcome, ProbeResult, WgProbeResults,
+};
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct NetstackArgs {
     #[arg(long, default_value_t = 180)]
     netstack_download_timeout_sec: u64,
@@ -94,9 +108,34 @@ pub struct NetstackArgs {
 
     #[arg(long, default_values_t = vec!["2001:4860:4860::8888".to_string(), "2606:4700:4700::1111".to_string(), "2620:fe::fe".to_string()])]
     netstack_ping_ips_v6: Vec<String>,
+
+    /// Benchmark server, as host:port, to measure throughput and latency through the WireGuard
+    /// tunnel against. See the `benchmark-server` subcommand.
+    #[arg(long)]
+    netstack_benchmark_server: Option<String>,
+
+    /// Duration of the benchmark upload and of the download.
+    #[arg(long, default_value_t = 10)]
+    netstack_benchmark_duration_sec: u64,
+
+    /// Number of UDP packets sent by the benchmark to measure round trip times and packet loss.
+    #[arg(long, default_value_t = 100)]
+    netstack_benchmark_rtt_probes: u32,
 }
 
-#[derive(Args)]
+impl Default for NetstackArgs {
+    fn default() -> Self {
+        #[derive(Parser)]
+        struct Defaults {
+            #[command(flatten)]
+            netstack_args: NetstackArgs,
+        }
+
+        Defaults::parse_from(["nym-gateway-probe"]).netstack_args
+    }
+}
+
+#[derive(Args, Clone, Default)]
 pub struct CredentialArgs {
     #[arg(long)]
     enable_credentials_mode: bool,
@@ -105,6 +144,16 @@ pub struct CredentialArgs {
     mnemonic: Option<String>,
 }
 
+impl CredentialArgs {
+    /// Enable the credentials mode, acquiring ticketbooks with the given account.
+    pub fn with_mnemonic(mnemonic: String) -> Self {
+        Self {
+            enable_credentials_mode: true,
+            mnemonic: Some(mnemonic),
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub enum TestedNode {
     #[default]
@@ -112,6 +161,8 @@ pub enum TestedNode {
     Custom {
         identity: NodeIdentity,
     },
+    /// A node already looked up by the caller, tested via the entry.
+    Node(Box<NymNode>),
 }
 
 impl TestedNode {
@@ -169,6 +220,7 @@ pub struct Probe {
     entrypoint: EntryPoint,
     tested_node: TestedNode,
     amnezia_args: String,
+    skip_wireguard: bool,
     netstack_args: NetstackArgs,
     credentials_args: CredentialArgs,
 }
@@ -184,6 +236,7 @@ impl Probe {
             entrypoint,
             tested_node,
             amnezia_args: "".into(),
+            skip_wireguard: false,
             netstack_args,
             credentials_args,
         }
@@ -193,17 +246,40 @@ impl Probe {
         self
     }
 
+    /// Skip registering with the authenticator of the tested node.
+    pub fn without_wireguard(&mut self) -> &Self {
+        self.skip_wireguard = true;
+        self
+    }
+
     pub async fn probe(
         self,
         gateway_config: GatewayDirectoryConfig,
         ignore_egress_epoch_role: bool,
         only_wireguard: bool,
     ) -> anyhow::Result<ProbeResult> {
-        let entry_point = self.entrypoint;
-
         // Setup the entry gateways
         let gateways = lookup_gateways(gateway_config.clone()).await?;
-        let entry_gateway = entry_point.lookup_gateway(&gateways).await?;
+        self.probe_with_gateways(
+            gateway_config,
+            &gateways,
+            ignore_egress_epoch_role,
+            only_wireguard,
+        )
+        .await
+    }
+
+    /// Probe using an already fetched list of gateways, avoiding a directory lookup per probe.
+    pub async fn probe_with_gateways(
+        self,
+        gateway_config: GatewayDirectoryConfig,
+        gateways: &GatewayList,
+        ignore_egress_epoch_role: bool,
+        only_wireguard: bool,
+    ) -> anyhow::Result<ProbeResult> {
+        let entry_point = self.entrypoint;
+
+        let entry_gateway = entry_point.lookup_gateway(gateways).await?;
         let tested_entry = self.tested_node.is_same_as_entry();
 
         let node_info: TestedNodeDetails = match self.tested_node {
@@ -215,6 +291,13 @@ impl Probe {
                 );
                 (&node).into()
             }
+            TestedNode::Node(node) => {
+                info!(
+                    "testing node {} (via entry {})",
+                    node.identity, entry_gateway.identity
+                );
+                node.as_ref().into()
+            }
             TestedNode::SameAsEntry => (&entry_gateway).into(),
         };
 
@@ -309,7 +392,9 @@ impl Probe {
             .await
         };
 
-        let wg_outcome = if let (Some(authenticator), Some(ip_address)) =
+        let wg_outcome = if self.skip_wireguard {
+            None
+        } else if let (Some(authenticator), Some(ip_address)) =
             (node_info.authenticator_address, node_info.ip_address)
         {
             // Start the mixnet listener that the auth clients use to receive messages.
@@ -329,16 +414,16 @@ impl Probe {
 
             let _ = mixnet_listener_task.cancel().await;
 
-            outcome
+            Some(outcome)
         } else {
-            WgProbeResults::default()
+            Some(WgProbeResults::default())
         };
 
         shared_client.disconnect().await;
 
         // Disconnect the mixnet client gracefully
         outcome.map(|mut outcome| {
-            outcome.wg = Some(wg_outcome);
+            outcome.wg = wg_outcome;
             ProbeResult {
                 node: node_info.identity.to_string(),
                 used_entry: mixnet_entry_gateway_id.to_string(),
@@ -348,6 +433,7 @@ impl Probe {
     }
 }
 
+#[cfg_attr(not(feature = "netstack"), allow(unused_variables))]
 async fn wg_probe(
     authenticator: AuthAddress,
     mut auth_client: nym_authenticator_client::AuthClient,
@@ -358,8 +444,8 @@ async fn wg_probe(
 ) -> anyhow::Result<WgProbeResults> {
     info!("attempting to use authenticator version {auth_version:?}");
 
-    let mut rng = rand::thread_rng();
-    let private_key = nym_crypto::asymmetric::encryption::PrivateKey::new(&mut rng);
+    // Don't hold the thread local rng across awaits, the probe runs on multi-threaded runtimes.
+    let private_key = nym_crypto::asymmetric::encryption::PrivateKey::new(&mut rand::thread_rng());
     let public_key = private_key.public_key();
 
     let authenticator_pub_key = PeerPublicKey::new(public_key.to_bytes().into());
@@ -457,10 +543,7 @@ async fn wg_probe(
         };
 
         let peer_public = registered_data.pub_key().inner();
-        let static_private = x25519_dalek::StaticSecret::from(private_key.to_bytes());
         let public_key_bs64 = general_purpose::STANDARD.encode(peer_public.as_bytes());
-        let private_key_hex = hex::encode(static_private.to_bytes());
-        let public_key_hex = hex::encode(peer_public.as_bytes());
 
         info!("WG connection details");
         info!("Peer public key: {}", public_key_bs64);
@@ -471,13 +554,17 @@ async fn wg_probe(
             registered_data.wg_port(),
         );
 
-        let wg_endpoint = format!("{gateway_ip}:{}", registered_data.wg_port());
-
         info!("Successfully registered with the gateway");
 
         wg_outcome.can_register = true;
 
-        if wg_outcome.can_register {
+        #[cfg(feature = "netstack")]
+        {
+            let static_private = x25519_dalek::StaticSecret::from(private_key.to_bytes());
+            let private_key_hex = hex::encode(static_private.to_bytes());
+            let public_key_hex = hex::encode(peer_public.as_bytes());
+            let wg_endpoint = format!("{gateway_ip}:{}", registered_data.wg_port());
+
             let netstack_request = NetstackRequest::new(
                 &registered_data.private_ips().ipv4.to_string(),
                 &registered_data.private_ips().ipv6.to_string(),
@@ -504,6 +591,9 @@ async fn wg_probe(
             wg_outcome.ping_ips_performance_v4 =
                 netstack_response_v4.received_ips as f32 / netstack_response_v4.sent_ips as f32;
 
+            wg_outcome.benchmark_v4 = netstack_response_v4.benchmark_result();
+            wg_outcome.benchmark_error_v4 = netstack_response_v4.benchmark_error;
+
             wg_outcome.download_duration_sec_v4 = netstack_response_v4.download_duration_sec;
             wg_outcome.downloaded_file_v4 = netstack_response_v4.downloaded_file;
             wg_outcome.download_error_v4 = netstack_response_v4.download_error;
@@ -523,6 +613,9 @@ async fn wg_probe(
             wg_outcome.ping_ips_performance_v6 =
                 netstack_response_v6.received_ips as f32 / netstack_response_v6.sent_ips as f32;
 
+            wg_outcome.benchmark_v6 = netstack_response_v6.benchmark_result();
+            wg_outcome.benchmark_error_v6 = netstack_response_v6.benchmark_error;
+
             wg_outcome.download_duration_sec_v6 = netstack_response_v6.download_duration_sec;
             wg_outcome.downloaded_file_v6 = netstack_response_v6.downloaded_file;
             wg_outcome.download_error_v6 = netstack_response_v6.download_error;
@@ -8,10 +8,22 @@ mod run;
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     match run::run().await {
-        Ok(ref result) => {
+        Ok(run::RunOutput::Single(ref result)) => {
             let json = serde_json::to_string_pretty(result)?;
             println!("{}", json);
         }
+        Ok(run::RunOutput::Batch(ref report, run::ReportFormat::Json)) => {
+            let json = serde_json::to_string_pretty(report)?;
+            println!("{}", json);
+        }
+        Ok(run::RunOutput::Batch(ref report, run::ReportFormat::Csv)) => {
+            print!("{}", report.to_csv());
+        }
+        #[cfg(feature = "store")]
+        Ok(run::RunOutput::History(ref report)) => {
+            let json = serde_json::to_string_pretty(report)?;
+            println!("{}", json);
+        }
         Err(err) => {
             eprintln!("An error occurred: {err}");
             std::process::exit(1)
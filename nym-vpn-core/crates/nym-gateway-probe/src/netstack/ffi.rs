@@ -1,3 +1,5 @@
+use nym_tunnel_benchmark::{BenchmarkResult, LatencyStats};
+
 use super::NetstackRequest;
 
 pub mod binding {
@@ -20,6 +22,9 @@ pub struct NetstackRequestGo {
     recv_timeout_sec: u64,
     download_timeout_sec: u64,
     awg_args: String,
+    benchmark_server: String,
+    benchmark_duration_sec: u64,
+    benchmark_rtt_probes: u32,
 }
 
 impl NetstackRequestGo {
@@ -38,6 +43,9 @@ impl NetstackRequestGo {
             recv_timeout_sec: req.v4_ping_config.recv_timeout_sec,
             download_timeout_sec: req.download_timeout_sec,
             awg_args: req.awg_args.clone(),
+            benchmark_server: req.benchmark.server.clone(),
+            benchmark_duration_sec: req.benchmark.duration_sec,
+            benchmark_rtt_probes: req.benchmark.rtt_probes,
         }
     }
 
@@ -56,6 +64,9 @@ impl NetstackRequestGo {
             recv_timeout_sec: req.v6_ping_config.recv_timeout_sec,
             download_timeout_sec: req.download_timeout_sec,
             awg_args: req.awg_args.clone(),
+            benchmark_server: req.benchmark.server.clone(),
+            benchmark_duration_sec: req.benchmark.duration_sec,
+            benchmark_rtt_probes: req.benchmark.rtt_probes,
         }
     }
 }
@@ -76,4 +87,29 @@ pub struct NetstackResponse {
     pub downloaded_file: String,
     pub download_duration_sec: u64,
     pub download_error: String,
+    // rust2go has no floating point support, hence the integer units.
+    pub benchmark_upload_kbps: u64,
+    pub benchmark_download_kbps: u64,
+    pub benchmark_packets_sent: u32,
+    pub benchmark_rtts_us: Vec<u64>,
+    pub benchmark_error: String,
+}
+
+impl NetstackResponse {
+    /// Results of the benchmark, unset when it didn't run or failed before measuring anything.
+    pub fn benchmark_result(&self) -> Option<BenchmarkResult> {
+        let measured = self.benchmark_packets_sent > 0
+            || self.benchmark_upload_kbps > 0
+            || self.benchmark_download_kbps > 0;
+        let rtts_ms = self
+            .benchmark_rtts_us
+            .iter()
+            .map(|&rtt_us| rtt_us as f64 / 1000.0)
+            .collect();
+        measured.then(|| BenchmarkResult {
+            upload_mbps: self.benchmark_upload_kbps as f64 / 1000.0,
+            download_mbps: self.benchmark_download_kbps as f64 / 1000.0,
+            latency: LatencyStats::new(self.benchmark_packets_sent, rtts_ms),
+        })
+    }
 }
@@ -10,6 +10,7 @@ pub struct NetstackRequest {
     v6_ping_config: PingConfig,
     download_timeout_sec: u64,
     awg_args: String,
+    benchmark: BenchmarkConfig,
 }
 
 pub struct PingConfig {
@@ -22,6 +23,23 @@ pub struct PingConfig {
     recv_timeout_sec: u64,
 }
 
+/// Benchmark run after the ping checks, skipped when no server is set.
+pub struct BenchmarkConfig {
+    server: String,
+    duration_sec: u64,
+    rtt_probes: u32,
+}
+
+impl BenchmarkConfig {
+    pub fn from_netstack_args(args: &NetstackArgs) -> Self {
+        Self {
+            server: args.netstack_benchmark_server.clone().unwrap_or_default(),
+            duration_sec: args.netstack_benchmark_duration_sec,
+            rtt_probes: args.netstack_benchmark_rtt_probes,
+        }
+    }
+}
+
 impl PingConfig {
     pub fn from_netstack_args_v4(wg_ip4: &str, args: &NetstackArgs) -> Self {
         Self {
@@ -67,6 +85,7 @@ impl NetstackRequest {
             awg_args: awg_args.to_string(),
             v4_ping_config: PingConfig::from_netstack_args_v4(wg_ip4, &netstack_args),
             v6_ping_config: PingConfig::from_netstack_args_v6(wg_ip6, &netstack_args),
+            benchmark: BenchmarkConfig::from_netstack_args(&netstack_args),
             download_timeout_sec,
         }
     }
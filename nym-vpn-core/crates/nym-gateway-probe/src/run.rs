@@ -1,14 +1,20 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use anyhow::anyhow;
-use clap::Parser;
+use anyhow::{anyhow, Context};
+use clap::{Parser, ValueEnum};
 use nym_bin_common::bin_info;
 use nym_config::defaults::setup_env;
-use nym_gateway_directory::{EntryPoint, GatewayMinPerformance};
-use nym_gateway_probe::{CredentialArgs, NetstackArgs, ProbeResult, TestedNode};
+use nym_gateway_directory::{EntryPoint, GatewayList, GatewayMinPerformance};
+use nym_gateway_probe::{
+    BatchProbe, BatchReport, CredentialArgs, NetstackArgs, ProbeResult, TestedNode,
+};
+#[cfg(feature = "store")]
+use nym_gateway_probe::{BatchProbeResult, HistoryReport, ProbeStore};
 use nym_sdk::mixnet::NodeIdentity;
-use std::{path::PathBuf, sync::OnceLock};
+#[cfg(feature = "store")]
+use std::path::Path;
+use std::{net::SocketAddr, path::PathBuf, sync::OnceLock, time::Duration};
 use tracing::*;
 
 fn pretty_build_info_static() -> &'static str {
@@ -58,6 +64,10 @@ struct CliArgs {
     #[arg(long, short)]
     amnezia_args: Option<String>,
 
+    /// Probe many gateways, each as its own entry, and print an aggregated report
+    #[command(flatten)]
+    batch_args: BatchArgs,
+
     /// Arguments to manage netstack downloads
     #[command(flatten)]
     netstack_args: NetstackArgs,
@@ -65,6 +75,104 @@ struct CliArgs {
     /// Arguments to manage credentials
     #[command(flatten)]
     credential_args: CredentialArgs,
+
+    /// Append the results to this SQLite database, for `report` to summarize them later
+    #[cfg(feature = "store")]
+    #[arg(long)]
+    store: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Summarize the results stored with `--store`: uptime, handshake success rate and
+    /// performance of each gateway over a time window
+    #[cfg(feature = "store")]
+    Report(ReportArgs),
+
+    /// Serve the throughput and latency benchmark of `--netstack-benchmark-server` and
+    /// `nym-vpnc benchmark`
+    BenchmarkServer(BenchmarkServerArgs),
+}
+
+#[derive(clap::Args)]
+struct BenchmarkServerArgs {
+    /// Address to listen on, over TCP and UDP. Use [::]:5201 to also accept IPv6 clients
+    #[arg(long, default_value = "0.0.0.0:5201")]
+    listen: SocketAddr,
+}
+
+#[cfg(feature = "store")]
+#[derive(clap::Args)]
+struct ReportArgs {
+    /// SQLite database the results were stored in
+    #[arg(long)]
+    store: PathBuf,
+
+    /// Only report on the gateway with this identity
+    #[arg(long)]
+    gateway: Option<String>,
+
+    /// Length of the window, ending now
+    #[arg(long, default_value_t = 168)]
+    since_hours: u64,
+
+    /// Length of the buckets the window is split into to show trends
+    #[arg(long, default_value_t = 24)]
+    bucket_hours: u64,
+}
+
+#[derive(clap::Args)]
+struct BatchArgs {
+    /// Probe a list of gateways instead of a single one
+    #[arg(long, conflicts_with_all = ["entry_gateway", "node"])]
+    batch: bool,
+
+    /// Only probe gateways located in this country, e.g. DE
+    #[arg(long, requires = "batch", conflicts_with = "gateways_file")]
+    country: Option<String>,
+
+    /// Only probe gateways of this type
+    #[arg(long, value_enum, requires = "batch", conflicts_with = "gateways_file")]
+    gateway_type: Option<BatchGatewayType>,
+
+    /// Probe the gateways listed in this file, one identity per line
+    #[arg(long, requires = "batch")]
+    gateways_file: Option<PathBuf>,
+
+    /// Number of probes running at the same time
+    #[arg(long, default_value_t = 4, requires = "batch")]
+    concurrency: usize,
+
+    /// Time after which a single probe is abandoned
+    #[arg(long, default_value_t = 300, requires = "batch")]
+    probe_timeout_sec: u64,
+
+    /// Format of the report
+    #[arg(long, value_enum, default_value_t = ReportFormat::Json, requires = "batch")]
+    report_format: ReportFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BatchGatewayType {
+    MixnetEntry,
+    MixnetExit,
+    Wg,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum ReportFormat {
+    Json,
+    Csv,
+}
+
+pub(crate) enum RunOutput {
+    Single(ProbeResult),
+    Batch(BatchReport, ReportFormat),
+    #[cfg(feature = "store")]
+    History(HistoryReport),
 }
 
 fn setup_logging() {
@@ -81,12 +189,27 @@ fn setup_logging() {
         .init();
 }
 
-pub(crate) async fn run() -> anyhow::Result<ProbeResult> {
+pub(crate) async fn run() -> anyhow::Result<RunOutput> {
     let args = CliArgs::parse();
     if !args.no_log {
         setup_logging();
     }
     debug!("{:?}", nym_bin_common::bin_info_local_vergen!());
+
+    match &args.command {
+        #[cfg(feature = "store")]
+        Some(Command::Report(report_args)) => {
+            return report(report_args).await.map(RunOutput::History);
+        }
+        Some(Command::BenchmarkServer(server_args)) => {
+            nym_tunnel_benchmark::run_server(server_args.listen)
+                .await
+                .context("benchmark server failed")?;
+            anyhow::bail!("benchmark server stopped");
+        }
+        None => {}
+    }
+
     setup_env(args.config_env_file.as_ref());
 
     let network = nym_sdk::NymNetworkDetails::new_from_env();
@@ -111,14 +234,35 @@ pub(crate) async fn run() -> anyhow::Result<ProbeResult> {
         min_gateway_performance: Some(min_gateway_performance),
         mix_score_thresholds: None,
         wg_score_thresholds: None,
+        custom_gateways: Vec::new(),
+        harbour_master_url: None,
     };
 
+    if args.batch_args.batch {
+        let report_format = args.batch_args.report_format;
+        #[cfg(feature = "store")]
+        let store = args.store.clone();
+        let report = run_batch(args, gateway_config).await?;
+        #[cfg(feature = "store")]
+        if let Some(path) = store {
+            store_results(&path, &report.results).await;
+        }
+        return Ok(RunOutput::Batch(report, report_format));
+    }
+
     let entry = if let Some(gateway) = args.entry_gateway {
         EntryPoint::from_base58_string(&gateway)?
     } else {
         fetch_random_gateway_with_ipr(gateway_config.clone()).await?
     };
 
+    #[cfg(feature = "store")]
+    let tested_gateway = match (&args.node, &entry) {
+        (Some(node), _) => Some(*node),
+        (None, EntryPoint::Gateway { identity }) => Some(*identity),
+        (None, _) => None,
+    };
+
     let test_point = if let Some(node) = args.node {
         TestedNode::Custom { identity: node }
     } else {
@@ -130,13 +274,122 @@ pub(crate) async fn run() -> anyhow::Result<ProbeResult> {
     if let Some(awg_args) = args.amnezia_args {
         trial.with_amnezia(&awg_args);
     }
-    trial
+    #[cfg(feature = "store")]
+    let started = std::time::Instant::now();
+    let result = trial
         .probe(
             gateway_config,
             args.only_wireguard,
             args.ignore_egress_epoch_role,
         )
+        .await;
+
+    #[cfg(feature = "store")]
+    if let (Some(path), Some(gateway)) = (&args.store, tested_gateway) {
+        let probe = BatchProbeResult {
+            gateway: gateway.to_base58_string(),
+            duration_sec: started.elapsed().as_secs_f64(),
+            result: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(ToString::to_string),
+        };
+        store_results(path, &[probe]).await;
+    }
+    result.map(RunOutput::Single)
+}
+
+/// Append the results to the store. Failing to do so doesn't fail the run.
+#[cfg(feature = "store")]
+async fn store_results(path: &Path, results: &[BatchProbeResult]) {
+    let stored = match ProbeStore::open(path).await {
+        Ok(store) => {
+            let stored = store.insert_batch(results).await;
+            store.close().await;
+            stored
+        }
+        Err(err) => Err(err),
+    };
+    match stored {
+        Ok(()) => info!("Stored {} results in {}", results.len(), path.display()),
+        Err(err) => error!("Failed to store results in {}: {err}", path.display()),
+    }
+}
+
+#[cfg(feature = "store")]
+async fn report(args: &ReportArgs) -> anyhow::Result<HistoryReport> {
+    let store = ProbeStore::open(&args.store)
+        .await
+        .with_context(|| format!("failed to open {}", args.store.display()))?;
+    let since = time::OffsetDateTime::now_utc() - Duration::from_secs(args.since_hours * 3600);
+    let probes = store.probes_since(since, args.gateway.as_deref()).await?;
+    store.close().await;
+
+    Ok(HistoryReport::new(
+        &probes,
+        since,
+        Duration::from_secs(args.bucket_hours * 3600),
+    ))
+}
+
+async fn run_batch(
+    args: CliArgs,
+    gateway_config: nym_gateway_directory::Config,
+) -> anyhow::Result<BatchReport> {
+    let gateways = nym_gateway_probe::fetch_gateways(gateway_config.clone()).await?;
+    let identities = if let Some(path) = &args.batch_args.gateways_file {
+        read_gateways_file(path).await?
+    } else {
+        select_gateways(gateways.clone(), &args.batch_args)
+    };
+    if identities.is_empty() {
+        return Err(anyhow!("No gateways to probe"));
+    }
+
+    let mut batch_probe = BatchProbe::new(identities, args.netstack_args, args.credential_args)
+        .with_concurrency(args.batch_args.concurrency)
+        .with_probe_timeout(Duration::from_secs(args.batch_args.probe_timeout_sec));
+    if let Some(awg_args) = args.amnezia_args {
+        batch_probe = batch_probe.with_amnezia(&awg_args);
+    }
+    Ok(batch_probe
+        .run(
+            gateway_config,
+            &gateways,
+            args.ignore_egress_epoch_role,
+            args.only_wireguard,
+        )
+        .await)
+}
+
+fn select_gateways(gateways: GatewayList, args: &BatchArgs) -> Vec<NodeIdentity> {
+    let gateways = match args.gateway_type {
+        None | Some(BatchGatewayType::MixnetEntry) => gateways,
+        Some(BatchGatewayType::MixnetExit) => gateways.into_exit_gateways(),
+        Some(BatchGatewayType::Wg) => gateways.into_vpn_gateways(),
+    };
+    gateways
+        .into_iter()
+        .filter(|gateway| {
+            args.country.as_ref().is_none_or(|country| {
+                gateway.is_two_letter_iso_country_code(&country.to_ascii_uppercase())
+            })
+        })
+        .map(|gateway| gateway.identity())
+        .collect()
+}
+
+async fn read_gateways_file(path: &PathBuf) -> anyhow::Result<Vec<NodeIdentity>> {
+    let contents = tokio::fs::read_to_string(path)
         .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            NodeIdentity::from_base58_string(line)
+                .map_err(|err| anyhow!("invalid gateway identity {line}: {err}"))
+        })
+        .collect()
 }
 
 async fn fetch_random_gateway_with_ipr(
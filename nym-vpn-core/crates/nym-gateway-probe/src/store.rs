@@ -0,0 +1,117 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+
+use sqlx::{ConnectOptions, FromRow};
+use time::OffsetDateTime;
+use tracing::{debug, log::LevelFilter, warn};
+
+use crate::{batch::BatchProbeResult, history::StoredProbe, types::ProbeResult};
+
+/// SQLite database the probe results are appended to.
+#[derive(Clone)]
+pub struct ProbeStore {
+    connection_pool: sqlx::SqlitePool,
+}
+
+#[derive(FromRow)]
+struct ProbeRow {
+    gateway: String,
+    result: Option<String>,
+    error: Option<String>,
+    timestamp: OffsetDateTime,
+}
+
+impl ProbeStore {
+    pub async fn open<P: AsRef<Path>>(database_path: P) -> Result<Self, sqlx::Error> {
+        debug!("Opening probe store: {}", database_path.as_ref().display());
+        let opts = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(&database_path)
+            .create_if_missing(true)
+            .log_statements(LevelFilter::Trace);
+        let connection_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .connect_with(opts)
+            .await?;
+        sqlx::migrate!("./migrations").run(&connection_pool).await?;
+
+        Ok(Self { connection_pool })
+    }
+
+    pub async fn close(&self) {
+        self.connection_pool.close().await
+    }
+
+    /// Append the outcome of probing `gateway`, either a result or the error the probe failed
+    /// with.
+    pub async fn insert(
+        &self,
+        gateway: &str,
+        result: Result<&ProbeResult, &str>,
+    ) -> Result<(), sqlx::Error> {
+        let (result, error) = match result {
+            Ok(result) => (
+                Some(serde_json::to_string(result).map_err(|err| sqlx::Error::Encode(err.into()))?),
+                None,
+            ),
+            Err(error) => (None, Some(error)),
+        };
+        sqlx::query(
+            "INSERT INTO probe_results (gateway, result, error, timestamp) VALUES (?, ?, ?, ?)",
+        )
+        .bind(gateway)
+        .bind(result)
+        .bind(error)
+        .bind(OffsetDateTime::now_utc())
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn insert_batch(&self, results: &[BatchProbeResult]) -> Result<(), sqlx::Error> {
+        for probe in results {
+            let result = match (&probe.result, &probe.error) {
+                (Some(result), _) => Ok(result),
+                (None, error) => Err(error.as_deref().unwrap_or_default()),
+            };
+            self.insert(&probe.gateway, result).await?;
+        }
+        Ok(())
+    }
+
+    /// Probes stored since the given time, oldest first, optionally limited to one gateway.
+    pub async fn probes_since(
+        &self,
+        since: OffsetDateTime,
+        gateway: Option<&str>,
+    ) -> Result<Vec<StoredProbe>, sqlx::Error> {
+        let rows: Vec<ProbeRow> = sqlx::query_as(
+            "SELECT gateway, result, error, timestamp FROM probe_results \
+             WHERE timestamp >= ? AND (? IS NULL OR gateway = ?) ORDER BY timestamp, id",
+        )
+        .bind(since)
+        .bind(gateway)
+        .bind(gateway)
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let result = row.result.and_then(|result| {
+                    serde_json::from_str(&result)
+                        .inspect_err(|err| {
+                            warn!("Ignoring unreadable result of {}: {err}", row.gateway)
+                        })
+                        .ok()
+                });
+                StoredProbe {
+                    gateway: row.gateway,
+                    timestamp: row.timestamp,
+                    result,
+                    error: row.error,
+                }
+            })
+            .collect())
+    }
+}
@@ -1,4 +1,5 @@
 use nym_connection_monitor::ConnectionStatusEvent;
+use nym_tunnel_benchmark::BenchmarkResult;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,17 @@ pub struct WgProbeResults {
     pub download_duration_sec_v6: u64,
     pub downloaded_file_v6: String,
     pub download_error_v6: String,
+
+    /// Throughput and latency through the tunnel, set when a benchmark server was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub benchmark_v4: Option<BenchmarkResult>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub benchmark_error_v4: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub benchmark_v6: Option<BenchmarkResult>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub benchmark_error_v6: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,12 +1,36 @@
+use std::{net::SocketAddr, time::Duration};
+
 use tracing::debug;
+use url::Url;
 
 use crate::{error::Result, responses::Gateway, Client, HarbourMasterApiClientExt};
 
-const HARBOUR_MASTER: &str = "https://harbourmaster.nymtech.net";
+/// The harbour master instance run by Nym.
+pub const HARBOUR_MASTER_URL: &str = "https://harbourmaster.nymtech.net";
+
+const HARBOUR_MASTER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Create a client for the harbour master at `base_url`, optionally pinning its domain to
+/// `static_addresses` so that requests don't depend on DNS.
+pub fn new_client(base_url: Url, static_addresses: Option<&[SocketAddr]>) -> Result<Client> {
+    let client: std::result::Result<Client, crate::HarbourMasterApiError> =
+        Client::builder(base_url.clone())
+            .map(|builder| {
+                let builder = builder.with_timeout(HARBOUR_MASTER_TIMEOUT);
+                match (base_url.domain(), static_addresses) {
+                    (Some(domain), Some(static_addresses)) if !static_addresses.is_empty() => {
+                        debug!("Resolving {domain} to {static_addresses:?}");
+                        builder.resolve_to_addrs(domain, static_addresses)
+                    }
+                    _ => builder,
+                }
+            })
+            .and_then(|builder| builder.build());
+    Ok(client?)
+}
 
-pub async fn get_gateways() -> Result<Vec<Gateway>> {
-    debug!("Fetching gateways");
-    let timeout = std::time::Duration::from_secs(10);
-    let client = Client::new_url(HARBOUR_MASTER, Some(timeout))?;
+pub async fn get_gateways(base_url: Url) -> Result<Vec<Gateway>> {
+    debug!("Fetching gateways from {base_url}");
+    let client = new_client(base_url, None)?;
     Ok(client.get_gateways().await?)
 }
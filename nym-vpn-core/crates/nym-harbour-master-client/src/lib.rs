@@ -6,5 +6,5 @@ mod routes;
 
 pub use client::{Client, HarbourMasterApiClientExt, HarbourMasterApiError};
 pub use error::HarbourMasterError;
-pub use helpers::get_gateways;
-pub use responses::{Gateway, PagedResult};
+pub use helpers::{get_gateways, new_client, HARBOUR_MASTER_URL};
+pub use responses::{Gateway, PagedResult, ProbeOutcome, ProbeResult};
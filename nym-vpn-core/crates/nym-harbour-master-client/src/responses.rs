@@ -74,6 +74,17 @@ pub struct Exit {
 }
 
 impl Gateway {
+    /// The last probe result, if it was updated recently enough to be relied on.
+    pub fn recent_probe_result(&self) -> Option<ProbeResult> {
+        if !is_recently_updated(&self.last_updated_utc) {
+            return None;
+        }
+        let last_probe_result = self.last_probe_result.as_ref()?;
+        serde_json::from_value(last_probe_result.clone())
+            .inspect_err(|err| warn!("Failed to parse probe result: {:?}", err))
+            .ok()
+    }
+
     pub fn is_fully_operational_entry(&self) -> bool {
         if !is_recently_updated(&self.last_updated_utc) {
             debug!(
@@ -263,11 +263,15 @@ impl RouteManagerHandle {
     }
 
     /// Ensure that packets are routed using the correct tables.
+    /// The IPv6 rules are only created when `enable_ipv6` is set.
     #[cfg(target_os = "linux")]
-    pub async fn create_routing_rules(&self) -> Result<(), Error> {
+    pub async fn create_routing_rules(&self, enable_ipv6: bool) -> Result<(), Error> {
         let (response_tx, response_rx) = oneshot::channel();
         self.tx
-            .send(RouteManagerCommand::CreateRoutingRules(true, response_tx))
+            .send(RouteManagerCommand::CreateRoutingRules(
+                enable_ipv6,
+                response_tx,
+            ))
             .map_err(|_| Error::RouteManagerDown)?;
         response_rx
             .await
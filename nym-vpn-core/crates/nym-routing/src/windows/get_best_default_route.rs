@@ -9,12 +9,13 @@ use widestring::{widecstr, WideCStr};
 use windows::Win32::{
     NetworkManagement::{
         IpHelper::{
-            FreeMibTable, GetIfEntry2, GetIpForwardTable2, IF_TYPE_SOFTWARE_LOOPBACK,
-            IF_TYPE_TUNNEL, MIB_IF_ROW2, MIB_IPFORWARD_ROW2,
+            FreeMibTable, GetIfEntry2, GetIpForwardTable2, ResolveIpNetEntry2,
+            IF_TYPE_SOFTWARE_LOOPBACK, IF_TYPE_TUNNEL, MIB_IF_ROW2, MIB_IPFORWARD_ROW2,
+            MIB_IPNET_ROW2,
         },
         Ndis::NET_LUID_LH,
     },
-    Networking::WinSock::ADDRESS_FAMILY,
+    Networking::WinSock::{ADDRESS_FAMILY, SOCKADDR_INET},
 };
 
 // Interface description substrings found for virtual adapters.
@@ -101,6 +102,29 @@ pub fn get_best_default_route(family: AddressFamily) -> Result<Option<InterfaceA
         .transpose()
 }
 
+/// Get the MAC address of the gateway of a default route, resolving it when it isn't in the
+/// neighbor cache yet. Returns `None` for interfaces without 6 byte link layer addresses.
+pub fn get_gateway_mac_address(route: &InterfaceAndGateway) -> Result<Option<[u8; 6]>> {
+    // SAFETY: We are allowed to initialize MIB_IPNET_ROW2 with zeroed because it is made up
+    // entirely of types for which the zero pattern (all zeros) is valid.
+    let mut row: MIB_IPNET_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceLuid = route.iface;
+    row.Address = SOCKADDR_INET::from(route.gateway);
+
+    // SAFETY: ResolveIpNetEntry2 reads the interface and address set above and fills in the
+    // rest of the row.
+    unsafe { ResolveIpNetEntry2(&mut row, None) }
+        .ok()
+        .map_err(Error::ResolveNeighborFailed)?;
+
+    if row.PhysicalAddressLength != 6 {
+        return Ok(None);
+    }
+    let mut mac_address = [0u8; 6];
+    mac_address.copy_from_slice(&row.PhysicalAddress[..6]);
+    Ok(Some(mac_address))
+}
+
 pub fn route_has_gateway(route: &MIB_IPFORWARD_ROW2) -> bool {
     try_socketaddr_from_inet_sockaddr(route.NextHop)
         .map(|addr| !addr.ip().is_unspecified())
@@ -11,7 +11,9 @@ use futures::{
     },
     StreamExt,
 };
-pub use get_best_default_route::{get_best_default_route, InterfaceAndGateway};
+pub use get_best_default_route::{
+    get_best_default_route, get_gateway_mac_address, InterfaceAndGateway,
+};
 use net::AddressFamily;
 use nym_common::ErrorExt;
 use nym_windows::net;
@@ -43,6 +45,9 @@ pub enum Error {
     /// GetIfEntry2 windows API call failed
     #[error("Failed to retrieve network interface entry")]
     GetIfEntryFailed(#[source] windows::core::Error),
+    /// ResolveIpNetEntry2 windows API call failed
+    #[error("Failed to resolve the link layer address of a neighbor")]
+    ResolveNeighborFailed(#[source] windows::core::Error),
     /// Low level error caused by failing to register the route callback
     #[error("Attempt to register notify route change callback failed")]
     RegisterNotifyRouteCallback(#[source] windows::core::Error),
@@ -0,0 +1,233 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+};
+use tracing::debug;
+
+use crate::{
+    error::{Error, Result},
+    stats::{mbps, BenchmarkResult, LatencyStats},
+    SessionToken, CHUNK_SIZE, MAX_DATAGRAM_SIZE, MODE_DOWNLOAD, MODE_LATENCY, MODE_UPLOAD,
+    TOKEN_LEN,
+};
+
+/// Session token, sequence number and send time in microseconds, padded to this size.
+const PACKET_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkOptions {
+    /// Duration of the upload and of the download.
+    pub duration: Duration,
+    /// Number of UDP packets sent to measure round trip times and packet loss.
+    pub rtt_probes: u32,
+    pub rtt_interval: Duration,
+    /// Time to wait for the last packets to be echoed back.
+    pub rtt_timeout: Duration,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(10),
+            rtt_probes: 100,
+            rtt_interval: Duration::from_millis(50),
+            rtt_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Run the benchmark against the server at `server`, given as `host:port`. The traffic goes
+/// through whatever route the system has to the server, i.e. through the tunnel while connected.
+pub async fn run(server: &str, options: &BenchmarkOptions) -> Result<BenchmarkResult> {
+    let addr = tokio::net::lookup_host(server)
+        .await
+        .map_err(|source| Error::ResolveServer {
+            server: server.to_owned(),
+            source,
+        })?
+        .next()
+        .ok_or_else(|| Error::NoServerAddress(server.to_owned()))?;
+
+    debug!("Measuring latency to {addr}");
+    let latency = latency(addr, options).await?;
+    debug!("Measuring upload to {addr}");
+    let upload_mbps = upload(addr, options.duration).await?;
+    debug!("Measuring download from {addr}");
+    let download_mbps = download(addr, options.duration).await?;
+
+    Ok(BenchmarkResult {
+        upload_mbps,
+        download_mbps,
+        latency,
+    })
+}
+
+async fn connect(addr: SocketAddr, mode: u8, duration: Duration) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect(addr)
+        .await
+        .map_err(|source| Error::Connect {
+            server: addr,
+            source,
+        })?;
+    stream.set_nodelay(true).ok();
+    let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+    async {
+        stream.write_u8(mode).await?;
+        stream.write_u64(duration_ms).await
+    }
+    .await
+    .map_err(|source| Error::Connect {
+        server: addr,
+        source,
+    })?;
+    Ok(stream)
+}
+
+async fn upload(addr: SocketAddr, duration: Duration) -> Result<f64> {
+    let mut stream = connect(addr, MODE_UPLOAD, duration).await?;
+    let started = Instant::now();
+    async {
+        let buf = vec![0; CHUNK_SIZE];
+        while started.elapsed() < duration {
+            stream.write_all(&buf).await?;
+        }
+        stream.shutdown().await?;
+        // The server answers once it read everything, so this includes the data in flight.
+        let received = stream.read_u64().await?;
+        Ok(mbps(received, started.elapsed()))
+    }
+    .await
+    .map_err(Error::Upload)
+}
+
+async fn download(addr: SocketAddr, duration: Duration) -> Result<f64> {
+    let mut stream = connect(addr, MODE_DOWNLOAD, duration).await?;
+    let started = Instant::now();
+    async {
+        let mut buf = vec![0; CHUNK_SIZE];
+        let mut received = 0u64;
+        loop {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            received += read as u64;
+        }
+        Ok(mbps(received, started.elapsed()))
+    }
+    .await
+    .map_err(Error::Download)
+}
+
+async fn latency(addr: SocketAddr, options: &BenchmarkOptions) -> Result<LatencyStats> {
+    let session_duration = options.rtt_interval * options.rtt_probes + options.rtt_timeout;
+    // The session lasts as long as this connection stays open.
+    let mut session = connect(addr, MODE_LATENCY, session_duration).await?;
+    let mut token = SessionToken::default();
+    session
+        .read_exact(&mut token)
+        .await
+        .map_err(Error::Latency)?;
+    measure_latency(addr, token, options)
+        .await
+        .map_err(Error::Latency)
+}
+
+async fn measure_latency(
+    addr: SocketAddr,
+    token: SessionToken,
+    options: &BenchmarkOptions,
+) -> io::Result<LatencyStats> {
+    let bind: SocketAddr = if addr.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind).await?;
+    socket.connect(addr).await?;
+
+    let started = Instant::now();
+    let deadline = started + options.rtt_interval * options.rtt_probes + options.rtt_timeout;
+
+    let send = async {
+        let mut interval = tokio::time::interval(options.rtt_interval);
+        let mut packet = [0u8; PACKET_SIZE];
+        packet[..TOKEN_LEN].copy_from_slice(&token);
+        for seq in 0..options.rtt_probes {
+            interval.tick().await;
+            let sent_us = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+            packet[TOKEN_LEN..TOKEN_LEN + 4].copy_from_slice(&seq.to_be_bytes());
+            packet[TOKEN_LEN + 4..TOKEN_LEN + 12].copy_from_slice(&sent_us.to_be_bytes());
+            socket.send(&packet).await?;
+        }
+        io::Result::Ok(())
+    };
+
+    let receive = async {
+        let mut echoed = vec![false; options.rtt_probes as usize];
+        let mut rtts_ms = Vec::new();
+        let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+        while rtts_ms.len() < echoed.len() {
+            let Ok(len) = tokio::time::timeout_at(deadline.into(), socket.recv(&mut buf)).await
+            else {
+                break;
+            };
+            let len = len?;
+            if len < TOKEN_LEN + 12 || buf[..TOKEN_LEN] != token {
+                continue;
+            }
+            let payload = &buf[TOKEN_LEN..len];
+            let seq = u32::from_be_bytes(payload[..4].try_into().unwrap_or_default()) as usize;
+            let sent_us = u64::from_be_bytes(payload[4..12].try_into().unwrap_or_default());
+            let Some(slot) = echoed.get_mut(seq).filter(|slot| !**slot) else {
+                continue;
+            };
+            *slot = true;
+            let rtt = started
+                .elapsed()
+                .saturating_sub(Duration::from_micros(sent_us));
+            rtts_ms.push(rtt.as_secs_f64() * 1000.0);
+        }
+        io::Result::Ok(rtts_ms)
+    };
+
+    let (sent, rtts_ms) = tokio::join!(send, receive);
+    sent?;
+    Ok(LatencyStats::new(options.rtt_probes, rtts_ms?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn benchmark_over_loopback() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        tokio::spawn(crate::run_server(addr));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let options = BenchmarkOptions {
+            duration: Duration::from_millis(200),
+            rtt_probes: 10,
+            rtt_interval: Duration::from_millis(1),
+            rtt_timeout: Duration::from_secs(1),
+        };
+        let result = run(&addr.to_string(), &options).await.unwrap();
+        assert!(result.upload_mbps > 0.0);
+        assert!(result.download_mbps > 0.0);
+        assert_eq!(result.latency.packets_sent, 10);
+        assert_eq!(result.latency.packets_received, 10);
+        assert!(result.latency.rtt_p95_ms.is_some());
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{io, net::SocketAddr};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to resolve benchmark server {server}")]
+    ResolveServer {
+        server: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("benchmark server {0} has no address")]
+    NoServerAddress(String),
+
+    #[error("failed to connect to benchmark server {server}")]
+    Connect {
+        server: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("upload failed")]
+    Upload(#[source] io::Error),
+
+    #[error("download failed")]
+    Download(#[source] io::Error),
+
+    #[error("latency measurement failed")]
+    Latency(#[source] io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,45 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Throughput and latency benchmark through a tunnel.
+//!
+//! The server listens on the same port for TCP and UDP. Over TCP, a client opens one connection
+//! per direction and sends a mode byte followed by the duration in milliseconds as a big-endian
+//! `u64`:
+//! - `U`: the client streams data for the duration and closes its side of the connection, and
+//!   the server answers with the number of bytes it received as a big-endian `u64`.
+//! - `D`: the server streams data for the duration and closes the connection.
+//! - `L`: the server answers with a random session token of [`TOKEN_LEN`] bytes, valid until the
+//!   client closes the connection or the duration elapses.
+//!
+//! Over UDP, the server echoes every datagram starting with the token of an open session and
+//! drops the others. The client follows the token with a big-endian `u32` sequence number to
+//! measure round trip times and packet loss.
+
+mod client;
+mod error;
+mod server;
+mod stats;
+
+pub use client::{run, BenchmarkOptions};
+pub use error::{Error, Result};
+pub use server::run_server;
+pub use stats::{BenchmarkResult, LatencyStats};
+
+/// Client streams data to the server.
+pub const MODE_UPLOAD: u8 = b'U';
+/// Server streams data to the client.
+pub const MODE_DOWNLOAD: u8 = b'D';
+/// Server hands out a token allowing the client to exchange UDP datagrams with it.
+pub const MODE_LATENCY: u8 = b'L';
+
+/// Length of the token prefixing the UDP datagrams of a latency session.
+pub const TOKEN_LEN: usize = 16;
+
+type SessionToken = [u8; TOKEN_LEN];
+
+/// Longest transfer the server accepts, to keep it from streaming indefinitely.
+pub const MAX_DURATION_MS: u64 = 60_000;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const MAX_DATAGRAM_SIZE: usize = 2048;
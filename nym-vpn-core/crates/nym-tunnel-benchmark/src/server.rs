@@ -0,0 +1,257 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::HashSet,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rand::RngCore;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream, UdpSocket},
+    sync::Semaphore,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    SessionToken, CHUNK_SIZE, MAX_DATAGRAM_SIZE, MAX_DURATION_MS, MODE_DOWNLOAD, MODE_LATENCY,
+    MODE_UPLOAD, TOKEN_LEN,
+};
+
+/// Time a transfer or latency session may take past its announced duration, e.g. to drain the
+/// buffers in flight.
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Longest time a single read from a client may take before the connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of benchmarks served at once. Connections beyond it are closed right away.
+const MAX_SESSIONS: usize = 32;
+
+/// Tokens of the open latency sessions. Datagrams not starting with one of them are dropped, so
+/// the server can't be used to reflect traffic towards a third party.
+type Sessions = Arc<Mutex<HashSet<SessionToken>>>;
+
+/// Serve benchmark clients on `listen`, over TCP and UDP, until an error occurs.
+pub async fn run_server(listen: SocketAddr) -> io::Result<()> {
+    let listener = TcpListener::bind(listen).await?;
+    let socket = UdpSocket::bind(listen).await?;
+    info!("Benchmark server listening on {}", listener.local_addr()?);
+
+    let sessions = Sessions::default();
+    let limit = Arc::new(Semaphore::new(MAX_SESSIONS));
+    tokio::select! {
+        result = accept(listener, sessions.clone(), limit) => result,
+        result = echo(socket, sessions) => result,
+    }
+}
+
+async fn accept(
+    listener: TcpListener,
+    sessions: Sessions,
+    limit: Arc<Semaphore>,
+) -> io::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let Ok(permit) = limit.clone().try_acquire_owned() else {
+            warn!("Rejecting benchmark with {peer}, {MAX_SESSIONS} benchmarks are running");
+            continue;
+        };
+        let sessions = sessions.clone();
+        tokio::spawn(async move {
+            if let Err(err) = serve(stream, peer, sessions).await {
+                warn!("Benchmark with {peer} failed: {err}");
+            }
+            drop(permit);
+        });
+    }
+}
+
+async fn read_with_timeout(stream: &mut TcpStream, buf: &mut [u8]) -> io::Result<usize> {
+    tokio::time::timeout(READ_TIMEOUT, stream.read(buf))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "read timed out"))?
+}
+
+async fn serve(mut stream: TcpStream, peer: SocketAddr, sessions: Sessions) -> io::Result<()> {
+    let mut header = [0u8; 9];
+    tokio::time::timeout(READ_TIMEOUT, stream.read_exact(&mut header))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "read timed out"))??;
+    let mode = header[0];
+    let duration_ms = u64::from_be_bytes(header[1..].try_into().unwrap_or_default());
+    let duration = Duration::from_millis(duration_ms.min(MAX_DURATION_MS));
+    let deadline = Instant::now() + duration + GRACE_PERIOD;
+
+    match mode {
+        MODE_UPLOAD => {
+            let mut buf = vec![0; CHUNK_SIZE];
+            let mut received = 0u64;
+            loop {
+                if Instant::now() > deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "upload exceeded its duration",
+                    ));
+                }
+                let read = read_with_timeout(&mut stream, &mut buf).await?;
+                if read == 0 {
+                    break;
+                }
+                received += read as u64;
+            }
+            debug!("Received {received} bytes from {peer}");
+            stream.write_u64(received).await?;
+        }
+        MODE_DOWNLOAD => {
+            let buf = vec![0; CHUNK_SIZE];
+            let started = Instant::now();
+            while started.elapsed() < duration {
+                // A client that stops reading would otherwise hold the session open forever
+                tokio::time::timeout_at(deadline.into(), stream.write_all(&buf))
+                    .await
+                    .map_err(|_| {
+                        io::Error::new(io::ErrorKind::TimedOut, "download exceeded its duration")
+                    })??;
+            }
+            debug!("Sent data to {peer} for {duration:?}");
+        }
+        MODE_LATENCY => {
+            let mut token = SessionToken::default();
+            rand::rngs::OsRng.fill_bytes(&mut token);
+            insert_session(&sessions, token);
+            // The session ends when the client closes the connection or its duration elapses.
+            let result = async {
+                stream.write_all(&token).await?;
+                let mut buf = [0u8; 1];
+                while tokio::time::timeout_at(deadline.into(), stream.read(&mut buf))
+                    .await
+                    .is_ok_and(|read| read.is_ok_and(|read| read > 0))
+                {}
+                io::Result::Ok(())
+            }
+            .await;
+            remove_session(&sessions, &token);
+            result?;
+            debug!("Closed latency session with {peer}");
+        }
+        mode => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown mode {mode}"),
+            ))
+        }
+    }
+    stream.shutdown().await
+}
+
+fn insert_session(sessions: &Sessions, token: SessionToken) {
+    sessions
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(token);
+}
+
+fn remove_session(sessions: &Sessions, token: &SessionToken) {
+    sessions
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .remove(token);
+}
+
+fn has_session(sessions: &Sessions, datagram: &[u8]) -> bool {
+    let Some(token) = datagram.get(..TOKEN_LEN) else {
+        return false;
+    };
+    sessions
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .contains(token)
+}
+
+async fn echo(socket: UdpSocket, sessions: Sessions) -> io::Result<()> {
+    let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (len, peer) = socket.recv_from(&mut buf).await?;
+        if !has_session(&sessions, &buf[..len]) {
+            debug!("Dropping datagram from {peer} without a session token");
+            continue;
+        }
+        if let Err(err) = socket.send_to(&buf[..len], peer).await {
+            debug!("Failed to echo to {peer}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_connections_over_the_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        tokio::spawn(accept(
+            listener,
+            Sessions::default(),
+            Arc::new(Semaphore::new(1)),
+        ));
+
+        let mut latency = TcpStream::connect(server_addr).await.unwrap();
+        let mut header = vec![MODE_LATENCY];
+        header.extend_from_slice(&1000u64.to_be_bytes());
+        latency.write_all(&header).await.unwrap();
+        let mut token = SessionToken::default();
+        latency.read_exact(&mut token).await.unwrap();
+
+        let mut rejected = TcpStream::connect(server_addr).await.unwrap();
+        let mut buf = [0u8; 1];
+        let read = tokio::time::timeout(Duration::from_secs(1), rejected.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read, 0);
+
+        drop(latency);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let mut accepted = TcpStream::connect(server_addr).await.unwrap();
+        accepted.write_all(&header).await.unwrap();
+        accepted.read_exact(&mut token).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn echoes_only_datagrams_of_open_sessions() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let sessions = Sessions::default();
+        tokio::spawn(echo(server, sessions.clone()));
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.connect(server_addr).await.unwrap();
+        let token = [7u8; TOKEN_LEN];
+        let mut packet = token.to_vec();
+        packet.extend_from_slice(b"ping");
+        let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+
+        client.send(&packet).await.unwrap();
+        let echoed = tokio::time::timeout(Duration::from_millis(200), client.recv(&mut buf)).await;
+        assert!(echoed.is_err());
+
+        insert_session(&sessions, token);
+        client.send(&packet).await.unwrap();
+        let len = tokio::time::timeout(Duration::from_secs(1), client.recv(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buf[..len], packet.as_slice());
+
+        remove_session(&sessions, &token);
+        client.send(&packet).await.unwrap();
+        let echoed = tokio::time::timeout(Duration::from_millis(200), client.recv(&mut buf)).await;
+        assert!(echoed.is_err());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub upload_mbps: f64,
+    pub download_mbps: f64,
+    pub latency: LatencyStats,
+}
+
+/// Round trip times of the UDP packets which were echoed back, in milliseconds. The times are
+/// unset when no packet came back.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub packets_sent: u32,
+    pub packets_received: u32,
+    /// Share of the packets which weren't echoed back, between 0 and 1.
+    pub packet_loss: f64,
+    pub rtt_min_ms: Option<f64>,
+    pub rtt_avg_ms: Option<f64>,
+    pub rtt_p50_ms: Option<f64>,
+    pub rtt_p95_ms: Option<f64>,
+    pub rtt_max_ms: Option<f64>,
+}
+
+impl LatencyStats {
+    pub fn new(packets_sent: u32, mut rtts_ms: Vec<f64>) -> Self {
+        rtts_ms.sort_by(f64::total_cmp);
+        let packets_received = u32::try_from(rtts_ms.len())
+            .unwrap_or(u32::MAX)
+            .min(packets_sent);
+        let packet_loss = if packets_sent > 0 {
+            f64::from(packets_sent - packets_received) / f64::from(packets_sent)
+        } else {
+            0.0
+        };
+        let rtt_avg_ms =
+            (!rtts_ms.is_empty()).then(|| rtts_ms.iter().sum::<f64>() / rtts_ms.len() as f64);
+
+        Self {
+            packets_sent,
+            packets_received,
+            packet_loss,
+            rtt_min_ms: rtts_ms.first().copied(),
+            rtt_avg_ms,
+            rtt_p50_ms: percentile(&rtts_ms, 0.5),
+            rtt_p95_ms: percentile(&rtts_ms, 0.95),
+            rtt_max_ms: rtts_ms.last().copied(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1) - 1).copied()
+}
+
+pub(crate) fn mbps(bytes: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        bytes as f64 * 8.0 / secs / 1_000_000.0
+    } else {
+        0.0
+    }
+}
+
+impl fmt::Display for BenchmarkResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "upload: {:.2} Mbps", self.upload_mbps)?;
+        writeln!(f, "download: {:.2} Mbps", self.download_mbps)?;
+        write!(f, "{}", self.latency)
+    }
+}
+
+impl fmt::Display for LatencyStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |value: Option<f64>| {
+            value
+                .map(|value| format!("{value:.1} ms"))
+                .unwrap_or_else(|| "-".to_owned())
+        };
+        writeln!(
+            f,
+            "packets: {} sent, {} received, {:.1}% loss",
+            self.packets_sent,
+            self.packets_received,
+            self.packet_loss * 100.0
+        )?;
+        write!(
+            f,
+            "rtt: min {}, avg {}, p50 {}, p95 {}, max {}",
+            ms(self.rtt_min_ms),
+            ms(self.rtt_avg_ms),
+            ms(self.rtt_p50_ms),
+            ms(self.rtt_p95_ms),
+            ms(self.rtt_max_ms)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_stats() {
+        let rtts = (1..=19).rev().map(f64::from).collect();
+        let stats = LatencyStats::new(20, rtts);
+        assert_eq!(stats.packets_received, 19);
+        assert_eq!(stats.packet_loss, 0.05);
+        assert_eq!(stats.rtt_min_ms, Some(1.0));
+        assert_eq!(stats.rtt_avg_ms, Some(10.0));
+        assert_eq!(stats.rtt_p50_ms, Some(10.0));
+        assert_eq!(stats.rtt_p95_ms, Some(19.0));
+        assert_eq!(stats.rtt_max_ms, Some(19.0));
+
+        let stats = LatencyStats::new(5, Vec::new());
+        assert_eq!(stats.packet_loss, 1.0);
+        assert_eq!(stats.rtt_p50_ms, None);
+    }
+
+    #[test]
+    fn throughput() {
+        assert_eq!(mbps(1_250_000, Duration::from_secs(2)), 5.0);
+        assert_eq!(mbps(1_250_000, Duration::ZERO), 0.0);
+    }
+}
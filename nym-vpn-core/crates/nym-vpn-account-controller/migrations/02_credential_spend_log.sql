@@ -0,0 +1,31 @@
+/*
+ * Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+ * SPDX-License-Identifier: GPL-3.0-only
+ */
+
+CREATE TABLE credential_spend_log
+(
+    id                  INTEGER     NOT NULL PRIMARY KEY AUTOINCREMENT,
+    ticket_type         TEXT        NOT NULL,
+    gateway_id          TEXT        NOT NULL,
+    tickets             INTEGER     NOT NULL,
+    bytes_granted       INTEGER     NOT NULL,
+    remaining_bandwidth INTEGER,
+    success             BOOLEAN     NOT NULL,
+    wasteful            BOOLEAN     NOT NULL,
+    error               TEXT,
+    timestamp           TIMESTAMP   WITHOUT TIME ZONE NOT NULL
+);
+
+-- The spend log is append-only
+CREATE TRIGGER credential_spend_log_no_update
+BEFORE UPDATE ON credential_spend_log
+BEGIN
+    SELECT RAISE(ABORT, 'credential_spend_log is append-only');
+END;
+
+CREATE TRIGGER credential_spend_log_no_delete
+BEFORE DELETE ON credential_spend_log
+BEGIN
+    SELECT RAISE(ABORT, 'credential_spend_log is append-only');
+END;
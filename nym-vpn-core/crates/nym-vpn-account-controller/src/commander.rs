@@ -14,7 +14,7 @@ use crate::{
     commands::{request_zknym::RequestZkNymSummary, AccountCommand, ReturnSender},
     error::Error,
     shared_state::{AccountRegistered, DeviceState, SharedAccountState},
-    AvailableTicketbooks,
+    AvailableTicketbooks, CredentialSpend, TicketbookImportSummary,
 };
 
 #[derive(Clone)]
@@ -122,6 +122,58 @@ impl AccountControllerCommander {
         rx.await.map_err(AccountCommandError::internal)?
     }
 
+    // Export all unspent ticketbooks into an encrypted bundle that can be imported on another
+    // device logged into the same account. The tickets remain spendable locally until the export
+    // is confirmed with `confirm_ticketbooks_exported`.
+    pub async fn export_ticketbooks(&self) -> Result<Vec<u8>, AccountCommandError> {
+        let (tx, rx) = ReturnSender::new();
+        self.command_tx
+            .send(AccountCommand::ExportTicketbooks(tx))
+            .map_err(AccountCommandError::internal)?;
+        rx.await.map_err(AccountCommandError::internal)?
+    }
+
+    // Mark the tickets of the last export as spent, once the bundle has been safely stored. Fails
+    // if any of the exported ticketbooks was used since, in which case the bundle is stale.
+    pub async fn confirm_ticketbooks_exported(&self) -> Result<(), AccountCommandError> {
+        let (tx, rx) = ReturnSender::new();
+        self.command_tx
+            .send(AccountCommand::ConfirmTicketbooksExported(tx))
+            .map_err(AccountCommandError::internal)?;
+        rx.await.map_err(AccountCommandError::internal)?
+    }
+
+    pub async fn import_ticketbooks(
+        &self,
+        bundle: Vec<u8>,
+    ) -> Result<TicketbookImportSummary, AccountCommandError> {
+        let (tx, rx) = ReturnSender::new();
+        self.command_tx
+            .send(AccountCommand::ImportTicketbooks(tx, bundle))
+            .map_err(AccountCommandError::internal)?;
+        rx.await.map_err(AccountCommandError::internal)?
+    }
+
+    // Append an entry to the credential spend log. This doesn't wait for the entry to be
+    // written, since it's called from the tunnel while spending tickets.
+    pub fn record_credential_spend(&self, spend: CredentialSpend) {
+        self.send(AccountCommand::RecordCredentialSpend(spend))
+            .inspect_err(|err| tracing::error!("Failed to record credential spend: {err}"))
+            .ok();
+    }
+
+    // Get the most recent entries in the credential spend log, newest first
+    pub async fn get_credential_spend_history(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<CredentialSpend>, AccountCommandError> {
+        let (tx, rx) = ReturnSender::new();
+        self.command_tx
+            .send(AccountCommand::GetCredentialSpendHistory(tx, limit))
+            .map_err(AccountCommandError::internal)?;
+        rx.await.map_err(AccountCommandError::internal)?
+    }
+
     pub async fn request_zk_nyms(&self) -> Result<RequestZkNymSummary, RequestZkNymError> {
         let (tx, rx) = ReturnSender::new();
         self.command_tx
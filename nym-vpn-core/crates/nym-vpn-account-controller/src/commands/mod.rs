@@ -17,7 +17,10 @@ use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 use nym_vpn_api_client::response::{NymVpnAccountSummaryResponse, NymVpnDevice, NymVpnUsage};
 use tokio::sync::oneshot;
 
-use crate::{shared_state::DeviceState, AvailableTicketbooks, Error};
+use crate::{
+    shared_state::DeviceState, AvailableTicketbooks, CredentialSpend, Error,
+    TicketbookImportSummary,
+};
 
 #[derive(Debug, Default)]
 pub(crate) struct RunningCommands {
@@ -98,6 +101,17 @@ pub enum AccountCommand {
     GetZkNymById(String),
     ConfirmZkNymIdDownloaded(String),
     GetAvailableTickets(ReturnSender<AvailableTicketbooks, AccountCommandError>),
+    ExportTicketbooks(ReturnSender<Vec<u8>, AccountCommandError>),
+    ConfirmTicketbooksExported(ReturnSender<(), AccountCommandError>),
+    ImportTicketbooks(
+        ReturnSender<TicketbookImportSummary, AccountCommandError>,
+        Vec<u8>,
+    ),
+    RecordCredentialSpend(CredentialSpend),
+    GetCredentialSpendHistory(
+        ReturnSender<Vec<CredentialSpend>, AccountCommandError>,
+        Option<u32>,
+    ),
     SetStaticApiAddresses(
         ReturnSender<(), AccountCommandError>,
         Option<Vec<SocketAddr>>,
@@ -190,5 +204,10 @@ mod tests {
             AccountCommand::GetAvailableTickets(tx).kind(),
             "GetAvailableTickets"
         );
+        let (tx, _) = ReturnSender::new();
+        assert_eq!(
+            AccountCommand::ConfirmTicketbooksExported(tx).kind(),
+            "ConfirmTicketbooksExported"
+        );
     }
 }
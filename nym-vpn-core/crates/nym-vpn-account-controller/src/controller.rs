@@ -29,8 +29,9 @@ use crate::{
     },
     error::Error,
     shared_state::{MnemonicState, ReadyToRegisterDevice, ReadyToRequestZkNym, SharedAccountState},
-    storage::{AccountStorage, VpnCredentialStorage},
-    AccountControllerCommander, AvailableTicketbooks,
+    storage::{AccountStorage, ExportedTicketbooks, VpnCredentialStorage},
+    ticketbook_bundle::TicketbookBundle,
+    AccountControllerCommander, AvailableTicketbooks, CredentialSpend, TicketbookImportSummary,
 };
 
 // The interval at which we automatically request zk-nyms
@@ -39,6 +40,9 @@ const ZK_NYM_AUTOMATIC_REQUEST_INTERVAL: Duration = Duration::from_secs(60);
 // The interval at which we update the account state
 const ACCOUNT_UPDATE_INTERVAL: Duration = Duration::from_secs(5 * 60);
 
+// The number of credential spend log entries returned when no limit is given
+const DEFAULT_CREDENTIAL_SPEND_HISTORY_LIMIT: u32 = 100;
+
 pub struct AccountController<S>
 where
     S: VpnStorage,
@@ -80,6 +84,10 @@ where
     // Zk-nym request command handler state reused between runs
     waiting_request_zknym_command_handler: WaitingRequestZkNymCommandHandler,
 
+    // Ticketbooks included in the last export, marked as spent once the consumer confirms that
+    // the exported bundle has been written out
+    pending_ticketbook_export: Option<ExportedTicketbooks>,
+
     // When credential mode is disabled we don't automatically request zk-nyms. We can still do
     // so manually, but we don't want to do it automatically
     background_zk_nym_refresh: bool,
@@ -164,6 +172,7 @@ where
             waiting_sync_account_command_handler,
             waiting_sync_device_command_handler,
             waiting_request_zknym_command_handler,
+            pending_ticketbook_export: None,
             background_zk_nym_refresh: credentials_mode,
             cancel_token,
             user_agent,
@@ -360,6 +369,7 @@ where
 
         // Once we have removed or reset all storage, we need to reset the account state
         self.waiting_request_zknym_command_handler.reset();
+        self.pending_ticketbook_export = None;
         self.account_state.reset().await;
 
         // And now we are ready to start reconstructing
@@ -671,6 +681,81 @@ where
             .map_err(|err| AccountCommandError::Storage(err.to_string()))
     }
 
+    async fn handle_export_ticketbooks(&mut self) -> Result<Vec<u8>, AccountCommandError> {
+        tracing::info!("Exporting unspent ticketbooks from local credential storage");
+        let account = self
+            .account_storage
+            .load_account()
+            .await
+            .map_err(|_err| AccountCommandError::NoAccountStored)?;
+        let (bundle, exported) = self
+            .credential_storage
+            .lock()
+            .await
+            .export_unspent_ticketbooks(account.id())
+            .await
+            .map_err(|err| AccountCommandError::Storage(err.to_string()))?;
+        let encrypted = bundle
+            .encrypt(&account)
+            .map_err(AccountCommandError::internal)?;
+        self.pending_ticketbook_export = Some(exported);
+        Ok(encrypted)
+    }
+
+    async fn handle_confirm_ticketbooks_exported(&mut self) -> Result<(), AccountCommandError> {
+        let exported = self
+            .pending_ticketbook_export
+            .take()
+            .ok_or_else(|| AccountCommandError::internal("no pending ticketbook export"))?;
+        tracing::info!("Marking {} exported ticketbooks as spent", exported.len());
+        self.credential_storage
+            .lock()
+            .await
+            .mark_ticketbooks_exported(&exported)
+            .await
+            .map_err(|err| AccountCommandError::Storage(err.to_string()))
+    }
+
+    async fn handle_import_ticketbooks(
+        &self,
+        bundle: Vec<u8>,
+    ) -> Result<TicketbookImportSummary, AccountCommandError> {
+        tracing::info!("Importing ticketbooks into local credential storage");
+        let account = self
+            .account_storage
+            .load_account()
+            .await
+            .map_err(|_err| AccountCommandError::NoAccountStored)?;
+        let bundle =
+            TicketbookBundle::decrypt(&bundle, &account).map_err(AccountCommandError::internal)?;
+        self.credential_storage
+            .lock()
+            .await
+            .import_ticketbook_bundle(bundle)
+            .await
+            .map_err(|err| AccountCommandError::Storage(err.to_string()))
+    }
+
+    async fn handle_record_credential_spend(&self, spend: CredentialSpend) -> Result<(), Error> {
+        self.credential_storage
+            .lock()
+            .await
+            .insert_credential_spend(spend)
+            .await
+    }
+
+    async fn handle_get_credential_spend_history(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<CredentialSpend>, AccountCommandError> {
+        self.credential_storage
+            .lock()
+            .await
+            .get_credential_spend_history(limit.unwrap_or(DEFAULT_CREDENTIAL_SPEND_HISTORY_LIMIT))
+            .await
+            .map_err(|err| AccountCommandError::Storage(err.to_string()))
+    }
+
     fn queue_command(&self, command: AccountCommand) {
         if let Err(err) = self.command_tx.send(command) {
             tracing::error!("Failed to queue command: {:#?}", err);
@@ -752,6 +837,30 @@ where
                 let result = self.handle_get_available_tickets().await;
                 result_tx.send(result);
             }
+            AccountCommand::ExportTicketbooks(result_tx) => {
+                let result = self.handle_export_ticketbooks().await;
+                result_tx.send(result);
+            }
+            AccountCommand::ConfirmTicketbooksExported(result_tx) => {
+                let result = self.handle_confirm_ticketbooks_exported().await;
+                result_tx.send(result);
+            }
+            AccountCommand::ImportTicketbooks(result_tx, bundle) => {
+                let result = self.handle_import_ticketbooks(bundle).await;
+                result_tx.send(result);
+            }
+            AccountCommand::RecordCredentialSpend(spend) => {
+                self.handle_record_credential_spend(spend)
+                    .await
+                    .inspect_err(|err| {
+                        tracing::error!("Failed to record credential spend: {:#?}", err);
+                    })
+                    .ok();
+            }
+            AccountCommand::GetCredentialSpendHistory(result_tx, limit) => {
+                let result = self.handle_get_credential_spend_history(limit).await;
+                result_tx.send(result);
+            }
             AccountCommand::SetStaticApiAddresses(result_tx, static_api_addresses) => {
                 result_tx.send(
                     nym_vpn_api_client::VpnApiClient::new_with_resolver_overrides(
@@ -0,0 +1,97 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt;
+
+use nym_credentials_interface::TicketType;
+use time::OffsetDateTime;
+
+// The result of trying to spend tickets with a gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSpendOutcome {
+    Success,
+    Failure {
+        // The gateway might have consumed the ticket even though we didn't get a successful
+        // response, so it can't be retried and is lost.
+        wasteful: bool,
+        error: String,
+    },
+}
+
+// A single entry in the credential spend log
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialSpend {
+    pub ticket_type: TicketType,
+    pub gateway_id: String,
+    pub timestamp: OffsetDateTime,
+    pub tickets: u32,
+    pub bytes_granted: u64,
+    // The remaining bandwidth as reported by the gateway after the spend, if available
+    pub remaining_bandwidth: Option<i64>,
+    pub outcome: CredentialSpendOutcome,
+}
+
+impl CredentialSpend {
+    pub fn success(
+        ticket_type: TicketType,
+        gateway_id: impl ToString,
+        tickets: u32,
+        remaining_bandwidth: Option<i64>,
+    ) -> Self {
+        Self {
+            ticket_type,
+            gateway_id: gateway_id.to_string(),
+            timestamp: OffsetDateTime::now_utc(),
+            tickets,
+            bytes_granted: u64::from(tickets) * ticket_type.to_repr().bandwidth_value(),
+            remaining_bandwidth,
+            outcome: CredentialSpendOutcome::Success,
+        }
+    }
+
+    pub fn failure(
+        ticket_type: TicketType,
+        gateway_id: impl ToString,
+        tickets: u32,
+        wasteful: bool,
+        error: impl ToString,
+    ) -> Self {
+        Self {
+            ticket_type,
+            gateway_id: gateway_id.to_string(),
+            timestamp: OffsetDateTime::now_utc(),
+            tickets,
+            bytes_granted: 0,
+            remaining_bandwidth: None,
+            outcome: CredentialSpendOutcome::Failure {
+                wasteful,
+                error: error.to_string(),
+            },
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcome == CredentialSpendOutcome::Success
+    }
+}
+
+impl fmt::Display for CredentialSpend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match &self.outcome {
+            CredentialSpendOutcome::Success => "success".to_owned(),
+            CredentialSpendOutcome::Failure { wasteful, error } => {
+                format!("failure (wasteful: {wasteful}): {error}")
+            }
+        };
+        write!(
+            f,
+            "{{ {}, type: {}, gateway: {}, tickets: {}, granted: {}, {} }}",
+            self.timestamp,
+            self.ticket_type,
+            self.gateway_id,
+            self.tickets,
+            si_scale::helpers::bibytes2(self.bytes_granted as f64),
+            outcome,
+        )
+    }
+}
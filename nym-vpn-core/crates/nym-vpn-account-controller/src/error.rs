@@ -57,6 +57,24 @@ pub enum Error {
 
     #[error("failed to parse ticket type: {0}")]
     ParseTicketType(String),
+
+    #[error("failed to derive ticketbook bundle key")]
+    DeriveTicketbookBundleKey(#[source] nym_vpn_api_client::VpnApiClientError),
+
+    #[error("failed to serialize ticketbook bundle")]
+    SerializeTicketbookBundle(#[source] bincode::Error),
+
+    #[error("failed to encrypt ticketbook bundle")]
+    EncryptTicketbookBundle,
+
+    #[error("failed to decrypt ticketbook bundle, was it exported from this account?")]
+    DecryptTicketbookBundle,
+
+    #[error("invalid ticketbook bundle: {0}")]
+    InvalidTicketbookBundle(String),
+
+    #[error("ticketbook {0} was used since the export, export the ticketbooks again")]
+    TicketbookUsedSinceExport(i64),
 }
 
 impl Error {
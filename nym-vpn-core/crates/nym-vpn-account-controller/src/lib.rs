@@ -13,13 +13,17 @@ pub mod storage_cleanup;
 mod commander;
 mod commands;
 mod controller;
+mod credential_spend;
 mod error;
 mod storage;
+mod ticketbook_bundle;
 mod ticketbooks;
 
 pub use commander::AccountControllerCommander;
 pub use commands::AccountCommand;
 pub use controller::AccountController;
+pub use credential_spend::{CredentialSpend, CredentialSpendOutcome};
 pub use error::Error;
 pub use shared_state::{AccountStateSummary, SharedAccountState};
+pub use ticketbook_bundle::TicketbookImportSummary;
 pub use ticketbooks::{AvailableTicketbook, AvailableTicketbooks};
@@ -9,7 +9,10 @@ pub use pending_credential_requests::{
 
 use pending_credential_requests::PendingCredentialRequestsStorage;
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
 
 use nym_compact_ecash::VerificationKeyAuth;
 use nym_credential_storage::persistent_storage::PersistentStorage as PersistentCredentialStorage;
@@ -20,10 +23,32 @@ use nym_credentials::{
 use nym_credentials_interface::{
     AnnotatedCoinIndexSignature, AnnotatedExpirationDateSignature, TicketType,
 };
+use nym_ecash_time::{ecash_today, EcashTime};
 use nym_sdk::mixnet::{CredentialStorage, StoragePaths};
+use strum::IntoEnumIterator;
 use time::Date;
 
-use crate::{error::Error, AvailableTicketbooks};
+use crate::{
+    credential_spend::CredentialSpend,
+    error::Error,
+    ticketbook_bundle::{
+        CoinIndexSignatures, ExpirationDateSignatures, MasterVerificationKey, TicketbookBundle,
+        TicketbookImportSummary,
+    },
+    AvailableTicketbooks,
+};
+
+// Number of tickets of each exported ticketbook that were already used at the time of the
+// export, keyed by ticketbook id
+pub(crate) type ExportedTicketbooks = HashMap<i64, u32>;
+
+// Tickets temporarily withdrawn from a ticketbook in the credential storage
+struct TicketbookWithdrawal {
+    ticketbook_id: i64,
+    withdrawn: u32,
+    total_spent: u32,
+    ticketbook: IssuedTicketBook,
+}
 
 #[derive(Clone)]
 pub(crate) struct VpnCredentialStorage {
@@ -179,6 +204,270 @@ impl VpnCredentialStorage {
             .map_err(Error::from)
     }
 
+    // Withdraw every unspent ticket of the given type. Each round trip empties the ticketbook
+    // with the most remaining tickets, so this takes one query per ticketbook. The withdrawals
+    // are recorded, even on failure, so that the caller can revert them.
+    async fn withdraw_all_unspent(
+        &self,
+        ticket_type: TicketType,
+        withdrawals: &mut Vec<TicketbookWithdrawal>,
+    ) -> Result<(), Error> {
+        let deadline = ecash_today().ecash_date();
+        let mut remaining: Vec<_> = self
+            .credential_storage
+            .get_ticketbooks_info()
+            .await?
+            .into_iter()
+            .filter(|info| info.ticketbook_type == ticket_type.to_string())
+            .filter(|info| info.expiration_date >= deadline)
+            .map(|info| (info.id, info.total_tickets - info.used_tickets))
+            .filter(|(_, remaining)| *remaining > 0)
+            .collect();
+
+        while let Some(tickets) = remaining.iter().map(|(_, remaining)| *remaining).max() {
+            let Some(retrieved) = self
+                .credential_storage
+                .get_next_unspent_usable_ticketbook(ticket_type.to_string(), tickets)
+                .await?
+            else {
+                // Spent by someone else in the meantime, retry with whatever is left
+                remaining.retain(|(_, remaining)| *remaining != tickets);
+                continue;
+            };
+            remaining.retain(|(id, _)| *id != retrieved.ticketbook_id);
+            withdrawals.push(TicketbookWithdrawal {
+                ticketbook_id: retrieved.ticketbook_id,
+                withdrawn: tickets,
+                total_spent: retrieved.ticketbook.spent_tickets() as u32 + tickets,
+                ticketbook: retrieved.ticketbook,
+            });
+        }
+        Ok(())
+    }
+
+    async fn withdraw_all_unspent_of_all_types(
+        &self,
+        withdrawals: &mut Vec<TicketbookWithdrawal>,
+    ) -> Result<(), Error> {
+        for ticket_type in TicketType::iter() {
+            self.withdraw_all_unspent(ticket_type, withdrawals).await?;
+        }
+        Ok(())
+    }
+
+    async fn revert_withdrawals(&self, withdrawals: &[TicketbookWithdrawal]) {
+        for withdrawal in withdrawals {
+            match self
+                .credential_storage
+                .attempt_revert_ticketbook_withdrawal(
+                    withdrawal.ticketbook_id,
+                    withdrawal.withdrawn,
+                    withdrawal.total_spent,
+                )
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => tracing::warn!(
+                    "Ticketbook {} was used while withdrawn, not reverting",
+                    withdrawal.ticketbook_id
+                ),
+                Err(err) => tracing::error!(
+                    "Failed to revert withdrawal of ticketbook {}: {err}",
+                    withdrawal.ticketbook_id
+                ),
+            }
+        }
+    }
+
+    // Collect all unspent tickets, together with the keys and signatures required to spend
+    // them, into a bundle. The tickets stay available locally; they only need to be marked as
+    // spent with `mark_ticketbooks_exported` once the bundle has been safely written out.
+    //
+    // The storage only hands out ticketbooks by withdrawing tickets from them, so the
+    // withdrawals are reverted before returning.
+    pub(crate) async fn export_unspent_ticketbooks(
+        &self,
+        account_id: String,
+    ) -> Result<(TicketbookBundle, ExportedTicketbooks), Error> {
+        let mut withdrawals = Vec::new();
+        let result = self
+            .withdraw_all_unspent_of_all_types(&mut withdrawals)
+            .await;
+        self.revert_withdrawals(&withdrawals).await;
+        result?;
+
+        let mut bundle = TicketbookBundle::new(account_id);
+        let mut exported = ExportedTicketbooks::new();
+        for withdrawal in withdrawals {
+            exported.insert(
+                withdrawal.ticketbook_id,
+                withdrawal.total_spent - withdrawal.withdrawn,
+            );
+            bundle.ticketbooks.push(withdrawal.ticketbook);
+        }
+
+        let epoch_ids: BTreeSet<_> = bundle.ticketbooks.iter().map(|t| t.epoch_id()).collect();
+        let expiration_dates: BTreeSet<_> = bundle
+            .ticketbooks
+            .iter()
+            .map(|t| (t.epoch_id(), t.expiration_date()))
+            .collect();
+
+        for epoch_id in epoch_ids {
+            let key = self
+                .get_master_verification_key(epoch_id)
+                .await?
+                .ok_or_else(|| {
+                    Error::internal(format!("missing master verification key: {epoch_id}"))
+                })?;
+            bundle
+                .master_verification_keys
+                .push(MasterVerificationKey::new(epoch_id, &key));
+
+            let signatures = self
+                .get_coin_index_signatures(epoch_id)
+                .await?
+                .ok_or_else(|| {
+                    Error::internal(format!("missing coin index signatures: {epoch_id}"))
+                })?;
+            bundle.coin_index_signatures.push(CoinIndexSignatures {
+                epoch_id,
+                signatures,
+            });
+        }
+
+        for (epoch_id, expiration_date) in expiration_dates {
+            let signatures = self
+                .get_expiration_date_signatures(expiration_date)
+                .await?
+                .ok_or_else(|| {
+                    Error::internal(format!(
+                        "missing expiration date signatures: {expiration_date}"
+                    ))
+                })?;
+            bundle
+                .expiration_date_signatures
+                .push(ExpirationDateSignatures {
+                    epoch_id,
+                    expiration_date,
+                    signatures,
+                });
+        }
+
+        tracing::info!("Exported {} ticketbooks", bundle.ticketbooks.len());
+        Ok((bundle, exported))
+    }
+
+    // Mark the remaining tickets of the exported ticketbooks as spent, so that they can't be
+    // double spent after being imported on another device. Ticketbooks that were added since
+    // the export are left untouched.
+    //
+    // If any exported ticketbook was used in the meantime, the bundle carries stale spent
+    // counters and nothing is marked, so that the tickets can't be spent twice.
+    pub(crate) async fn mark_ticketbooks_exported(
+        &self,
+        exported: &ExportedTicketbooks,
+    ) -> Result<(), Error> {
+        let mut withdrawals = Vec::new();
+        if let Err(err) = self
+            .withdraw_all_unspent_of_all_types(&mut withdrawals)
+            .await
+        {
+            self.revert_withdrawals(&withdrawals).await;
+            return Err(err);
+        }
+
+        let (spent, other): (Vec<_>, Vec<_>) = withdrawals
+            .into_iter()
+            .partition(|withdrawal| exported.contains_key(&withdrawal.ticketbook_id));
+        for (ticketbook_id, used_tickets) in exported {
+            let unchanged = spent.iter().any(|withdrawal| {
+                withdrawal.ticketbook_id == *ticketbook_id
+                    && withdrawal.total_spent - withdrawal.withdrawn == *used_tickets
+            });
+            if !unchanged {
+                self.revert_withdrawals(&spent).await;
+                self.revert_withdrawals(&other).await;
+                return Err(Error::TicketbookUsedSinceExport(*ticketbook_id));
+            }
+        }
+        self.revert_withdrawals(&other).await;
+        tracing::info!("Marked {} exported ticketbooks as spent", spent.len());
+        Ok(())
+    }
+
+    pub(crate) async fn import_ticketbook_bundle(
+        &self,
+        bundle: TicketbookBundle,
+    ) -> Result<TicketbookImportSummary, Error> {
+        for key in bundle.master_verification_keys {
+            if self
+                .get_master_verification_key(key.epoch_id)
+                .await?
+                .is_none()
+            {
+                self.insert_master_verification_key(&key.to_epoch_verification_key()?)
+                    .await?;
+            }
+        }
+        for signatures in bundle.coin_index_signatures {
+            if self
+                .get_coin_index_signatures(signatures.epoch_id)
+                .await?
+                .is_none()
+            {
+                self.insert_coin_index_signatures(&signatures.into())
+                    .await?;
+            }
+        }
+        for signatures in bundle.expiration_date_signatures {
+            if self
+                .get_expiration_date_signatures(signatures.expiration_date)
+                .await?
+                .is_none()
+            {
+                self.insert_expiration_date_signatures(&signatures.into())
+                    .await?;
+            }
+        }
+
+        let mut summary = TicketbookImportSummary::default();
+        for ticketbook in bundle.ticketbooks {
+            if ticketbook.expired() {
+                tracing::debug!("Skipping expired ticketbook");
+                summary.expired += 1;
+                continue;
+            }
+            if self
+                .get_master_verification_key(ticketbook.epoch_id())
+                .await?
+                .is_none()
+            {
+                return Err(Error::InvalidTicketbookBundle(format!(
+                    "missing master verification key for epoch: {}",
+                    ticketbook.epoch_id()
+                )));
+            }
+            match self.insert_issued_ticketbook(&ticketbook).await {
+                Ok(()) => summary.imported += 1,
+                // Same workaround as when importing zk-nyms from the API, until the credential
+                // storage lets us check for already imported ticketbooks
+                Err(Error::CredentialStorage(
+                    nym_credential_storage::error::StorageError::InternalDatabaseError(
+                        sqlx::Error::Database(err),
+                    ),
+                )) if err.kind() == sqlx::error::ErrorKind::UniqueViolation => {
+                    tracing::warn!("Ticketbook already imported");
+                    summary.already_present += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        tracing::info!("Imported ticketbooks: {summary:?}");
+        Ok(summary)
+    }
+
     pub(crate) async fn print_info(&self) -> Result<(), Error> {
         let ticketbooks_info = self.get_available_ticketbooks().await?;
         let num_ticketbooks = ticketbooks_info.len_not_expired();
@@ -270,4 +559,157 @@ impl VpnCredentialStorage {
             .await
             .map_err(Error::from)
     }
+
+    pub(crate) async fn insert_credential_spend(
+        &self,
+        spend: CredentialSpend,
+    ) -> Result<(), Error> {
+        tracing::debug!("Recording credential spend: {spend}");
+        self.pending_requests_storage
+            .insert_credential_spend(spend)
+            .await
+            .map_err(Error::from)
+    }
+
+    pub(crate) async fn get_credential_spend_history(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<CredentialSpend>, Error> {
+        self.pending_requests_storage
+            .get_credential_spends(limit)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ticketbook_bundle::tests::{test_account, test_bundle, test_ticketbook};
+
+    use super::*;
+
+    async fn used_tickets(storage: &VpnCredentialStorage) -> Vec<(i64, u32, u32)> {
+        storage
+            .credential_storage
+            .get_ticketbooks_info()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| (info.id, info.used_tickets, info.total_tickets))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn export_spends_tickets_only_once_confirmed() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let storage = VpnCredentialStorage::setup_from_path(data_dir.path())
+            .await
+            .unwrap();
+        let (entry, verification_key) = test_ticketbook(TicketType::V1MixnetEntry);
+        let (exit, _) = test_ticketbook(TicketType::V1WireguardExit);
+        storage
+            .import_ticketbook_bundle(test_bundle(vec![entry, exit], &verification_key))
+            .await
+            .unwrap();
+
+        let (bundle, exported) = storage
+            .export_unspent_ticketbooks(test_account().id())
+            .await
+            .unwrap();
+        assert_eq!(bundle.ticketbooks.len(), 2);
+        assert_eq!(exported.len(), 2);
+        assert_eq!(bundle.master_verification_keys.len(), 1);
+        assert_eq!(bundle.coin_index_signatures.len(), 1);
+        assert_eq!(bundle.expiration_date_signatures.len(), 1);
+        assert!(used_tickets(&storage)
+            .await
+            .iter()
+            .all(|(_, used, _)| *used == 0));
+
+        // Ticketbooks added after the export are not affected by the confirmation
+        let (later, _) = test_ticketbook(TicketType::V1MixnetEntry);
+        storage.insert_issued_ticketbook(&later).await.unwrap();
+
+        storage.mark_ticketbooks_exported(&exported).await.unwrap();
+        for (id, used, total) in used_tickets(&storage).await {
+            if exported.contains_key(&id) {
+                assert_eq!(used, total);
+            } else {
+                assert_eq!(used, 0);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn export_confirmation_fails_if_tickets_were_used() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let storage = VpnCredentialStorage::setup_from_path(data_dir.path())
+            .await
+            .unwrap();
+        let (entry, verification_key) = test_ticketbook(TicketType::V1MixnetEntry);
+        let (exit, _) = test_ticketbook(TicketType::V1WireguardExit);
+        storage
+            .import_ticketbook_bundle(test_bundle(vec![entry, exit], &verification_key))
+            .await
+            .unwrap();
+
+        let (_, exported) = storage
+            .export_unspent_ticketbooks(test_account().id())
+            .await
+            .unwrap();
+
+        // A ticket is spent between the export and the confirmation
+        storage
+            .credential_storage
+            .get_next_unspent_usable_ticketbook(TicketType::V1MixnetEntry.to_string(), 1)
+            .await
+            .unwrap()
+            .unwrap();
+        let before = used_tickets(&storage).await;
+
+        assert!(matches!(
+            storage.mark_ticketbooks_exported(&exported).await,
+            Err(Error::TicketbookUsedSinceExport(_))
+        ));
+        assert_eq!(used_tickets(&storage).await, before);
+    }
+
+    #[tokio::test]
+    async fn import_skips_already_present_ticketbooks() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let storage = VpnCredentialStorage::setup_from_path(data_dir.path())
+            .await
+            .unwrap();
+        let (ticketbook, verification_key) = test_ticketbook(TicketType::V1MixnetEntry);
+
+        let summary = storage
+            .import_ticketbook_bundle(test_bundle(
+                vec![ticketbook.clone(), ticketbook.clone()],
+                &verification_key,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            TicketbookImportSummary {
+                imported: 1,
+                already_present: 1,
+                expired: 0,
+            }
+        );
+
+        let summary = storage
+            .import_ticketbook_bundle(test_bundle(vec![ticketbook], &verification_key))
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            TicketbookImportSummary {
+                imported: 0,
+                already_present: 1,
+                expired: 0,
+            }
+        );
+        assert_eq!(used_tickets(&storage).await.len(), 1);
+    }
 }
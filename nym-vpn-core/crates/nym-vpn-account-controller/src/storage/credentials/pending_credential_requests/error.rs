@@ -22,4 +22,7 @@ pub enum PendingCredentialRequestsStorageError {
 
     #[error("failed to remove pending credential request storage: {0}")]
     RemoveStorage(#[source] std::io::Error),
+
+    #[error("invalid ticket type stored: {0}")]
+    InvalidTicketType(String),
 }
@@ -17,7 +17,9 @@ use time::OffsetDateTime;
 use tracing::log::LevelFilter;
 
 use error::PendingCredentialRequestsStorageError;
-use models::{PendingCredentialRequest, PendingCredentialRequestStored};
+use models::{CredentialSpendStored, PendingCredentialRequest, PendingCredentialRequestStored};
+
+use crate::credential_spend::CredentialSpend;
 
 // Consider requests older than 60 days as stale
 const DEFAULT_STALE_REQUESTS_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 60);
@@ -172,6 +174,28 @@ impl PendingCredentialRequestsStorage {
             .await
             .map_err(Into::into)
     }
+
+    pub(crate) async fn insert_credential_spend(
+        &self,
+        spend: CredentialSpend,
+    ) -> Result<(), PendingCredentialRequestsStorageError> {
+        self.storage_manager
+            .insert_credential_spend(&CredentialSpendStored::from(spend))
+            .await
+            .map_err(Into::into)
+    }
+
+    pub(crate) async fn get_credential_spends(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<CredentialSpend>, PendingCredentialRequestsStorageError> {
+        self.storage_manager
+            .get_credential_spends(limit)
+            .await?
+            .into_iter()
+            .map(CredentialSpend::try_from)
+            .collect()
+    }
 }
 
 fn set_file_permission_owner_rw<P: AsRef<Path>>(path: P) -> Result<(), std::io::Error> {
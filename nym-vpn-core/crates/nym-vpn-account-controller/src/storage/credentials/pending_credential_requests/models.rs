@@ -5,9 +5,12 @@ use bincode::Options;
 use nym_credentials_interface::RequestInfo;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use time::Date;
+use time::{Date, OffsetDateTime};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use super::error::PendingCredentialRequestsStorageError;
+use crate::credential_spend::{CredentialSpend, CredentialSpendOutcome};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, Zeroize, ZeroizeOnDrop)]
 pub struct PendingCredentialRequestStored {
     pub id: String,
@@ -50,9 +53,119 @@ impl TryFrom<PendingCredentialRequest> for PendingCredentialRequestStored {
     }
 }
 
+#[derive(Debug, Clone, FromRow)]
+pub struct CredentialSpendStored {
+    pub ticket_type: String,
+    pub gateway_id: String,
+    pub tickets: i64,
+    pub bytes_granted: i64,
+    pub remaining_bandwidth: Option<i64>,
+    pub success: bool,
+    pub wasteful: bool,
+    pub error: Option<String>,
+    pub timestamp: OffsetDateTime,
+}
+
+impl From<CredentialSpend> for CredentialSpendStored {
+    fn from(value: CredentialSpend) -> Self {
+        let (success, wasteful, error) = match value.outcome {
+            CredentialSpendOutcome::Success => (true, false, None),
+            CredentialSpendOutcome::Failure { wasteful, error } => (false, wasteful, Some(error)),
+        };
+        Self {
+            ticket_type: value.ticket_type.to_string(),
+            gateway_id: value.gateway_id,
+            tickets: i64::from(value.tickets),
+            bytes_granted: i64::try_from(value.bytes_granted).unwrap_or(i64::MAX),
+            remaining_bandwidth: value.remaining_bandwidth,
+            success,
+            wasteful,
+            error,
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+impl TryFrom<CredentialSpendStored> for CredentialSpend {
+    type Error = PendingCredentialRequestsStorageError;
+
+    fn try_from(value: CredentialSpendStored) -> Result<Self, Self::Error> {
+        let ticket_type = value.ticket_type.parse().map_err(|_| {
+            PendingCredentialRequestsStorageError::InvalidTicketType(value.ticket_type.clone())
+        })?;
+        let outcome = if value.success {
+            CredentialSpendOutcome::Success
+        } else {
+            CredentialSpendOutcome::Failure {
+                wasteful: value.wasteful,
+                error: value.error.unwrap_or_default(),
+            }
+        };
+        Ok(Self {
+            ticket_type,
+            gateway_id: value.gateway_id,
+            timestamp: value.timestamp,
+            tickets: u32::try_from(value.tickets).unwrap_or_default(),
+            bytes_granted: u64::try_from(value.bytes_granted).unwrap_or_default(),
+            remaining_bandwidth: value.remaining_bandwidth,
+            outcome,
+        })
+    }
+}
+
 fn binary_serialiser() -> impl bincode::Options {
     use bincode::Options;
     bincode::DefaultOptions::new()
         .with_big_endian()
         .with_varint_encoding()
 }
+
+#[cfg(test)]
+mod tests {
+    use nym_credentials_interface::TicketType;
+
+    use super::*;
+
+    #[test]
+    fn credential_spend_round_trip() {
+        let spends = [
+            CredentialSpend::success(TicketType::V1MixnetEntry, "gateway", 2, Some(1024)),
+            CredentialSpend::failure(TicketType::V1WireguardExit, "gateway", 1, true, "timeout"),
+        ];
+        for spend in spends {
+            let stored = CredentialSpendStored::from(spend.clone());
+            assert_eq!(CredentialSpend::try_from(stored).unwrap(), spend);
+        }
+    }
+
+    #[test]
+    fn credential_spend_stored_outcome_columns() {
+        let stored = CredentialSpendStored::from(CredentialSpend::failure(
+            TicketType::V1WireguardEntry,
+            "gateway",
+            1,
+            false,
+            "rejected",
+        ));
+        assert!(!stored.success);
+        assert!(!stored.wasteful);
+        assert_eq!(stored.error.as_deref(), Some("rejected"));
+        assert_eq!(stored.bytes_granted, 0);
+    }
+
+    #[test]
+    fn credential_spend_stored_with_invalid_ticket_type() {
+        let mut stored = CredentialSpendStored::from(CredentialSpend::success(
+            TicketType::V1MixnetExit,
+            "gateway",
+            1,
+            None,
+        ));
+        stored.ticket_type = "v0_unknown".to_owned();
+        assert!(matches!(
+            CredentialSpend::try_from(stored),
+            Err(PendingCredentialRequestsStorageError::InvalidTicketType(ticket_type))
+                if ticket_type == "v0_unknown"
+        ));
+    }
+}
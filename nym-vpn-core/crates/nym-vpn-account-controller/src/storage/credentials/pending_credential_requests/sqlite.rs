@@ -3,7 +3,7 @@
 
 use time::{Date, OffsetDateTime};
 
-use super::models::PendingCredentialRequestStored;
+use super::models::{CredentialSpendStored, PendingCredentialRequestStored};
 
 #[derive(Clone)]
 pub struct SqliteZkNymRequestsStorageManager {
@@ -72,4 +72,98 @@ impl SqliteZkNymRequestsStorageManager {
             .await?;
         Ok(())
     }
+
+    pub async fn insert_credential_spend(
+        &self,
+        spend: &CredentialSpendStored,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT INTO credential_spend_log (ticket_type, gateway_id, tickets, bytes_granted, remaining_bandwidth, success, wasteful, error, timestamp) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            spend.ticket_type,
+            spend.gateway_id,
+            spend.tickets,
+            spend.bytes_granted,
+            spend.remaining_bandwidth,
+            spend.success,
+            spend.wasteful,
+            spend.error,
+            spend.timestamp,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+        Ok(())
+    }
+
+    // Get the most recent spends, newest first
+    pub async fn get_credential_spends(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<CredentialSpendStored>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM credential_spend_log ORDER BY id DESC LIMIT ?")
+            .bind(limit)
+            .fetch_all(&self.connection_pool)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nym_credentials_interface::TicketType;
+    use time::OffsetDateTime;
+
+    use super::super::PendingCredentialRequestsStorage;
+    use crate::credential_spend::CredentialSpend;
+
+    async fn setup_storage(dir: &tempfile::TempDir) -> PendingCredentialRequestsStorage {
+        PendingCredentialRequestsStorage::init(dir.path().join("pending_requests.sqlite"))
+            .await
+            .unwrap()
+    }
+
+    fn test_spend(tickets: u32) -> CredentialSpend {
+        // SQLite stores the timestamp with limited precision, so use a whole second
+        let mut spend =
+            CredentialSpend::success(TicketType::V1WireguardEntry, "gateway", tickets, None);
+        spend.timestamp = OffsetDateTime::from_unix_timestamp(1_750_000_000).unwrap();
+        spend
+    }
+
+    #[tokio::test]
+    async fn credential_spends_are_listed_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = setup_storage(&dir).await;
+
+        for tickets in 1..=3 {
+            storage
+                .insert_credential_spend(test_spend(tickets))
+                .await
+                .unwrap();
+        }
+
+        let spends = storage.get_credential_spends(2).await.unwrap();
+        assert_eq!(spends, vec![test_spend(3), test_spend(2)]);
+    }
+
+    #[tokio::test]
+    async fn credential_spend_log_is_append_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = setup_storage(&dir).await;
+        storage
+            .insert_credential_spend(test_spend(1))
+            .await
+            .unwrap();
+
+        let connection_pool = &storage.storage_manager.connection_pool;
+        sqlx::query("UPDATE credential_spend_log SET tickets = 0")
+            .execute(connection_pool)
+            .await
+            .unwrap_err();
+        sqlx::query("DELETE FROM credential_spend_log")
+            .execute(connection_pool)
+            .await
+            .unwrap_err();
+
+        let spends = storage.get_credential_spends(10).await.unwrap();
+        assert_eq!(spends, vec![test_spend(1)]);
+    }
 }
@@ -7,4 +7,4 @@ mod credentials;
 pub use credentials::PendingCredentialRequestsStorageError;
 
 pub(crate) use account::AccountStorage;
-pub(crate) use credentials::{PendingCredentialRequest, VpnCredentialStorage};
+pub(crate) use credentials::{ExportedTicketbooks, PendingCredentialRequest, VpnCredentialStorage};
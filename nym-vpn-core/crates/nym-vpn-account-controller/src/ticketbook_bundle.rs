@@ -0,0 +1,340 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Offline transfer of zk-nym ticketbooks between devices belonging to the same account.
+//
+// The bundle contains the unspent ticketbooks together with everything needed to spend them
+// without contacting the nym-vpn-api: the master verification keys, the coin index signatures
+// and the expiration date signatures for the epochs and dates involved.
+//
+// The bundle is encrypted with a key derived from the account mnemonic, so only a device logged
+// into the same account can import it. The ticketbooks are bound to the account ecash keypair
+// anyway, so they would be unusable by any other account.
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use nym_compact_ecash::{Base58, VerificationKeyAuth};
+use nym_credentials::{
+    AggregatedCoinIndicesSignatures, AggregatedExpirationDateSignatures, EpochVerificationKey,
+    IssuedTicketBook,
+};
+use nym_credentials_interface::{AnnotatedCoinIndexSignature, AnnotatedExpirationDateSignature};
+use nym_vpn_api_client::types::VpnApiAccount;
+use serde::{Deserialize, Serialize};
+use time::Date;
+use zeroize::Zeroize;
+
+use crate::error::Error;
+
+// Identifies the file format, followed by a single version byte
+const BUNDLE_MAGIC: &[u8; 8] = b"NYMZKTB\0";
+const BUNDLE_FORMAT_VERSION: u8 = 1;
+const NONCE_LEN: usize = 24;
+
+// Context string used when deriving the bundle encryption key from the account
+const BUNDLE_KEY_INFO: &[u8] = b"nym-vpn ticketbook bundle v1";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct MasterVerificationKey {
+    pub(crate) epoch_id: u64,
+    pub(crate) bs58_encoded_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CoinIndexSignatures {
+    pub(crate) epoch_id: u64,
+    pub(crate) signatures: Vec<AnnotatedCoinIndexSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ExpirationDateSignatures {
+    pub(crate) epoch_id: u64,
+    pub(crate) expiration_date: Date,
+    pub(crate) signatures: Vec<AnnotatedExpirationDateSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TicketbookBundle {
+    pub(crate) account_id: String,
+    pub(crate) ticketbooks: Vec<IssuedTicketBook>,
+    pub(crate) master_verification_keys: Vec<MasterVerificationKey>,
+    pub(crate) coin_index_signatures: Vec<CoinIndexSignatures>,
+    pub(crate) expiration_date_signatures: Vec<ExpirationDateSignatures>,
+}
+
+impl TicketbookBundle {
+    pub(crate) fn new(account_id: String) -> Self {
+        Self {
+            account_id,
+            ticketbooks: Vec::new(),
+            master_verification_keys: Vec::new(),
+            coin_index_signatures: Vec::new(),
+            expiration_date_signatures: Vec::new(),
+        }
+    }
+
+    pub(crate) fn encrypt(&self, account: &VpnApiAccount) -> Result<Vec<u8>, Error> {
+        let mut plaintext = bincode::serialize(self).map_err(Error::SerializeTicketbookBundle)?;
+
+        let cipher = bundle_cipher(account)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| Error::EncryptTicketbookBundle);
+        plaintext.zeroize();
+
+        let mut bundle = Vec::with_capacity(BUNDLE_MAGIC.len() + 1 + NONCE_LEN);
+        bundle.extend_from_slice(BUNDLE_MAGIC);
+        bundle.push(BUNDLE_FORMAT_VERSION);
+        bundle.extend_from_slice(&nonce);
+        bundle.extend_from_slice(&ciphertext?);
+        Ok(bundle)
+    }
+
+    pub(crate) fn decrypt(bundle: &[u8], account: &VpnApiAccount) -> Result<Self, Error> {
+        let payload = bundle
+            .strip_prefix(BUNDLE_MAGIC.as_slice())
+            .ok_or_else(|| Error::InvalidTicketbookBundle("not a ticketbook bundle".to_owned()))?;
+        let (version, payload) = payload
+            .split_first()
+            .ok_or_else(|| Error::InvalidTicketbookBundle("truncated header".to_owned()))?;
+        if *version != BUNDLE_FORMAT_VERSION {
+            return Err(Error::InvalidTicketbookBundle(format!(
+                "unsupported format version: {version}"
+            )));
+        }
+        if payload.len() < NONCE_LEN {
+            return Err(Error::InvalidTicketbookBundle(
+                "truncated header".to_owned(),
+            ));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+
+        let cipher = bundle_cipher(account)?;
+        let mut plaintext = cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::DecryptTicketbookBundle)?;
+        let decoded = bincode::deserialize::<TicketbookBundle>(&plaintext)
+            .map_err(Error::SerializeTicketbookBundle);
+        plaintext.zeroize();
+        let decoded = decoded?;
+
+        if decoded.account_id != account.id() {
+            return Err(Error::InvalidTicketbookBundle(
+                "bundle was exported from a different account".to_owned(),
+            ));
+        }
+        Ok(decoded)
+    }
+}
+
+fn bundle_cipher(account: &VpnApiAccount) -> Result<XChaCha20Poly1305, Error> {
+    let mut key = account
+        .derive_symmetric_key(BUNDLE_KEY_INFO)
+        .map_err(Error::DeriveTicketbookBundleKey)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    key.zeroize();
+    Ok(cipher)
+}
+
+impl MasterVerificationKey {
+    pub(crate) fn new(epoch_id: u64, key: &VerificationKeyAuth) -> Self {
+        Self {
+            epoch_id,
+            bs58_encoded_key: key.to_bs58(),
+        }
+    }
+
+    pub(crate) fn to_epoch_verification_key(&self) -> Result<EpochVerificationKey, Error> {
+        let key = VerificationKeyAuth::try_from_bs58(&self.bs58_encoded_key)
+            .map_err(|err| Error::InvalidTicketbookBundle(err.to_string()))?;
+        Ok(EpochVerificationKey {
+            epoch_id: self.epoch_id,
+            key,
+        })
+    }
+}
+
+impl From<CoinIndexSignatures> for AggregatedCoinIndicesSignatures {
+    fn from(value: CoinIndexSignatures) -> Self {
+        AggregatedCoinIndicesSignatures {
+            epoch_id: value.epoch_id,
+            signatures: value.signatures,
+        }
+    }
+}
+
+impl From<ExpirationDateSignatures> for AggregatedExpirationDateSignatures {
+    fn from(value: ExpirationDateSignatures) -> Self {
+        AggregatedExpirationDateSignatures {
+            epoch_id: value.epoch_id,
+            expiration_date: value.expiration_date,
+            signatures: value.signatures,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TicketbookImportSummary {
+    // Number of ticketbooks added to the local credential storage
+    pub imported: u32,
+
+    // Number of ticketbooks that were already present locally
+    pub already_present: u32,
+
+    // Number of ticketbooks skipped since they have expired
+    pub expired: u32,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use nym_compact_ecash::{
+        aggregate_verification_keys, aggregate_wallets, generate_keypair_user, issue, issue_verify,
+        ttp_keygen, withdrawal_request,
+    };
+    use nym_credentials_interface::TicketType;
+    use nym_ecash_time::{ecash_default_expiration_date, EcashTime};
+    use nym_vpn_store::mnemonic::Mnemonic;
+
+    use super::*;
+
+    const TEST_MNEMONIC: &str = "range mystery picture decline olympic acoustic lesson quick rebuild panda royal fold start leader egg hammer width olympic worry length crawl couch link mobile";
+    const OTHER_TEST_MNEMONIC: &str = "pitch deputy proof fire movie put bread ribbon what chef zebra car vacuum gadget steak board state oyster layer glory barely thrive nice box";
+
+    pub(crate) fn test_account() -> VpnApiAccount {
+        VpnApiAccount::from(Mnemonic::parse(TEST_MNEMONIC).unwrap())
+    }
+
+    // A bundle with the given ticketbooks, which are all expected to be issued for the same
+    // epoch and expiration date
+    pub(crate) fn test_bundle(
+        ticketbooks: Vec<IssuedTicketBook>,
+        verification_key: &VerificationKeyAuth,
+    ) -> TicketbookBundle {
+        let mut bundle = TicketbookBundle::new(test_account().id());
+        let ticketbook = &ticketbooks[0];
+        bundle
+            .master_verification_keys
+            .push(MasterVerificationKey::new(
+                ticketbook.epoch_id(),
+                verification_key,
+            ));
+        bundle.coin_index_signatures.push(CoinIndexSignatures {
+            epoch_id: ticketbook.epoch_id(),
+            signatures: Vec::new(),
+        });
+        bundle
+            .expiration_date_signatures
+            .push(ExpirationDateSignatures {
+                epoch_id: ticketbook.epoch_id(),
+                expiration_date: ticketbook.expiration_date(),
+                signatures: Vec::new(),
+            });
+        bundle.ticketbooks = ticketbooks;
+        bundle
+    }
+
+    // Issue a ticketbook signed by a single test authority, returning it together with the
+    // master verification key needed to spend it
+    pub(crate) fn test_ticketbook(
+        ticket_type: TicketType,
+    ) -> (IssuedTicketBook, VerificationKeyAuth) {
+        let authority = ttp_keygen(1, 1).unwrap().remove(0);
+        let verification_key =
+            aggregate_verification_keys(&[authority.verification_key()], Some(&[1])).unwrap();
+        let user = generate_keypair_user();
+        let expiration_date = ecash_default_expiration_date();
+        let (request, request_info) = withdrawal_request(
+            user.secret_key(),
+            expiration_date.ecash_unix_timestamp(),
+            ticket_type.encode(),
+        )
+        .unwrap();
+        let blinded_signature = issue(
+            authority.secret_key(),
+            user.public_key(),
+            &request,
+            expiration_date.ecash_unix_timestamp(),
+            ticket_type.encode(),
+        )
+        .unwrap();
+        let partial_wallet = issue_verify(
+            &authority.verification_key(),
+            user.secret_key(),
+            &blinded_signature,
+            &request_info,
+            1,
+        )
+        .unwrap();
+        let wallet = aggregate_wallets(
+            &verification_key,
+            user.secret_key(),
+            &[partial_wallet],
+            &request_info,
+        )
+        .unwrap();
+        let ticketbook = IssuedTicketBook::new(
+            wallet.into_wallet_signatures(),
+            1,
+            user.secret_key().clone(),
+            ticket_type,
+            expiration_date,
+        );
+        (ticketbook, verification_key)
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let account = test_account();
+        let (ticketbook, verification_key) = test_ticketbook(TicketType::V1MixnetEntry);
+        let mut bundle = TicketbookBundle::new(account.id());
+        bundle.ticketbooks.push(ticketbook.clone());
+        bundle
+            .master_verification_keys
+            .push(MasterVerificationKey::new(1, &verification_key));
+
+        let encrypted = bundle.encrypt(&account).unwrap();
+        assert!(encrypted.starts_with(BUNDLE_MAGIC));
+
+        let decrypted = TicketbookBundle::decrypt(&encrypted, &account).unwrap();
+        assert_eq!(decrypted.account_id, account.id());
+        assert_eq!(decrypted.ticketbooks.len(), 1);
+        assert_eq!(decrypted.ticketbooks[0], ticketbook);
+        assert_eq!(
+            decrypted.master_verification_keys[0]
+                .to_epoch_verification_key()
+                .unwrap()
+                .key,
+            verification_key
+        );
+    }
+
+    #[test]
+    fn decrypt_with_other_account_fails() {
+        let account = test_account();
+        let other_account = VpnApiAccount::from(Mnemonic::parse(OTHER_TEST_MNEMONIC).unwrap());
+        let (ticketbook, _) = test_ticketbook(TicketType::V1MixnetEntry);
+        let mut bundle = TicketbookBundle::new(account.id());
+        bundle.ticketbooks.push(ticketbook);
+
+        let encrypted = bundle.encrypt(&account).unwrap();
+        assert!(matches!(
+            TicketbookBundle::decrypt(&encrypted, &other_account),
+            Err(Error::DecryptTicketbookBundle)
+        ));
+    }
+
+    #[test]
+    fn decrypt_rejects_truncated_bundle() {
+        let account = test_account();
+        let encrypted = TicketbookBundle::new(account.id())
+            .encrypt(&account)
+            .unwrap();
+        assert!(matches!(
+            TicketbookBundle::decrypt(&encrypted[..BUNDLE_MAGIC.len() + 4], &account),
+            Err(Error::InvalidTicketbookBundle(_))
+        ));
+    }
+}
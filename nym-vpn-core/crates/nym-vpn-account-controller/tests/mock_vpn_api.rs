@@ -0,0 +1,252 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Drive the account controller against the in-process mock nym-vpn-api
+
+use std::{path::Path, sync::Arc};
+
+use nym_http_api_client::UserAgent;
+use nym_vpn_account_controller::{
+    shared_state::{AccountRegistered, DeviceState, MnemonicState, RegisterDeviceResult},
+    AccountController, AccountControllerCommander, SharedAccountState,
+};
+use nym_vpn_api_client::{response::NymVpnDeviceStatus, types::VpnApiAccount};
+use nym_vpn_api_mock::{MockVpnApi, MockVpnApiHandle};
+use nym_vpn_lib_types::{AccountCommandError, RegisterDeviceError, StoreAccountError};
+use nym_vpn_network_config::Network;
+use nym_vpn_store::{
+    keys::{
+        persistence::{DeviceKeysPaths, OnDiskKeys, OnDiskKeysError},
+        DeviceKeys, KeyStore,
+    },
+    mnemonic::{
+        on_disk::{OnDiskMnemonicStorage, OnDiskMnemonicStorageError},
+        Mnemonic, MnemonicStorage,
+    },
+    VpnStorage,
+};
+use tokio_util::sync::CancellationToken;
+
+// The same account mnemonic as used in the nym-vpn-api-client tests
+const TEST_MNEMONIC: &str = "range mystery picture decline olympic acoustic lesson quick rebuild panda royal fold start leader egg hammer width olympic worry length crawl couch link mobile";
+
+struct TestStorage {
+    key_store: OnDiskKeys,
+    mnemonic_storage: OnDiskMnemonicStorage,
+}
+
+impl TestStorage {
+    fn new(data_dir: &Path) -> Self {
+        TestStorage {
+            key_store: OnDiskKeys::new(DeviceKeysPaths::new(data_dir)),
+            mnemonic_storage: OnDiskMnemonicStorage::new(data_dir.join("mnemonic.json")),
+        }
+    }
+}
+
+impl VpnStorage for TestStorage {}
+
+impl KeyStore for TestStorage {
+    type StorageError = OnDiskKeysError;
+
+    async fn load_keys(&self) -> Result<DeviceKeys, Self::StorageError> {
+        self.key_store.load_keys().await
+    }
+
+    async fn store_keys(&self, keys: &DeviceKeys) -> Result<(), Self::StorageError> {
+        self.key_store.store_keys(keys).await
+    }
+
+    async fn init_keys(&self, seed: Option<[u8; 32]>) -> Result<(), Self::StorageError> {
+        self.key_store.init_keys(seed).await
+    }
+
+    async fn reset_keys(&self, seed: Option<[u8; 32]>) -> Result<(), Self::StorageError> {
+        self.key_store.reset_keys(seed).await
+    }
+
+    async fn remove_keys(&self) -> Result<(), Self::StorageError> {
+        self.key_store.remove_keys().await
+    }
+}
+
+impl MnemonicStorage for TestStorage {
+    type StorageError = OnDiskMnemonicStorageError;
+
+    async fn load_mnemonic(&self) -> Result<Mnemonic, Self::StorageError> {
+        self.mnemonic_storage.load_mnemonic().await
+    }
+
+    async fn store_mnemonic(&self, mnemonic: Mnemonic) -> Result<(), Self::StorageError> {
+        self.mnemonic_storage.store_mnemonic(mnemonic).await
+    }
+
+    async fn remove_mnemonic(&self) -> Result<(), Self::StorageError> {
+        self.mnemonic_storage.remove_mnemonic().await
+    }
+}
+
+struct TestAccountController {
+    commander: AccountControllerCommander,
+    shared_state: SharedAccountState,
+    cancel_token: CancellationToken,
+}
+
+impl Drop for TestAccountController {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+    }
+}
+
+fn test_mnemonic() -> Mnemonic {
+    Mnemonic::parse(TEST_MNEMONIC).unwrap()
+}
+
+fn test_account_id() -> String {
+    VpnApiAccount::from(test_mnemonic()).id()
+}
+
+fn user_agent() -> UserAgent {
+    UserAgent {
+        version: "0.1.0".to_string(),
+        application: "nym-vpn-account-controller".to_string(),
+        platform: "test".to_string(),
+        git_commit: "0000000".to_string(),
+    }
+}
+
+async fn start_account_controller(
+    mock: &MockVpnApiHandle,
+    data_dir: &Path,
+) -> TestAccountController {
+    let mut network = Network::mainnet_default().unwrap();
+    network.nym_vpn_network.nym_vpn_api_url = mock.url();
+
+    let cancel_token = CancellationToken::new();
+    let storage = Arc::new(tokio::sync::Mutex::new(TestStorage::new(data_dir)));
+    let account_controller = AccountController::new(
+        storage,
+        data_dir.to_path_buf(),
+        user_agent(),
+        Some(false),
+        network,
+        cancel_token.child_token(),
+    )
+    .await
+    .unwrap();
+
+    let controller = TestAccountController {
+        commander: account_controller.commander(),
+        shared_state: account_controller.shared_state(),
+        cancel_token,
+    };
+    tokio::spawn(account_controller.run());
+    controller
+}
+
+#[tokio::test]
+async fn login_register_device_and_sync() {
+    let account_id = test_account_id();
+    let mock = MockVpnApi::new()
+        .with_account(&account_id)
+        .spawn()
+        .await
+        .unwrap();
+    let data_dir = tempfile::tempdir().unwrap();
+    let controller = start_account_controller(&mock, data_dir.path()).await;
+
+    controller.commander.login(test_mnemonic()).await.unwrap();
+    {
+        let state = controller.shared_state.lock().await;
+        assert_eq!(
+            state.mnemonic,
+            Some(MnemonicState::Stored {
+                id: account_id.clone()
+            })
+        );
+        assert_eq!(
+            state.account_registered,
+            Some(AccountRegistered::Registered)
+        );
+    }
+
+    controller.commander.ensure_register_device().await.unwrap();
+    let device_identity = controller.commander.get_device_identity().await.unwrap();
+    let devices = mock.devices(&account_id).await;
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].device_identity_key, device_identity);
+    assert_eq!(devices[0].status, NymVpnDeviceStatus::Active);
+
+    assert_eq!(
+        controller.commander.sync_device_state().await.unwrap(),
+        DeviceState::Active
+    );
+    let summary = controller.commander.sync_account_state().await.unwrap();
+    assert_eq!(summary.devices.active, 1);
+
+    let state = controller.shared_state.lock().await.clone();
+    assert_eq!(state.device, Some(DeviceState::Active));
+    assert_eq!(
+        state.register_device_result,
+        Some(RegisterDeviceResult::Success)
+    );
+    assert_eq!(
+        state
+            .account_summary
+            .map(|summary| summary.device_summary.active),
+        Some(1)
+    );
+
+    mock.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn login_with_unknown_account_fails() {
+    let mock = MockVpnApi::new().spawn().await.unwrap();
+    let data_dir = tempfile::tempdir().unwrap();
+    let controller = start_account_controller(&mock, data_dir.path()).await;
+
+    let err = controller
+        .commander
+        .login(test_mnemonic())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        AccountCommandError::StoreAccount(StoreAccountError::GetAccountEndpointFailure(_))
+    ));
+    assert!(!controller.shared_state.is_account_stored().await);
+
+    mock.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn register_device_fails_when_max_devices_reached() {
+    let account_id = test_account_id();
+    let mock = MockVpnApi::new()
+        .with_max_devices(1)
+        .with_device(&account_id, "some-other-device")
+        .spawn()
+        .await
+        .unwrap();
+    let data_dir = tempfile::tempdir().unwrap();
+    let controller = start_account_controller(&mock, data_dir.path()).await;
+
+    controller.commander.login(test_mnemonic()).await.unwrap();
+    assert_eq!(
+        controller.shared_state.lock().await.device,
+        Some(DeviceState::NotRegistered)
+    );
+
+    let err = controller.commander.register_device().await.unwrap_err();
+    let RegisterDeviceError::RegisterDeviceEndpointFailure(response) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(
+        response.message_id.as_deref(),
+        Some("mock.device.max-devices-reached")
+    );
+    assert_eq!(mock.devices(&account_id).await.len(), 1);
+
+    mock.shutdown().await.unwrap();
+}
@@ -95,6 +95,9 @@ pub enum VpnApiClientError {
         #[source] nym_validator_client::signing::direct_wallet::DirectSecp256k1HdWalletError,
     ),
 
+    #[error("failed to derive symmetric key from account")]
+    DeriveSymmetricKey,
+
     #[error("failed to get directory zk-nym ticketbook partial verification keys")]
     FailedToGetDirectoryZkNymsTicketbookPartialVerificationKeys(
         #[source] HttpClientError<ErrorMessage>,
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 pub mod response;
+pub mod routes;
 pub mod types;
 
 pub(crate) mod jwt;
@@ -11,7 +12,6 @@ mod client;
 mod error;
 mod network_compatibility;
 mod request;
-mod routes;
 
 pub use bootstrap::BootstrapVpnApiClient;
 pub use client::VpnApiClient;
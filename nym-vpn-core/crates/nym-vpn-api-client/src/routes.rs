@@ -1,32 +1,32 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-pub(crate) const PUBLIC: &str = "public";
-pub(crate) const V1: &str = "v1";
-pub(crate) const ACCOUNT: &str = "account";
-pub(crate) const HEALTH: &str = "health";
-pub(crate) const SUMMARY: &str = "summary";
-pub(crate) const DEVICE: &str = "device";
-pub(crate) const ACTIVE: &str = "active";
-pub(crate) const ZKNYM: &str = "zknym";
-pub(crate) const AVAILABLE: &str = "available";
-pub(crate) const FREEPASS: &str = "freepass";
-pub(crate) const SUBSCRIPTION: &str = "subscription";
-pub(crate) const USAGE: &str = "usage";
-pub(crate) const DIRECTORY: &str = "directory";
-pub(crate) const GATEWAYS: &str = "gateways";
-pub(crate) const COUNTRIES: &str = "countries";
-pub(crate) const ENTRY: &str = "entry";
-pub(crate) const EXIT: &str = "exit";
-pub(crate) const ZK_NYMS: &str = "zk-nyms";
-pub(crate) const TICKETBOOK: &str = "ticketbook";
-pub(crate) const PARTIAL_VERIFICATION_KEYS: &str = "partial-verification-keys";
+pub const PUBLIC: &str = "public";
+pub const V1: &str = "v1";
+pub const ACCOUNT: &str = "account";
+pub const HEALTH: &str = "health";
+pub const SUMMARY: &str = "summary";
+pub const DEVICE: &str = "device";
+pub const ACTIVE: &str = "active";
+pub const ZKNYM: &str = "zknym";
+pub const AVAILABLE: &str = "available";
+pub const FREEPASS: &str = "freepass";
+pub const SUBSCRIPTION: &str = "subscription";
+pub const USAGE: &str = "usage";
+pub const DIRECTORY: &str = "directory";
+pub const GATEWAYS: &str = "gateways";
+pub const COUNTRIES: &str = "countries";
+pub const ENTRY: &str = "entry";
+pub const EXIT: &str = "exit";
+pub const ZK_NYMS: &str = "zk-nyms";
+pub const TICKETBOOK: &str = "ticketbook";
+pub const PARTIAL_VERIFICATION_KEYS: &str = "partial-verification-keys";
 
-pub(crate) const SHOW_VPN_ONLY: &str = "show_vpn_only";
-pub(crate) const VPN_MIN_PERFORMANCE: &str = "vpn_min_performance";
-pub(crate) const MIXNET_MIN_PERFORMANCE: &str = "mixnet_min_performance";
+pub const SHOW_VPN_ONLY: &str = "show_vpn_only";
+pub const VPN_MIN_PERFORMANCE: &str = "vpn_min_performance";
+pub const MIXNET_MIN_PERFORMANCE: &str = "mixnet_min_performance";
 
-pub(crate) const WELLKNOWN: &str = ".wellknown";
-pub(crate) const ENVS_FILE: &str = "envs.json";
-pub(crate) const DISCOVERY_FILE: &str = "discovery.json";
-pub(crate) const CURRENT_ENV: &str = "current-env.json";
+pub const WELLKNOWN: &str = ".wellknown";
+pub const ENVS_FILE: &str = "envs.json";
+pub const DISCOVERY_FILE: &str = "discovery.json";
+pub const CURRENT_ENV: &str = "current-env.json";
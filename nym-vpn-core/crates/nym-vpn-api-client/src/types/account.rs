@@ -3,10 +3,12 @@
 
 use std::fmt;
 
+use hkdf::Hkdf;
 use nym_compact_ecash::scheme::keygen::KeyPairUser;
 use nym_validator_client::{
     nyxd::bip32::DerivationPath, signing::signer::OfflineSigner as _, DirectSecp256k1HdWallet,
 };
+use sha2::Sha256;
 use time::{Duration, OffsetDateTime};
 
 use crate::{error::Result, jwt::Jwt, VpnApiClientError};
@@ -48,6 +50,22 @@ impl VpnApiAccount {
             extended_private_key.private_key().to_bytes(),
         ))
     }
+
+    // Derive a 256-bit symmetric key bound to this account. Devices sharing the same mnemonic
+    // derive the same key for the same `info`, which lets them exchange encrypted data offline.
+    pub fn derive_symmetric_key(&self, info: &[u8]) -> Result<[u8; 32]> {
+        let hd_path = cosmos_derivation_path();
+        let extended_private_key = self
+            .wallet
+            .derive_extended_private_key(&hd_path)
+            .map_err(VpnApiClientError::CosmosDeriveFromPath)?;
+        let secret = extended_private_key.private_key().to_bytes();
+        let hkdf = Hkdf::<Sha256>::new(Some(self.id().as_bytes()), &secret[..]);
+        let mut key = [0u8; 32];
+        hkdf.expand(info, &mut key)
+            .map_err(|_| VpnApiClientError::DeriveSymmetricKey)?;
+        Ok(key)
+    }
 }
 
 impl From<bip39::Mnemonic> for VpnApiAccount {
@@ -0,0 +1,20 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to bind mock nym-vpn-api listener")]
+    Bind(#[source] std::io::Error),
+
+    #[error("failed to get mock nym-vpn-api listener address")]
+    LocalAddr(#[source] std::io::Error),
+
+    #[error("failed to construct mock nym-vpn-api url")]
+    Url(#[source] url::ParseError),
+
+    #[error("mock nym-vpn-api server failed")]
+    Serve(#[source] std::io::Error),
+
+    #[error("mock nym-vpn-api server task panicked")]
+    Join(#[source] tokio::task::JoinError),
+}
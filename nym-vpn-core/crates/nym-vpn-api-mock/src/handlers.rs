@@ -0,0 +1,529 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use itertools::Itertools;
+use nym_vpn_api_client::response::{
+    NymDirectoryGateway, NymErrorResponse, NymVpnAccountResponse, NymVpnAccountSummaryResponse,
+    NymVpnDevice, NymVpnDeviceStatus, NymVpnDevicesResponse, NymVpnHealthResponse,
+    NymVpnSubscriptionResponse, NymVpnSubscriptionsResponse, NymVpnUsagesResponse, NymVpnZkNym,
+    NymVpnZkNymPost, NymVpnZkNymResponse, NymWellknownDiscoveryItem,
+    NymWellknownDiscoveryItemResponse,
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use crate::{
+    request::{RegisterDeviceRequestBody, RequestZkNymRequestBody, UpdateDeviceRequestBody},
+    state::SharedMockState,
+};
+
+const DEVICE_AUTHORIZATION_HEADER: &str = "x-device-authorization";
+
+// Every list endpoint returns everything on a single page
+const PAGE_SIZE: u64 = 100;
+
+// Error returned by the mock, serialized the same way as the real nym-vpn-api does it
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+    message_id: &'static str,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: String, message_id: &'static str) -> Self {
+        ApiError {
+            status,
+            message,
+            message_id,
+        }
+    }
+
+    fn unauthorized(header: &str) -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            format!("missing bearer token in {header} header"),
+            "mock.auth.missing-bearer-token",
+        )
+    }
+
+    pub(crate) fn account_not_found(account_id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("account {account_id} not found"),
+            "mock.account.not-found",
+        )
+    }
+
+    pub(crate) fn device_not_found(device_identity_key: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("device {device_identity_key} not found"),
+            "mock.device.not-found",
+        )
+    }
+
+    pub(crate) fn max_devices_reached(max_devices: u64) -> Self {
+        Self::new(
+            StatusCode::CONFLICT,
+            format!("maximum number of active devices ({max_devices}) reached"),
+            "mock.device.max-devices-reached",
+        )
+    }
+
+    pub(crate) fn no_active_subscription(account_id: &str) -> Self {
+        Self::new(
+            StatusCode::PAYMENT_REQUIRED,
+            format!("account {account_id} has no active subscription"),
+            "mock.zk-nym.no-active-subscription",
+        )
+    }
+
+    pub(crate) fn zk_nym_not_found(id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("zk-nym {id} not found"),
+            "mock.zk-nym.not-found",
+        )
+    }
+
+    fn network_not_found(network_name: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("network {network_name} not found"),
+            "mock.wellknown.network-not-found",
+        )
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        tracing::debug!("Mock nym-vpn-api error: {}", self.message);
+        let body = NymErrorResponse {
+            message: self.message,
+            message_id: Some(self.message_id.to_string()),
+            code_reference_id: None,
+            status: self.status.as_u16().to_string(),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+// The JWTs are only checked for presence. Validating them would require the mock to track the
+// account and device public keys, which doesn't add much for testing the client side.
+fn require_bearer(headers: &HeaderMap, name: &str) -> Result<(), ApiError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .filter(|token| !token.is_empty())
+        .map(|_| ())
+        .ok_or_else(|| ApiError::unauthorized(name))
+}
+
+fn require_account_auth(headers: &HeaderMap) -> Result<(), ApiError> {
+    require_bearer(headers, header::AUTHORIZATION.as_str())
+}
+
+fn require_device_auth(headers: &HeaderMap) -> Result<(), ApiError> {
+    require_account_auth(headers)?;
+    require_bearer(headers, DEVICE_AUTHORIZATION_HEADER)
+}
+
+fn devices_response(items: Vec<NymVpnDevice>) -> NymVpnDevicesResponse {
+    NymVpnDevicesResponse {
+        total_items: items.len() as u64,
+        page: 0,
+        page_size: PAGE_SIZE,
+        items,
+    }
+}
+
+fn zk_nyms_response(items: Vec<NymVpnZkNym>) -> NymVpnZkNymResponse {
+    NymVpnZkNymResponse {
+        total_items: items.len() as u64,
+        page: 0,
+        page_size: PAGE_SIZE,
+        items,
+    }
+}
+
+// HEALTH
+
+pub(crate) async fn get_health() -> Json<NymVpnHealthResponse> {
+    Json(NymVpnHealthResponse {
+        status: "up".to_string(),
+        timestamp_utc: OffsetDateTime::now_utc(),
+    })
+}
+
+// ACCOUNT
+
+pub(crate) async fn get_account(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnAccountResponse> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let account = state.account(&account_id)?;
+    Ok(Json(account.account.clone()))
+}
+
+pub(crate) async fn get_account_summary(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnAccountSummaryResponse> {
+    require_account_auth(&headers)?;
+    state.lock().await.summary(&account_id).map(Json)
+}
+
+// DEVICES
+
+pub(crate) async fn get_devices(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnDevicesResponse> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let account = state.account(&account_id)?;
+    Ok(Json(devices_response(account.devices.clone())))
+}
+
+pub(crate) async fn get_active_devices(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnDevicesResponse> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let account = state.account(&account_id)?;
+    let active = account
+        .devices
+        .iter()
+        .filter(|device| device.status == NymVpnDeviceStatus::Active)
+        .cloned()
+        .collect();
+    Ok(Json(devices_response(active)))
+}
+
+pub(crate) async fn register_device(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<RegisterDeviceRequestBody>,
+) -> ApiResult<NymVpnDevice> {
+    require_device_auth(&headers)?;
+    tracing::debug!(
+        "Mock nym-vpn-api registering device {} for {account_id}",
+        body.device_identity_key
+    );
+    state
+        .lock()
+        .await
+        .register_device(&account_id, &body.device_identity_key)
+        .map(Json)
+}
+
+pub(crate) async fn get_device(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnDevice> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let device = state.account(&account_id)?.device(&device_id)?;
+    Ok(Json(device.clone()))
+}
+
+pub(crate) async fn update_device(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<UpdateDeviceRequestBody>,
+) -> ApiResult<NymVpnDevice> {
+    require_device_auth(&headers)?;
+    state
+        .lock()
+        .await
+        .account_mut(&account_id)?
+        .update_device(&device_id, body.status)
+        .map(Json)
+}
+
+// ZK-NYM
+
+pub(crate) async fn get_device_zk_nyms(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnZkNymResponse> {
+    require_device_auth(&headers)?;
+    let state = state.lock().await;
+    let account = state.account(&account_id)?;
+    Ok(Json(zk_nyms_response(account.device_zk_nyms(&device_id))))
+}
+
+pub(crate) async fn request_zk_nym(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<RequestZkNymRequestBody>,
+) -> ApiResult<NymVpnZkNymPost> {
+    require_device_auth(&headers)?;
+    tracing::debug!(
+        "Mock nym-vpn-api received zk-nym request of type {} for {device_id}",
+        body.ticketbook_type
+    );
+    let zk_nym = state
+        .lock()
+        .await
+        .request_zk_nym(&account_id, &device_id, body)?;
+    Ok(Json(NymVpnZkNymPost {
+        created_on_utc: zk_nym.created_on_utc,
+        last_updated_utc: zk_nym.last_updated_utc,
+        id: zk_nym.id,
+        ticketbook_type: zk_nym.ticketbook_type,
+        valid_until_utc: zk_nym.valid_until_utc,
+        valid_from_utc: zk_nym.valid_from_utc,
+        issued_bandwidth_in_gb: zk_nym.issued_bandwidth_in_gb,
+        blinded_shares: None,
+        status: zk_nym.status,
+    }))
+}
+
+pub(crate) async fn get_available_zk_nyms(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnZkNymResponse> {
+    require_device_auth(&headers)?;
+    let state = state.lock().await;
+    let account = state.account(&account_id)?;
+    Ok(Json(zk_nyms_response(
+        account.available_zk_nyms(&device_id),
+    )))
+}
+
+pub(crate) async fn get_zk_nym(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id, zk_nym_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnZkNym> {
+    require_device_auth(&headers)?;
+    let mut state = state.lock().await;
+    let zk_nym = state
+        .account_mut(&account_id)?
+        .zk_nym_mut(&device_id, &zk_nym_id)?;
+    Ok(Json(zk_nym.zk_nym.clone()))
+}
+
+pub(crate) async fn confirm_zk_nym_download(
+    State(state): State<SharedMockState>,
+    Path((account_id, device_id, zk_nym_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<serde_json::Value> {
+    require_device_auth(&headers)?;
+    let mut state = state.lock().await;
+    state
+        .account_mut(&account_id)?
+        .zk_nym_mut(&device_id, &zk_nym_id)?
+        .downloaded = true;
+    Ok(Json(serde_json::json!({ "status": "ok" })))
+}
+
+// SUBSCRIPTIONS
+
+pub(crate) async fn get_subscriptions(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnSubscriptionsResponse> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let items: Vec<_> = state
+        .account(&account_id)?
+        .subscription
+        .clone()
+        .into_iter()
+        .collect();
+    Ok(Json(NymVpnSubscriptionsResponse {
+        total_items: items.len() as u64,
+        page: 0,
+        page_size: PAGE_SIZE,
+        items,
+    }))
+}
+
+pub(crate) async fn get_active_subscription(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnSubscriptionResponse> {
+    require_account_auth(&headers)?;
+    let state = state.lock().await;
+    let subscription = state.account(&account_id)?.subscription.clone();
+    Ok(Json(NymVpnSubscriptionResponse {
+        is_subscription_active: subscription.is_some(),
+        subscription,
+        remaining_allowance_in_gb: 0.0,
+    }))
+}
+
+pub(crate) async fn get_usage(
+    State(state): State<SharedMockState>,
+    Path(account_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<NymVpnUsagesResponse> {
+    require_account_auth(&headers)?;
+    state.lock().await.account(&account_id)?;
+    Ok(Json(NymVpnUsagesResponse {
+        total_items: 0,
+        page: 0,
+        page_size: PAGE_SIZE,
+        items: Vec::new(),
+    }))
+}
+
+// GATEWAYS
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct GatewaysQuery {
+    show_vpn_only: Option<bool>,
+}
+
+#[derive(Clone, Copy)]
+enum GatewayFilter {
+    Any,
+    Entry,
+    Exit,
+}
+
+impl GatewayFilter {
+    fn matches(self, gateway: &NymDirectoryGateway) -> bool {
+        match self {
+            GatewayFilter::Any | GatewayFilter::Entry => true,
+            GatewayFilter::Exit => gateway.ip_packet_router.is_some(),
+        }
+    }
+}
+
+async fn filter_gateways(
+    state: &SharedMockState,
+    filter: GatewayFilter,
+    query: &GatewaysQuery,
+) -> Vec<NymDirectoryGateway> {
+    let vpn_only = query.show_vpn_only.unwrap_or(false);
+    state
+        .lock()
+        .await
+        .gateways
+        .iter()
+        .filter(|gateway| filter.matches(gateway))
+        .filter(|gateway| !vpn_only || gateway.authenticator.is_some())
+        .cloned()
+        .collect()
+}
+
+async fn filter_countries(
+    state: &SharedMockState,
+    filter: GatewayFilter,
+    query: &GatewaysQuery,
+) -> Vec<String> {
+    filter_gateways(state, filter, query)
+        .await
+        .into_iter()
+        .map(|gateway| gateway.location.two_letter_iso_country_code)
+        .unique()
+        .sorted()
+        .collect()
+}
+
+pub(crate) async fn get_gateways(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<NymDirectoryGateway>> {
+    Json(filter_gateways(&state, GatewayFilter::Any, &query).await)
+}
+
+pub(crate) async fn get_gateway_countries(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<String>> {
+    Json(filter_countries(&state, GatewayFilter::Any, &query).await)
+}
+
+pub(crate) async fn get_entry_gateways(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<NymDirectoryGateway>> {
+    Json(filter_gateways(&state, GatewayFilter::Entry, &query).await)
+}
+
+pub(crate) async fn get_entry_gateway_countries(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<String>> {
+    Json(filter_countries(&state, GatewayFilter::Entry, &query).await)
+}
+
+pub(crate) async fn get_exit_gateways(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<NymDirectoryGateway>> {
+    Json(filter_gateways(&state, GatewayFilter::Exit, &query).await)
+}
+
+pub(crate) async fn get_exit_gateway_countries(
+    State(state): State<SharedMockState>,
+    Query(query): Query<GatewaysQuery>,
+) -> Json<Vec<String>> {
+    Json(filter_countries(&state, GatewayFilter::Exit, &query).await)
+}
+
+// WELL-KNOWN
+
+pub(crate) async fn get_envs(State(state): State<SharedMockState>) -> Json<Vec<String>> {
+    Json(vec![state.lock().await.network_name.clone()])
+}
+
+pub(crate) async fn get_discovery(
+    State(state): State<SharedMockState>,
+    Path(network_name): Path<String>,
+) -> ApiResult<NymWellknownDiscoveryItemResponse> {
+    let state = state.lock().await;
+    if network_name != state.network_name {
+        return Err(ApiError::network_not_found(&network_name));
+    }
+    Ok(Json(NymWellknownDiscoveryItemResponse {
+        network_name: state.network_name.clone(),
+        nym_api_url: state.nym_api_url.to_string(),
+        nym_vpn_api_url: state.url.to_string(),
+        account_management: None,
+        feature_flags: None,
+        system_messages: None,
+        system_configuration: None,
+        network_compatibility: None,
+    }))
+}
+
+pub(crate) async fn get_current_env(
+    State(state): State<SharedMockState>,
+) -> Json<NymWellknownDiscoveryItem> {
+    let state = state.lock().await;
+    Json(NymWellknownDiscoveryItem {
+        network_name: state.network_name.clone(),
+        nym_api_url: state.nym_api_url.to_string(),
+        nym_vpn_api_url: state.url.to_string(),
+    })
+}
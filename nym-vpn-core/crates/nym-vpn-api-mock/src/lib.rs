@@ -0,0 +1,78 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// In-process mock of the nym-vpn-api, used to exercise the account controller and the other
+// nym-vpn-api consumers in integration tests without a real deployment. The routes and response
+// types come from nym-vpn-api-client.
+//
+// The mock does not run the ecash issuance protocol: requested zk-nyms are recorded and stay
+// pending without any blinded shares attached.
+
+mod error;
+mod handlers;
+mod request;
+mod server;
+mod state;
+
+pub use error::Error;
+pub use server::{MockVpnApi, MockVpnApiHandle};
+
+#[cfg(test)]
+mod tests {
+    use nym_http_api_client::UserAgent;
+    use nym_vpn_api_client::{BootstrapVpnApiClient, VpnApiClient};
+
+    use super::*;
+
+    fn user_agent() -> UserAgent {
+        UserAgent {
+            version: "0.1.0".to_string(),
+            application: "nym-vpn-api-mock".to_string(),
+            platform: "test".to_string(),
+            git_commit: "0000000".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_wellknown_discovery() {
+        let mock = MockVpnApi::new()
+            .with_network_name("mocknet")
+            .spawn()
+            .await
+            .unwrap();
+
+        let client = BootstrapVpnApiClient::new(mock.url()).unwrap();
+        let envs = client.get_wellknown_envs().await.unwrap();
+        assert!(envs.contains("mocknet"));
+
+        let discovery = client.get_wellknown_discovery("mocknet").await.unwrap();
+        assert_eq!(discovery.network_name, "mocknet");
+        assert_eq!(discovery.nym_vpn_api_url, mock.url().to_string());
+
+        assert!(client.get_wellknown_discovery("mainnet").await.is_err());
+
+        mock.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_health_and_empty_directory() {
+        let mock = MockVpnApi::new().spawn().await.unwrap();
+        let client = VpnApiClient::new(mock.url(), user_agent()).unwrap();
+
+        assert_eq!(client.get_health().await.unwrap().status, "up");
+        assert!(client
+            .get_gateways(None)
+            .await
+            .unwrap()
+            .into_inner()
+            .is_empty());
+        assert!(client
+            .get_exit_gateway_countries(None)
+            .await
+            .unwrap()
+            .into_inner()
+            .is_empty());
+
+        mock.shutdown().await.unwrap();
+    }
+}
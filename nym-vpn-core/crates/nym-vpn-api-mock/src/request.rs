@@ -0,0 +1,33 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// The request bodies the mock accepts. The client's own request types are private to
+// nym-vpn-api-client, so these mirror the fields the mock needs.
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegisterDeviceRequestBody {
+    pub(crate) device_identity_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateDeviceRequestBody {
+    pub(crate) status: UpdateDeviceRequestStatus,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UpdateDeviceRequestStatus {
+    Active,
+    Inactive,
+    DeleteMe,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestZkNymRequestBody {
+    pub(crate) ticketbook_type: String,
+}
@@ -0,0 +1,334 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::HashMap, net::Ipv4Addr, sync::Arc};
+
+use axum::{routing::get, Router};
+use itertools::Itertools;
+use nym_vpn_api_client::{
+    response::{NymDirectoryGateway, NymVpnDevice, NymVpnZkNym},
+    routes,
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, DropGuard};
+use url::Url;
+
+use crate::{
+    error::Error,
+    handlers,
+    state::{MockAccount, MockState, SharedMockState},
+};
+
+const DEFAULT_NETWORK_NAME: &str = "mock";
+const DEFAULT_MAX_DEVICES: u64 = 10;
+
+// Path parameters, extracted by the handlers
+const ACCOUNT_ID: &str = ":account_id";
+const DEVICE_ID: &str = ":device_id";
+const ZK_NYM_ID: &str = ":zk_nym_id";
+const NETWORK_NAME: &str = ":network_name";
+
+// Builder for an in-process nym-vpn-api. Accounts added here are active and have an active
+// subscription.
+pub struct MockVpnApi {
+    network_name: String,
+    nym_api_url: Option<Url>,
+    max_devices: u64,
+    accounts: HashMap<String, MockAccount>,
+    gateways: Vec<NymDirectoryGateway>,
+}
+
+impl Default for MockVpnApi {
+    fn default() -> Self {
+        MockVpnApi {
+            network_name: DEFAULT_NETWORK_NAME.to_string(),
+            nym_api_url: None,
+            max_devices: DEFAULT_MAX_DEVICES,
+            accounts: HashMap::new(),
+            gateways: Vec::new(),
+        }
+    }
+}
+
+impl MockVpnApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The network name served by the well-known endpoints
+    pub fn with_network_name(mut self, network_name: impl Into<String>) -> Self {
+        self.network_name = network_name.into();
+        self
+    }
+
+    // The nym-api url advertised by the well-known endpoints. Defaults to the url of the mock
+    // itself.
+    pub fn with_nym_api_url(mut self, nym_api_url: Url) -> Self {
+        self.nym_api_url = Some(nym_api_url);
+        self
+    }
+
+    pub fn with_max_devices(mut self, max_devices: u64) -> Self {
+        self.max_devices = max_devices;
+        self
+    }
+
+    pub fn with_account(mut self, account_id: impl Into<String>) -> Self {
+        let account_id = account_id.into();
+        self.accounts
+            .entry(account_id.clone())
+            .or_insert_with(|| MockAccount::new(&account_id));
+        self
+    }
+
+    // Add an already registered, active device to the account. The device limit is not enforced
+    // for devices added this way.
+    pub fn with_device(
+        mut self,
+        account_id: impl Into<String>,
+        device_identity_key: impl AsRef<str>,
+    ) -> Self {
+        let account_id = account_id.into();
+        self.accounts
+            .entry(account_id.clone())
+            .or_insert_with(|| MockAccount::new(&account_id))
+            .insert_device(device_identity_key.as_ref());
+        self
+    }
+
+    pub fn with_gateways(mut self, gateways: Vec<NymDirectoryGateway>) -> Self {
+        self.gateways = gateways;
+        self
+    }
+
+    // Start serving on an ephemeral port on localhost
+    pub async fn spawn(self) -> Result<MockVpnApiHandle, Error> {
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .map_err(Error::Bind)?;
+        let addr = listener.local_addr().map_err(Error::LocalAddr)?;
+        let url = Url::parse(&format!("http://{addr}/")).map_err(Error::Url)?;
+
+        let state = Arc::new(tokio::sync::Mutex::new(MockState::new(
+            self.network_name,
+            url.clone(),
+            self.nym_api_url.unwrap_or_else(|| url.clone()),
+            self.max_devices,
+            self.accounts,
+            self.gateways,
+        )));
+
+        let cancel_token = CancellationToken::new();
+        let server = axum::serve(listener, router(state.clone()))
+            .with_graceful_shutdown(cancel_token.clone().cancelled_owned());
+        let server_handle = tokio::spawn(async move { server.await.map_err(Error::Serve) });
+
+        tracing::info!("Mock nym-vpn-api listening on {url}");
+
+        Ok(MockVpnApiHandle {
+            url,
+            state,
+            cancel_token: cancel_token.clone(),
+            server_handle,
+            _drop_guard: cancel_token.drop_guard(),
+        })
+    }
+}
+
+// Handle to a running mock. The server is stopped when the handle is dropped.
+pub struct MockVpnApiHandle {
+    url: Url,
+    state: SharedMockState,
+    cancel_token: CancellationToken,
+    server_handle: JoinHandle<Result<(), Error>>,
+    _drop_guard: DropGuard,
+}
+
+impl MockVpnApiHandle {
+    // The base url to point the nym-vpn-api client at
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    pub async fn add_account(&self, account_id: impl Into<String>) {
+        let account_id = account_id.into();
+        self.state
+            .lock()
+            .await
+            .accounts
+            .entry(account_id.clone())
+            .or_insert_with(|| MockAccount::new(&account_id));
+    }
+
+    pub async fn set_max_devices(&self, max_devices: u64) {
+        self.state.lock().await.max_devices = max_devices;
+    }
+
+    // The devices the mock has registered for the account
+    pub async fn devices(&self, account_id: &str) -> Vec<NymVpnDevice> {
+        self.state
+            .lock()
+            .await
+            .accounts
+            .get(account_id)
+            .map(|account| account.devices.clone())
+            .unwrap_or_default()
+    }
+
+    // The zk-nyms that have been requested for the account, across all devices
+    pub async fn zk_nyms(&self, account_id: &str) -> Vec<NymVpnZkNym> {
+        self.state
+            .lock()
+            .await
+            .accounts
+            .get(account_id)
+            .map(|account| {
+                account
+                    .zk_nyms
+                    .iter()
+                    .map(|zk_nym| zk_nym.zk_nym.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub async fn shutdown(self) -> Result<(), Error> {
+        self.cancel_token.cancel();
+        self.server_handle.await.map_err(Error::Join)?
+    }
+}
+
+// Build an axum route from the path segments the client uses
+fn route(segments: &[&str]) -> String {
+    std::iter::once("")
+        .chain([routes::PUBLIC, routes::V1])
+        .chain(segments.iter().copied())
+        .join("/")
+}
+
+fn router(state: SharedMockState) -> Router {
+    Router::new()
+        .route(&route(&[routes::HEALTH]), get(handlers::get_health))
+        // ACCOUNT
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID]),
+            get(handlers::get_account),
+        )
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::SUMMARY]),
+            get(handlers::get_account_summary),
+        )
+        // DEVICES
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::DEVICE]),
+            get(handlers::get_devices).post(handlers::register_device),
+        )
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::DEVICE, routes::ACTIVE]),
+            get(handlers::get_active_devices),
+        )
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::DEVICE, DEVICE_ID]),
+            get(handlers::get_device).patch(handlers::update_device),
+        )
+        // ZK-NYM
+        .route(
+            &route(&[
+                routes::ACCOUNT,
+                ACCOUNT_ID,
+                routes::DEVICE,
+                DEVICE_ID,
+                routes::ZKNYM,
+            ]),
+            get(handlers::get_device_zk_nyms).post(handlers::request_zk_nym),
+        )
+        .route(
+            &route(&[
+                routes::ACCOUNT,
+                ACCOUNT_ID,
+                routes::DEVICE,
+                DEVICE_ID,
+                routes::ZKNYM,
+                routes::AVAILABLE,
+            ]),
+            get(handlers::get_available_zk_nyms),
+        )
+        .route(
+            &route(&[
+                routes::ACCOUNT,
+                ACCOUNT_ID,
+                routes::DEVICE,
+                DEVICE_ID,
+                routes::ZKNYM,
+                ZK_NYM_ID,
+            ]),
+            get(handlers::get_zk_nym).delete(handlers::confirm_zk_nym_download),
+        )
+        // SUBSCRIPTIONS
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::SUBSCRIPTION]),
+            get(handlers::get_subscriptions),
+        )
+        .route(
+            &route(&[
+                routes::ACCOUNT,
+                ACCOUNT_ID,
+                routes::SUBSCRIPTION,
+                routes::ACTIVE,
+            ]),
+            get(handlers::get_active_subscription),
+        )
+        .route(
+            &route(&[routes::ACCOUNT, ACCOUNT_ID, routes::USAGE]),
+            get(handlers::get_usage),
+        )
+        // GATEWAYS
+        .route(
+            &route(&[routes::DIRECTORY, routes::GATEWAYS]),
+            get(handlers::get_gateways),
+        )
+        .route(
+            &route(&[routes::DIRECTORY, routes::GATEWAYS, routes::COUNTRIES]),
+            get(handlers::get_gateway_countries),
+        )
+        .route(
+            &route(&[routes::DIRECTORY, routes::GATEWAYS, routes::ENTRY]),
+            get(handlers::get_entry_gateways),
+        )
+        .route(
+            &route(&[
+                routes::DIRECTORY,
+                routes::GATEWAYS,
+                routes::ENTRY,
+                routes::COUNTRIES,
+            ]),
+            get(handlers::get_entry_gateway_countries),
+        )
+        .route(
+            &route(&[routes::DIRECTORY, routes::GATEWAYS, routes::EXIT]),
+            get(handlers::get_exit_gateways),
+        )
+        .route(
+            &route(&[
+                routes::DIRECTORY,
+                routes::GATEWAYS,
+                routes::EXIT,
+                routes::COUNTRIES,
+            ]),
+            get(handlers::get_exit_gateway_countries),
+        )
+        // WELL-KNOWN
+        .route(
+            &route(&[routes::WELLKNOWN, routes::ENVS_FILE]),
+            get(handlers::get_envs),
+        )
+        .route(
+            &route(&[routes::WELLKNOWN, routes::CURRENT_ENV]),
+            get(handlers::get_current_env),
+        )
+        .route(
+            &route(&[routes::WELLKNOWN, NETWORK_NAME, routes::DISCOVERY_FILE]),
+            get(handlers::get_discovery),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,308 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::HashMap, sync::Arc};
+
+use nym_vpn_api_client::response::{
+    NymDirectoryGateway, NymVpnAccountResponse, NymVpnAccountStatusResponse,
+    NymVpnAccountSummaryDevices, NymVpnAccountSummaryFairUsage, NymVpnAccountSummaryResponse,
+    NymVpnAccountSummarySubscription, NymVpnDevice, NymVpnDeviceStatus, NymVpnSubscription,
+    NymVpnSubscriptionKind, NymVpnSubscriptionStatus, NymVpnZkNym, NymVpnZkNymStatus,
+};
+use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+use url::Url;
+
+use crate::{
+    handlers::ApiError,
+    request::{RequestZkNymRequestBody, UpdateDeviceRequestStatus},
+};
+
+// The bandwidth we pretend to issue for every zk-nym request
+const ISSUED_BANDWIDTH_IN_GB: f64 = 25.0;
+
+// How long issued zk-nyms and subscriptions are valid for
+const VALIDITY: Duration = Duration::days(30);
+
+pub(crate) type SharedMockState = Arc<tokio::sync::Mutex<MockState>>;
+
+pub(crate) struct MockState {
+    pub(crate) network_name: String,
+    pub(crate) url: Url,
+    pub(crate) nym_api_url: Url,
+    pub(crate) max_devices: u64,
+    pub(crate) accounts: HashMap<String, MockAccount>,
+    pub(crate) gateways: Vec<NymDirectoryGateway>,
+    next_zk_nym_id: u64,
+}
+
+impl MockState {
+    pub(crate) fn new(
+        network_name: String,
+        url: Url,
+        nym_api_url: Url,
+        max_devices: u64,
+        accounts: HashMap<String, MockAccount>,
+        gateways: Vec<NymDirectoryGateway>,
+    ) -> Self {
+        MockState {
+            network_name,
+            url,
+            nym_api_url,
+            max_devices,
+            accounts,
+            gateways,
+            next_zk_nym_id: 0,
+        }
+    }
+
+    pub(crate) fn account(&self, account_id: &str) -> Result<&MockAccount, ApiError> {
+        self.accounts
+            .get(account_id)
+            .ok_or_else(|| ApiError::account_not_found(account_id))
+    }
+
+    pub(crate) fn account_mut(&mut self, account_id: &str) -> Result<&mut MockAccount, ApiError> {
+        self.accounts
+            .get_mut(account_id)
+            .ok_or_else(|| ApiError::account_not_found(account_id))
+    }
+
+    pub(crate) fn summary(
+        &self,
+        account_id: &str,
+    ) -> Result<NymVpnAccountSummaryResponse, ApiError> {
+        let account = self.account(account_id)?;
+        let active = account.active_devices();
+        Ok(NymVpnAccountSummaryResponse {
+            account: account.account.clone(),
+            subscription: NymVpnAccountSummarySubscription {
+                is_active: account.subscription.is_some(),
+                active: account.subscription.clone(),
+            },
+            devices: NymVpnAccountSummaryDevices {
+                active,
+                max: self.max_devices,
+                remaining: self.max_devices.saturating_sub(active),
+            },
+            fair_usage: NymVpnAccountSummaryFairUsage {
+                usedGB: 0,
+                limitGB: 0,
+                resetsOnUtc: None,
+            },
+        })
+    }
+
+    pub(crate) fn register_device(
+        &mut self,
+        account_id: &str,
+        device_identity_key: &str,
+    ) -> Result<NymVpnDevice, ApiError> {
+        let max_devices = self.max_devices;
+        self.account_mut(account_id)?
+            .register_device(device_identity_key, max_devices)
+    }
+
+    pub(crate) fn request_zk_nym(
+        &mut self,
+        account_id: &str,
+        device_identity_key: &str,
+        request: RequestZkNymRequestBody,
+    ) -> Result<NymVpnZkNym, ApiError> {
+        let id = format!("mock-zk-nym-{}", self.next_zk_nym_id);
+        let account = self.account_mut(account_id)?;
+        account.device(device_identity_key)?;
+        if account.subscription.is_none() {
+            return Err(ApiError::no_active_subscription(account_id));
+        }
+
+        let zk_nym = MockZkNym::new(id, device_identity_key, request.ticketbook_type);
+        account.zk_nyms.push(zk_nym.clone());
+        self.next_zk_nym_id += 1;
+        Ok(zk_nym.zk_nym)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct MockAccount {
+    pub(crate) account: NymVpnAccountResponse,
+    pub(crate) subscription: Option<NymVpnSubscription>,
+    pub(crate) devices: Vec<NymVpnDevice>,
+    pub(crate) zk_nyms: Vec<MockZkNym>,
+}
+
+impl MockAccount {
+    // An active account with an active subscription and no devices
+    pub(crate) fn new(account_id: &str) -> Self {
+        let now = now_utc();
+        MockAccount {
+            account: NymVpnAccountResponse {
+                created_on_utc: now.clone(),
+                last_updated_utc: now.clone(),
+                account_addr: account_id.to_string(),
+                status: NymVpnAccountStatusResponse::Active,
+            },
+            subscription: Some(NymVpnSubscription {
+                created_on_utc: now.clone(),
+                last_updated_utc: now.clone(),
+                id: format!("mock-subscription-{account_id}"),
+                valid_until_utc: valid_until_utc(),
+                valid_from_utc: now,
+                status: NymVpnSubscriptionStatus::Active,
+                kind: NymVpnSubscriptionKind::OneMonth,
+            }),
+            devices: Vec::new(),
+            zk_nyms: Vec::new(),
+        }
+    }
+
+    pub(crate) fn active_devices(&self) -> u64 {
+        self.devices
+            .iter()
+            .filter(|device| device.status == NymVpnDeviceStatus::Active)
+            .count() as u64
+    }
+
+    pub(crate) fn device(&self, device_identity_key: &str) -> Result<&NymVpnDevice, ApiError> {
+        self.devices
+            .iter()
+            .find(|device| device.device_identity_key == device_identity_key)
+            .ok_or_else(|| ApiError::device_not_found(device_identity_key))
+    }
+
+    pub(crate) fn register_device(
+        &mut self,
+        device_identity_key: &str,
+        max_devices: u64,
+    ) -> Result<NymVpnDevice, ApiError> {
+        let active_devices = self.active_devices();
+        let at_capacity = active_devices >= max_devices;
+
+        // Registering an already known device re-activates it
+        if let Some(device) = self
+            .devices
+            .iter_mut()
+            .find(|device| device.device_identity_key == device_identity_key)
+        {
+            if device.status != NymVpnDeviceStatus::Active {
+                if at_capacity {
+                    return Err(ApiError::max_devices_reached(max_devices));
+                }
+                device.status = NymVpnDeviceStatus::Active;
+                device.last_updated_utc = now_utc();
+            }
+            return Ok(device.clone());
+        }
+
+        if at_capacity {
+            return Err(ApiError::max_devices_reached(max_devices));
+        }
+
+        Ok(self.insert_device(device_identity_key))
+    }
+
+    // Add an active device without checking the device limit
+    pub(crate) fn insert_device(&mut self, device_identity_key: &str) -> NymVpnDevice {
+        let now = now_utc();
+        let device = NymVpnDevice {
+            created_on_utc: now.clone(),
+            last_updated_utc: now,
+            device_identity_key: device_identity_key.to_string(),
+            status: NymVpnDeviceStatus::Active,
+        };
+        self.devices.push(device.clone());
+        device
+    }
+
+    pub(crate) fn update_device(
+        &mut self,
+        device_identity_key: &str,
+        status: UpdateDeviceRequestStatus,
+    ) -> Result<NymVpnDevice, ApiError> {
+        let device = self
+            .devices
+            .iter_mut()
+            .find(|device| device.device_identity_key == device_identity_key)
+            .ok_or_else(|| ApiError::device_not_found(device_identity_key))?;
+
+        device.status = match status {
+            UpdateDeviceRequestStatus::Active => NymVpnDeviceStatus::Active,
+            UpdateDeviceRequestStatus::Inactive => NymVpnDeviceStatus::Inactive,
+            UpdateDeviceRequestStatus::DeleteMe => NymVpnDeviceStatus::DeleteMe,
+        };
+        device.last_updated_utc = now_utc();
+        Ok(device.clone())
+    }
+
+    pub(crate) fn device_zk_nyms(&self, device_identity_key: &str) -> Vec<NymVpnZkNym> {
+        self.zk_nyms
+            .iter()
+            .filter(|zk_nym| zk_nym.device_identity_key == device_identity_key)
+            .map(|zk_nym| zk_nym.zk_nym.clone())
+            .collect()
+    }
+
+    pub(crate) fn available_zk_nyms(&self, device_identity_key: &str) -> Vec<NymVpnZkNym> {
+        self.zk_nyms
+            .iter()
+            .filter(|zk_nym| {
+                zk_nym.device_identity_key == device_identity_key && !zk_nym.downloaded
+            })
+            .map(|zk_nym| zk_nym.zk_nym.clone())
+            .collect()
+    }
+
+    pub(crate) fn zk_nym_mut(
+        &mut self,
+        device_identity_key: &str,
+        id: &str,
+    ) -> Result<&mut MockZkNym, ApiError> {
+        self.zk_nyms
+            .iter_mut()
+            .find(|zk_nym| {
+                zk_nym.device_identity_key == device_identity_key && zk_nym.zk_nym.id == id
+            })
+            .ok_or_else(|| ApiError::zk_nym_not_found(id))
+    }
+}
+
+// The mock can't run the ecash issuance protocol, so requested zk-nyms stay pending without any
+// blinded shares attached.
+#[derive(Clone)]
+pub(crate) struct MockZkNym {
+    pub(crate) device_identity_key: String,
+    pub(crate) zk_nym: NymVpnZkNym,
+    pub(crate) downloaded: bool,
+}
+
+impl MockZkNym {
+    fn new(id: String, device_identity_key: &str, ticketbook_type: String) -> Self {
+        let now = now_utc();
+        MockZkNym {
+            device_identity_key: device_identity_key.to_string(),
+            zk_nym: NymVpnZkNym {
+                created_on_utc: now.clone(),
+                last_updated_utc: now.clone(),
+                id,
+                ticketbook_type,
+                valid_until_utc: valid_until_utc(),
+                valid_from_utc: now,
+                issued_bandwidth_in_gb: ISSUED_BANDWIDTH_IN_GB,
+                blinded_shares: None,
+                status: NymVpnZkNymStatus::Pending,
+            },
+            downloaded: false,
+        }
+    }
+}
+
+fn now_utc() -> String {
+    format_utc(OffsetDateTime::now_utc())
+}
+
+fn valid_until_utc() -> String {
+    format_utc(OffsetDateTime::now_utc() + VALIDITY)
+}
+
+fn format_utc(time: OffsetDateTime) -> String {
+    time.format(&Rfc3339).unwrap_or_default()
+}
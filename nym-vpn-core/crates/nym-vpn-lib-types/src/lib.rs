@@ -22,8 +22,8 @@ pub use connection_data::{
     WireguardConnectionData, WireguardNode,
 };
 pub use tunnel_event::{
-    BandwidthEvent, ConnectionEvent, ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates,
-    TunnelEvent,
+    BandwidthEvent, BandwidthForecast, BandwidthGateway, ConnectionEvent,
+    ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates, TunnelEvent,
 };
 pub use tunnel_state::{
     ActionAfterDisconnect, ClientErrorReason, ErrorStateReason, TunnelState, TunnelType,
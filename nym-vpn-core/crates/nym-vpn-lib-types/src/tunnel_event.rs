@@ -1,7 +1,7 @@
 // Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use si_scale::helpers::bibytes2;
 
@@ -52,6 +52,7 @@ impl fmt::Display for MixnetEvent {
 pub enum BandwidthEvent {
     NoBandwidth,
     RemainingBandwidth(i64),
+    DepletionForecast(BandwidthForecast),
 }
 
 impl fmt::Display for BandwidthEvent {
@@ -65,6 +66,52 @@ impl fmt::Display for BandwidthEvent {
                     si_scale::helpers::bibytes2(*value as f64)
                 )
             }
+            Self::DepletionForecast(forecast) => forecast.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BandwidthGateway {
+    Entry,
+    Exit,
+}
+
+impl fmt::Display for BandwidthGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Entry => f.write_str("entry"),
+            Self::Exit => f.write_str("exit"),
+        }
+    }
+}
+
+// Forecast of when the bandwidth allowance with a gateway runs out, based on the rate it was
+// consumed at since the previous check
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BandwidthForecast {
+    pub gateway: BandwidthGateway,
+    pub remaining_bandwidth: i64,
+    // Bytes per second
+    pub depletion_rate: u64,
+    // None if nothing is being consumed
+    pub time_to_exhaustion: Option<Duration>,
+}
+
+impl fmt::Display for BandwidthForecast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bandwidth forecast ({}): {} remaining at {}/s",
+            self.gateway,
+            bibytes2(self.remaining_bandwidth as f64),
+            bibytes2(self.depletion_rate as f64),
+        )?;
+        match self.time_to_exhaustion {
+            Some(time_to_exhaustion) => {
+                write!(f, ", exhausted in {}s", time_to_exhaustion.as_secs())
+            }
+            None => f.write_str(", not depleting"),
         }
     }
 }
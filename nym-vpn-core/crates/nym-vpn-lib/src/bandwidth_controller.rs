@@ -3,7 +3,10 @@
 
 use std::time::Duration;
 
+use nym_vpn_account_controller::{AccountControllerCommander, CredentialSpend};
+use nym_vpn_lib_types::{BandwidthForecast, BandwidthGateway};
 use nym_vpn_network_config::Network;
+use tokio::task::JoinHandle;
 use tokio_stream::{wrappers::IntervalStream, StreamExt};
 
 use nym_credentials_interface::TicketType;
@@ -28,6 +31,17 @@ const UPPER_BOUND_CHECK_DURATION: Duration =
 const DEFAULT_BANDWIDTH_DEPLETION_RATE: u64 = 1024 * 1024; // 1 MB/s
 const MINIMUM_RAMAINING_BANDWIDTH: u64 = 500 * 1024 * 1024; // 500 MB, the same as a wireguard ticket size (but it doesn't have to be)
 
+// Top up when the forecast exhaustion is closer than this plus the current check period, so the
+// top-up has time to go through before we run out
+const TOP_UP_LEAD_TIME: Duration = Duration::from_secs(60);
+// Start requesting new zk-nyms when the forecast exhaustion is closer than this, since getting
+// them issued can take a while
+const ZK_NYM_PREFETCH_HORIZON: Duration = Duration::from_secs(15 * 60);
+// Forecasts are only reported when the time to exhaustion crosses one of these, or when the
+// forecast changed by more than this percentage since the last report
+const FORECAST_THRESHOLDS: [Duration; 2] = [ZK_NYM_PREFETCH_HORIZON, TOP_UP_LEAD_TIME];
+const FORECAST_MATERIAL_CHANGE_PERCENT: u64 = 25;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to lookup gateway ip: {source}")]
@@ -82,19 +96,52 @@ fn get_nyxd_client(network: &Network) -> Result<QueryHttpRpcNyxdClient> {
 
 pub(crate) struct DepletionRate {
     current_depletion_rate: u64,
+    // The depletion rate over the last check period, which unlike the current depletion rate can
+    // be zero
+    measured_depletion_rate: u64,
     available_bandwidth: u64,
+    last_reported_forecast: Option<BandwidthForecast>,
 }
 
 impl Default for DepletionRate {
     fn default() -> Self {
         Self {
             current_depletion_rate: DEFAULT_BANDWIDTH_DEPLETION_RATE,
+            measured_depletion_rate: 0,
             available_bandwidth: 0,
+            last_reported_forecast: None,
         }
     }
 }
 
 impl DepletionRate {
+    fn time_to_exhaustion(&self) -> Option<Duration> {
+        self.available_bandwidth
+            .checked_div(self.measured_depletion_rate)
+            .map(Duration::from_secs)
+    }
+
+    fn forecast(&self, gateway: BandwidthGateway) -> BandwidthForecast {
+        BandwidthForecast {
+            gateway,
+            remaining_bandwidth: i64::try_from(self.available_bandwidth).unwrap_or(i64::MAX),
+            depletion_rate: self.measured_depletion_rate,
+            time_to_exhaustion: self.time_to_exhaustion(),
+        }
+    }
+
+    // Remember the forecast if it's worth reporting
+    fn should_report_forecast(&mut self, forecast: &BandwidthForecast) -> bool {
+        let should_report = self
+            .last_reported_forecast
+            .as_ref()
+            .is_none_or(|last| is_material_forecast_change(last, forecast));
+        if should_report {
+            self.last_reported_forecast = Some(*forecast);
+        }
+        should_report
+    }
+
     fn update_dynamic_check_interval(
         &mut self,
         current_period: Duration,
@@ -115,6 +162,7 @@ impl DepletionRate {
             current_period.as_secs()
         );
         self.available_bandwidth = remaining_bandwidth;
+        self.measured_depletion_rate = new_depletion_rate;
         // if nothing was consumed since last time, it's possible we had a recent topup already,
         // so take the safer approach of waiting minimal interval
         if new_depletion_rate != 0 {
@@ -134,6 +182,11 @@ impl DepletionRate {
             estimated_depletion_secs
         );
 
+        // top up ahead of time if we're forecast to run out before a later check could react
+        if estimated_depletion_secs < TOP_UP_LEAD_TIME.as_secs() + current_period.as_secs() {
+            return Ok(None);
+        }
+
         let number_of_checks_before_depletion = estimated_depletion_secs
             .checked_div(current_period.as_secs())
             .unwrap_or_default();
@@ -164,6 +217,9 @@ pub(crate) struct BandwidthController<St> {
     timeout_check_interval: IntervalStream,
     entry_depletion_rate: DepletionRate,
     exit_depletion_rate: DepletionRate,
+    account_controller_tx: AccountControllerCommander,
+    enable_credentials_mode: bool,
+    zk_nym_prefetch: Option<JoinHandle<()>>,
     shutdown: TaskClient,
 }
 
@@ -173,6 +229,8 @@ impl<St: Storage> BandwidthController<St> {
         network: &Network,
         wg_entry_gateway_client: WgGatewayLightClient,
         wg_exit_gateway_client: WgGatewayLightClient,
+        account_controller_tx: AccountControllerCommander,
+        enable_credentials_mode: bool,
         shutdown: TaskClient,
     ) -> Result<Self> {
         let client = get_nyxd_client(network)?;
@@ -187,10 +245,31 @@ impl<St: Storage> BandwidthController<St> {
             timeout_check_interval,
             entry_depletion_rate: Default::default(),
             exit_depletion_rate: Default::default(),
+            account_controller_tx,
+            enable_credentials_mode,
+            zk_nym_prefetch: None,
             shutdown,
         })
     }
 
+    fn record_spend_failure(
+        &self,
+        ticketbook_type: TicketType,
+        gateway_id: String,
+        error: &nym_wg_gateway_client::Error,
+    ) {
+        if error.is_ticket_spent() {
+            self.account_controller_tx
+                .record_credential_spend(CredentialSpend::failure(
+                    ticketbook_type,
+                    gateway_id,
+                    TICKETS_TO_SPEND,
+                    error.is_wasteful(),
+                    error,
+                ));
+        }
+    }
+
     pub(crate) async fn get_initial_bandwidth(
         &self,
         enable_credentials_mode: bool,
@@ -221,12 +300,27 @@ impl<St: Storage> BandwidthController<St> {
                 ticketbook_type,
             )
             .await
+            .inspect_err(|err| {
+                // Registration errors that don't involve the credential are not spends
+                if enable_credentials_mode && err.is_wasteful() {
+                    self.record_spend_failure(ticketbook_type, gateway_id.to_base58_string(), err);
+                }
+            })
             .map_err(|source| Error::RegisterWireguard {
                 gateway_id: gateway_id.to_base58_string(),
                 authenticator_address: Box::new(authenticator_address),
                 source,
             })?;
         tracing::debug!("Received wireguard gateway data: {wg_gateway_data:?}");
+        if enable_credentials_mode {
+            self.account_controller_tx
+                .record_credential_spend(CredentialSpend::success(
+                    ticketbook_type,
+                    gateway_id.to_base58_string(),
+                    TICKETS_TO_SPEND,
+                    None,
+                ));
+        }
 
         Ok(wg_gateway_data)
     }
@@ -244,6 +338,9 @@ impl<St: Storage> BandwidthController<St> {
         let remaining_bandwidth =
             WgGatewayClient::top_up_wireguard(wg_gateway_client, &self.inner, ticketbook_type)
                 .await
+                .inspect_err(|err| {
+                    self.record_spend_failure(ticketbook_type, gateway_id.to_base58_string(), err)
+                })
                 .map_err(|source| Error::TopUpWireguard {
                     gateway_id: gateway_id.to_string(),
                     ticketbook_type,
@@ -257,70 +354,119 @@ impl<St: Storage> BandwidthController<St> {
             }
             .into(),
         );
+        self.account_controller_tx
+            .record_credential_spend(CredentialSpend::success(
+                ticketbook_type,
+                gateway_id.to_base58_string(),
+                TICKETS_TO_SPEND,
+                Some(remaining_bandwidth),
+            ));
         Ok(remaining_bandwidth)
     }
 
+    // Request new zk-nyms in the background, so that the local credential storage doesn't run
+    // dry while we keep topping up
+    fn prefetch_zk_nyms(&mut self) {
+        if !self.enable_credentials_mode {
+            return;
+        }
+        if self
+            .zk_nym_prefetch
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return;
+        }
+        let account_controller_tx = self.account_controller_tx.clone();
+        self.zk_nym_prefetch = Some(tokio::spawn(async move {
+            if let Err(err) = account_controller_tx.ensure_available_zk_nyms().await {
+                tracing::warn!("Failed to request zk-nyms ahead of bandwidth exhaustion: {err}");
+            }
+        }));
+    }
+
+    fn report_forecast(&mut self, forecast: BandwidthForecast, should_report: bool) {
+        tracing::debug!("{forecast}");
+        if forecast
+            .time_to_exhaustion
+            .is_some_and(|time_to_exhaustion| time_to_exhaustion < ZK_NYM_PREFETCH_HORIZON)
+        {
+            self.prefetch_zk_nyms();
+        }
+        if should_report {
+            self.shutdown
+                .send_status_msg(Box::new(BandwidthForecastStatus(forecast)));
+        }
+    }
+
     async fn check_bandwidth(&mut self, entry: bool, current_period: Duration) -> Option<Duration>
     where
         <St as Storage>::StorageError: Send + Sync + 'static,
     {
-        let (mut wg_gateway_client, current_depletion_rate) = if entry {
-            (
-                self.wg_entry_gateway_client.clone(),
-                &mut self.entry_depletion_rate,
-            )
+        let mut wg_gateway_client = if entry {
+            self.wg_entry_gateway_client.clone()
         } else {
-            (
-                self.wg_exit_gateway_client.clone(),
-                &mut self.exit_depletion_rate,
-            )
+            self.wg_exit_gateway_client.clone()
         };
 
-        tokio::select! {
+        let ret = tokio::select! {
             _ = self.shutdown.recv() => {
                 tracing::trace!("BandwidthController: Received shutdown");
+                return None;
             }
-            ret = wg_gateway_client.query_bandwidth() => {
-                match ret {
-                    Err(e) => tracing::warn!("Error querying remaining bandwidth {:?}", e),
-                    Ok(Some(remaining_bandwidth)) => {
-                        match current_depletion_rate
-                            .update_dynamic_check_interval(current_period, remaining_bandwidth as u64)
-                        {
-                            Err(e) => tracing::warn!("Error while updating query coefficients: {:?}", e),
-                            Ok(Some(new_duration)) => {
-                                tracing::debug!("Adjusting check interval to {} seconds", new_duration.as_secs());
-                                return Some(new_duration);
-                            }
-                            Ok(None) => {
-                                let ticketbook_type = if entry {
-                                    TicketType::V1WireguardEntry
-                                } else {
-                                    TicketType::V1WireguardExit
-                                };
-                                tracing::debug!("Topping up our bandwidth allowance for {ticketbook_type}");
-                                if let Err(e) = self
-                                    .top_up_bandwidth(ticketbook_type, &mut wg_gateway_client)
-                                    .await
-                                {
-                                    tracing::warn!("Error topping up with more bandwidth {:?}", e);
-                                    // TODO: try to return this error in the JoinHandle instead
-                                    self.shutdown
-                                        .send_we_stopped(Box::new(ErrorMessage::OutOfBandwidth {
-                                            gateway_id: Box::new(
-                                                wg_gateway_client.auth_recipient().gateway(),
-                                            ),
-                                            authenticator_address: Box::new(
-                                                wg_gateway_client.auth_recipient(),
-                                            ),
-                                        }));
-                                }
-                            }
-                        }
-                    }
-                    Ok(None) => {
-                        tracing::info!("Empty query for {} gadeway bandwidth check. This is normal, as long as it is not repeating for the same gateway", if entry {"entry".to_string()} else {"exit".to_string()});
-                    }
+            ret = wg_gateway_client.query_bandwidth() => ret,
+        };
+        let remaining_bandwidth = match ret {
+            Err(e) => {
+                tracing::warn!("Error querying remaining bandwidth {:?}", e);
+                return None;
+            }
+            Ok(None) => {
+                tracing::info!("Empty query for {} gadeway bandwidth check. This is normal, as long as it is not repeating for the same gateway", if entry {"entry".to_string()} else {"exit".to_string()});
+                return None;
+            }
+            Ok(Some(remaining_bandwidth)) => remaining_bandwidth,
+        };
+
+        let (current_depletion_rate, gateway) = if entry {
+            (&mut self.entry_depletion_rate, BandwidthGateway::Entry)
+        } else {
+            (&mut self.exit_depletion_rate, BandwidthGateway::Exit)
+        };
+        let next_check = current_depletion_rate
+            .update_dynamic_check_interval(current_period, remaining_bandwidth as u64);
+        let forecast = current_depletion_rate.forecast(gateway);
+        let should_report = current_depletion_rate.should_report_forecast(&forecast);
+        self.report_forecast(forecast, should_report);
+
+        match next_check {
+            Err(e) => tracing::warn!("Error while updating query coefficients: {:?}", e),
+            Ok(Some(new_duration)) => {
+                tracing::debug!(
+                    "Adjusting check interval to {} seconds",
+                    new_duration.as_secs()
+                );
+                return Some(new_duration);
+            }
+            Ok(None) => {
+                let ticketbook_type = if entry {
+                    TicketType::V1WireguardEntry
+                } else {
+                    TicketType::V1WireguardExit
+                };
+                tracing::debug!("Topping up our bandwidth allowance for {ticketbook_type}");
+                self.prefetch_zk_nyms();
+                if let Err(e) = self
+                    .top_up_bandwidth(ticketbook_type, &mut wg_gateway_client)
+                    .await
+                {
+                    tracing::warn!("Error topping up with more bandwidth {:?}", e);
+                    // TODO: try to return this error in the JoinHandle instead
+                    self.shutdown
+                        .send_we_stopped(Box::new(ErrorMessage::OutOfBandwidth {
+                            gateway_id: Box::new(wg_gateway_client.auth_recipient().gateway()),
+                            authenticator_address: Box::new(wg_gateway_client.auth_recipient()),
+                        }));
                 }
             }
         }
@@ -366,6 +512,45 @@ impl<St: Storage> BandwidthController<St> {
     }
 }
 
+fn is_material_forecast_change(previous: &BandwidthForecast, current: &BandwidthForecast) -> bool {
+    let changed_materially = |previous: u64, current: u64| {
+        previous.abs_diff(current).saturating_mul(100)
+            > previous.saturating_mul(FORECAST_MATERIAL_CHANGE_PERCENT)
+    };
+    let crossed_threshold = FORECAST_THRESHOLDS.iter().any(|threshold| {
+        let below = |forecast: &BandwidthForecast| {
+            forecast
+                .time_to_exhaustion
+                .is_some_and(|time_to_exhaustion| time_to_exhaustion < *threshold)
+        };
+        below(previous) != below(current)
+    });
+
+    crossed_threshold
+        || previous.time_to_exhaustion.is_some() != current.time_to_exhaustion.is_some()
+        || changed_materially(previous.depletion_rate, current.depletion_rate)
+        || changed_materially(
+            previous.remaining_bandwidth.unsigned_abs(),
+            current.remaining_bandwidth.unsigned_abs(),
+        )
+}
+
+// Sent to the tunnel status listener whenever the forecast changes materially
+#[derive(Debug)]
+pub(crate) struct BandwidthForecastStatus(pub(crate) BandwidthForecast);
+
+impl std::fmt::Display for BandwidthForecastStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl nym_task::TaskStatusEvent for BandwidthForecastStatus {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -459,4 +644,100 @@ mod tests {
         assert!(current_bandwidth < 500 * BW_1MB);
         assert!(ret.is_none());
     }
+
+    #[test]
+    fn depletion_rate_tops_up_ahead_of_forecast_exhaustion() {
+        let mut depletion_rate = DepletionRate::default();
+        let current_period = DEFAULT_BANDWIDTH_CHECK;
+        // the first check would force the placeholder values to be replaced by the actual values
+        assert_eq!(
+            depletion_rate
+                .update_dynamic_check_interval(current_period, BW_1GB)
+                .unwrap(),
+            Some(DEFAULT_BANDWIDTH_CHECK)
+        );
+
+        // simulate 16 MB/s depletion rate, which leaves plenty of checks and bandwidth above the
+        // minimum, but would run out less than a minute from now
+        let consumed = current_period.as_secs() * 16 * BW_1MB;
+        assert!(depletion_rate
+            .update_dynamic_check_interval(current_period, BW_1GB - consumed)
+            .unwrap()
+            .is_none());
+
+        let forecast = depletion_rate.forecast(BandwidthGateway::Entry);
+        assert_eq!(forecast.remaining_bandwidth as u64, BW_1GB - consumed);
+        assert_eq!(forecast.depletion_rate, 16 * BW_1MB);
+        assert_eq!(
+            forecast.time_to_exhaustion,
+            Some(Duration::from_secs((BW_1GB - consumed) / (16 * BW_1MB)))
+        );
+    }
+
+    #[test]
+    fn depletion_rate_forecast_idle() {
+        let mut depletion_rate = DepletionRate::default();
+        let current_period = DEFAULT_BANDWIDTH_CHECK;
+        depletion_rate
+            .update_dynamic_check_interval(current_period, BW_512MB)
+            .unwrap();
+        depletion_rate
+            .update_dynamic_check_interval(current_period, BW_512MB)
+            .unwrap();
+
+        let forecast = depletion_rate.forecast(BandwidthGateway::Exit);
+        assert_eq!(forecast.depletion_rate, 0);
+        assert_eq!(forecast.time_to_exhaustion, None);
+    }
+
+    #[test]
+    fn forecast_reported_only_on_material_change() {
+        let mut depletion_rate = DepletionRate::default();
+        let forecast = BandwidthForecast {
+            gateway: BandwidthGateway::Entry,
+            remaining_bandwidth: BW_1GB as i64,
+            depletion_rate: BW_1KB,
+            time_to_exhaustion: Some(Duration::from_secs(BW_1GB / BW_1KB)),
+        };
+        assert!(depletion_rate.should_report_forecast(&forecast));
+        assert!(!depletion_rate.should_report_forecast(&forecast));
+
+        // small fluctuations between checks aren't reported
+        let fluctuation = BandwidthForecast {
+            remaining_bandwidth: forecast.remaining_bandwidth - BW_1MB as i64,
+            depletion_rate: BW_1KB + 100,
+            ..forecast
+        };
+        assert!(!depletion_rate.should_report_forecast(&fluctuation));
+
+        let faster = BandwidthForecast {
+            depletion_rate: 2 * BW_1KB,
+            ..forecast
+        };
+        assert!(depletion_rate.should_report_forecast(&faster));
+
+        let idle = BandwidthForecast {
+            depletion_rate: 0,
+            time_to_exhaustion: None,
+            ..faster
+        };
+        assert!(depletion_rate.should_report_forecast(&idle));
+        assert!(!depletion_rate.should_report_forecast(&idle));
+    }
+
+    #[test]
+    fn forecast_reported_on_threshold_crossing() {
+        let previous = BandwidthForecast {
+            gateway: BandwidthGateway::Exit,
+            remaining_bandwidth: BW_512MB as i64,
+            depletion_rate: BW_1MB,
+            time_to_exhaustion: Some(TOP_UP_LEAD_TIME + Duration::from_secs(1)),
+        };
+        let current = BandwidthForecast {
+            time_to_exhaustion: Some(TOP_UP_LEAD_TIME - Duration::from_secs(1)),
+            ..previous
+        };
+        assert!(is_material_forecast_change(&previous, &current));
+        assert!(!is_material_forecast_change(&current, &current));
+    }
 }
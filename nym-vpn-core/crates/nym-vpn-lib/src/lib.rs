@@ -23,6 +23,8 @@ use hickory_resolver::config::NameServerConfigGroup;
 pub use nym_authenticator_client::Error as AuthenticatorClientError;
 pub use nym_config;
 pub use nym_connection_monitor as connection_monitor;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use nym_dns as dns;
 pub use nym_gateway_directory as gateway_directory;
 pub use nym_ip_packet_requests::IpPair;
 pub use nym_sdk::{
@@ -324,6 +324,8 @@ async fn get_gateway_countries(
         min_gateway_performance,
         mix_score_thresholds,
         wg_score_thresholds,
+        custom_gateways: Vec::new(),
+        harbour_master_url: None,
     };
     GatewayClient::new(directory_config, user_agent.into())
         .map_err(VpnError::internal)?
@@ -377,6 +379,8 @@ async fn get_gateways(
         min_gateway_performance,
         mix_score_thresholds,
         wg_score_thresholds,
+        custom_gateways: Vec::new(),
+        harbour_master_url: None,
     };
     GatewayClient::new(directory_config, user_agent.into())
         .map_err(VpnError::internal)?
@@ -11,8 +11,8 @@ use nym_gateway_directory::Config as GatewayDirectoryConfig;
 
 use super::TunnelEvent as PlatformTunnelEvent;
 use crate::tunnel_state_machine::{
-    DnsOptions, GatewayPerformanceOptions, MixnetTunnelOptions, NymConfig, TunnelCommand,
-    TunnelSettings, TunnelStateMachine, WireguardTunnelOptions,
+    DnsOptions, GatewayPerformanceOptions, IpFamily, MixnetTunnelOptions, NymConfig, TunnelCommand,
+    TunnelRoutes, TunnelSettings, TunnelStateMachine, WireguardTunnelOptions,
 };
 use nym_vpn_lib_types::TunnelType;
 
@@ -117,6 +117,8 @@ pub(super) async fn start_state_machine(
         min_gateway_performance: None,
         mix_score_thresholds,
         wg_score_thresholds,
+        custom_gateways: Vec::new(),
+        harbour_master_url: None,
     };
 
     let nym_config = NymConfig {
@@ -124,6 +126,15 @@ pub(super) async fn start_state_machine(
         data_path: config.credential_data_path,
         gateway_config,
         network_env,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_backend: Default::default(),
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        leak_test_ip_url: None,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_filter: None,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_routes: Vec::new(),
+        gateway_precheck: None,
     };
 
     let tunnel_settings = TunnelSettings {
@@ -136,7 +147,11 @@ pub(super) async fn start_state_machine(
         mixnet_client_config: None,
         entry_point: Box::new(entry_point),
         exit_point: Box::new(exit_point),
+        custom_gateways: Vec::new(),
+        precheck_gateways: false,
         dns: DnsOptions::default(),
+        tunnel_routes: TunnelRoutes::default(),
+        ip_family: IpFamily::default(),
         user_agent: Some(config.user_agent.into()),
     };
 
@@ -162,6 +177,9 @@ pub(super) async fn start_state_machine(
         account_controller_tx,
         #[cfg(any(target_os = "ios", target_os = "android"))]
         config.tun_provider,
+        // The bindings don't report the DNS status
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        tokio::sync::watch::channel(Default::default()).0,
         shutdown_token.child_token(),
     )
     .await?;
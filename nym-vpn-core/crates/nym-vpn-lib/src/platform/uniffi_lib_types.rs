@@ -3,11 +3,15 @@
 
 //! Types providing a bridge between uniffi and nym-vpn-lib-types.
 
-use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
 
 use nym_vpn_api_client::response::NymErrorResponse;
 use nym_vpn_lib_types::{
     ActionAfterDisconnect as CoreActionAfterDisconnect, BandwidthEvent as CoreBandwidthEvent,
+    BandwidthForecast as CoreBandwidthForecast, BandwidthGateway as CoreBandwidthGateway,
     ClientErrorReason, ConnectionData as CoreConnectionData,
     ConnectionEvent as CoreConnectionEvent,
     ConnectionStatisticsEvent as CoreConnectionStatisticsEvent,
@@ -163,6 +167,7 @@ pub struct SphinxPacketRates {
 pub enum BandwidthEvent {
     NoBandwidth,
     RemainingBandwidth(i64),
+    DepletionForecast(BandwidthForecast),
 }
 
 impl From<CoreBandwidthEvent> for BandwidthEvent {
@@ -172,6 +177,43 @@ impl From<CoreBandwidthEvent> for BandwidthEvent {
             CoreBandwidthEvent::RemainingBandwidth(value) => {
                 BandwidthEvent::RemainingBandwidth(value)
             }
+            CoreBandwidthEvent::DepletionForecast(forecast) => {
+                BandwidthEvent::DepletionForecast(BandwidthForecast::from(forecast))
+            }
+        }
+    }
+}
+
+#[derive(uniffi::Enum)]
+pub enum BandwidthGateway {
+    Entry,
+    Exit,
+}
+
+impl From<CoreBandwidthGateway> for BandwidthGateway {
+    fn from(value: CoreBandwidthGateway) -> Self {
+        match value {
+            CoreBandwidthGateway::Entry => Self::Entry,
+            CoreBandwidthGateway::Exit => Self::Exit,
+        }
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct BandwidthForecast {
+    pub gateway: BandwidthGateway,
+    pub remaining_bandwidth: i64,
+    pub depletion_rate: u64,
+    pub time_to_exhaustion: Option<Duration>,
+}
+
+impl From<CoreBandwidthForecast> for BandwidthForecast {
+    fn from(value: CoreBandwidthForecast) -> Self {
+        Self {
+            gateway: BandwidthGateway::from(value.gateway),
+            remaining_bandwidth: value.remaining_bandwidth,
+            depletion_rate: value.depletion_rate,
+            time_to_exhaustion: value.time_to_exhaustion,
         }
     }
 }
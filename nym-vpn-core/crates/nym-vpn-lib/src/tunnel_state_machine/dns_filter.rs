@@ -0,0 +1,470 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Domain filter applied by the local resolver before forwarding queries to the tunnel DNS.
+//!
+//! Blocklists are read in hosts-file format (`0.0.0.0 ads.example.com`), AdGuard format
+//! (`||ads.example.com^`, with `@@||` exceptions) or as plain domain lists. Hosts-file entries
+//! block the exact name, AdGuard rules and plain domains also block all subdomains. Rules with
+//! AdGuard modifiers or wildcards are skipped since they can't be applied to DNS queries alone.
+
+use std::{
+    collections::HashMap,
+    fmt, io,
+    net::IpAddr,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Host names commonly found in hosts files that must never be blocked.
+const RESERVED_HOSTS: &[&str] = &[
+    "localhost",
+    "localhost.localdomain",
+    "local",
+    "broadcasthost",
+    "ip6-localhost",
+    "ip6-loopback",
+    "ip6-localnet",
+    "ip6-mcastprefix",
+    "ip6-allnodes",
+    "ip6-allrouters",
+    "ip6-allhosts",
+    "0.0.0.0",
+];
+
+/// The category a blocklist belongs to. Each category can be toggled independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterCategory {
+    Ads,
+    Trackers,
+    Malware,
+    Custom,
+}
+
+impl FilterCategory {
+    pub const ALL: [FilterCategory; 4] = [
+        FilterCategory::Ads,
+        FilterCategory::Trackers,
+        FilterCategory::Malware,
+        FilterCategory::Custom,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn bit(self) -> u8 {
+        1 << self.index()
+    }
+}
+
+impl fmt::Display for FilterCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterCategory::Ads => f.write_str("ads"),
+            FilterCategory::Trackers => f.write_str("trackers"),
+            FilterCategory::Malware => f.write_str("malware"),
+            FilterCategory::Custom => f.write_str("custom"),
+        }
+    }
+}
+
+impl FromStr for FilterCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FilterCategory::ALL
+            .into_iter()
+            .find(|category| category.to_string() == s)
+            .ok_or_else(|| format!("unknown filter category: {s}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pattern {
+    /// Matches the name only.
+    Exact,
+    /// Matches the name and all of its subdomains.
+    Subdomains,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    Block(String, Pattern),
+    Allow(String, Pattern),
+}
+
+/// Categories a domain is blocked for, as a bit set of [`FilterCategory::bit`].
+type CategorySet = u8;
+
+#[derive(Debug, Default)]
+struct DomainSet {
+    exact: HashMap<String, CategorySet>,
+    subdomains: HashMap<String, CategorySet>,
+}
+
+impl DomainSet {
+    fn insert(&mut self, domain: String, pattern: Pattern, categories: CategorySet) {
+        let map = match pattern {
+            Pattern::Exact => &mut self.exact,
+            Pattern::Subdomains => &mut self.subdomains,
+        };
+        *map.entry(domain).or_default() |= categories;
+    }
+
+    /// Returns the categories the name matches.
+    fn matches(&self, name: &str) -> CategorySet {
+        let mut categories = self.exact.get(name).copied().unwrap_or_default();
+
+        let mut suffix = name;
+        loop {
+            categories |= self.subdomains.get(suffix).copied().unwrap_or_default();
+            match suffix.split_once('.') {
+                Some((_, parent)) => suffix = parent,
+                None => break,
+            }
+        }
+        categories
+    }
+
+    fn len(&self) -> usize {
+        self.exact.len() + self.subdomains.len()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    queries: AtomicU64,
+    blocked: [AtomicU64; FilterCategory::ALL.len()],
+}
+
+/// Blocklists, allowlist and counters of the local resolver.
+pub struct DnsFilter {
+    blocked: DomainSet,
+    allowed: DomainSet,
+    enabled: CategorySet,
+    rules: [usize; FilterCategory::ALL.len()],
+    counters: Counters,
+}
+
+impl fmt::Debug for DnsFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DnsFilter")
+            .field("blocked_rules", &self.blocked.len())
+            .field("allowed_rules", &self.allowed.len())
+            .field("enabled", &self.enabled_categories().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for DnsFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DnsFilter {
+    /// Creates an empty filter with all categories enabled.
+    pub fn new() -> Self {
+        Self {
+            blocked: DomainSet::default(),
+            allowed: DomainSet::default(),
+            enabled: FilterCategory::ALL
+                .iter()
+                .fold(0, |set, category| set | category.bit()),
+            rules: [0; FilterCategory::ALL.len()],
+            counters: Counters::default(),
+        }
+    }
+
+    pub fn set_category_enabled(&mut self, category: FilterCategory, enabled: bool) {
+        if enabled {
+            self.enabled |= category.bit();
+        } else {
+            self.enabled &= !category.bit();
+        }
+    }
+
+    pub fn is_category_enabled(&self, category: FilterCategory) -> bool {
+        self.enabled & category.bit() != 0
+    }
+
+    fn enabled_categories(&self) -> impl Iterator<Item = FilterCategory> + '_ {
+        FilterCategory::ALL
+            .into_iter()
+            .filter(|category| self.is_category_enabled(*category))
+    }
+
+    /// Adds the rules of a blocklist in any of the supported formats. Exception rules are added
+    /// to the allowlist. Returns the number of rules added.
+    pub fn add_blocklist(&mut self, category: FilterCategory, contents: &str) -> usize {
+        let mut added = 0;
+        for rule in contents.lines().flat_map(parse_line) {
+            match rule {
+                Rule::Block(domain, pattern) => {
+                    self.blocked.insert(domain, pattern, category.bit());
+                    self.rules[category.index()] += 1;
+                }
+                Rule::Allow(domain, pattern) => self.allowed.insert(domain, pattern, 1),
+            }
+            added += 1;
+        }
+        added
+    }
+
+    /// Reads a blocklist from a file. See [`DnsFilter::add_blocklist`].
+    pub fn load_blocklist(&mut self, category: FilterCategory, path: &Path) -> io::Result<usize> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(self.add_blocklist(category, &contents))
+    }
+
+    /// Allows the domain and all of its subdomains regardless of the blocklists.
+    pub fn allow(&mut self, domain: &str) {
+        match normalize_domain(domain) {
+            Some(domain) => self.allowed.insert(domain, Pattern::Subdomains, 1),
+            None => tracing::warn!("Ignoring invalid allowlist entry: {domain}"),
+        }
+    }
+
+    /// Checks a queried name, updating the counters. Returns the category the name is blocked
+    /// for, if any.
+    pub fn check(&self, name: &str) -> Option<FilterCategory> {
+        self.counters.queries.fetch_add(1, Ordering::Relaxed);
+
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        if self.allowed.matches(&name) != 0 {
+            return None;
+        }
+
+        let categories = self.blocked.matches(&name) & self.enabled;
+        let category = self
+            .enabled_categories()
+            .find(|category| categories & category.bit() != 0)?;
+        self.counters.blocked[category.index()].fetch_add(1, Ordering::Relaxed);
+        Some(category)
+    }
+
+    pub fn stats(&self) -> DnsFilterStats {
+        DnsFilterStats {
+            queries: self.counters.queries.load(Ordering::Relaxed),
+            allowlist_rules: self.allowed.len(),
+            categories: FilterCategory::ALL
+                .into_iter()
+                .map(|category| CategoryStats {
+                    category,
+                    enabled: self.is_category_enabled(category),
+                    rules: self.rules[category.index()],
+                    blocked: self.counters.blocked[category.index()].load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Snapshot of the filter counters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsFilterStats {
+    /// Queries checked against the filter.
+    pub queries: u64,
+    pub allowlist_rules: usize,
+    pub categories: Vec<CategoryStats>,
+}
+
+impl DnsFilterStats {
+    pub fn blocked(&self) -> u64 {
+        self.categories
+            .iter()
+            .map(|category| category.blocked)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryStats {
+    pub category: FilterCategory,
+    pub enabled: bool,
+    pub rules: usize,
+    pub blocked: u64,
+}
+
+fn parse_line(line: &str) -> Option<Rule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+        return None;
+    }
+
+    if let Some(rule) = line.strip_prefix("@@||") {
+        return parse_adguard_domain(rule).map(|domain| Rule::Allow(domain, Pattern::Subdomains));
+    }
+    if let Some(rule) = line.strip_prefix("||") {
+        return parse_adguard_domain(rule).map(|domain| Rule::Block(domain, Pattern::Subdomains));
+    }
+
+    // Strip inline comments of hosts files
+    let line = line.split_once('#').map_or(line, |(rule, _)| rule);
+    let mut tokens = line.split_whitespace();
+    let first = tokens.next()?;
+
+    if first.parse::<IpAddr>().is_ok() {
+        // Only the first name is used, which covers the usual one entry per line
+        let host = tokens.next()?;
+        if RESERVED_HOSTS.contains(&host) {
+            return None;
+        }
+        return normalize_domain(host).map(|domain| Rule::Block(domain, Pattern::Exact));
+    }
+
+    if tokens.next().is_none() {
+        return normalize_domain(first).map(|domain| Rule::Block(domain, Pattern::Subdomains));
+    }
+    None
+}
+
+fn parse_adguard_domain(rule: &str) -> Option<String> {
+    let (domain, rest) = rule.split_once('^').unwrap_or((rule, ""));
+    if !rest.is_empty() && rest != "|" {
+        // Modifiers like `$third-party` can't be applied to DNS queries
+        return None;
+    }
+    normalize_domain(domain)
+}
+
+fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let valid = domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.contains("..")
+        && domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then_some(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hosts_format() {
+        assert_eq!(
+            parse_line("0.0.0.0 ads.example.com"),
+            Some(Rule::Block("ads.example.com".to_owned(), Pattern::Exact))
+        );
+        assert_eq!(
+            parse_line("127.0.0.1\tTracker.Example.com. # inline comment"),
+            Some(Rule::Block(
+                "tracker.example.com".to_owned(),
+                Pattern::Exact
+            ))
+        );
+        assert_eq!(parse_line("127.0.0.1 localhost"), None);
+        assert_eq!(parse_line("# comment"), None);
+        assert_eq!(parse_line(""), None);
+    }
+
+    #[test]
+    fn parses_adguard_format() {
+        assert_eq!(
+            parse_line("||ads.example.com^"),
+            Some(Rule::Block(
+                "ads.example.com".to_owned(),
+                Pattern::Subdomains
+            ))
+        );
+        assert_eq!(
+            parse_line("@@||cdn.example.com^"),
+            Some(Rule::Allow(
+                "cdn.example.com".to_owned(),
+                Pattern::Subdomains
+            ))
+        );
+        assert_eq!(parse_line("||ads.example.com^$third-party"), None);
+        assert_eq!(parse_line("||*.example.com^"), None);
+        assert_eq!(parse_line("! comment"), None);
+        assert_eq!(parse_line("/banner/*"), None);
+    }
+
+    #[test]
+    fn parses_plain_domains() {
+        assert_eq!(
+            parse_line("malware.example.net"),
+            Some(Rule::Block(
+                "malware.example.net".to_owned(),
+                Pattern::Subdomains
+            ))
+        );
+        assert_eq!(parse_line("not a domain"), None);
+        assert_eq!(parse_line("localhost"), None);
+    }
+
+    #[test]
+    fn blocks_and_counts() {
+        let mut filter = DnsFilter::new();
+        filter.add_blocklist(
+            FilterCategory::Ads,
+            "0.0.0.0 ads.example.com\n||doubleclick.example^\n",
+        );
+        filter.add_blocklist(FilterCategory::Malware, "bad.example.org\n");
+
+        assert_eq!(filter.check("ads.example.com."), Some(FilterCategory::Ads));
+        assert_eq!(filter.check("sub.ads.example.com."), None);
+        assert_eq!(
+            filter.check("x.doubleclick.example."),
+            Some(FilterCategory::Ads)
+        );
+        assert_eq!(
+            filter.check("www.BAD.example.org."),
+            Some(FilterCategory::Malware)
+        );
+        assert_eq!(filter.check("example.com."), None);
+
+        let stats = filter.stats();
+        assert_eq!(stats.queries, 5);
+        assert_eq!(stats.blocked(), 3);
+        let ads = &stats.categories[FilterCategory::Ads.index()];
+        assert_eq!((ads.rules, ads.blocked), (2, 2));
+    }
+
+    #[test]
+    fn allowlist_overrides_blocklists() {
+        let mut filter = DnsFilter::new();
+        filter.add_blocklist(
+            FilterCategory::Trackers,
+            "||example.com^\n@@||cdn.example.com^\n",
+        );
+        filter.allow("login.example.com");
+
+        assert_eq!(
+            filter.check("track.example.com"),
+            Some(FilterCategory::Trackers)
+        );
+        assert_eq!(filter.check("img.cdn.example.com"), None);
+        assert_eq!(filter.check("login.example.com"), None);
+        assert_eq!(filter.stats().allowlist_rules, 2);
+    }
+
+    #[test]
+    fn disabled_categories_are_not_blocked() {
+        let mut filter = DnsFilter::new();
+        filter.add_blocklist(FilterCategory::Ads, "shared.example.com\n");
+        filter.add_blocklist(FilterCategory::Malware, "shared.example.com\n");
+
+        filter.set_category_enabled(FilterCategory::Ads, false);
+        assert_eq!(
+            filter.check("shared.example.com"),
+            Some(FilterCategory::Malware)
+        );
+
+        filter.set_category_enabled(FilterCategory::Malware, false);
+        assert_eq!(filter.check("shared.example.com"), None);
+    }
+
+    #[test]
+    fn category_from_str() {
+        for category in FilterCategory::ALL {
+            assert_eq!(category.to_string().parse(), Ok(category));
+        }
+        assert!("social".parse::<FilterCategory>().is_err());
+    }
+}
@@ -1,9 +1,9 @@
 // Copyright 2023 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use nym_dns::{DnsMonitor, ResolvedDnsConfig};
+use nym_dns::{DnsBackend, DnsMonitor, DnsStatus, ResolvedDnsConfig};
 use tokio::{
-    sync::{mpsc, oneshot},
+    sync::{mpsc, oneshot, watch},
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
@@ -17,10 +17,12 @@ struct DnsHandler {
 
 impl DnsHandler {
     fn new(
+        backend: DnsBackend,
         #[cfg(target_os = "linux")] route_handler: &RouteHandler,
     ) -> Result<Self, nym_dns::Error> {
         Ok(Self {
             inner: DnsMonitor::new(
+                backend,
                 #[cfg(target_os = "linux")]
                 route_handler.inner_handle(),
             )?,
@@ -43,6 +45,10 @@ impl DnsHandler {
     pub async fn reset_before_interface_removal(&mut self) -> Result<(), nym_dns::Error> {
         self.inner.reset_before_interface_removal().await
     }
+
+    pub fn status(&self) -> DnsStatus {
+        self.inner.status()
+    }
 }
 
 enum DnsHandlerCommand {
@@ -64,17 +70,23 @@ enum DnsHandlerCommand {
 #[derive(Debug, Clone)]
 pub struct DnsHandlerHandle {
     tx: mpsc::UnboundedSender<DnsHandlerCommand>,
+    status_rx: watch::Receiver<DnsStatus>,
 }
 
 impl DnsHandlerHandle {
     pub fn spawn(
+        backend: DnsBackend,
         #[cfg(target_os = "linux")] route_handler: &RouteHandler,
+        status_tx: watch::Sender<DnsStatus>,
         shutdown_token: CancellationToken,
     ) -> Result<(Self, JoinHandle<()>)> {
         let mut dns_handler = DnsHandler::new(
+            backend,
             #[cfg(target_os = "linux")]
             route_handler,
         )?;
+        status_tx.send_replace(dns_handler.status());
+        let status_rx = status_tx.subscribe();
 
         let (tx, mut rx) = mpsc::unbounded_channel();
         let join_handle = tokio::spawn(async move {
@@ -97,6 +109,7 @@ impl DnsHandlerHandle {
                                 _ = reply_tx.send(dns_handler.reset_before_interface_removal().await);
                             }
                         }
+                        status_tx.send_replace(dns_handler.status());
                     }
                     _ = shutdown_token.cancelled() => break,
                     else => break
@@ -105,7 +118,7 @@ impl DnsHandlerHandle {
             tracing::debug!("Exiting dns handler loop");
         });
 
-        Ok((Self { tx }, join_handle))
+        Ok((Self { tx, status_rx }, join_handle))
     }
 
     pub async fn set(&mut self, interface: String, config: ResolvedDnsConfig) -> Result<()> {
@@ -140,6 +153,10 @@ impl DnsHandlerHandle {
         .await
     }
 
+    pub fn status(&self) -> DnsStatus {
+        self.status_rx.borrow().clone()
+    }
+
     async fn send_and_wait<T>(
         &self,
         command: DnsHandlerCommand,
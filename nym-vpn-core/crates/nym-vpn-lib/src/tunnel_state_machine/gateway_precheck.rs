@@ -0,0 +1,36 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt, time::Duration};
+
+use nym_gateway_directory::Gateway;
+use nym_vpn_lib_types::TunnelType;
+
+/// Number of gateway pairs checked before giving up on connecting.
+pub(crate) const MAX_PRECHECK_ATTEMPTS: usize = 3;
+
+/// Time spent on prechecks per connection attempt, after which the selected gateways are used
+/// without checking them.
+pub(crate) const MAX_PRECHECK_DURATION: Duration = Duration::from_secs(120);
+
+/// Health check run against the selected gateways before the tunnel is established, so that a
+/// gateway listed in the directory but unable to route can be swapped for another candidate.
+#[async_trait::async_trait]
+pub trait GatewayPrecheck: fmt::Debug + Send + Sync {
+    /// Checks the gateways without spending any ticketbooks of the account.
+    async fn check(
+        &self,
+        tunnel_type: TunnelType,
+        entry: &Gateway,
+        exit: &Gateway,
+    ) -> Result<(), GatewayPrecheckError>;
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum GatewayPrecheckError {
+    #[error("entry gateway {identity} failed the precheck: {reason}")]
+    Entry { identity: String, reason: String },
+
+    #[error("exit gateway {identity} failed the precheck: {reason}")]
+    Exit { identity: String, reason: String },
+}
@@ -0,0 +1,534 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Self-test checking that DNS queries and traffic leave the host through the tunnel.
+//!
+//! The test is prepared by the connected state, which knows the tunnel interface, the DNS config
+//! enforced by the firewall and the exit gateway. The slow part, issuing queries and asking a
+//! remote service for the observed address, runs outside of the state machine.
+//!
+//! A resolver on the loopback interface, such as the systemd-resolved stub, can only forward
+//! queries to the servers the firewall lets through, so it's judged by how queries to the tunnel
+//! servers are routed.
+
+use std::{net::IpAddr, time::Duration};
+
+use nym_dns::{DnsStatus, DnsVerification, ResolvedDnsConfig};
+use url::Url;
+
+use super::system_handles::RouteControl;
+
+/// Hostnames resolved through the system resolver during the test.
+const TEST_HOSTNAMES: &[&str] = &["nymvpn.com", "nymtech.net"];
+
+/// Service replying with the address the request was seen coming from, used unless another one
+/// is configured.
+const DEFAULT_EXIT_IP_URL: &str = "https://api.ipify.org";
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const EXIT_IP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeakTestError {
+    #[error("the leak test requires a connected tunnel")]
+    NotConnected,
+
+    #[error("the tunnel state machine is down")]
+    StateMachineDown,
+}
+
+/// Outcome of the leak test.
+#[derive(Debug, Clone)]
+pub struct LeakReport {
+    pub dns: DnsLeakReport,
+    pub ip: IpLeakReport,
+}
+
+impl LeakReport {
+    /// Returns true if any of the checks saw traffic leaving outside of the tunnel.
+    pub fn is_leaking(&self) -> bool {
+        self.dns.verdict() == DnsLeakVerdict::Leak || self.ip.verdict == IpLeakVerdict::Leak
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsLeakReport {
+    /// The tunnel interface DNS queries are expected to egress via.
+    pub tunnel_interface: String,
+    /// The DNS servers configured for the tunnel.
+    pub tunnel_servers: Vec<IpAddr>,
+    /// Whether the system resolver uses the tunnel DNS servers.
+    pub resolver: DnsVerification,
+    /// Queries issued through the system resolver.
+    pub queries: Vec<DnsQueryCheck>,
+    /// How queries to each server used by the system resolver are routed.
+    pub servers: Vec<DnsServerCheck>,
+}
+
+impl DnsLeakReport {
+    pub fn verdict(&self) -> DnsLeakVerdict {
+        if self
+            .servers
+            .iter()
+            .any(|server| server.verdict == DnsServerVerdict::Leak)
+        {
+            return DnsLeakVerdict::Leak;
+        }
+
+        // The system resolver being configured with servers that are neither tunnel servers nor
+        // allowed outside of the tunnel is a leak, even if the firewall ends up dropping them.
+        if let DnsVerification::Mismatch { resolver_servers } = &self.resolver {
+            let unexpected = resolver_servers.iter().any(|server| {
+                !server.is_loopback()
+                    && !self.tunnel_servers.contains(server)
+                    && !self.servers.iter().any(|check| {
+                        check.server == *server
+                            && check.verdict == DnsServerVerdict::AllowedOutsideTunnel
+                    })
+            });
+            if unexpected {
+                return DnsLeakVerdict::Leak;
+            }
+        }
+
+        if !self.queries.is_empty() && self.queries.iter().all(|query| query.result.is_err()) {
+            return DnsLeakVerdict::Inconclusive("none of the test queries resolved".to_owned());
+        }
+
+        match self
+            .servers
+            .iter()
+            .find_map(|server| match &server.verdict {
+                DnsServerVerdict::Inconclusive(reason) => Some(reason),
+                _ => None,
+            }) {
+            Some(reason) => DnsLeakVerdict::Inconclusive(reason.clone()),
+            None => DnsLeakVerdict::Tunnel,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsLeakVerdict {
+    /// Queries issued through the system resolver go through the tunnel.
+    Tunnel,
+    /// Queries can egress outside of the tunnel.
+    Leak,
+    /// Could not tell how queries are routed.
+    Inconclusive(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsQueryCheck {
+    pub hostname: String,
+    /// The resolved addresses, or why the query failed.
+    pub result: Result<Vec<IpAddr>, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsServerCheck {
+    pub server: IpAddr,
+    /// The interface queries to the server egress via, if known.
+    pub interface: Option<String>,
+    pub verdict: DnsServerVerdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DnsServerVerdict {
+    /// Queries to the server go through the tunnel.
+    Tunnel,
+    /// The server is allowed outside of the tunnel by the DNS config, e.g. a resolver on the LAN.
+    AllowedOutsideTunnel,
+    /// Queries to the server egress via another interface.
+    Leak,
+    /// Could not tell how queries to the server are routed.
+    Inconclusive(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct IpLeakReport {
+    /// The addresses of the exit gateway.
+    pub exit_ips: Vec<IpAddr>,
+    /// The address a remote service saw the request coming from.
+    pub observed_ip: Option<IpAddr>,
+    pub verdict: IpLeakVerdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpLeakVerdict {
+    /// Traffic is seen coming from the exit gateway.
+    Exit,
+    /// Traffic is seen coming from an address other than the exit gateway.
+    Leak,
+    /// The observed address could not be determined.
+    Inconclusive(String),
+}
+
+/// How traffic to a DNS server leaves the host.
+struct Egress {
+    /// The interface traffic egresses via, if known.
+    interface: Option<String>,
+    via_tunnel: bool,
+}
+
+/// A leak test prepared with the state of the connected tunnel.
+pub(super) struct LeakTest {
+    dns: DnsLeakReport,
+    exit_ips: Vec<IpAddr>,
+    exit_ip_url: Option<Url>,
+}
+
+impl LeakTest {
+    pub(super) async fn prepare(
+        tunnel_interface: String,
+        #[cfg_attr(target_os = "linux", allow(unused_variables))] tunnel_ips: &[IpAddr],
+        dns_config: &ResolvedDnsConfig,
+        dns_status: DnsStatus,
+        exit_ips: Vec<IpAddr>,
+        exit_ip_url: Option<Url>,
+        #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+        route_control: &mut dyn RouteControl,
+    ) -> Self {
+        let resolver = nym_dns::verify(&dns_status).await;
+        let resolver_servers = match &resolver {
+            DnsVerification::Mismatch { resolver_servers } => resolver_servers.clone(),
+            DnsVerification::Verified | DnsVerification::Inconclusive(_) => {
+                dns_config.tunnel_config().to_vec()
+            }
+        };
+
+        // Local resolvers are judged by the tunnel servers, so make sure those are checked.
+        let mut checked_servers: Vec<IpAddr> = resolver_servers
+            .iter()
+            .copied()
+            .filter(|server| !server.is_loopback())
+            .collect();
+        if resolver_servers.iter().any(IpAddr::is_loopback) {
+            for server in dns_config.tunnel_config() {
+                if !server.is_loopback() && !checked_servers.contains(server) {
+                    checked_servers.push(*server);
+                }
+            }
+        }
+
+        let mut servers = Vec::with_capacity(resolver_servers.len());
+        for server in checked_servers {
+            #[cfg(target_os = "linux")]
+            let egress = route_control
+                .destination_interface(server)
+                .await
+                .map_err(|e| format!("failed to look up the route: {e}"))
+                .and_then(|interface| interface.ok_or_else(|| "no route to the server".to_owned()))
+                .map(|interface| Egress {
+                    via_tunnel: interface == tunnel_interface,
+                    interface: Some(interface),
+                });
+            #[cfg(not(target_os = "linux"))]
+            let egress = source_address(server)
+                .map_err(|e| format!("failed to look up the source address: {e}"))
+                .map(|source| {
+                    let via_tunnel = tunnel_ips.contains(&source);
+                    Egress {
+                        interface: via_tunnel.then(|| tunnel_interface.clone()),
+                        via_tunnel,
+                    }
+                });
+
+            servers.push(check_dns_server(server, egress, dns_config));
+        }
+
+        let stubs = resolver_servers
+            .into_iter()
+            .filter(IpAddr::is_loopback)
+            .map(|server| check_local_resolver(server, dns_config, &servers))
+            .collect::<Vec<_>>();
+        servers.extend(stubs);
+
+        Self {
+            dns: DnsLeakReport {
+                tunnel_interface,
+                tunnel_servers: dns_config.tunnel_config().to_vec(),
+                resolver,
+                queries: Vec::new(),
+                servers,
+            },
+            exit_ips,
+            exit_ip_url,
+        }
+    }
+
+    pub(super) async fn run(mut self) -> LeakReport {
+        let exit_ip_url = self
+            .exit_ip_url
+            .as_ref()
+            .map(Url::as_str)
+            .unwrap_or(DEFAULT_EXIT_IP_URL);
+        let queries = futures::future::join_all(TEST_HOSTNAMES.iter().copied().map(query));
+        let (queries, observed_ip) = tokio::join!(queries, fetch_exit_ip(exit_ip_url));
+        self.dns.queries = queries;
+
+        let report = LeakReport {
+            dns: self.dns,
+            ip: check_exit_ip(self.exit_ips, observed_ip),
+        };
+        tracing::info!(
+            "Leak test finished, leaking: {}",
+            if report.is_leaking() { "yes" } else { "no" }
+        );
+        report
+    }
+}
+
+fn check_dns_server(
+    server: IpAddr,
+    egress: Result<Egress, String>,
+    dns_config: &ResolvedDnsConfig,
+) -> DnsServerCheck {
+    let verdict = if dns_config.non_tunnel_servers().contains(&server) {
+        DnsServerVerdict::AllowedOutsideTunnel
+    } else {
+        match &egress {
+            Ok(egress) if egress.via_tunnel => DnsServerVerdict::Tunnel,
+            Ok(_) => DnsServerVerdict::Leak,
+            Err(reason) => DnsServerVerdict::Inconclusive(reason.clone()),
+        }
+    };
+
+    DnsServerCheck {
+        server,
+        interface: egress.ok().and_then(|egress| egress.interface),
+        verdict,
+    }
+}
+
+// The firewall only lets DNS through to the configured servers, so a local resolver leaks if
+// queries to any of the tunnel servers do.
+fn check_local_resolver(
+    server: IpAddr,
+    dns_config: &ResolvedDnsConfig,
+    checks: &[DnsServerCheck],
+) -> DnsServerCheck {
+    let upstream = checks
+        .iter()
+        .filter(|check| dns_config.tunnel_config().contains(&check.server))
+        .collect::<Vec<_>>();
+
+    let verdict = if upstream
+        .iter()
+        .any(|check| check.verdict == DnsServerVerdict::Leak)
+    {
+        DnsServerVerdict::Leak
+    } else if !upstream.is_empty()
+        && upstream
+            .iter()
+            .all(|check| check.verdict == DnsServerVerdict::Tunnel)
+    {
+        DnsServerVerdict::Tunnel
+    } else {
+        DnsServerVerdict::Inconclusive(
+            "queries are forwarded by a local resolver to servers that could not be checked"
+                .to_owned(),
+        )
+    };
+
+    DnsServerCheck {
+        server,
+        interface: None,
+        verdict,
+    }
+}
+
+// Connecting a UDP socket doesn't send anything, it only picks the source address from the route
+// to the destination.
+#[cfg(not(target_os = "linux"))]
+fn source_address(server: IpAddr) -> std::io::Result<IpAddr> {
+    use std::net::{Ipv4Addr, Ipv6Addr, UdpSocket};
+
+    let socket = match server {
+        IpAddr::V4(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?,
+        IpAddr::V6(_) => UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0))?,
+    };
+    socket.connect((server, 53))?;
+    Ok(socket.local_addr()?.ip())
+}
+
+fn check_exit_ip(exit_ips: Vec<IpAddr>, observed_ip: Result<IpAddr, String>) -> IpLeakReport {
+    let (observed_ip, verdict) = match observed_ip {
+        Ok(ip) if exit_ips.contains(&ip) => (Some(ip), IpLeakVerdict::Exit),
+        Ok(ip) => (Some(ip), IpLeakVerdict::Leak),
+        Err(reason) => (None, IpLeakVerdict::Inconclusive(reason)),
+    };
+
+    IpLeakReport {
+        exit_ips,
+        observed_ip,
+        verdict,
+    }
+}
+
+async fn query(hostname: &str) -> DnsQueryCheck {
+    let lookup = tokio::net::lookup_host((hostname, 0));
+    let result = match tokio::time::timeout(QUERY_TIMEOUT, lookup).await {
+        Ok(Ok(addrs)) => Ok(addrs.map(|addr| addr.ip()).collect()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("query timed out".to_owned()),
+    };
+
+    DnsQueryCheck {
+        hostname: hostname.to_owned(),
+        result,
+    }
+}
+
+async fn fetch_exit_ip(url: &str) -> Result<IpAddr, String> {
+    let client = reqwest::Client::builder()
+        .timeout(EXIT_IP_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to create http client: {e}"))?;
+
+    let body = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("failed to query {url}: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("failed to read the response from {url}: {e}"))?;
+
+    body.trim()
+        .parse()
+        .map_err(|e| format!("unexpected response from {url}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use nym_dns::DnsConfig;
+
+    use super::*;
+
+    const TUNNEL_DNS: IpAddr = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+    const LAN_DNS: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+    const EXIT_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+    const RESOLVED_STUB: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 53));
+
+    fn dns_config() -> ResolvedDnsConfig {
+        DnsConfig::from_addresses(&[TUNNEL_DNS], &[LAN_DNS]).resolve(
+            &[],
+            #[cfg(target_os = "macos")]
+            53,
+        )
+    }
+
+    fn egress(interface: &str) -> Result<Egress, String> {
+        Ok(Egress {
+            interface: Some(interface.to_owned()),
+            via_tunnel: interface == "nym0",
+        })
+    }
+
+    fn dns_report(resolver: DnsVerification, servers: Vec<DnsServerCheck>) -> DnsLeakReport {
+        DnsLeakReport {
+            tunnel_interface: "nym0".to_owned(),
+            tunnel_servers: vec![TUNNEL_DNS],
+            resolver,
+            queries: vec![DnsQueryCheck {
+                hostname: "nymvpn.com".to_owned(),
+                result: Ok(vec![EXIT_IP]),
+            }],
+            servers,
+        }
+    }
+
+    #[test]
+    fn classifies_dns_servers() {
+        let config = dns_config();
+
+        let check = check_dns_server(TUNNEL_DNS, egress("nym0"), &config);
+        assert_eq!(check.verdict, DnsServerVerdict::Tunnel);
+
+        let check = check_dns_server(TUNNEL_DNS, egress("eth0"), &config);
+        assert_eq!(check.verdict, DnsServerVerdict::Leak);
+        assert_eq!(check.interface.as_deref(), Some("eth0"));
+
+        let check = check_dns_server(LAN_DNS, egress("eth0"), &config);
+        assert_eq!(check.verdict, DnsServerVerdict::AllowedOutsideTunnel);
+
+        let check = check_dns_server(TUNNEL_DNS, Err("down".to_owned()), &config);
+        assert_eq!(
+            check.verdict,
+            DnsServerVerdict::Inconclusive("down".to_owned())
+        );
+    }
+
+    #[test]
+    fn judges_local_resolver_by_tunnel_servers() {
+        let config = dns_config();
+
+        let checks = [check_dns_server(TUNNEL_DNS, egress("nym0"), &config)];
+        let check = check_local_resolver(RESOLVED_STUB, &config, &checks);
+        assert_eq!(check.verdict, DnsServerVerdict::Tunnel);
+
+        let checks = [check_dns_server(TUNNEL_DNS, egress("eth0"), &config)];
+        let check = check_local_resolver(RESOLVED_STUB, &config, &checks);
+        assert_eq!(check.verdict, DnsServerVerdict::Leak);
+
+        let check = check_local_resolver(RESOLVED_STUB, &config, &[]);
+        assert!(matches!(check.verdict, DnsServerVerdict::Inconclusive(_)));
+    }
+
+    #[test]
+    fn dns_verdict() {
+        let config = dns_config();
+        let tunnel = check_dns_server(TUNNEL_DNS, egress("nym0"), &config);
+        let lan = check_dns_server(LAN_DNS, egress("eth0"), &config);
+
+        let report = dns_report(DnsVerification::Verified, vec![tunnel.clone()]);
+        assert_eq!(report.verdict(), DnsLeakVerdict::Tunnel);
+
+        let report = dns_report(
+            DnsVerification::Verified,
+            vec![check_dns_server(TUNNEL_DNS, egress("eth0"), &config)],
+        );
+        assert_eq!(report.verdict(), DnsLeakVerdict::Leak);
+
+        // The resolver uses a server the tunnel config knows nothing about
+        let other = IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9));
+        let report = dns_report(
+            DnsVerification::Mismatch {
+                resolver_servers: vec![other],
+            },
+            vec![check_dns_server(other, egress("nym0"), &config)],
+        );
+        assert_eq!(report.verdict(), DnsLeakVerdict::Leak);
+
+        // Servers allowed outside of the tunnel are expected
+        let report = dns_report(
+            DnsVerification::Mismatch {
+                resolver_servers: vec![TUNNEL_DNS, LAN_DNS],
+            },
+            vec![tunnel.clone(), lan],
+        );
+        assert_eq!(report.verdict(), DnsLeakVerdict::Tunnel);
+
+        let mut report = dns_report(DnsVerification::Verified, vec![tunnel]);
+        report.queries[0].result = Err("query timed out".to_owned());
+        assert!(matches!(report.verdict(), DnsLeakVerdict::Inconclusive(_)));
+    }
+
+    #[test]
+    fn compares_observed_ip_with_exit() {
+        let report = check_exit_ip(vec![EXIT_IP], Ok(EXIT_IP));
+        assert_eq!(report.verdict, IpLeakVerdict::Exit);
+
+        let report = check_exit_ip(vec![EXIT_IP], Ok(LAN_DNS));
+        assert_eq!(report.verdict, IpLeakVerdict::Leak);
+        assert_eq!(report.observed_ip, Some(LAN_DNS));
+
+        let report = check_exit_ip(vec![EXIT_IP], Err("offline".to_owned()));
+        assert_eq!(report.observed_ip, None);
+        assert!(matches!(report.verdict, IpLeakVerdict::Inconclusive(_)));
+    }
+}
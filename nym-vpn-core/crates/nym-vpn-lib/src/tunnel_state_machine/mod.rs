@@ -5,12 +5,22 @@ mod account;
 #[cfg(target_os = "android")]
 mod android_connectivity_adapter;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+mod dns_filter;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 mod dns_handler;
-#[cfg(target_os = "macos")]
+mod gateway_precheck;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+mod leak_test;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod mtu_probe;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
 mod resolver;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 mod route_handler;
 mod states;
+mod system_handles;
+#[cfg(all(test, target_os = "linux"))]
+mod test_harness;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 mod tun_ipv6;
 #[cfg(any(target_os = "ios", target_os = "android"))]
@@ -20,23 +30,30 @@ mod tunnel_monitor;
 #[cfg(windows)]
 mod wintun;
 
-#[cfg(any(target_os = "ios", target_os = "android"))]
-use std::sync::Arc;
 use std::{
+    fmt,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::PathBuf,
+    sync::Arc,
 };
 
+use ipnetwork::IpNetwork;
 use nym_vpn_account_controller::AccountControllerCommander;
 use nym_vpn_network_config::Network;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use tokio::sync::{oneshot, watch};
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-use nym_dns::DnsConfig;
+use nym_dns::{DnsBackend, DnsConfig, DnsStatus, DomainRoute};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use nym_firewall::AllowedNonTunnelTraffic;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use nym_firewall::{Firewall, FirewallArguments, InitialFirewallState};
-use nym_gateway_directory::{Config as GatewayDirectoryConfig, EntryPoint, ExitPoint, Recipient};
+use nym_gateway_directory::{
+    Config as GatewayDirectoryConfig, EntryPoint, ExitPoint, GatewayDescriptor, Recipient,
+};
 use nym_sdk::UserAgent;
 use nym_vpn_lib_types::{
     ActionAfterDisconnect, ClientErrorReason, ConnectionData, ErrorStateReason, MixnetEvent,
@@ -44,7 +61,9 @@ use nym_vpn_lib_types::{
 };
 use nym_wg_gateway_client::Error as WgGatewayClientError;
 
-use tunnel::SelectedGateways;
+use tunnel::{wireguard::two_hop_config::MtuMismatch, SelectedGateways};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use url::Url;
 #[cfg(windows)]
 use wintun::SetupWintunAdapterError;
 
@@ -57,10 +76,21 @@ use crate::{
     MixnetClientConfig,
 };
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use dns_filter::{CategoryStats, DnsFilter, DnsFilterStats, FilterCategory};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use dns_handler::DnsHandlerHandle;
+pub use gateway_precheck::{GatewayPrecheck, GatewayPrecheckError};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub use leak_test::{
+    DnsLeakReport, DnsLeakVerdict, DnsQueryCheck, DnsServerCheck, DnsServerVerdict, IpLeakReport,
+    IpLeakVerdict, LeakReport, LeakTestError,
+};
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use route_handler::RouteHandler;
 use states::{DisconnectedState, OfflineState};
+use system_handles::{ConnectivityMonitor, DefaultTunnelConnector, TunnelConnector};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use system_handles::{DnsControl, FirewallControl, RouteControl};
 
 #[async_trait::async_trait]
 trait TunnelStateHandler: Send {
@@ -110,9 +140,21 @@ pub struct TunnelSettings {
     /// Exit node.
     pub exit_point: Box<ExitPoint>,
 
+    /// Gateways added to the directory gateways in WireGuard mode.
+    pub custom_gateways: Vec<GatewayDescriptor>,
+
+    /// Run the gateway precheck against the selected gateways before connecting.
+    pub precheck_gateways: bool,
+
     /// DNS configuration.
     pub dns: DnsOptions,
 
+    /// Destinations routed through the tunnel.
+    pub tunnel_routes: TunnelRoutes,
+
+    /// IP families configured inside the tunnel.
+    pub ip_family: IpFamily,
+
     /// The user agent used for HTTP requests.
     pub user_agent: Option<UserAgent>,
 }
@@ -156,6 +198,48 @@ impl Default for WireguardMultihopMode {
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub struct WireguardTunnelOptions {
     pub multihop_mode: WireguardMultihopMode,
+
+    /// MTUs of the entry and exit tunnels.
+    pub mtu: WireguardMtu,
+}
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum WireguardMtu {
+    /// Use the built-in MTUs.
+    #[default]
+    Default,
+
+    /// Override the MTUs. When only the entry MTU is set, the exit MTU is derived from it.
+    Manual {
+        entry: Option<u16>,
+        exit: Option<u16>,
+    },
+
+    /// Derive the MTUs from the route towards the entry gateway, then lower them if probing
+    /// through the tunnel shows that full sized packets don't get through.
+    ///
+    /// Only Linux supports both steps. Windows only derives the MTUs from the route, macOS only
+    /// probes and otherwise starts from the built-in MTUs.
+    Auto,
+}
+
+impl fmt::Display for WireguardMtu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mtu_or_default = |mtu: &Option<u16>| {
+            mtu.map(|mtu| mtu.to_string())
+                .unwrap_or_else(|| "default".to_owned())
+        };
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::Manual { entry, exit } => write!(
+                f,
+                "entry {}, exit {}",
+                mtu_or_default(entry),
+                mtu_or_default(exit)
+            ),
+            Self::Auto => f.write_str("auto"),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -166,23 +250,32 @@ pub enum DnsOptions {
 }
 
 impl DnsOptions {
-    /// Convert dns options into [DnsConfig].
+    /// Convert dns options into [DnsConfig], keeping only the servers usable with the IP family.
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-    fn to_dns_config(&self) -> DnsConfig {
-        match self {
-            Self::Default => DnsConfig::default(),
-            Self::Custom(addrs) => {
-                if addrs.is_empty() {
-                    DnsConfig::default()
-                } else {
-                    let (non_tunnel_config, tunnel_config): (Vec<_>, Vec<_>) = addrs
-                        .iter()
-                        // Private IP ranges should not be tunneled
-                        .partition(|&addr| nym_firewall::is_local_address(addr));
-                    DnsConfig::from_addresses(&tunnel_config, &non_tunnel_config)
+    fn to_dns_config(&self, ip_family: IpFamily) -> DnsConfig {
+        let addrs = match self {
+            Self::Custom(addrs) if !addrs.is_empty() => {
+                let usable = ip_family.dns_servers(addrs);
+                if usable.is_empty() {
+                    tracing::warn!(
+                        "No custom DNS server is usable in {ip_family} mode, using the defaults"
+                    );
                 }
+                usable
             }
-        }
+            _ => Vec::new(),
+        };
+        let addrs = if addrs.is_empty() {
+            ip_family.dns_servers(&crate::DEFAULT_DNS_SERVERS)
+        } else {
+            addrs
+        };
+
+        let (non_tunnel_config, tunnel_config): (Vec<_>, Vec<_>) = addrs
+            .into_iter()
+            // Private IP ranges should not be tunneled
+            .partition(|addr| nym_firewall::is_local_address(addr));
+        DnsConfig::from_addresses(&tunnel_config, &non_tunnel_config)
     }
 
     #[cfg(any(target_os = "ios", target_os = "android"))]
@@ -194,6 +287,111 @@ impl DnsOptions {
     }
 }
 
+/// Destinations routed through the tunnel. Only has an effect on Linux and macOS.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub enum TunnelRoutes {
+    /// Route all traffic through the tunnel.
+    #[default]
+    All,
+    /// Route all traffic through the tunnel, except traffic to these networks.
+    Exclude(Vec<IpNetwork>),
+    /// Route only traffic to these networks through the tunnel.
+    IncludeOnly(Vec<IpNetwork>),
+}
+
+impl TunnelRoutes {
+    /// Adds the tunnel DNS servers to the included networks, so that DNS queries keep going
+    /// through the tunnel in include-only mode.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    fn with_tunnel_dns(&self, dns_servers: &[IpAddr]) -> Self {
+        match self {
+            Self::IncludeOnly(networks) => {
+                let mut networks = networks.clone();
+                for server in dns_servers {
+                    let server = IpNetwork::from(*server);
+                    if !networks.iter().any(|network| network.contains(server.ip())) {
+                        networks.push(server);
+                    }
+                }
+                Self::IncludeOnly(networks)
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Traffic the firewall has to allow outside of the tunnel.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn allowed_non_tunnel_traffic(&self) -> AllowedNonTunnelTraffic {
+        match self {
+            Self::All => AllowedNonTunnelTraffic::None,
+            Self::Exclude(networks) => AllowedNonTunnelTraffic::Networks(networks.clone()),
+            Self::IncludeOnly(networks) => AllowedNonTunnelTraffic::AllExcept(networks.clone()),
+        }
+    }
+}
+
+impl fmt::Display for TunnelRoutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |networks: &[IpNetwork]| {
+            networks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Exclude(networks) => write!(f, "all except {}", join(networks)),
+            Self::IncludeOnly(networks) => write!(f, "only {}", join(networks)),
+        }
+    }
+}
+
+/// IP families configured inside the tunnel.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum IpFamily {
+    /// Configure both IPv4 and IPv6.
+    #[default]
+    DualStack,
+    /// Configure only IPv4 and drop all IPv6 traffic outside of the tunnel.
+    Ipv4Only,
+    /// Configure both IPv4 and IPv6, and prefer the IPv6 DNS servers.
+    Ipv6Preferred,
+}
+
+impl IpFamily {
+    /// Whether IPv6 is configured in the tunnel.
+    pub fn ipv6_enabled(self) -> bool {
+        self != Self::Ipv4Only
+    }
+
+    /// Returns the DNS servers usable with this family, with the IPv6 servers first if IPv6 is
+    /// preferred.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    fn dns_servers(self, servers: &[IpAddr]) -> Vec<IpAddr> {
+        let mut servers = servers
+            .iter()
+            .filter(|server| server.is_ipv4() || self.ipv6_enabled())
+            .copied()
+            .collect::<Vec<_>>();
+        if self == Self::Ipv6Preferred {
+            // Stable, so that the order within each family is kept
+            servers.sort_by_key(IpAddr::is_ipv4);
+        }
+        servers
+    }
+}
+
+impl fmt::Display for IpFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DualStack => f.write_str("dual-stack"),
+            Self::Ipv4Only => f.write_str("IPv4 only"),
+            Self::Ipv6Preferred => f.write_str("IPv6 preferred"),
+        }
+    }
+}
+
 impl Default for TunnelSettings {
     fn default() -> Self {
         Self {
@@ -206,13 +404,17 @@ impl Default for TunnelSettings {
             gateway_performance_options: GatewayPerformanceOptions::default(),
             entry_point: Box::new(EntryPoint::Random),
             exit_point: Box::new(ExitPoint::Random),
+            custom_gateways: Vec::new(),
+            precheck_gateways: false,
             dns: DnsOptions::default(),
+            tunnel_routes: TunnelRoutes::default(),
+            ip_family: IpFamily::default(),
             user_agent: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum TunnelCommand {
     /// Connect the tunnel.
     Connect,
@@ -222,6 +424,10 @@ pub enum TunnelCommand {
 
     /// Set new tunnel settings.
     SetTunnelSettings(TunnelSettings),
+
+    /// Check that DNS queries and traffic leave through the tunnel. Fails unless connected.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    DiagnoseLeaks(oneshot::Sender<Result<LeakReport, LeakTestError>>),
 }
 
 impl From<PrivateTunnelState> for TunnelState {
@@ -349,31 +555,68 @@ impl From<TunnelInterface> for nym_firewall::TunnelInterface {
 pub struct SharedState {
     mixnet_event_sender: mpsc::UnboundedSender<MixnetEvent>,
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-    route_handler: RouteHandler,
+    route_handler: Box<dyn RouteControl>,
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-    firewall: Firewall,
+    firewall: Box<dyn FirewallControl>,
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-    dns_handler: DnsHandlerHandle,
-    offline_monitor: nym_offline_monitor::MonitorHandle,
+    dns_handler: Box<dyn DnsControl>,
+    offline_monitor: Box<dyn ConnectivityMonitor>,
+    tunnel_connector: Box<dyn TunnelConnector>,
     /// Filtering resolver handle
     #[cfg(target_os = "macos")]
     filtering_resolver: resolver::ResolverHandle,
+    /// Filtering resolver handle, only running once DNS filtering or domain routes were needed
+    #[cfg(target_os = "linux")]
+    filtering_resolver: Option<resolver::ResolverHandle>,
     nym_config: NymConfig,
     tunnel_settings: TunnelSettings,
     status_listener_handle: Option<JoinHandle<()>>,
     #[cfg(target_os = "ios")]
     tun_provider: Arc<dyn OSTunProvider>,
-    #[cfg(target_os = "android")]
-    tun_provider: Arc<dyn AndroidTunProvider>,
     account_command_tx: AccountControllerCommander,
 }
 
+impl SharedState {
+    /// Returns the filtering resolver, starting it on first use. Failing to bind the resolver
+    /// leaves DNS unfiltered rather than failing the connection.
+    #[cfg(target_os = "linux")]
+    async fn filtering_resolver(&mut self) -> Option<&resolver::ResolverHandle> {
+        if self.filtering_resolver.is_none() {
+            let filter = self.nym_config.dns_filter.clone();
+            match resolver::start_resolver(resolver::LINUX_RESOLVER_ADDR, filter).await {
+                Ok(filtering_resolver) => self.filtering_resolver = Some(filtering_resolver),
+                Err(e) => tracing::warn!(
+                    "Failed to start the local resolver on {}, using the tunnel DNS directly: {e}",
+                    resolver::LINUX_RESOLVER_ADDR
+                ),
+            }
+        }
+        self.filtering_resolver.as_ref()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NymConfig {
     pub config_path: Option<PathBuf>,
     pub data_path: Option<PathBuf>,
     pub gateway_config: GatewayDirectoryConfig,
     pub network_env: Network,
+    /// The mechanism used to manage the system DNS.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    pub dns_backend: DnsBackend,
+    /// Service the leak test asks for the address traffic is seen coming from, defaults to
+    /// api.ipify.org.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    pub leak_test_ip_url: Option<Url>,
+    /// Domain filter applied by the local resolver. Not supported on Windows.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    pub dns_filter: Option<Arc<DnsFilter>>,
+    /// Domains resolved by their own servers outside the tunnel. Connecting fails on Windows if
+    /// any are set.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    pub dns_routes: Vec<DomainRoute>,
+    /// Health check used when `TunnelSettings::precheck_gateways` is set.
+    pub gateway_precheck: Option<Arc<dyn GatewayPrecheck>>,
 }
 
 pub struct TunnelStateMachine {
@@ -398,12 +641,26 @@ impl TunnelStateMachine {
         account_command_tx: AccountControllerCommander,
         #[cfg(target_os = "ios")] tun_provider: Arc<dyn OSTunProvider>,
         #[cfg(target_os = "android")] tun_provider: Arc<dyn AndroidTunProvider>,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_status_tx: watch::Sender<DnsStatus>,
         shutdown_token: CancellationToken,
     ) -> Result<JoinHandle<()>> {
         #[cfg(target_os = "macos")]
-        let filtering_resolver = resolver::start_resolver()
-            .await
-            .map_err(Error::StartLocalDnsResolver)?;
+        let filtering_resolver = resolver::start_resolver(
+            std::net::SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0),
+            nym_config.dns_filter.clone(),
+        )
+        .await
+        .map_err(Error::StartLocalDnsResolver)?;
+
+        // Started on first use, see `SharedState::filtering_resolver`
+        #[cfg(target_os = "linux")]
+        let filtering_resolver = None;
+
+        #[cfg(target_os = "windows")]
+        if nym_config.dns_filter.is_some() {
+            tracing::warn!("DNS filtering is not supported on Windows");
+        }
 
         #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
         let route_handler = RouteHandler::new()
@@ -414,8 +671,10 @@ impl TunnelStateMachine {
         let dns_handler_shutdown_token = CancellationToken::new();
         #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
         let (dns_handler, dns_handler_task) = DnsHandlerHandle::spawn(
+            nym_config.dns_backend,
             #[cfg(target_os = "linux")]
             &route_handler,
+            dns_status_tx,
             dns_handler_shutdown_token.child_token(),
         )
         .map_err(Error::CreateDnsHandler)?;
@@ -441,26 +700,61 @@ impl TunnelStateMachine {
         })
         .map_err(Error::CreateFirewall)?;
 
-        let mut shared_state = SharedState {
+        let tunnel_connector = DefaultTunnelConnector::new(
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            route_handler.clone(),
+            #[cfg(any(target_os = "ios", target_os = "android"))]
+            tun_provider.clone(),
+        );
+
+        let shared_state = SharedState {
             mixnet_event_sender,
             #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-            route_handler,
+            route_handler: Box::new(route_handler),
             #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-            firewall,
+            firewall: Box::new(firewall),
             #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-            dns_handler,
-            offline_monitor,
-            #[cfg(target_os = "macos")]
+            dns_handler: Box::new(dns_handler),
+            offline_monitor: Box::new(offline_monitor),
+            tunnel_connector: Box::new(tunnel_connector),
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
             filtering_resolver,
             nym_config,
             tunnel_settings,
             status_listener_handle: None,
-            #[cfg(any(target_os = "ios", target_os = "android"))]
+            #[cfg(target_os = "ios")]
             tun_provider,
             account_command_tx,
         };
 
-        let (current_state_handler, _) = if shared_state
+        let tunnel_state_machine = Self::new(
+            shared_state,
+            command_receiver,
+            event_sender,
+            mixnet_event_receiver,
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            dns_handler_task,
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            dns_handler_shutdown_token,
+            shutdown_token,
+        )
+        .await;
+
+        Ok(tokio::spawn(tunnel_state_machine.run()))
+    }
+
+    async fn new(
+        mut shared_state: SharedState,
+        command_receiver: mpsc::UnboundedReceiver<TunnelCommand>,
+        event_sender: mpsc::UnboundedSender<TunnelEvent>,
+        mixnet_event_receiver: mpsc::UnboundedReceiver<MixnetEvent>,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_handler_task: JoinHandle<()>,
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        dns_handler_shutdown_token: CancellationToken,
+        shutdown_token: CancellationToken,
+    ) -> Self {
+        let (current_state_handler, initial_state) = if shared_state
             .offline_monitor
             .connectivity()
             .await
@@ -471,7 +765,12 @@ impl TunnelStateMachine {
             DisconnectedState::enter(&mut shared_state).await
         };
 
-        let tunnel_state_machine = Self {
+        // Report the initial state too, so that listeners learn when the host starts offline
+        let state = TunnelState::from(initial_state);
+        tracing::info!("Initial tunnel state: {}", state);
+        let _ = event_sender.send(TunnelEvent::NewState(state));
+
+        Self {
             current_state_handler,
             shared_state,
             command_receiver,
@@ -482,9 +781,7 @@ impl TunnelStateMachine {
             #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
             dns_handler_shutdown_token,
             shutdown_token,
-        };
-
-        Ok(tokio::spawn(tunnel_state_machine.run()))
+        }
     }
 
     async fn run(mut self) {
@@ -558,7 +855,7 @@ pub enum Error {
     #[error("failed to resolve gateway addresses: {}", _0)]
     ResolveGatewayAddrs(#[source] nym_gateway_directory::Error),
 
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
     #[error("failed to start local dns resolver: {}", _0)]
     StartLocalDnsResolver(#[source] resolver::Error),
 
@@ -601,6 +898,9 @@ pub enum Error {
     #[error("failed to set dns: {}", _0)]
     SetDns(#[source] dns_handler::Error),
 
+    #[error("invalid WireGuard MTU: {}", _0)]
+    InvalidWireguardMtu(#[source] MtuMismatch),
+
     #[error("tunnel error: {}", _0)]
     Tunnel(#[from] tunnel::Error),
 
@@ -636,6 +936,7 @@ impl Error {
             Self::LocateTunDevice(_) => ErrorStateReason::TunDevice,
             #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
             Self::GetRouteHandle(e) => ErrorStateReason::Internal(e.to_string()),
+            Self::InvalidWireguardMtu(e) => ErrorStateReason::Internal(e.to_string()),
             Self::Account(err) => err.error_state_reason()?,
         })
     }
@@ -673,6 +974,7 @@ impl tunnel::Error {
             | Self::SetupStoragePaths(_)
             | Self::StartMixnetClientTimeout
             | Self::CreateGatewayClient(_)
+            | Self::GatewayPrecheck(_)
             | Self::BandwidthController(_)
             | Self::Wireguard(_)
             | Self::Cancelled => None,
@@ -697,3 +999,31 @@ impl account::Error {
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(all(
+    test,
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_servers_of_ip_family() {
+        let servers: Vec<IpAddr> = vec![
+            "1.1.1.1".parse().unwrap(),
+            "2606:4700:4700::1111".parse().unwrap(),
+            "1.0.0.1".parse().unwrap(),
+            "2606:4700:4700::1001".parse().unwrap(),
+        ];
+
+        assert_eq!(IpFamily::DualStack.dns_servers(&servers), servers);
+        assert_eq!(
+            IpFamily::Ipv4Only.dns_servers(&servers),
+            [servers[0], servers[2]]
+        );
+        assert_eq!(
+            IpFamily::Ipv6Preferred.dns_servers(&servers),
+            [servers[1], servers[3], servers[0], servers[2]]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    ffi::CString,
+    io,
+    net::{Ipv4Addr, SocketAddr, UdpSocket as StdUdpSocket},
+    os::fd::AsRawFd,
+    time::Duration,
+};
+
+use futures::future::join_all;
+use nix::{
+    libc,
+    sys::socket::{self, AddressFamily, SockFlag, SockProtocol, SockType},
+};
+use tokio::net::UdpSocket;
+
+use super::tunnel::wireguard::two_hop_config::{MIN_IPV6_MTU, WG_TUNNEL_OVERHEAD};
+
+/// Size of the IPv4 and ICMP headers added to the ping payload.
+const IPV4_ICMP_HEADER_SIZE: u16 = 28;
+
+const ICMP_HEADER_SIZE: usize = 8;
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_ECHO_REPLY: u8 = 0;
+
+/// Time to wait for a reply to each probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Maximum number of probes sent at once.
+const MAX_PROBES: u16 = 16;
+
+#[cfg(target_os = "linux")]
+nix::ioctl_write_ptr_bad!(siocsifmtu, libc::SIOCSIFMTU, libc::ifreq);
+
+// _IOW('i', 52, struct ifreq), not exported by libc on macOS
+#[cfg(target_os = "macos")]
+nix::ioctl_write_ptr!(siocsifmtu, b'i', 52, libc::ifreq);
+
+/// Probes the path MTU through the tunnel and lowers the tunnel MTUs if full sized packets don't
+/// get through, e.g. on PPPoE or tethered links.
+pub struct MtuProbe {
+    /// Interface of the innermost tunnel that the probes are sent over.
+    pub interface: String,
+
+    /// Interface of the outer tunnel carrying the traffic of `interface`, if any.
+    pub outer_interface: Option<String>,
+
+    /// Host replying to the probes through the tunnel.
+    pub destination: Ipv4Addr,
+
+    /// Current MTU of `interface`.
+    pub mtu: u16,
+}
+
+impl MtuProbe {
+    pub async fn run(self) {
+        tracing::debug!(
+            "Probing the path MTU over {} towards {}",
+            self.interface,
+            self.destination
+        );
+
+        let Some(verified_mtu) =
+            probe_mtu(&self.interface, self.destination, MIN_IPV6_MTU, self.mtu).await
+        else {
+            tracing::warn!(
+                "No MTU probe got a reply, keeping the tunnel MTU at {}",
+                self.mtu
+            );
+            return;
+        };
+
+        if verified_mtu >= self.mtu {
+            tracing::info!("Verified tunnel MTU {}", self.mtu);
+            return;
+        }
+
+        tracing::info!(
+            "Lowering the tunnel MTU from {} to {}",
+            self.mtu,
+            verified_mtu
+        );
+        if let Err(e) = set_mtu(&self.interface, verified_mtu) {
+            tracing::error!("Failed to set the MTU of {}: {}", self.interface, e);
+        }
+        if let Some(outer_interface) = self.outer_interface {
+            if let Err(e) = set_mtu(&outer_interface, verified_mtu + WG_TUNNEL_OVERHEAD) {
+                tracing::error!("Failed to set the MTU of {}: {}", outer_interface, e);
+            }
+        }
+    }
+}
+
+/// Sends pings that must not be fragmented, and returns the largest MTU that got a reply.
+async fn probe_mtu(
+    interface: &str,
+    destination: Ipv4Addr,
+    min_mtu: u16,
+    max_mtu: u16,
+) -> Option<u16> {
+    let identifier = rand::random();
+    let probes = probe_sizes(min_mtu, max_mtu)
+        .into_iter()
+        .map(|mtu| async move {
+            match ping(interface, destination, identifier, mtu).await {
+                Ok(replied) => replied.then_some(mtu),
+                Err(e) => {
+                    tracing::debug!("MTU probe of {} failed: {}", mtu, e);
+                    None
+                }
+            }
+        });
+
+    join_all(probes).await.into_iter().flatten().max()
+}
+
+/// Returns at most `MAX_PROBES` MTUs between `min_mtu` and `max_mtu`, including both.
+fn probe_sizes(min_mtu: u16, max_mtu: u16) -> Vec<u16> {
+    if min_mtu >= max_mtu {
+        return vec![max_mtu];
+    }
+    let step = (max_mtu - min_mtu).div_ceil(MAX_PROBES - 1);
+    let mut sizes = (min_mtu..max_mtu)
+        .step_by(usize::from(step))
+        .collect::<Vec<_>>();
+    sizes.push(max_mtu);
+    sizes
+}
+
+/// Sends an ICMP echo request filling `mtu` over `interface`, and waits for the reply. The MTU is
+/// used as sequence number, so that the replies of concurrent probes can be told apart.
+async fn ping(
+    interface: &str,
+    destination: Ipv4Addr,
+    identifier: u16,
+    mtu: u16,
+) -> io::Result<bool> {
+    let socket = icmp_socket(interface)?;
+    let request = echo_request(identifier, mtu, usize::from(mtu - IPV4_ICMP_HEADER_SIZE));
+    match socket
+        .send_to(&request, SocketAddr::new(destination.into(), 0))
+        .await
+    {
+        Ok(_) => {}
+        // Larger than the MTU of the interface
+        Err(e) if e.raw_os_error() == Some(libc::EMSGSIZE) => return Ok(false),
+        Err(e) => return Err(e),
+    }
+
+    let mut buf = vec![0; usize::from(mtu)];
+    let wait_for_reply = async {
+        loop {
+            let len = socket.recv(&mut buf).await?;
+            if is_echo_reply(&buf[..len], identifier, mtu) {
+                return Ok(true);
+            }
+        }
+    };
+    tokio::time::timeout(PROBE_TIMEOUT, wait_for_reply)
+        .await
+        .unwrap_or(Ok(false))
+}
+
+/// Opens a raw ICMP socket bound to `interface`, sending packets with the don't fragment bit set.
+fn icmp_socket(interface: &str) -> io::Result<UdpSocket> {
+    let fd = socket::socket(
+        AddressFamily::Inet,
+        SockType::Raw,
+        SockFlag::empty(),
+        SockProtocol::Icmp,
+    )?;
+
+    #[cfg(target_os = "linux")]
+    {
+        socket::setsockopt(&fd, socket::sockopt::BindToDevice, &interface.into())?;
+        setsockopt_int(
+            fd.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            libc::IP_PMTUDISC_DO,
+        )?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let index = nix::net::if_::if_nametoindex(interface)?;
+        setsockopt_int(
+            fd.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_BOUND_IF,
+            index as libc::c_int,
+        )?;
+        socket::setsockopt(&fd, socket::sockopt::IpDontFrag, &true)?;
+    }
+
+    // Raw sockets support the datagram calls of UDP sockets, which tokio knows how to drive
+    let socket = StdUdpSocket::from(fd);
+    socket.set_nonblocking(true)?;
+    UdpSocket::from_std(socket)
+}
+
+fn setsockopt_int(
+    fd: i32,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: libc::c_int,
+) -> io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            name,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+fn echo_request(identifier: u16, sequence: u16, payload_size: usize) -> Vec<u8> {
+    let mut packet = vec![0; ICMP_HEADER_SIZE + payload_size];
+    packet[0] = ICMP_ECHO_REQUEST;
+    packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+    packet[6..8].copy_from_slice(&sequence.to_be_bytes());
+    let checksum = internet_checksum(&packet);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    packet
+}
+
+/// Checks whether `packet`, an IPv4 packet as read from a raw socket, is the reply to the echo
+/// request with the given identifier and sequence number.
+fn is_echo_reply(packet: &[u8], identifier: u16, sequence: u16) -> bool {
+    let Some(header_len) = packet.first().map(|byte| usize::from(byte & 0x0f) * 4) else {
+        return false;
+    };
+    let Some(icmp) = packet.get(header_len..header_len + ICMP_HEADER_SIZE) else {
+        return false;
+    };
+    icmp[0] == ICMP_ECHO_REPLY
+        && icmp[4..6] == identifier.to_be_bytes()
+        && icmp[6..8] == sequence.to_be_bytes()
+}
+
+/// The ones' complement checksum of RFC 1071.
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum = data
+        .chunks(2)
+        .map(|chunk| match chunk {
+            [high, low] => u32::from(u16::from_be_bytes([*high, *low])),
+            [high] => u32::from(*high) << 8,
+            _ => unreachable!(),
+        })
+        .sum::<u32>();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn set_mtu(interface: &str, mtu: u16) -> io::Result<()> {
+    let name = CString::new(interface).map_err(io::Error::other)?;
+    let name = name.as_bytes_with_nul();
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    if name.len() > ifr.ifr_name.len() {
+        return Err(io::Error::other("interface name too long"));
+    }
+    for (dst, src) in ifr.ifr_name.iter_mut().zip(name) {
+        *dst = *src as libc::c_char;
+    }
+    ifr.ifr_ifru.ifru_mtu = libc::c_int::from(mtu);
+
+    let fd = socket::socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None,
+    )?;
+    unsafe { siocsifmtu(fd.as_raw_fd(), &ifr) }?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_sizes_cover_range() {
+        let sizes = probe_sizes(1280, 1420);
+        assert!(sizes.len() <= usize::from(MAX_PROBES));
+        assert_eq!(sizes.first(), Some(&1280));
+        assert_eq!(sizes.last(), Some(&1420));
+        assert!(sizes.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn probe_sizes_small_range() {
+        assert_eq!(probe_sizes(1280, 1283), vec![1280, 1281, 1282, 1283]);
+        assert_eq!(probe_sizes(1280, 1280), vec![1280]);
+        assert_eq!(probe_sizes(1400, 1280), vec![1280]);
+    }
+}
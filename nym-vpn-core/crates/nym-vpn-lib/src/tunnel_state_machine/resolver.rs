@@ -9,6 +9,9 @@
 //!   lets us use the routing table to determine where to send them, instead of them being forced
 //!   out on the primary interface (in some cases).
 //!
+//! When a [DnsFilter] is given, forwarded queries for blocked domains receive an empty response.
+//! Queries matching a [DomainRoute] are forwarded to the servers of the route instead.
+//!
 //! See [start_resolver].
 use std::{
     io,
@@ -46,6 +49,10 @@ use hickory_server::{
     ServerFuture,
 };
 
+use nym_dns::DomainRoute;
+
+use super::dns_filter::DnsFilter;
+
 const ALLOWED_RECORD_TYPES: &[RecordType] = &[RecordType::A, RecordType::CNAME];
 const CAPTIVE_PORTAL_DOMAINS: &[&str] = &["captive.apple.com", "netcts.cdn-apple.com"];
 
@@ -61,10 +68,19 @@ const TTL_SECONDS: u32 = 3;
 /// belongs to the documentation range so should never be reachable.
 const RESOLVED_ADDR: Ipv4Addr = Ipv4Addr::new(198, 51, 100, 1);
 
-/// Starts a resolver. Returns a cloneable handle, which can activate, deactivate and shut down the
-/// resolver. When all instances of a handle are dropped, the server will stop.
-pub async fn start_resolver() -> Result<ResolverHandle, Error> {
-    let (resolver, resolver_handle) = LocalResolver::new().await?;
+/// Address the resolver listens on for Linux, where the system DNS config can't carry a port.
+#[cfg(target_os = "linux")]
+pub const LINUX_RESOLVER_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 153)), 53);
+
+/// Starts a resolver listening on `listen_addr`. Returns a cloneable handle, which can activate,
+/// deactivate and shut down the resolver. When all instances of a handle are dropped, the server
+/// will stop.
+pub async fn start_resolver(
+    listen_addr: SocketAddr,
+    filter: Option<Arc<DnsFilter>>,
+) -> Result<ResolverHandle, Error> {
+    let (resolver, resolver_handle) = LocalResolver::new(listen_addr, filter).await?;
     tokio::spawn(resolver.run());
     Ok(resolver_handle)
 }
@@ -88,6 +104,7 @@ struct LocalResolver {
     rx: mpsc::UnboundedReceiver<ResolverMessage>,
     dns_server: Option<(tokio::task::JoinHandle<()>, oneshot::Receiver<()>)>,
     inner_resolver: Resolver,
+    filter: Option<Arc<DnsFilter>>,
 }
 
 /// A message to [LocalResolver]
@@ -120,6 +137,8 @@ enum Config {
     Forwarding {
         /// Remote DNS server to use
         dns_servers: Vec<IpAddr>,
+        /// Domains forwarded to their own servers
+        domain_routes: Vec<DomainRoute>,
     },
 }
 
@@ -127,34 +146,53 @@ enum Resolver {
     /// Drop DNS queries. For captive portal domains, return faux records
     Blocking,
 
-    /// Forward DNS queries to a configured server
-    Forwarding(TokioAsyncResolver),
+    /// Forward DNS queries to a configured server, or to the servers of the most specific
+    /// matching domain route
+    Forwarding {
+        resolver: TokioAsyncResolver,
+        domain_routes: Vec<(DomainRoute, TokioAsyncResolver)>,
+    },
 }
 
 impl From<Config> for Resolver {
-    fn from(mut config: Config) -> Self {
-        match &mut config {
+    fn from(config: Config) -> Self {
+        match config {
             Config::Blocking => Resolver::Blocking,
-            Config::Forwarding { dns_servers } => {
-                // make sure not to accidentally forward queries to ourselves
-                dns_servers.retain(|addr| !addr.is_loopback());
-
-                let forward_server_config =
-                    NameServerConfigGroup::from_ips_clear(dns_servers, 53, true);
-
-                let forward_config =
-                    ResolverConfig::from_parts(None, vec![], forward_server_config);
-                let resolver_opts = ResolverOpts::default();
-
-                let resolver = TokioAsyncResolver::tokio(forward_config, resolver_opts);
-
-                Resolver::Forwarding(resolver)
+            Config::Forwarding {
+                dns_servers,
+                mut domain_routes,
+            } => {
+                domain_routes.sort_by_key(|route| std::cmp::Reverse(route.domain.len()));
+                let domain_routes = domain_routes
+                    .into_iter()
+                    .map(|route| {
+                        let resolver = Self::forwarding_resolver(route.servers.clone());
+                        (route, resolver)
+                    })
+                    .collect();
+
+                Resolver::Forwarding {
+                    resolver: Self::forwarding_resolver(dns_servers),
+                    domain_routes,
+                }
             }
         }
     }
 }
 
 impl Resolver {
+    fn forwarding_resolver(mut dns_servers: Vec<IpAddr>) -> TokioAsyncResolver {
+        // make sure not to accidentally forward queries to ourselves
+        dns_servers.retain(|addr| !addr.is_loopback());
+
+        let forward_server_config = NameServerConfigGroup::from_ips_clear(&dns_servers, 53, true);
+
+        let forward_config = ResolverConfig::from_parts(None, vec![], forward_server_config);
+        let resolver_opts = ResolverOpts::default();
+
+        TokioAsyncResolver::tokio(forward_config, resolver_opts)
+    }
+
     pub fn resolve(
         &self,
         query: LowerQuery,
@@ -162,7 +200,15 @@ impl Resolver {
     ) {
         let lookup = match self {
             Resolver::Blocking => Either::Left(async move { Self::resolve_blocked(query) }),
-            Resolver::Forwarding(resolver) => {
+            Resolver::Forwarding {
+                resolver,
+                domain_routes,
+            } => {
+                let name = query.name().to_string();
+                let resolver = domain_routes
+                    .iter()
+                    .find(|(route, _)| route.matches(&name))
+                    .map_or(resolver, |(_, resolver)| resolver);
                 Either::Right(Self::resolve_forward(resolver.clone(), query))
             }
         };
@@ -228,24 +274,33 @@ impl Resolver {
 #[derive(Clone)]
 pub struct ResolverHandle {
     tx: Arc<mpsc::UnboundedSender<ResolverMessage>>,
-    listening_port: u16,
+    listening_addr: SocketAddr,
 }
 
 impl ResolverHandle {
-    fn new(tx: Arc<mpsc::UnboundedSender<ResolverMessage>>, listening_port: u16) -> Self {
-        Self { tx, listening_port }
+    fn new(tx: Arc<mpsc::UnboundedSender<ResolverMessage>>, listening_addr: SocketAddr) -> Self {
+        Self { tx, listening_addr }
+    }
+
+    /// Get listening address for resolver handle
+    pub fn listening_addr(&self) -> SocketAddr {
+        self.listening_addr
     }
 
     /// Get listening port for resolver handle
     pub fn listening_port(&self) -> u16 {
-        self.listening_port
+        self.listening_addr.port()
     }
 
-    /// Set the DNS server to forward queries to `dns_servers`
-    pub async fn enable_forward(&self, dns_servers: Vec<IpAddr>) {
+    /// Set the DNS server to forward queries to `dns_servers`, except for the domains of
+    /// `domain_routes`
+    pub async fn enable_forward(&self, dns_servers: Vec<IpAddr>, domain_routes: Vec<DomainRoute>) {
         let (response_tx, response_rx) = oneshot::channel();
         let _ = self.tx.unbounded_send(ResolverMessage::SetConfig {
-            new_config: Config::Forwarding { dns_servers },
+            new_config: Config::Forwarding {
+                dns_servers,
+                domain_routes,
+            },
             response_tx,
         });
 
@@ -266,12 +321,15 @@ impl ResolverHandle {
 
 impl LocalResolver {
     /// Constructs a new filtering resolver and it's handle.
-    async fn new() -> Result<(Self, ResolverHandle), Error> {
+    async fn new(
+        listen_addr: SocketAddr,
+        filter: Option<Arc<DnsFilter>>,
+    ) -> Result<(Self, ResolverHandle), Error> {
         let (tx, rx) = mpsc::unbounded();
         let command_tx = Arc::new(tx);
 
         let weak_tx = Arc::downgrade(&command_tx);
-        let (mut server, port) = Self::new_server(0, weak_tx.clone()).await?;
+        let (mut server, listening_addr) = Self::new_server(listen_addr, weak_tx.clone()).await?;
 
         let (server_done_tx, server_done_rx) = oneshot::channel();
         let server_handle = tokio::spawn(async move {
@@ -281,8 +339,8 @@ impl LocalResolver {
 
                     if weak_tx.strong_count() > 0 {
                         tracing::debug!("Attempting restart server");
-                        match Self::new_server(port, weak_tx.clone()).await {
-                            Ok((new_server, _port)) => {
+                        match Self::new_server(listening_addr, weak_tx.clone()).await {
+                            Ok((new_server, _addr)) => {
                                 server = new_server;
                                 continue;
                             }
@@ -302,28 +360,43 @@ impl LocalResolver {
             rx,
             dns_server: Some((server_handle, server_done_rx)),
             inner_resolver: Resolver::from(Config::Blocking),
+            filter,
         };
 
-        Ok((resolver, ResolverHandle::new(command_tx, port)))
+        Ok((resolver, ResolverHandle::new(command_tx, listening_addr)))
     }
 
     async fn new_server(
-        port: u16,
+        listen_addr: SocketAddr,
         command_tx: Weak<mpsc::UnboundedSender<ResolverMessage>>,
-    ) -> Result<(ServerFuture<ResolverImpl>, u16), Error> {
+    ) -> Result<(ServerFuture<ResolverImpl>, SocketAddr), Error> {
         let mut server = ServerFuture::new(ResolverImpl { tx: command_tx });
 
-        let server_listening_socket =
-            tokio::net::UdpSocket::bind(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port))
-                .await
-                .map_err(Error::UdpBindError)?;
-        let port = server_listening_socket
+        let server_listening_socket = tokio::net::UdpSocket::bind(listen_addr)
+            .await
+            .map_err(Error::UdpBindError)?;
+        let listening_addr = server_listening_socket
             .local_addr()
-            .map_err(Error::GetSocketAddrError)?
-            .port();
+            .map_err(Error::GetSocketAddrError)?;
         server.register_socket(server_listening_socket);
 
-        Ok((server, port))
+        Ok((server, listening_addr))
+    }
+
+    /// Returns true if the query is forwarded and the filter blocks it.
+    fn is_filtered(&self, query: &LowerQuery) -> bool {
+        let (Resolver::Forwarding { .. }, Some(filter)) = (&self.inner_resolver, &self.filter)
+        else {
+            return false;
+        };
+
+        match filter.check(&query.name().to_string()) {
+            Some(category) => {
+                tracing::debug!("Blocking query for {} ({category})", query.name());
+                true
+            }
+            None => false,
+        }
     }
 
     /// Runs the filtering resolver as an actor, listening for new queries instances.  When all
@@ -346,7 +419,11 @@ impl LocalResolver {
                     dns_query,
                     response_tx,
                 } => {
-                    self.inner_resolver.resolve(dns_query, response_tx);
+                    if self.is_filtered(&dns_query) {
+                        let _ = response_tx.send(Ok(Box::new(EmptyLookup)));
+                    } else {
+                        self.inner_resolver.resolve(dns_query, response_tx);
+                    }
                 }
             }
         }
@@ -359,15 +436,23 @@ impl LocalResolver {
 }
 
 /// Flush the DNS cache.
+#[cfg(target_os = "macos")]
 fn flush_system_cache() {
     if let Err(error) = kill_mdnsresponder() {
         tracing::error!("Failed to kill mDNSResponder: {error}");
     }
 }
 
+/// The system DNS config is re-applied on each connection, which flushes the caches of the
+/// system resolver.
+#[cfg(target_os = "linux")]
+fn flush_system_cache() {}
+
+#[cfg(target_os = "macos")]
 const MDNS_RESPONDER_PATH: &str = "/usr/sbin/mDNSResponder";
 
 /// Find and kill mDNSResponder. The OS will restart the service.
+#[cfg(target_os = "macos")]
 fn kill_mdnsresponder() -> io::Result<()> {
     if let Some(mdns_pid) = nym_macos::process::pid_of_path(MDNS_RESPONDER_PATH) {
         nix::sys::signal::kill(
@@ -500,6 +585,7 @@ impl LookupObject for ForwardLookup {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::tunnel_state_machine::dns_filter::FilterCategory;
     use hickory_server::resolver::{
         config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
         TokioAsyncResolver,
@@ -507,7 +593,9 @@ mod test {
     use std::{mem, net::UdpSocket, thread, time::Duration};
 
     async fn start_resolver() -> ResolverHandle {
-        super::start_resolver().await.unwrap()
+        super::start_resolver(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0), None)
+            .await
+            .unwrap()
     }
 
     fn get_test_resolver(port: u16) -> hickory_server::resolver::TokioAsyncResolver {
@@ -553,6 +641,31 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_filtered_lookup() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+
+        let mut filter = DnsFilter::new();
+        filter.add_blocklist(FilterCategory::Ads, "ads.example.com\n");
+        let filter = Arc::new(filter);
+
+        let handle = rt
+            .block_on(super::start_resolver(
+                SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0),
+                Some(filter.clone()),
+            ))
+            .unwrap();
+        // The filter only applies to forwarded queries, the upstream is never reached
+        rt.block_on(handle.enable_forward(vec![IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))], vec![]));
+        let test_resolver = get_test_resolver(handle.listening_port());
+
+        let blocked_domain = LowerName::from(Name::from_str("www.ads.example.com").unwrap());
+        let resolver_result =
+            rt.block_on(async move { test_resolver.lookup(blocked_domain, RecordType::A).await });
+        assert!(resolver_result.is_err(), "Blocked DNS request should fail");
+        assert_eq!(filter.stats().blocked(), 1);
+    }
+
     #[test]
     fn test_shutdown() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -11,6 +11,8 @@ use nym_routing::NetNode;
 pub use nym_routing::{Callback, CallbackHandle};
 use nym_routing::{Node, RequiredRoute, RouteManagerHandle};
 
+use super::TunnelRoutes;
+
 #[cfg(target_os = "linux")]
 pub const TUNNEL_TABLE_ID: u32 = 0x14d;
 #[cfg(target_os = "linux")]
@@ -53,11 +55,24 @@ impl RouteHandler {
         Ok(Self { route_manager })
     }
 
-    pub async fn add_routes(&mut self, routing_config: RoutingConfig) -> Result<()> {
-        let routes = Self::get_routes(routing_config);
+    pub async fn add_routes(
+        &mut self,
+        routing_config: RoutingConfig,
+        tunnel_routes: &TunnelRoutes,
+        ipv6_enabled: bool,
+    ) -> Result<()> {
+        #[allow(unused_mut)]
+        let mut routes = Self::get_routes(routing_config, tunnel_routes, ipv6_enabled);
 
         #[cfg(target_os = "linux")]
-        self.route_manager.create_routing_rules().await?;
+        if let TunnelRoutes::Exclude(networks) = tunnel_routes {
+            routes.extend(self.get_excluded_routes(networks).await?);
+        }
+
+        #[cfg(target_os = "linux")]
+        self.route_manager
+            .create_routing_rules(ipv6_enabled)
+            .await?;
 
         self.route_manager.add_routes(routes).await?;
 
@@ -93,6 +108,25 @@ impl RouteHandler {
             .map_err(Error::from)
     }
 
+    /// Returns the interface that traffic to `destination` egresses via.
+    #[cfg(target_os = "linux")]
+    pub async fn destination_interface(&mut self, destination: IpAddr) -> Result<Option<String>> {
+        let route = self
+            .route_manager
+            .get_destination_route(destination, None)
+            .await?;
+        Ok(route.and_then(|route| route.get_node().get_device().map(ToOwned::to_owned)))
+    }
+
+    /// Returns the MTU of the route that traffic to `destination` takes.
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    pub async fn get_mtu_for_route(&self, destination: IpAddr) -> Result<u16> {
+        self.route_manager
+            .get_mtu_for_route(destination)
+            .await
+            .map_err(Error::from)
+    }
+
     pub async fn stop(self) {
         self.route_manager.stop().await;
     }
@@ -102,7 +136,65 @@ impl RouteHandler {
         self.route_manager.clone()
     }
 
-    fn get_routes(routing_config: RoutingConfig) -> HashSet<RequiredRoute> {
+    /// Returns routes in the main table for the excluded networks, via the route they would take
+    /// outside of the tunnel.
+    #[cfg(target_os = "linux")]
+    async fn get_excluded_routes(&self, networks: &[IpNetwork]) -> Result<Vec<RequiredRoute>> {
+        let mut routes = Vec::new();
+        for network in networks {
+            // Marked packets skip the tunnel table
+            let route = self
+                .route_manager
+                .get_destination_route(network.network(), Some(TUNNEL_FWMARK))
+                .await?;
+            match route {
+                Some(route) => {
+                    routes.push(RequiredRoute::new(*network, route.get_node().clone()));
+                }
+                None => tracing::warn!("No route to {} outside of the tunnel", network),
+            }
+        }
+        Ok(routes)
+    }
+
+    /// Returns the routes sending traffic into the tunnel device. IPv6 traffic is only routed
+    /// into the tunnel when `ipv6_enabled` is set.
+    fn get_tunnel_routes(
+        tun_name: &str,
+        tunnel_routes: &TunnelRoutes,
+        ipv6_enabled: bool,
+    ) -> Vec<RequiredRoute> {
+        let networks = match tunnel_routes {
+            TunnelRoutes::All | TunnelRoutes::Exclude(_) => {
+                vec!["0.0.0.0/0".parse().unwrap(), "::0/0".parse().unwrap()]
+            }
+            TunnelRoutes::IncludeOnly(networks) => networks.clone(),
+        };
+
+        #[allow(unused_mut)]
+        let mut routes = networks
+            .into_iter()
+            .filter(|network| network.is_ipv4() || ipv6_enabled)
+            .map(|network| RequiredRoute::new(network, Node::device(tun_name.to_owned())))
+            .collect::<Vec<_>>();
+
+        #[cfg(not(target_os = "linux"))]
+        if let TunnelRoutes::Exclude(networks) = tunnel_routes {
+            routes.extend(
+                networks
+                    .iter()
+                    .map(|network| RequiredRoute::new(*network, NetNode::DefaultNode)),
+            );
+        }
+
+        routes
+    }
+
+    fn get_routes(
+        routing_config: RoutingConfig,
+        tunnel_routes: &TunnelRoutes,
+        ipv6_enabled: bool,
+    ) -> HashSet<RequiredRoute> {
         let mut routes = HashSet::new();
 
         match routing_config {
@@ -117,14 +209,10 @@ impl RouteHandler {
                     NetNode::DefaultNode,
                 ));
 
-                routes.insert(RequiredRoute::new(
-                    "0.0.0.0/0".parse().unwrap(),
-                    Node::device(tun_name.to_owned()),
-                ));
-
-                routes.insert(RequiredRoute::new(
-                    "::0/0".parse().unwrap(),
-                    Node::device(tun_name.to_owned()),
+                routes.extend(Self::get_tunnel_routes(
+                    &tun_name,
+                    tunnel_routes,
+                    ipv6_enabled,
                 ));
             }
             RoutingConfig::Wireguard {
@@ -145,14 +233,10 @@ impl RouteHandler {
                     Node::device(entry_tun_name.to_owned()),
                 ));
 
-                routes.insert(RequiredRoute::new(
-                    "0.0.0.0/0".parse().unwrap(),
-                    Node::device(exit_tun_name.to_owned()),
-                ));
-
-                routes.insert(RequiredRoute::new(
-                    "::0/0".parse().unwrap(),
-                    Node::device(exit_tun_name.to_owned()),
+                routes.extend(Self::get_tunnel_routes(
+                    &exit_tun_name,
+                    tunnel_routes,
+                    ipv6_enabled,
                 ));
             }
             RoutingConfig::WireguardNetstack {
@@ -166,14 +250,10 @@ impl RouteHandler {
                     NetNode::DefaultNode,
                 ));
 
-                routes.insert(RequiredRoute::new(
-                    "0.0.0.0/0".parse().unwrap(),
-                    Node::device(exit_tun_name.to_owned()),
-                ));
-
-                routes.insert(RequiredRoute::new(
-                    "::0/0".parse().unwrap(),
-                    Node::device(exit_tun_name.to_owned()),
+                routes.extend(Self::get_tunnel_routes(
+                    &exit_tun_name,
+                    tunnel_routes,
+                    ipv6_enabled,
                 ));
             }
         }
@@ -217,3 +297,79 @@ impl fmt::Display for Error {
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routing_config() -> RoutingConfig {
+        RoutingConfig::Mixnet {
+            tun_name: "nymtun0".to_owned(),
+            #[cfg(not(target_os = "linux"))]
+            entry_gateway_address: "192.0.2.1".parse().unwrap(),
+        }
+    }
+
+    fn tunnel_route(prefix: &str) -> RequiredRoute {
+        let route = RequiredRoute::new(prefix.parse().unwrap(), Node::device("nymtun0".to_owned()));
+        #[cfg(target_os = "linux")]
+        let route = route.use_main_table(false);
+        route
+    }
+
+    #[test]
+    fn all_traffic_through_tunnel() {
+        let routes = RouteHandler::get_routes(routing_config(), &TunnelRoutes::All, true);
+        assert!(routes.contains(&tunnel_route("0.0.0.0/0")));
+        assert!(routes.contains(&tunnel_route("::0/0")));
+    }
+
+    #[test]
+    fn ipv4_only_skips_ipv6_routes() {
+        let routes = RouteHandler::get_routes(routing_config(), &TunnelRoutes::All, false);
+        assert!(routes.contains(&tunnel_route("0.0.0.0/0")));
+        assert!(!routes.contains(&tunnel_route("::0/0")));
+
+        let tunnel_routes = TunnelRoutes::IncludeOnly(vec![
+            "10.20.0.0/16".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+        ]);
+        let routes = RouteHandler::get_routes(routing_config(), &tunnel_routes, false);
+        assert!(routes.contains(&tunnel_route("10.20.0.0/16")));
+        assert!(!routes.contains(&tunnel_route("2001:db8::/32")));
+    }
+
+    #[test]
+    fn excluded_networks_keep_default_routes() {
+        let tunnel_routes = TunnelRoutes::Exclude(vec!["10.20.0.0/16".parse().unwrap()]);
+        let routes = RouteHandler::get_routes(routing_config(), &tunnel_routes, true);
+        assert!(routes.contains(&tunnel_route("0.0.0.0/0")));
+        assert!(!routes.contains(&tunnel_route("10.20.0.0/16")));
+    }
+
+    #[test]
+    fn include_only_networks() {
+        let tunnel_routes = TunnelRoutes::IncludeOnly(vec![
+            "10.20.0.0/16".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+        ]);
+        let routes = RouteHandler::get_routes(routing_config(), &tunnel_routes, true);
+        assert!(routes.contains(&tunnel_route("10.20.0.0/16")));
+        assert!(routes.contains(&tunnel_route("2001:db8::/32")));
+        assert!(!routes.contains(&tunnel_route("0.0.0.0/0")));
+        assert!(!routes.contains(&tunnel_route("::0/0")));
+    }
+
+    #[test]
+    fn include_only_adds_tunnel_dns() {
+        let tunnel_routes = TunnelRoutes::IncludeOnly(vec!["10.20.0.0/16".parse().unwrap()]);
+        let dns_servers = ["10.20.0.53".parse().unwrap(), "1.1.1.1".parse().unwrap()];
+        assert_eq!(
+            tunnel_routes.with_tunnel_dns(&dns_servers),
+            TunnelRoutes::IncludeOnly(vec![
+                "10.20.0.0/16".parse().unwrap(),
+                "1.1.1.1/32".parse().unwrap(),
+            ])
+        );
+    }
+}
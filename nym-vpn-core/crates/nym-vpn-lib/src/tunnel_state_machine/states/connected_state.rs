@@ -4,8 +4,12 @@
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
-#[cfg(target_os = "macos")]
+#[cfg(target_os = "linux")]
+use nym_dns::DnsBackend;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
 use nym_dns::DnsConfig;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use nym_dns::ResolvedDnsConfig;
 #[cfg(target_os = "macos")]
 use nym_firewall::LOCAL_DNS_RESOLVER;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
@@ -14,6 +18,8 @@ use nym_gateway_directory::ResolvedConfig;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use nym_vpn_lib_types::TunnelConnectionData;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::{leak_test::LeakTest, Error, Result};
 use crate::tunnel_state_machine::{
     states::DisconnectingState,
     tunnel::SelectedGateways,
@@ -21,8 +27,6 @@ use crate::tunnel_state_machine::{
     ConnectionData, NextTunnelState, PrivateActionAfterDisconnect, PrivateTunnelState, SharedState,
     TunnelCommand, TunnelInterface, TunnelStateHandler,
 };
-#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-use crate::tunnel_state_machine::{Error, Result};
 
 /// Default websocket port used as a fallback
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
@@ -133,11 +137,7 @@ impl ConnectedState {
             peer_endpoints.push(allowed_endpoint);
         }
 
-        let dns_config = shared_state.tunnel_settings.dns.to_dns_config().resolve(
-            &crate::DEFAULT_DNS_SERVERS,
-            #[cfg(target_os = "macos")]
-            53,
-        );
+        let dns_config = Self::dns_config(shared_state);
 
         let allowed_endpoints = self
             .resolved_gateway_config
@@ -161,6 +161,13 @@ impl ConnectedState {
             allow_lan: true,
             allowed_endpoints,
             dns_config,
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            allowed_non_tunnel_traffic: shared_state
+                .tunnel_settings
+                .tunnel_routes
+                .allowed_non_tunnel_traffic(),
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            block_ipv6: !shared_state.tunnel_settings.ip_family.ipv6_enabled(),
             // todo: split tunneling
             #[cfg(target_os = "macos")]
             redirect_interface: None,
@@ -174,13 +181,24 @@ impl ConnectedState {
             .map_err(Error::CreateFirewall)
     }
 
+    /// The DNS config of the tunnel settings, with the domain routes of the daemon.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    fn dns_config(shared_state: &SharedState) -> ResolvedDnsConfig {
+        shared_state
+            .tunnel_settings
+            .dns
+            .to_dns_config(shared_state.tunnel_settings.ip_family)
+            .with_domain_routes(&shared_state.nym_config.dns_routes)
+            .resolve(
+                &crate::DEFAULT_DNS_SERVERS,
+                #[cfg(target_os = "macos")]
+                53,
+            )
+    }
+
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
     async fn set_dns(&self, shared_state: &mut SharedState) -> Result<()> {
-        let dns_config = shared_state.tunnel_settings.dns.to_dns_config().resolve(
-            &crate::DEFAULT_DNS_SERVERS,
-            #[cfg(target_os = "macos")]
-            53,
-        );
+        let dns_config = Self::dns_config(shared_state);
 
         let tunnel_metadata = match &self.tunnel_interface {
             TunnelInterface::One(interface) => interface,
@@ -202,9 +220,10 @@ impl ConnectedState {
             log::debug!("Enabling local DNS resolver");
             // Tell local DNS resolver to start forwarding DNS queries to whatever `dns_config`
             // specifies as DNS.
+            let domain_routes = dns_config.domain_routes().to_vec();
             shared_state
                 .filtering_resolver
-                .enable_forward(dns_config.addresses().collect())
+                .enable_forward(dns_config.addresses().collect(), domain_routes)
                 .await;
 
             // Set system DNS to our local DNS resolver
@@ -219,6 +238,31 @@ impl ConnectedState {
                 .map_err(Error::SetDns)?;
         }
 
+        // On Linux, point the tunnel DNS at the local resolver when filtering is enabled, or when
+        // domain routes can't be applied with systemd-resolved routing domains
+        #[cfg(target_os = "linux")]
+        let use_local_resolver = !dns_config.is_loopback()
+            && (shared_state.nym_config.dns_filter.is_some()
+                || (!dns_config.domain_routes().is_empty()
+                    && shared_state.dns_handler.status().active_backend
+                        != Some(DnsBackend::SystemdResolved)));
+        #[cfg(target_os = "linux")]
+        let dns_config = if use_local_resolver {
+            match shared_state.filtering_resolver().await {
+                Some(filtering_resolver) => {
+                    log::debug!("Enabling local DNS resolver");
+                    let domain_routes = dns_config.domain_routes().to_vec();
+                    filtering_resolver
+                        .enable_forward(dns_config.addresses().collect(), domain_routes)
+                        .await;
+                    DnsConfig::default().resolve(&[filtering_resolver.listening_addr().ip()])
+                }
+                None => dns_config,
+            }
+        } else {
+            dns_config
+        };
+
         #[cfg(any(target_os = "linux", target_os = "windows"))]
         shared_state
             .dns_handler
@@ -228,6 +272,27 @@ impl ConnectedState {
 
         Ok(())
     }
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    async fn prepare_leak_test(&self, shared_state: &mut SharedState) -> LeakTest {
+        let dns_config = Self::dns_config(shared_state);
+
+        let tunnel_metadata = match &self.tunnel_interface {
+            TunnelInterface::One(interface) => interface,
+            TunnelInterface::Two { exit, .. } => exit,
+        };
+
+        LeakTest::prepare(
+            tunnel_metadata.interface.clone(),
+            &tunnel_metadata.ips,
+            &dns_config,
+            shared_state.dns_handler.status(),
+            self.selected_gateways.exit.ips.clone(),
+            shared_state.nym_config.leak_test_ip_url.clone(),
+            shared_state.route_handler.as_mut(),
+        )
+        .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -249,6 +314,14 @@ impl TunnelStateHandler for ConnectedState {
                             shared_state
                         ))
                     },
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        let leak_test = self.prepare_leak_test(shared_state).await;
+                        tokio::spawn(async move {
+                            _ = reply_tx.send(Ok(leak_test.run().await));
+                        });
+                        NextTunnelState::SameState(self)
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         if shared_state.tunnel_settings == tunnel_settings {
                             NextTunnelState::SameState(self)
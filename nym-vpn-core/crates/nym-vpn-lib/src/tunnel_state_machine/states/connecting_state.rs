@@ -26,15 +26,19 @@ use nym_firewall::{
 use nym_gateway_directory::Gateway;
 use nym_gateway_directory::ResolvedConfig;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::Error;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::LeakTestError;
 use crate::tunnel_state_machine::{
     states::{ConnectedState, DisconnectedState, DisconnectingState, ErrorState, OfflineState},
     tunnel::{SelectedGateways, Tombstone},
     tunnel_monitor::{
-        TunnelMonitor, TunnelMonitorEvent, TunnelMonitorEventReceiver, TunnelMonitorEventSender,
+        TunnelMonitorEvent, TunnelMonitorEventReceiver, TunnelMonitorEventSender,
         TunnelMonitorHandle, TunnelParameters,
     },
-    Error, ErrorStateReason, NextTunnelState, PrivateActionAfterDisconnect, PrivateTunnelState,
-    Result, SharedState, TunnelCommand, TunnelInterface, TunnelStateHandler,
+    ErrorStateReason, NextTunnelState, PrivateActionAfterDisconnect, PrivateTunnelState, Result,
+    SharedState, TunnelCommand, TunnelInterface, TunnelStateHandler,
 };
 
 /// Default websocket port used as a fallback
@@ -99,14 +103,10 @@ impl ConnectingState {
             }
         }
 
-        let gateway_config = shared_state.nym_config.gateway_config.clone();
-        let resolve_config_fut = async move {
-            nym_gateway_directory::resolve_config(&gateway_config)
-                .await
-                .map_err(Error::ResolveGatewayAddrs)
-        }
-        .boxed()
-        .fuse();
+        let resolve_config_fut = shared_state
+            .tunnel_connector
+            .resolve_gateway_config(shared_state.nym_config.gateway_config.clone())
+            .fuse();
 
         let (monitor_event_sender, monitor_event_receiver) = mpsc::unbounded_channel();
 
@@ -354,15 +354,11 @@ impl ConnectingState {
             selected_gateways: self.selected_gateways.clone(),
             retry_attempt: self.retry_attempt,
         };
-        let monitor_handle = TunnelMonitor::start(
+        let monitor_handle = shared_state.tunnel_connector.start_tunnel_monitor(
             tunnel_parameters,
             shared_state.account_command_tx.clone(),
             monitor_event_sender,
             shared_state.mixnet_event_sender.clone(),
-            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-            shared_state.route_handler.clone(),
-            #[cfg(any(target_os = "ios", target_os = "android"))]
-            shared_state.tun_provider.clone(),
         );
 
         self.monitor_handle = Some(monitor_handle);
@@ -573,6 +569,11 @@ impl TunnelStateHandler for ConnectingState {
                             NextTunnelState::NewState(DisconnectedState::enter(shared_state).await)
                         }
                     },
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        _ = reply_tx.send(Err(LeakTestError::NotConnected));
+                        NextTunnelState::SameState(self)
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         if shared_state.tunnel_settings == tunnel_settings {
                             NextTunnelState::SameState(self)
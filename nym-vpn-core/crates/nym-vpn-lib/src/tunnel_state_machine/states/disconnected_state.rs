@@ -7,6 +7,8 @@ use tokio_util::sync::CancellationToken;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use nym_common::ErrorExt;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::LeakTestError;
 use crate::tunnel_state_machine::{
     states::{ConnectingState, OfflineState},
     NextTunnelState, PrivateTunnelState, SharedState, TunnelCommand, TunnelStateHandler,
@@ -57,6 +59,11 @@ impl TunnelStateHandler for DisconnectedState {
                         NextTunnelState::NewState(ConnectingState::enter(0, None, shared_state).await)
                     },
                     TunnelCommand::Disconnect => NextTunnelState::SameState(self),
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        _ = reply_tx.send(Err(LeakTestError::NotConnected));
+                        NextTunnelState::SameState(self)
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         shared_state.tunnel_settings = tunnel_settings;
                         NextTunnelState::SameState(self)
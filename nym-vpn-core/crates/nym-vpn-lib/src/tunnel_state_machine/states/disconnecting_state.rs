@@ -5,6 +5,8 @@ use futures::future::{BoxFuture, Fuse, FutureExt};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::LeakTestError;
 use crate::tunnel_state_machine::{
     states::{ConnectingState, DisconnectedState, ErrorState, OfflineState},
     tunnel::Tombstone,
@@ -64,6 +66,11 @@ impl DisconnectingState {
         #[cfg(target_os = "macos")]
         shared_state.filtering_resolver.disable_forward().await;
 
+        #[cfg(target_os = "linux")]
+        if let Some(filtering_resolver) = &shared_state.filtering_resolver {
+            filtering_resolver.disable_forward().await;
+        }
+
         #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
         shared_state.route_handler.remove_routes().await;
 
@@ -125,6 +132,10 @@ impl TunnelStateHandler for DisconnectingState {
                             _ => PrivateActionAfterDisconnect::Nothing
                         };
                     }
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        _ = reply_tx.send(Err(LeakTestError::NotConnected));
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         shared_state.tunnel_settings = tunnel_settings;
                     }
@@ -23,6 +23,8 @@ use nym_firewall::FirewallPolicy;
 use crate::tunnel_provider::{ios::OSTunProvider, tunnel_settings::TunnelSettings};
 #[cfg(target_os = "ios")]
 use crate::tunnel_state_machine::tunnel::wireguard::two_hop_config::MIN_IPV6_MTU;
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::LeakTestError;
 use crate::tunnel_state_machine::{
     states::{ConnectingState, DisconnectedState, OfflineState},
     ErrorStateReason, NextTunnelState, PrivateTunnelState, SharedState, TunnelCommand,
@@ -177,6 +179,11 @@ impl TunnelStateHandler for ErrorState {
                             NextTunnelState::NewState(DisconnectedState::enter(shared_state).await)
                         }
                     },
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        _ = reply_tx.send(Err(LeakTestError::NotConnected));
+                        NextTunnelState::SameState(self)
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         shared_state.tunnel_settings = tunnel_settings;
                         NextTunnelState::SameState(self)
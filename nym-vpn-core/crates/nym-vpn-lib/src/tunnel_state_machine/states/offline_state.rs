@@ -11,6 +11,8 @@ use nym_dns::DnsConfig;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use nym_firewall::FirewallPolicy;
 
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use crate::tunnel_state_machine::LeakTestError;
 #[cfg(target_os = "macos")]
 use crate::tunnel_state_machine::{states::ErrorState, ErrorStateReason};
 use crate::tunnel_state_machine::{
@@ -147,6 +149,11 @@ impl TunnelStateHandler for OfflineState {
                             NextTunnelState::SameState(self)
                         }
                     },
+                    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+                    TunnelCommand::DiagnoseLeaks(reply_tx) => {
+                        _ = reply_tx.send(Err(LeakTestError::NotConnected));
+                        NextTunnelState::SameState(self)
+                    }
                     TunnelCommand::SetTunnelSettings(tunnel_settings) => {
                         shared_state.tunnel_settings = tunnel_settings;
                         NextTunnelState::SameState(self)
@@ -0,0 +1,249 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Abstractions over the system resources driven by the tunnel state machine.
+//!
+//! The state machine only talks to the firewall, routing table, DNS, offline monitor and tunnel
+//! through these traits, which allows substituting them with fakes in tests.
+
+#[cfg(any(target_os = "ios", target_os = "android"))]
+use std::sync::Arc;
+
+use futures::future::{BoxFuture, FutureExt};
+use nym_gateway_directory::{Config as GatewayDirectoryConfig, ResolvedConfig};
+use nym_offline_monitor::Connectivity;
+use nym_vpn_account_controller::AccountControllerCommander;
+use nym_vpn_lib_types::MixnetEvent;
+use tokio::sync::mpsc;
+
+#[cfg(target_os = "linux")]
+use std::net::IpAddr;
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use nym_dns::{DnsStatus, ResolvedDnsConfig};
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use nym_firewall::{Firewall, FirewallPolicy};
+
+#[cfg(target_os = "android")]
+use crate::tunnel_provider::android::AndroidTunProvider;
+#[cfg(target_os = "ios")]
+use crate::tunnel_provider::ios::OSTunProvider;
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use super::{
+    dns_handler::{self, DnsHandlerHandle},
+    route_handler::{self, RouteHandler},
+};
+use super::{
+    tunnel_monitor::{
+        TunnelMonitor, TunnelMonitorEventSender, TunnelMonitorHandle, TunnelParameters,
+    },
+    Error, Result,
+};
+
+/// Applies firewall policies.
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub trait FirewallControl: Send {
+    fn apply_policy(&mut self, policy: FirewallPolicy) -> Result<(), nym_firewall::Error>;
+
+    fn reset_policy(&mut self) -> Result<(), nym_firewall::Error>;
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+impl FirewallControl for Firewall {
+    fn apply_policy(&mut self, policy: FirewallPolicy) -> Result<(), nym_firewall::Error> {
+        Firewall::apply_policy(self, policy)
+    }
+
+    fn reset_policy(&mut self) -> Result<(), nym_firewall::Error> {
+        Firewall::reset_policy(self)
+    }
+}
+
+/// Manages the routes added for the tunnel.
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[async_trait::async_trait]
+pub trait RouteControl: Send {
+    async fn remove_routes(&mut self);
+
+    #[cfg(target_os = "macos")]
+    async fn refresh_routes(&mut self);
+
+    /// Returns the interface that traffic to `destination` egresses via.
+    #[cfg(target_os = "linux")]
+    async fn destination_interface(
+        &mut self,
+        destination: IpAddr,
+    ) -> Result<Option<String>, route_handler::Error>;
+
+    async fn stop(self: Box<Self>);
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[async_trait::async_trait]
+impl RouteControl for RouteHandler {
+    async fn remove_routes(&mut self) {
+        RouteHandler::remove_routes(self).await
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn refresh_routes(&mut self) {
+        RouteHandler::refresh_routes(self).await
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn destination_interface(
+        &mut self,
+        destination: IpAddr,
+    ) -> Result<Option<String>, route_handler::Error> {
+        RouteHandler::destination_interface(self, destination).await
+    }
+
+    async fn stop(self: Box<Self>) {
+        RouteHandler::stop(*self).await
+    }
+}
+
+/// Configures the system DNS.
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[async_trait::async_trait]
+pub trait DnsControl: Send {
+    async fn set(
+        &mut self,
+        interface: String,
+        config: ResolvedDnsConfig,
+    ) -> dns_handler::Result<()>;
+
+    async fn reset(&mut self) -> dns_handler::Result<()>;
+
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    async fn reset_before_interface_removal(&mut self) -> dns_handler::Result<()>;
+
+    /// Returns the DNS configuration currently applied.
+    fn status(&self) -> DnsStatus;
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+#[async_trait::async_trait]
+impl DnsControl for DnsHandlerHandle {
+    async fn set(
+        &mut self,
+        interface: String,
+        config: ResolvedDnsConfig,
+    ) -> dns_handler::Result<()> {
+        DnsHandlerHandle::set(self, interface, config).await
+    }
+
+    async fn reset(&mut self) -> dns_handler::Result<()> {
+        DnsHandlerHandle::reset(self).await
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    async fn reset_before_interface_removal(&mut self) -> dns_handler::Result<()> {
+        DnsHandlerHandle::reset_before_interface_removal(self).await
+    }
+
+    fn status(&self) -> DnsStatus {
+        DnsHandlerHandle::status(self)
+    }
+}
+
+/// Reports the host connectivity.
+#[async_trait::async_trait]
+pub trait ConnectivityMonitor: Send + Sync {
+    /// Returns current connectivity status.
+    async fn connectivity(&self) -> Connectivity;
+
+    /// Returns next connectivity status once changed.
+    ///
+    /// Implementations must be cancel safe.
+    async fn next(&mut self) -> Option<Connectivity>;
+}
+
+#[async_trait::async_trait]
+impl ConnectivityMonitor for nym_offline_monitor::MonitorHandle {
+    async fn connectivity(&self) -> Connectivity {
+        nym_offline_monitor::MonitorHandle::connectivity(self).await
+    }
+
+    async fn next(&mut self) -> Option<Connectivity> {
+        nym_offline_monitor::MonitorHandle::next(self).await
+    }
+}
+
+/// Establishes tunnels.
+pub trait TunnelConnector: Send {
+    /// Resolves the addresses of the directory and API endpoints.
+    fn resolve_gateway_config(
+        &self,
+        gateway_config: GatewayDirectoryConfig,
+    ) -> BoxFuture<'static, Result<ResolvedConfig>>;
+
+    /// Starts the tunnel monitor responsible for bringing up and maintaining the tunnel.
+    fn start_tunnel_monitor(
+        &self,
+        tunnel_parameters: TunnelParameters,
+        account_controller_tx: AccountControllerCommander,
+        monitor_event_sender: TunnelMonitorEventSender,
+        mixnet_event_sender: mpsc::UnboundedSender<MixnetEvent>,
+    ) -> TunnelMonitorHandle;
+}
+
+/// Tunnel connector establishing real mixnet and wireguard tunnels.
+pub struct DefaultTunnelConnector {
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    route_handler: RouteHandler,
+    #[cfg(target_os = "ios")]
+    tun_provider: Arc<dyn OSTunProvider>,
+    #[cfg(target_os = "android")]
+    tun_provider: Arc<dyn AndroidTunProvider>,
+}
+
+impl DefaultTunnelConnector {
+    pub fn new(
+        #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+        route_handler: RouteHandler,
+        #[cfg(target_os = "ios")] tun_provider: Arc<dyn OSTunProvider>,
+        #[cfg(target_os = "android")] tun_provider: Arc<dyn AndroidTunProvider>,
+    ) -> Self {
+        Self {
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            route_handler,
+            #[cfg(any(target_os = "ios", target_os = "android"))]
+            tun_provider,
+        }
+    }
+}
+
+impl TunnelConnector for DefaultTunnelConnector {
+    fn resolve_gateway_config(
+        &self,
+        gateway_config: GatewayDirectoryConfig,
+    ) -> BoxFuture<'static, Result<ResolvedConfig>> {
+        async move {
+            nym_gateway_directory::resolve_config(&gateway_config)
+                .await
+                .map_err(Error::ResolveGatewayAddrs)
+        }
+        .boxed()
+    }
+
+    fn start_tunnel_monitor(
+        &self,
+        tunnel_parameters: TunnelParameters,
+        account_controller_tx: AccountControllerCommander,
+        monitor_event_sender: TunnelMonitorEventSender,
+        mixnet_event_sender: mpsc::UnboundedSender<MixnetEvent>,
+    ) -> TunnelMonitorHandle {
+        TunnelMonitor::start(
+            tunnel_parameters,
+            account_controller_tx,
+            monitor_event_sender,
+            mixnet_event_sender,
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+            self.route_handler.clone(),
+            #[cfg(any(target_os = "ios", target_os = "android"))]
+            self.tun_provider.clone(),
+        )
+    }
+}
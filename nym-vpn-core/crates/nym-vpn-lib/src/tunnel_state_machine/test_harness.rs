@@ -0,0 +1,873 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Harness driving the tunnel state machine against fake system resources.
+//!
+//! Tunnels are never established for real. Instead, each connection attempt plays back a script
+//! of tunnel monitor events, and failures of the firewall, DNS or gateway config resolution can be
+//! injected. The harness collects the emitted tunnel states so that tests can assert the exact
+//! sequence of transitions.
+
+use std::{
+    collections::VecDeque,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures::future::{BoxFuture, FutureExt};
+use nym_crypto::asymmetric::ed25519;
+use nym_dns::{DnsStatus, ResolvedDnsConfig};
+use nym_firewall::FirewallPolicy;
+use nym_gateway_directory::{Config as GatewayDirectoryConfig, ResolvedConfig};
+use nym_offline_monitor::Connectivity;
+use nym_vpn_account_controller::{AccountController, AccountControllerCommander};
+use nym_vpn_lib_types::{
+    ActionAfterDisconnect, ClientErrorReason, ConnectionData, ErrorStateReason, Gateway,
+    MixnetConnectionData, MixnetEvent, NymAddress, SyncAccountError, TunnelConnectionData,
+    TunnelEvent, TunnelState,
+};
+use nym_vpn_network_config::Network;
+use tempfile::TempDir;
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    dns_handler, route_handler,
+    system_handles::{
+        ConnectivityMonitor, DnsControl, FirewallControl, RouteControl, TunnelConnector,
+    },
+    tunnel::{SelectedGateways, Tombstone},
+    tunnel_monitor::{
+        TunnelMonitorEvent, TunnelMonitorEventSender, TunnelMonitorHandle, TunnelParameters,
+    },
+    Error, LeakTestError, NymConfig, Result, SharedState, TunnelCommand, TunnelInterface,
+    TunnelMetadata, TunnelSettings, TunnelStateMachine,
+};
+
+/// How long to wait for the next tunnel state before failing the test.
+const STATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name of the fake tunnel interface.
+const TUN_NAME: &str = "faketun0";
+
+/// Calls made by the state machine into the fake system resources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SystemCall {
+    ApplyConnectingPolicy,
+    ApplyConnectedPolicy,
+    ApplyBlockedPolicy,
+    ResetFirewallPolicy,
+    SetDns(String),
+    ResetDns,
+    ResetDnsBeforeInterfaceRemoval,
+    RemoveRoutes,
+    StartTunnel { retry_attempt: u32 },
+}
+
+/// Steps played back by the fake tunnel monitor during a connection attempt.
+#[derive(Debug, Clone)]
+enum MonitorStep {
+    SyncingAccount,
+    RequestingZkNyms,
+    SelectedGateways,
+    InterfaceUp,
+    Up,
+    /// Tunnel goes down on its own, ending the script.
+    Down(Option<ErrorStateReason>),
+}
+
+#[derive(Default)]
+struct FakeSystem {
+    calls: Vec<SystemCall>,
+    fail_apply_firewall_policy: bool,
+    fail_set_dns: bool,
+    gateway_config_failures: u32,
+    tunnel_scripts: VecDeque<Vec<MonitorStep>>,
+}
+
+type SharedFakeSystem = Arc<Mutex<FakeSystem>>;
+
+fn record(system: &SharedFakeSystem, call: SystemCall) {
+    system.lock().unwrap().calls.push(call);
+}
+
+struct FakeFirewall {
+    system: SharedFakeSystem,
+}
+
+impl FirewallControl for FakeFirewall {
+    fn apply_policy(&mut self, policy: FirewallPolicy) -> Result<(), nym_firewall::Error> {
+        let call = match policy {
+            FirewallPolicy::Connecting { .. } => SystemCall::ApplyConnectingPolicy,
+            FirewallPolicy::Connected { .. } => SystemCall::ApplyConnectedPolicy,
+            FirewallPolicy::Blocked { .. } => SystemCall::ApplyBlockedPolicy,
+        };
+        record(&self.system, call);
+
+        if self.system.lock().unwrap().fail_apply_firewall_policy {
+            Err(nym_firewall::Error::NetfilterTableNotSetError)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn reset_policy(&mut self) -> Result<(), nym_firewall::Error> {
+        record(&self.system, SystemCall::ResetFirewallPolicy);
+        Ok(())
+    }
+}
+
+struct FakeRoutes {
+    system: SharedFakeSystem,
+}
+
+#[async_trait::async_trait]
+impl RouteControl for FakeRoutes {
+    async fn remove_routes(&mut self) {
+        record(&self.system, SystemCall::RemoveRoutes);
+    }
+
+    async fn destination_interface(
+        &mut self,
+        _destination: IpAddr,
+    ) -> Result<Option<String>, route_handler::Error> {
+        Ok(None)
+    }
+
+    async fn stop(self: Box<Self>) {}
+}
+
+struct FakeDns {
+    system: SharedFakeSystem,
+}
+
+#[async_trait::async_trait]
+impl DnsControl for FakeDns {
+    async fn set(
+        &mut self,
+        interface: String,
+        _config: ResolvedDnsConfig,
+    ) -> dns_handler::Result<()> {
+        record(&self.system, SystemCall::SetDns(interface));
+
+        if self.system.lock().unwrap().fail_set_dns {
+            Err(dns_handler::Error::ChannelClosed)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn reset(&mut self) -> dns_handler::Result<()> {
+        record(&self.system, SystemCall::ResetDns);
+        Ok(())
+    }
+
+    async fn reset_before_interface_removal(&mut self) -> dns_handler::Result<()> {
+        record(&self.system, SystemCall::ResetDnsBeforeInterfaceRemoval);
+        Ok(())
+    }
+
+    fn status(&self) -> DnsStatus {
+        DnsStatus::default()
+    }
+}
+
+struct FakeConnectivityMonitor {
+    rx: watch::Receiver<Connectivity>,
+}
+
+#[async_trait::async_trait]
+impl ConnectivityMonitor for FakeConnectivityMonitor {
+    async fn connectivity(&self) -> Connectivity {
+        *self.rx.borrow()
+    }
+
+    async fn next(&mut self) -> Option<Connectivity> {
+        self.rx.changed().await.ok()?;
+        Some(*self.rx.borrow_and_update())
+    }
+}
+
+struct FakeTunnelConnector {
+    system: SharedFakeSystem,
+}
+
+impl TunnelConnector for FakeTunnelConnector {
+    fn resolve_gateway_config(
+        &self,
+        _gateway_config: GatewayDirectoryConfig,
+    ) -> BoxFuture<'static, Result<ResolvedConfig>> {
+        let result = {
+            let mut system = self.system.lock().unwrap();
+            if system.gateway_config_failures > 0 {
+                system.gateway_config_failures -= 1;
+                Err(Error::ResolveGatewayAddrs(
+                    nym_gateway_directory::Error::ResolvedHostnameButNoIp(
+                        "fake.nymtech.net".to_owned(),
+                    ),
+                ))
+            } else {
+                Ok(ResolvedConfig {
+                    nyxd_socket_addrs: Vec::new(),
+                    api_socket_addrs: Vec::new(),
+                    nym_vpn_api_socket_addrs: None,
+                    harbour_master_socket_addrs: None,
+                })
+            }
+        };
+        futures::future::ready(result).boxed()
+    }
+
+    fn start_tunnel_monitor(
+        &self,
+        tunnel_parameters: TunnelParameters,
+        _account_controller_tx: AccountControllerCommander,
+        monitor_event_sender: TunnelMonitorEventSender,
+        _mixnet_event_sender: mpsc::UnboundedSender<MixnetEvent>,
+    ) -> TunnelMonitorHandle {
+        let script = {
+            let mut system = self.system.lock().unwrap();
+            system.calls.push(SystemCall::StartTunnel {
+                retry_attempt: tunnel_parameters.retry_attempt,
+            });
+            system.tunnel_scripts.pop_front().unwrap_or_default()
+        };
+
+        let cancel_token = CancellationToken::new();
+        let join_handle = tokio::spawn(run_fake_monitor(
+            script,
+            monitor_event_sender,
+            cancel_token.clone(),
+        ));
+        TunnelMonitorHandle::new(cancel_token, join_handle)
+    }
+}
+
+/// Plays back the script, then keeps the fake tunnel up until cancelled.
+async fn run_fake_monitor(
+    script: Vec<MonitorStep>,
+    event_sender: TunnelMonitorEventSender,
+    cancel_token: CancellationToken,
+) -> Tombstone {
+    for step in script {
+        if cancel_token.is_cancelled() {
+            break;
+        }
+
+        match step {
+            MonitorStep::SyncingAccount => {
+                _ = event_sender.send(TunnelMonitorEvent::SyncingAccount);
+            }
+            MonitorStep::RequestingZkNyms => {
+                _ = event_sender.send(TunnelMonitorEvent::RequestingZkNyms);
+            }
+            MonitorStep::SelectedGateways => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                _ = event_sender.send(TunnelMonitorEvent::SelectedGateways {
+                    gateways: Box::new(fake_selected_gateways()),
+                    reply_tx,
+                });
+                _ = reply_rx.await;
+            }
+            MonitorStep::InterfaceUp => {
+                let (reply_tx, reply_rx) = oneshot::channel();
+                _ = event_sender.send(TunnelMonitorEvent::InterfaceUp {
+                    tunnel_interface: fake_tunnel_interface(),
+                    connection_data: Box::new(fake_connection_data()),
+                    reply_tx,
+                });
+                _ = reply_rx.await;
+            }
+            MonitorStep::Up => {
+                _ = event_sender.send(TunnelMonitorEvent::Up {
+                    tunnel_interface: fake_tunnel_interface(),
+                    connection_data: Box::new(fake_connection_data()),
+                });
+            }
+            MonitorStep::Down(error_state_reason) => {
+                send_down(&event_sender, error_state_reason).await;
+                return Tombstone::default();
+            }
+        }
+    }
+
+    cancel_token.cancelled().await;
+    send_down(&event_sender, None).await;
+
+    Tombstone::default()
+}
+
+async fn send_down(
+    event_sender: &TunnelMonitorEventSender,
+    error_state_reason: Option<ErrorStateReason>,
+) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    _ = event_sender.send(TunnelMonitorEvent::Down {
+        error_state_reason,
+        reply_tx,
+    });
+    _ = reply_rx.await;
+}
+
+fn fake_gateway() -> nym_gateway_directory::Gateway {
+    let keypair = ed25519::KeyPair::new(&mut rand::rngs::OsRng);
+    nym_gateway_directory::Gateway {
+        identity: *keypair.public_key(),
+        moniker: "fake".to_owned(),
+        location: None,
+        ipr_address: None,
+        authenticator_address: None,
+        last_probe: None,
+        ips: vec![IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))],
+        host: None,
+        clients_ws_port: Some(9000),
+        clients_wss_port: None,
+        mixnet_performance: None,
+        wg_performance: None,
+        wg_score: None,
+        mixnet_score: None,
+        version: None,
+    }
+}
+
+fn fake_selected_gateways() -> SelectedGateways {
+    SelectedGateways {
+        entry: Box::new(fake_gateway()),
+        exit: Box::new(fake_gateway()),
+    }
+}
+
+fn fake_tunnel_interface() -> TunnelInterface {
+    TunnelInterface::One(TunnelMetadata {
+        interface: TUN_NAME.to_owned(),
+        ips: vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))],
+        ipv4_gateway: Some(Ipv4Addr::new(10, 0, 0, 1)),
+        ipv6_gateway: None,
+    })
+}
+
+fn fake_connection_data() -> ConnectionData {
+    ConnectionData {
+        entry_gateway: Gateway::new("entry".to_owned()),
+        exit_gateway: Gateway::new("exit".to_owned()),
+        connected_at: None,
+        tunnel: TunnelConnectionData::Mixnet(MixnetConnectionData {
+            nym_address: NymAddress::new("client".to_owned(), "entry".to_owned()),
+            exit_ipr: NymAddress::new("ipr".to_owned(), "exit".to_owned()),
+            entry_ip: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)),
+            exit_ip: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)),
+            ipv4: Ipv4Addr::new(10, 0, 0, 2),
+            ipv6: Ipv6Addr::LOCALHOST,
+        }),
+    }
+}
+
+fn connectivity(online: bool) -> Connectivity {
+    Connectivity::Status {
+        ipv4: online,
+        ipv6: false,
+    }
+}
+
+/// Account controller commander whose controller is gone. The state machine only uses it to
+/// reset static API addresses, which then fails without blocking.
+async fn detached_account_commander(
+    data_dir: &TempDir,
+    network: Network,
+) -> AccountControllerCommander {
+    let storage = Arc::new(tokio::sync::Mutex::new(
+        crate::storage::VpnClientOnDiskStorage::new(data_dir.path()),
+    ));
+    let account_controller = AccountController::new(
+        storage,
+        data_dir.path().to_path_buf(),
+        crate::util::construct_user_agent(),
+        Some(false),
+        network,
+        CancellationToken::new(),
+    )
+    .await
+    .unwrap();
+    account_controller.commander()
+}
+
+struct TunnelStateMachineHarness {
+    system: SharedFakeSystem,
+    command_tx: mpsc::UnboundedSender<TunnelCommand>,
+    event_rx: mpsc::UnboundedReceiver<TunnelEvent>,
+    connectivity_tx: watch::Sender<Connectivity>,
+    shutdown_token: CancellationToken,
+    join_handle: JoinHandle<()>,
+    _data_dir: TempDir,
+}
+
+impl TunnelStateMachineHarness {
+    async fn start(online: bool) -> Self {
+        Self::start_with(online, FakeSystem::default()).await
+    }
+
+    /// Start the state machine and consume the initial state it reports.
+    async fn start_with(online: bool, system: FakeSystem) -> Self {
+        let mut harness = Self::spawn(online, system).await;
+        let initial_state = if online {
+            TunnelState::Disconnected
+        } else {
+            TunnelState::Offline { reconnect: false }
+        };
+        harness.expect_states(&[initial_state]).await;
+        harness
+    }
+
+    async fn spawn(online: bool, system: FakeSystem) -> Self {
+        let system = Arc::new(Mutex::new(system));
+        let data_dir = tempfile::tempdir().unwrap();
+        let network = Network::mainnet_default().unwrap();
+        let account_command_tx = detached_account_commander(&data_dir, network.clone()).await;
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let (mixnet_event_sender, mixnet_event_receiver) = mpsc::unbounded_channel();
+        let (connectivity_tx, connectivity_rx) = watch::channel(connectivity(online));
+
+        let shared_state = SharedState {
+            mixnet_event_sender,
+            route_handler: Box::new(FakeRoutes {
+                system: system.clone(),
+            }),
+            firewall: Box::new(FakeFirewall {
+                system: system.clone(),
+            }),
+            dns_handler: Box::new(FakeDns {
+                system: system.clone(),
+            }),
+            offline_monitor: Box::new(FakeConnectivityMonitor {
+                rx: connectivity_rx,
+            }),
+            tunnel_connector: Box::new(FakeTunnelConnector {
+                system: system.clone(),
+            }),
+            nym_config: NymConfig {
+                config_path: None,
+                data_path: Some(data_dir.path().to_path_buf()),
+                gateway_config: GatewayDirectoryConfig {
+                    nyxd_url: network.nyxd_url.clone(),
+                    api_url: network.api_url.clone(),
+                    nym_vpn_api_url: None,
+                    min_gateway_performance: None,
+                    mix_score_thresholds: None,
+                    wg_score_thresholds: None,
+                    custom_gateways: Vec::new(),
+                    harbour_master_url: None,
+                },
+                network_env: network,
+                dns_backend: Default::default(),
+                leak_test_ip_url: None,
+                dns_filter: None,
+                dns_routes: Vec::new(),
+                gateway_precheck: None,
+            },
+            tunnel_settings: TunnelSettings::default(),
+            status_listener_handle: None,
+            filtering_resolver: None,
+            account_command_tx,
+        };
+
+        let shutdown_token = CancellationToken::new();
+        let state_machine = TunnelStateMachine::new(
+            shared_state,
+            command_rx,
+            event_tx,
+            mixnet_event_receiver,
+            tokio::spawn(async {}),
+            CancellationToken::new(),
+            shutdown_token.clone(),
+        )
+        .await;
+        let join_handle = tokio::spawn(state_machine.run());
+
+        Self {
+            system,
+            command_tx,
+            event_rx,
+            connectivity_tx,
+            shutdown_token,
+            join_handle,
+            _data_dir: data_dir,
+        }
+    }
+
+    /// Queue the monitor events played back by the next connection attempt.
+    fn script_tunnel(&self, script: Vec<MonitorStep>) {
+        self.system.lock().unwrap().tunnel_scripts.push_back(script);
+    }
+
+    fn fail_apply_firewall_policy(&self, fail: bool) {
+        self.system.lock().unwrap().fail_apply_firewall_policy = fail;
+    }
+
+    fn fail_set_dns(&self, fail: bool) {
+        self.system.lock().unwrap().fail_set_dns = fail;
+    }
+
+    fn fail_gateway_config_resolution(&self, times: u32) {
+        self.system.lock().unwrap().gateway_config_failures = times;
+    }
+
+    fn system_calls(&self) -> Vec<SystemCall> {
+        self.system.lock().unwrap().calls.clone()
+    }
+
+    fn send(&self, command: TunnelCommand) {
+        self.command_tx.send(command).unwrap();
+    }
+
+    fn set_online(&self, online: bool) {
+        self.connectivity_tx.send_replace(connectivity(online));
+    }
+
+    async fn next_state(&mut self) -> TunnelState {
+        loop {
+            let event = tokio::time::timeout(STATE_TIMEOUT, self.event_rx.recv())
+                .await
+                .expect("timed out waiting for the next tunnel state")
+                .expect("tunnel state machine exited");
+
+            if let TunnelEvent::NewState(state) = event {
+                return state;
+            }
+        }
+    }
+
+    async fn expect_states(&mut self, expected: &[TunnelState]) {
+        for expected_state in expected {
+            assert_eq!(&self.next_state().await, expected_state);
+        }
+    }
+
+    /// Shut down the state machine and return the states emitted on the way out.
+    async fn shutdown(mut self) -> Vec<TunnelState> {
+        self.shutdown_token.cancel();
+        tokio::time::timeout(STATE_TIMEOUT, self.join_handle)
+            .await
+            .expect("timed out waiting for the state machine to exit")
+            .unwrap();
+
+        let mut states = Vec::new();
+        while let Ok(event) = self.event_rx.try_recv() {
+            if let TunnelEvent::NewState(state) = event {
+                states.push(state);
+            }
+        }
+        states
+    }
+}
+
+fn connecting() -> TunnelState {
+    TunnelState::Connecting {
+        connection_data: None,
+    }
+}
+
+fn connecting_with_data() -> TunnelState {
+    TunnelState::Connecting {
+        connection_data: Some(fake_connection_data()),
+    }
+}
+
+fn connected() -> TunnelState {
+    TunnelState::Connected {
+        connection_data: fake_connection_data(),
+    }
+}
+
+fn disconnecting(after_disconnect: ActionAfterDisconnect) -> TunnelState {
+    TunnelState::Disconnecting { after_disconnect }
+}
+
+fn successful_connection() -> Vec<MonitorStep> {
+    vec![
+        MonitorStep::SyncingAccount,
+        MonitorStep::RequestingZkNyms,
+        MonitorStep::SelectedGateways,
+        MonitorStep::InterfaceUp,
+        MonitorStep::Up,
+    ]
+}
+
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn connect_and_disconnect() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+        assert!(harness
+            .system_calls()
+            .contains(&SystemCall::SetDns(TUN_NAME.to_owned())));
+
+        harness.send(TunnelCommand::Disconnect);
+        harness
+            .expect_states(&[
+                disconnecting(ActionAfterDisconnect::Nothing),
+                TunnelState::Disconnected,
+            ])
+            .await;
+
+        let calls = harness.system_calls();
+        assert!(calls.contains(&SystemCall::ResetDnsBeforeInterfaceRemoval));
+        assert!(calls.contains(&SystemCall::RemoveRoutes));
+        assert_eq!(calls.last(), Some(&SystemCall::ResetFirewallPolicy));
+
+        assert!(harness.shutdown().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn leak_test_requires_connected_tunnel() {
+        let harness = TunnelStateMachineHarness::start(true).await;
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        harness.send(TunnelCommand::DiagnoseLeaks(reply_tx));
+        let result = tokio::time::timeout(STATE_TIMEOUT, reply_rx)
+            .await
+            .expect("timed out waiting for the leak test")
+            .unwrap();
+        assert!(matches!(result, Err(LeakTestError::NotConnected)));
+
+        assert!(harness.shutdown().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sync_account_failure_enters_error_state() {
+        let reason = ErrorStateReason::SyncAccount(SyncAccountError::NoAccountStored);
+
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(vec![
+            MonitorStep::SyncingAccount,
+            MonitorStep::Down(Some(reason.clone())),
+        ]);
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[
+                connecting(),
+                disconnecting(ActionAfterDisconnect::Error),
+                TunnelState::Error(ClientErrorReason::from(reason)),
+            ])
+            .await;
+        assert_eq!(
+            harness.system_calls().last(),
+            Some(&SystemCall::ApplyBlockedPolicy)
+        );
+
+        harness.send(TunnelCommand::Disconnect);
+        harness.expect_states(&[TunnelState::Disconnected]).await;
+    }
+
+    #[tokio::test]
+    async fn gateway_config_resolution_failure_enters_error_state() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.fail_gateway_config_resolution(1);
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[
+                connecting(),
+                TunnelState::Error(ClientErrorReason::from(
+                    ErrorStateReason::ResolveGatewayAddrs,
+                )),
+            ])
+            .await;
+
+        // Reconnecting from the error state retries the resolution
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn firewall_failure_enters_error_state() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.fail_apply_firewall_policy(true);
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[TunnelState::Error(ClientErrorReason::Firewall)])
+            .await;
+        assert!(!harness
+            .system_calls()
+            .iter()
+            .any(|call| matches!(call, SystemCall::StartTunnel { .. })));
+    }
+
+    #[tokio::test]
+    async fn dns_failure_tears_down_tunnel() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.fail_set_dns(true);
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[
+                connecting(),
+                connecting_with_data(),
+                disconnecting(ActionAfterDisconnect::Error),
+                TunnelState::Error(ClientErrorReason::from(ErrorStateReason::Dns)),
+            ])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn tunnel_down_while_connecting_retries() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(vec![MonitorStep::SyncingAccount, MonitorStep::Down(None)]);
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[
+                connecting(),
+                connecting(),
+                connecting_with_data(),
+                connected(),
+            ])
+            .await;
+
+        let attempts = harness
+            .system_calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                SystemCall::StartTunnel { retry_attempt } => Some(retry_attempt),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(attempts, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn settings_change_while_connected_reconnects() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(successful_connection());
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+
+        // Changing the settings while connected forces a reconnect
+        harness.send(TunnelCommand::SetTunnelSettings(TunnelSettings {
+            enable_credentials_mode: true,
+            ..TunnelSettings::default()
+        }));
+        harness
+            .expect_states(&[
+                disconnecting(ActionAfterDisconnect::Reconnect),
+                connecting(),
+                connecting_with_data(),
+                connected(),
+            ])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn offline_while_connected_reconnects_once_online() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(successful_connection());
+        // Gateways are already known when reconnecting
+        harness.script_tunnel(vec![MonitorStep::InterfaceUp, MonitorStep::Up]);
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+
+        harness.set_online(false);
+        harness
+            .expect_states(&[
+                disconnecting(ActionAfterDisconnect::Offline),
+                TunnelState::Offline { reconnect: true },
+            ])
+            .await;
+
+        harness.set_online(true);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn reports_initial_state() {
+        for (online, initial_state) in [
+            (true, TunnelState::Disconnected),
+            (false, TunnelState::Offline { reconnect: false }),
+        ] {
+            let mut harness = TunnelStateMachineHarness::spawn(online, FakeSystem::default()).await;
+            let event = harness.event_rx.try_recv().expect("no initial event");
+            assert!(
+                matches!(&event, TunnelEvent::NewState(state) if *state == initial_state),
+                "unexpected initial event {event:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn offline_without_reconnect_returns_to_disconnected() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+
+        harness.set_online(false);
+        harness
+            .expect_states(&[TunnelState::Offline { reconnect: false }])
+            .await;
+
+        harness.set_online(true);
+        harness.expect_states(&[TunnelState::Disconnected]).await;
+        assert!(harness.system_calls().contains(&SystemCall::ResetDns));
+    }
+
+    #[tokio::test]
+    async fn connect_while_offline_connects_once_online() {
+        let mut harness = TunnelStateMachineHarness::start(false).await;
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[TunnelState::Offline { reconnect: true }])
+            .await;
+
+        harness.set_online(true);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+    }
+
+    #[tokio::test]
+    async fn shutdown_while_connected_disconnects() {
+        let mut harness = TunnelStateMachineHarness::start(true).await;
+        harness.script_tunnel(successful_connection());
+
+        harness.send(TunnelCommand::Connect);
+        harness
+            .expect_states(&[connecting(), connecting_with_data(), connected()])
+            .await;
+
+        let states = harness.shutdown().await;
+        assert_eq!(
+            states,
+            vec![
+                disconnecting(ActionAfterDisconnect::Nothing),
+                TunnelState::Disconnected
+            ]
+        );
+    }
+}
@@ -16,6 +16,7 @@ pub async fn select_gateways(
     tunnel_type: TunnelType,
     entry_point: Box<EntryPoint>,
     exit_point: Box<ExitPoint>,
+    excluded_gateways: &[Gateway],
 ) -> Result<SelectedGateways, GatewayDirectoryError> {
     // The set of exit gateways is smaller than the set of entry gateways, so we start by selecting
     // the exit gateway and then filter out the exit gateway from the set of entry gateways.
@@ -36,7 +37,7 @@ pub async fn select_gateways(
         }
     };
 
-    let (mut entry_gateways, exit_gateways) = match tunnel_type {
+    let (mut entry_gateways, mut exit_gateways) = match tunnel_type {
         TunnelType::Wireguard => {
             let all_gateways = gateway_directory_client
                 .lookup_gateways(GatewayType::Wg)
@@ -59,6 +60,12 @@ pub async fn select_gateways(
         }
     };
 
+    // Skip the gateways that failed the precheck in earlier attempts
+    for gateway in excluded_gateways {
+        entry_gateways.remove_gateway(gateway);
+        exit_gateways.remove_gateway(gateway);
+    }
+
     tracing::info!("Found {} entry gateways", entry_gateways.len());
     tracing::info!("Found {} exit gateways", exit_gateways.len());
 
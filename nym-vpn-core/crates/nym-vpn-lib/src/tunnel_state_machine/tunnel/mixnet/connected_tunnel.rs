@@ -47,8 +47,8 @@ impl ConnectedTunnel {
         &self.assigned_addresses
     }
 
-    pub async fn run(self, tun_device: AsyncDevice) -> TunnelHandle {
-        let connection_monitor = ConnectionMonitorTask::setup();
+    pub async fn run(self, tun_device: AsyncDevice, ipv6_enabled: bool) -> TunnelHandle {
+        let connection_monitor = ConnectionMonitorTask::setup(ipv6_enabled);
 
         let processor_config = MixnetProcessorConfig::new(
             self.assigned_addresses.exit_mix_addresses,
@@ -13,20 +13,21 @@ use std::{error::Error as StdError, fmt, path::PathBuf, time::Duration};
 use std::{os::fd::RawFd, sync::Arc};
 
 pub use gateway_selector::SelectedGateways;
-use nym_gateway_directory::{EntryPoint, ExitPoint, GatewayClient, Recipient};
+use nym_gateway_directory::{EntryPoint, ExitPoint, Gateway, GatewayClient, Recipient};
 use nym_mixnet_client::SharedMixnetClient;
 use nym_sdk::UserAgent;
 use nym_task::{TaskManager, TaskStatus};
+use nym_vpn_account_controller::AccountControllerCommander;
 use nym_vpn_network_config::Network;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 #[cfg(windows)]
 use super::route_handler;
-use super::{MixnetEvent, TunnelType};
+use super::{GatewayPrecheckError, MixnetEvent, TunnelType};
 use crate::{GatewayDirectoryError, MixnetClientConfig, MixnetError};
 pub use any_tunnel_handle::AnyTunnelHandle;
-use status_listener::StatusListener;
+use status_listener::{MixnetSpendTracker, StatusListener};
 pub use tombstone::Tombstone;
 
 pub(crate) const MIXNET_CLIENT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
@@ -54,6 +55,7 @@ impl ConnectedMixnet {
     pub async fn start_event_listener(
         &mut self,
         event_sender: mpsc::UnboundedSender<MixnetEvent>,
+        account_controller_tx: Option<AccountControllerCommander>,
         cancel_token: CancellationToken,
     ) -> JoinHandle<()> {
         let (status_tx, status_rx) = futures::channel::mpsc::channel(10);
@@ -62,7 +64,15 @@ impl ConnectedMixnet {
             .start_status_listener(status_tx, TaskStatus::Ready)
             .await;
 
-        StatusListener::spawn(status_rx, event_sender, cancel_token)
+        // Only track spends when the mixnet client is actually using credentials
+        let spend_tracker = account_controller_tx.map(|account_controller_tx| {
+            MixnetSpendTracker::spawn(
+                account_controller_tx,
+                self.selected_gateways.entry.identity().to_base58_string(),
+            )
+        });
+
+        StatusListener::spawn(status_rx, event_sender, spend_tracker, cancel_token)
     }
 
     /// Creates a tunnel over Mixnet.
@@ -93,6 +103,7 @@ impl ConnectedMixnet {
         self,
         network: &Network,
         enable_credentials_mode: bool,
+        account_controller_tx: AccountControllerCommander,
         cancel_token: CancellationToken,
     ) -> Result<wireguard::connected_tunnel::ConnectedTunnel> {
         let connector = wireguard::connector::Connector::new(
@@ -107,6 +118,7 @@ impl ConnectedMixnet {
                 enable_credentials_mode,
                 self.selected_gateways,
                 self.data_path,
+                account_controller_tx,
                 cancel_token,
             )
             .await
@@ -145,6 +157,7 @@ pub async fn select_gateways(
     tunnel_type: TunnelType,
     entry_point: Box<EntryPoint>,
     exit_point: Box<ExitPoint>,
+    excluded_gateways: &[Gateway],
     user_agent: Option<UserAgent>,
     cancel_token: CancellationToken,
 ) -> Result<SelectedGateways> {
@@ -155,13 +168,15 @@ pub async fn select_gateways(
         user_agent,
         resolved_gateway_config.nym_vpn_api_socket_addrs.as_deref(),
     )
-    .map_err(Error::CreateGatewayClient)?;
+    .map_err(Error::CreateGatewayClient)?
+    .with_harbour_master_socket_addrs(resolved_gateway_config.harbour_master_socket_addrs);
 
     let select_gateways_fut = gateway_selector::select_gateways(
         &gateway_directory_client,
         tunnel_type,
         entry_point,
         exit_point,
+        excluded_gateways,
     );
     cancel_token
         .run_until_cancelled(select_gateways_fut)
@@ -272,6 +287,9 @@ pub enum Error {
     #[error("failed to select gateways: {}", _0)]
     SelectGateways(#[source] GatewayDirectoryError),
 
+    #[error(transparent)]
+    GatewayPrecheck(#[from] GatewayPrecheckError),
+
     #[error("start mixnet client timeout")]
     StartMixnetClientTimeout,
 
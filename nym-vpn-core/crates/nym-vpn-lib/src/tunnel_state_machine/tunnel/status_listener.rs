@@ -6,13 +6,96 @@ use tokio::{sync::mpsc, task::JoinHandle};
 
 use nym_bandwidth_controller::BandwidthStatusMessage;
 use nym_connection_monitor::ConnectionMonitorStatus;
+use nym_credentials_interface::TicketType;
 use nym_task::{StatusReceiver, TaskStatus};
+use nym_vpn_account_controller::{AccountControllerCommander, CredentialSpend};
 use nym_vpn_lib_types::{BandwidthEvent, ConnectionEvent, ConnectionStatisticsEvent, MixnetEvent};
 use tokio_util::sync::CancellationToken;
 
+use crate::bandwidth_controller::BandwidthForecastStatus;
+
+// The mixnet client spends tickets with the entry gateway internally, so the only trace of a
+// spend we get is the remaining bandwidth going up. When that happens we compare the local
+// credential storage with the previous snapshot to find out which tickets were spent.
+//
+// Wireguard spends are recorded by the bandwidth controller, so only mixnet tickets are tracked
+// here.
+const MIXNET_TICKET_TYPES: [TicketType; 2] = [TicketType::V1MixnetEntry, TicketType::V1MixnetExit];
+
+pub struct MixnetSpendTracker {
+    remaining_bandwidth_tx: mpsc::UnboundedSender<i64>,
+}
+
+impl MixnetSpendTracker {
+    pub fn spawn(account_controller_tx: AccountControllerCommander, gateway_id: String) -> Self {
+        let (remaining_bandwidth_tx, remaining_bandwidth_rx) = mpsc::unbounded_channel();
+        // Exits once the tracker, and with it the sender, is dropped
+        tokio::spawn(Self::run(
+            account_controller_tx,
+            gateway_id,
+            remaining_bandwidth_rx,
+        ));
+        Self {
+            remaining_bandwidth_tx,
+        }
+    }
+
+    fn on_bandwidth_status(&self, msg: &BandwidthStatusMessage) {
+        if let BandwidthStatusMessage::RemainingBandwidth(remaining_bandwidth) = *msg {
+            let _ = self.remaining_bandwidth_tx.send(remaining_bandwidth);
+        }
+    }
+
+    async fn run(
+        account_controller_tx: AccountControllerCommander,
+        gateway_id: String,
+        mut remaining_bandwidth_rx: mpsc::UnboundedReceiver<i64>,
+    ) {
+        let mut last_remaining_bandwidth = None;
+        let mut last_remaining_tickets: Option<[u64; MIXNET_TICKET_TYPES.len()]> = None;
+
+        while let Some(remaining_bandwidth) = remaining_bandwidth_rx.recv().await {
+            let is_top_up = last_remaining_bandwidth.is_some_and(|last| remaining_bandwidth > last);
+            last_remaining_bandwidth = Some(remaining_bandwidth);
+            if !is_top_up && last_remaining_tickets.is_some() {
+                continue;
+            }
+
+            let remaining_tickets = match account_controller_tx.get_available_tickets().await {
+                Ok(ticketbooks) => MIXNET_TICKET_TYPES
+                    .map(|ticket_type| ticketbooks.remaining_tickets(ticket_type)),
+                Err(err) => {
+                    tracing::warn!("Failed to get available tickets to track spends: {err}");
+                    continue;
+                }
+            };
+
+            if let Some(last_remaining_tickets) = last_remaining_tickets.filter(|_| is_top_up) {
+                for ((ticket_type, last), remaining) in MIXNET_TICKET_TYPES
+                    .into_iter()
+                    .zip(last_remaining_tickets)
+                    .zip(remaining_tickets)
+                {
+                    let spent = last.saturating_sub(remaining);
+                    if spent > 0 {
+                        account_controller_tx.record_credential_spend(CredentialSpend::success(
+                            ticket_type,
+                            &gateway_id,
+                            u32::try_from(spent).unwrap_or(u32::MAX),
+                            Some(remaining_bandwidth),
+                        ));
+                    }
+                }
+            }
+            last_remaining_tickets = Some(remaining_tickets);
+        }
+    }
+}
+
 pub struct StatusListener {
     rx: StatusReceiver,
     tx: mpsc::UnboundedSender<MixnetEvent>,
+    spend_tracker: Option<MixnetSpendTracker>,
     cancel_token: CancellationToken,
 }
 
@@ -20,12 +103,14 @@ impl StatusListener {
     pub fn spawn(
         rx: StatusReceiver,
         tx: mpsc::UnboundedSender<MixnetEvent>,
+        spend_tracker: Option<MixnetSpendTracker>,
         cancel_token: CancellationToken,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
             let status_listener = Self {
                 rx,
                 tx,
+                spend_tracker,
                 cancel_token,
             };
             status_listener.run().await;
@@ -54,7 +139,14 @@ impl StatusListener {
                     } else if let Some(msg) = msg.as_any().downcast_ref::<ConnectionMonitorStatus>() {
                         self.send_event(MixnetEvent::Connection(ConnectionEvent::from(msg)));
                     } else if let Some(msg) = msg.as_any().downcast_ref::<BandwidthStatusMessage>() {
+                        if let Some(spend_tracker) = self.spend_tracker.as_ref() {
+                            spend_tracker.on_bandwidth_status(msg);
+                        }
                         self.send_event(MixnetEvent::Bandwidth(BandwidthEvent::from(msg)));
+                    } else if let Some(msg) = msg.as_any().downcast_ref::<BandwidthForecastStatus>() {
+                        self.send_event(MixnetEvent::Bandwidth(BandwidthEvent::DepletionForecast(
+                            msg.0,
+                        )));
                     } else if let Some(msg) = msg
                         .as_any()
                         .downcast_ref::<MixnetBandwidthStatisticsEvent>()
@@ -29,7 +29,10 @@ use crate::tunnel_state_machine::route_handler::TUNNEL_FWMARK;
 use crate::tunnel_state_machine::tunnel::wireguard::fd::DupFd;
 use crate::{
     tunnel_state_machine::tunnel::{
-        wireguard::{connector::ConnectionData, two_hop_config::TwoHopConfig},
+        wireguard::{
+            connector::ConnectionData,
+            two_hop_config::{TwoHopConfig, TwoHopMtu},
+        },
         Error, Result, Tombstone,
     },
     wg_config::WgNodeConfig,
@@ -42,6 +45,7 @@ pub struct ConnectedTunnel {
     connection_data: ConnectionData,
     bandwidth_controller_handle: JoinHandle<()>,
     auth_client_mixnet_listener_handle: AuthClientMixnetListenerHandle,
+    mtu: TwoHopMtu,
 }
 
 impl ConnectedTunnel {
@@ -60,6 +64,7 @@ impl ConnectedTunnel {
             connection_data,
             bandwidth_controller_handle,
             auth_client_mixnet_listener_handle,
+            mtu: TwoHopMtu::default(),
         }
     }
 
@@ -68,13 +73,16 @@ impl ConnectedTunnel {
     }
 
     pub fn entry_mtu(&self) -> u16 {
-        // 1500 - 80 (ipv6+wg header)
-        1420
+        self.mtu.entry
     }
 
     pub fn exit_mtu(&self) -> u16 {
-        // 1420 - 80 (ipv6+wg header)
-        1340
+        self.mtu.exit
+    }
+
+    /// Override the MTUs used for the entry and exit tunnels.
+    pub fn set_mtu(&mut self, mtu: TwoHopMtu) {
+        self.mtu = mtu;
     }
 
     pub async fn run(
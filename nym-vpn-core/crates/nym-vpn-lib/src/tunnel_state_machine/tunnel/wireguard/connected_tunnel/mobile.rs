@@ -33,7 +33,7 @@ use crate::{
         wireguard::{
             connector::ConnectionData,
             fd::DupFd,
-            two_hop_config::{TwoHopConfig, ENTRY_MTU, EXIT_MTU},
+            two_hop_config::{TwoHopConfig, TwoHopMtu},
         },
         Error, Result, Tombstone,
     },
@@ -51,6 +51,7 @@ pub struct ConnectedTunnel {
     connection_data: ConnectionData,
     bandwidth_controller_handle: JoinHandle<()>,
     auth_client_mixnet_listener_handle: AuthClientMixnetListenerHandle,
+    mtu: TwoHopMtu,
 }
 
 impl ConnectedTunnel {
@@ -69,6 +70,7 @@ impl ConnectedTunnel {
             connection_data,
             bandwidth_controller_handle,
             auth_client_mixnet_listener_handle,
+            mtu: TwoHopMtu::default(),
         }
     }
 
@@ -77,12 +79,16 @@ impl ConnectedTunnel {
     }
 
     pub fn entry_mtu(&self) -> u16 {
-        ENTRY_MTU
+        self.mtu.entry
     }
 
     pub fn exit_mtu(&self) -> u16 {
-        // minimum mtu guaranteed by ipv6
-        EXIT_MTU
+        self.mtu.exit
+    }
+
+    /// Override the MTUs used for the entry and exit tunnels.
+    pub fn set_mtu(&mut self, mtu: TwoHopMtu) {
+        self.mtu = mtu;
     }
 
     pub async fn run(
@@ -12,6 +12,7 @@ use nym_gateway_directory::{AuthAddresses, Gateway, GatewayClient};
 use nym_mixnet_client::SharedMixnetClient;
 use nym_sdk::mixnet::{ConnectionStatsEvent, EphemeralCredentialStorage, StoragePaths};
 use nym_task::TaskManager;
+use nym_vpn_account_controller::AccountControllerCommander;
 use nym_wg_gateway_client::{GatewayData, WgGatewayClient};
 use tokio_util::sync::CancellationToken;
 
@@ -52,6 +53,7 @@ impl Connector {
         enable_credentials_mode: bool,
         selected_gateways: SelectedGateways,
         data_path: Option<PathBuf>,
+        account_controller_tx: AccountControllerCommander,
         cancel_token: CancellationToken,
     ) -> Result<ConnectedTunnel, ConnectorError> {
         let result = Self::connect_inner(
@@ -62,6 +64,7 @@ impl Connector {
             enable_credentials_mode,
             selected_gateways,
             data_path,
+            account_controller_tx,
             cancel_token,
         )
         .await;
@@ -95,6 +98,7 @@ impl Connector {
         enable_credentials_mode: bool,
         selected_gateways: SelectedGateways,
         data_path: Option<PathBuf>,
+        account_controller_tx: AccountControllerCommander,
         cancel_token: CancellationToken,
     ) -> Result<ConnectResult> {
         let auth_addresses =
@@ -162,6 +166,8 @@ impl Connector {
                 network,
                 wg_entry_gateway_client.light_client(),
                 wg_exit_gateway_client.light_client(),
+                account_controller_tx,
+                enable_credentials_mode,
                 shutdown,
             )?;
             let entry_fut = bw.get_initial_bandwidth(
@@ -192,6 +198,8 @@ impl Connector {
                 network,
                 wg_entry_gateway_client.light_client(),
                 wg_exit_gateway_client.light_client(),
+                account_controller_tx,
+                enable_credentials_mode,
                 shutdown,
             )?;
             let entry = bw
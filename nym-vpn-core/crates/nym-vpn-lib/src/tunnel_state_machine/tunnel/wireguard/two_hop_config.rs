@@ -31,6 +31,62 @@ pub const EXIT_MTU: u16 = if cfg!(any(target_os = "ios", target_os = "android"))
     ETHERNET_V2_MTU - WG_TUNNEL_OVERHEAD * 2
 };
 
+/// Entry and exit MTUs of the two-hop tunnel.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TwoHopMtu {
+    pub entry: u16,
+    pub exit: u16,
+}
+
+impl TwoHopMtu {
+    /// Derive the MTUs from the MTU of the link used to reach the entry gateway.
+    pub fn from_link_mtu(link_mtu: u16) -> Self {
+        Self::from_entry_mtu(link_mtu.saturating_sub(WG_TUNNEL_OVERHEAD))
+    }
+
+    /// Derive the exit MTU from the entry MTU, so that the exit traffic fits into the entry tunnel.
+    pub fn from_entry_mtu(entry_mtu: u16) -> Self {
+        let entry = entry_mtu.max(MIN_IPV6_MTU + WG_TUNNEL_OVERHEAD);
+        Self {
+            entry,
+            exit: entry - WG_TUNNEL_OVERHEAD,
+        }
+    }
+
+    /// Apply the MTU overrides, falling back to the built-in MTUs. Fails if the exit traffic
+    /// doesn't fit into the entry tunnel.
+    pub fn with_overrides(
+        entry_mtu: Option<u16>,
+        exit_mtu: Option<u16>,
+    ) -> Result<Self, MtuMismatch> {
+        let mtu = entry_mtu.map(Self::from_entry_mtu).unwrap_or_default();
+        let entry = entry_mtu.unwrap_or(mtu.entry);
+        let exit = exit_mtu.unwrap_or(mtu.exit);
+
+        if u32::from(exit) + u32::from(WG_TUNNEL_OVERHEAD) > u32::from(entry) {
+            return Err(MtuMismatch { entry, exit });
+        }
+        Ok(Self { entry, exit })
+    }
+}
+
+/// The exit MTU is too large to fit into the entry MTU.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
+#[error("exit MTU {exit} doesn't fit into the entry MTU {entry}")]
+pub struct MtuMismatch {
+    pub entry: u16,
+    pub exit: u16,
+}
+
+impl Default for TwoHopMtu {
+    fn default() -> Self {
+        Self {
+            entry: ENTRY_MTU,
+            exit: EXIT_MTU,
+        }
+    }
+}
+
 /// A struct that holds all configuration needed to setup the tunnels, tun device and forwarder.
 #[derive(Debug)]
 pub struct TwoHopConfig {
@@ -80,8 +136,8 @@ impl TwoHopConfig {
         };
 
         // Since we collect the exit traffic on tun, the tun's mtu must be lesser than entry mtu.
-        let exit_mtu = EXIT_MTU;
-        let entry_mtu = ENTRY_MTU;
+        let exit_mtu = exit.interface.mtu;
+        let entry_mtu = entry.interface.mtu;
 
         let tun_config = TunConfig {
             addresses: exit.interface.addresses.clone(),
@@ -133,3 +189,78 @@ pub struct TunConfig {
     pub dns: Vec<IpAddr>,
     pub mtu: u16,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mtu_from_link_mtu() {
+        assert_eq!(
+            TwoHopMtu::from_link_mtu(ETHERNET_V2_MTU),
+            TwoHopMtu {
+                entry: 1420,
+                exit: 1340
+            }
+        );
+        // PPPoE
+        assert_eq!(
+            TwoHopMtu::from_link_mtu(1492),
+            TwoHopMtu {
+                entry: 1412,
+                exit: 1332
+            }
+        );
+        // The exit MTU never drops below the minimum IPv6 MTU
+        assert_eq!(TwoHopMtu::from_link_mtu(1000).exit, MIN_IPV6_MTU);
+    }
+
+    #[test]
+    fn mtu_with_overrides() {
+        assert_eq!(
+            TwoHopMtu::with_overrides(None, None),
+            Ok(TwoHopMtu::default())
+        );
+        assert_eq!(
+            TwoHopMtu::with_overrides(Some(1400), None),
+            Ok(TwoHopMtu {
+                entry: 1400,
+                exit: 1320
+            })
+        );
+        assert_eq!(
+            TwoHopMtu::with_overrides(None, Some(1300)),
+            Ok(TwoHopMtu {
+                entry: ENTRY_MTU,
+                exit: 1300
+            })
+        );
+    }
+
+    #[test]
+    fn mtu_overrides_must_fit() {
+        // The derived exit MTU never drops below the minimum IPv6 MTU
+        assert_eq!(
+            TwoHopMtu::with_overrides(Some(1300), None),
+            Err(MtuMismatch {
+                entry: 1300,
+                exit: MIN_IPV6_MTU
+            })
+        );
+        assert!(TwoHopMtu::with_overrides(Some(1360), None).is_ok());
+        assert_eq!(
+            TwoHopMtu::with_overrides(Some(1400), Some(1350)),
+            Err(MtuMismatch {
+                entry: 1400,
+                exit: 1350
+            })
+        );
+        assert_eq!(
+            TwoHopMtu::with_overrides(None, Some(ENTRY_MTU)),
+            Err(MtuMismatch {
+                entry: ENTRY_MTU,
+                exit: ENTRY_MTU
+            })
+        );
+    }
+}
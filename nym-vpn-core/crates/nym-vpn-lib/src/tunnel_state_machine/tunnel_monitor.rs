@@ -4,15 +4,17 @@
 #[cfg(target_os = "linux")]
 use nix::sys::socket::{sockopt::Mark, SetSockOpt};
 use nym_vpn_network_config::start_background_discovery_refresh;
-#[cfg(any(target_os = "linux", target_os = "macos"))]
-use std::net::Ipv4Addr;
 #[cfg(any(target_os = "linux", target_os = "ios", target_os = "android"))]
 use std::os::fd::BorrowedFd;
 #[cfg(any(target_os = "android", target_os = "ios"))]
 use std::os::fd::{AsRawFd, IntoRawFd};
 #[cfg(target_os = "android")]
 use std::os::fd::{FromRawFd, OwnedFd};
-use std::{cmp, net::IpAddr, time::Duration};
+use std::{
+    cmp,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
 #[cfg(unix)]
 use std::{os::fd::RawFd, sync::Arc};
 
@@ -20,7 +22,7 @@ use std::{os::fd::RawFd, sync::Arc};
 use super::wintun::{self, WintunAdapterConfig};
 #[cfg(any(target_os = "ios", target_os = "android"))]
 use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
-use nym_gateway_directory::{GatewayMinPerformance, ResolvedConfig};
+use nym_gateway_directory::{EntryPoint, ExitPoint, GatewayMinPerformance, ResolvedConfig};
 use nym_vpn_account_controller::{AccountCommand, AccountControllerCommander};
 use time::OffsetDateTime;
 use tokio::{sync::mpsc, task::JoinHandle};
@@ -32,18 +34,22 @@ use tun::Device;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use nym_ip_packet_requests::IpPair;
 
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use super::mtu_probe::MtuProbe;
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use super::route_handler::RouteHandler;
 #[cfg(any(target_os = "ios", target_os = "android"))]
 use super::tun_name;
-#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-use super::{route_handler::RoutingConfig, tun_ipv6};
 use super::{
+    gateway_precheck::{GatewayPrecheckError, MAX_PRECHECK_ATTEMPTS, MAX_PRECHECK_DURATION},
     tunnel::{
-        self, AnyTunnelHandle, ConnectedMixnet, MixnetConnectOptions, SelectedGateways, Tombstone,
+        self, wireguard::two_hop_config::TwoHopMtu, AnyTunnelHandle, ConnectedMixnet,
+        MixnetConnectOptions, SelectedGateways, Tombstone,
     },
-    Error, NymConfig, Result, TunnelInterface, TunnelMetadata, TunnelSettings,
+    Error, NymConfig, Result, TunnelInterface, TunnelMetadata, TunnelSettings, WireguardMtu,
 };
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+use super::{route_handler::RoutingConfig, tun_ipv6};
 use nym_vpn_lib_types::{
     ConnectionData, ErrorStateReason, Gateway, MixnetConnectionData, MixnetEvent, NymAddress,
     RequestZkNymError, TunnelConnectionData, TunnelType, WireguardConnectionData, WireguardNode,
@@ -61,6 +67,8 @@ use crate::tunnel_provider::android::AndroidTunProvider;
 use crate::tunnel_provider::ios::OSTunProvider;
 #[cfg(target_os = "linux")]
 use crate::tunnel_state_machine::route_handler::TUNNEL_FWMARK;
+#[cfg(windows)]
+use crate::tunnel_state_machine::TunnelRoutes;
 use crate::tunnel_state_machine::{account, WireguardMultihopMode};
 
 /// Default MTU for mixnet tun device.
@@ -170,6 +178,13 @@ pub struct TunnelMonitorHandle {
 }
 
 impl TunnelMonitorHandle {
+    pub fn new(cancel_token: CancellationToken, join_handle: JoinHandle<Tombstone>) -> Self {
+        Self {
+            cancel_token,
+            join_handle,
+        }
+    }
+
     pub fn cancel(&self) {
         tracing::info!("Cancelling tunnel monitor handle");
         self.cancel_token.cancel();
@@ -233,10 +248,7 @@ impl TunnelMonitor {
         };
         let join_handle = tokio::spawn(tunnel_monitor.run());
 
-        TunnelMonitorHandle {
-            cancel_token,
-            join_handle,
-        }
+        TunnelMonitorHandle::new(cancel_token, join_handle)
     }
 
     async fn run(mut self) -> Tombstone {
@@ -260,6 +272,112 @@ impl TunnelMonitor {
         tombstone
     }
 
+    /// Select new gateways, replacing the ones failing the precheck when it's enabled.
+    async fn select_gateways(
+        &self,
+        gateway_config: &nym_gateway_directory::Config,
+    ) -> Result<SelectedGateways> {
+        let tunnel_settings = &self.tunnel_parameters.tunnel_settings;
+        let precheck = self
+            .tunnel_parameters
+            .nym_config
+            .gateway_precheck
+            .as_ref()
+            .filter(|_| tunnel_settings.precheck_gateways);
+        let precheck_deadline = tokio::time::Instant::now() + MAX_PRECHECK_DURATION;
+        let mut excluded_gateways = Vec::new();
+
+        loop {
+            let new_gateways = tunnel::select_gateways(
+                gateway_config.clone(),
+                self.tunnel_parameters.resolved_gateway_config.clone(),
+                tunnel_settings.tunnel_type,
+                tunnel_settings.entry_point.clone(),
+                tunnel_settings.exit_point.clone(),
+                &excluded_gateways,
+                tunnel_settings.user_agent.clone(),
+                self.cancel_token.child_token(),
+            )
+            .await?;
+
+            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+            self.send_event(TunnelMonitorEvent::SelectedGateways {
+                gateways: Box::new(new_gateways.clone()),
+                reply_tx,
+            });
+
+            // Wait for reply before proceeding to connect to let state machine configure firewall.
+            // The precheck connects to the entry gateway, so it also has to wait for it.
+            if tokio::time::timeout(REPLY_TIMEOUT, reply_rx).await.is_err() {
+                tracing::warn!("Failed to receive selected gateways reply in time");
+            }
+
+            let Some(precheck) = precheck else {
+                return Ok(new_gateways);
+            };
+
+            tracing::info!(
+                "Running precheck against entry {} and exit {}",
+                new_gateways.entry.identity(),
+                new_gateways.exit.identity()
+            );
+            let result = self
+                .cancel_token
+                .run_until_cancelled(tokio::time::timeout_at(
+                    precheck_deadline,
+                    precheck.check(
+                        tunnel_settings.tunnel_type,
+                        &new_gateways.entry,
+                        &new_gateways.exit,
+                    ),
+                ))
+                .await
+                .ok_or(tunnel::Error::Cancelled)?;
+
+            let err = match result {
+                Ok(Ok(())) => return Ok(new_gateways),
+                Ok(Err(err)) => err,
+                Err(_) => {
+                    tracing::warn!(
+                        "Precheck took longer than {}s, connecting without it",
+                        MAX_PRECHECK_DURATION.as_secs()
+                    );
+                    return Ok(new_gateways);
+                }
+            };
+            tracing::warn!("{err}");
+
+            // Gateways requested by identity can't be replaced by another candidate.
+            let failed_gateway = match &err {
+                GatewayPrecheckError::Entry { .. }
+                    if !matches!(
+                        tunnel_settings.entry_point.as_ref(),
+                        EntryPoint::Gateway { .. }
+                    ) =>
+                {
+                    new_gateways.entry
+                }
+                GatewayPrecheckError::Exit { .. }
+                    if matches!(
+                        tunnel_settings.exit_point.as_ref(),
+                        ExitPoint::Location { .. } | ExitPoint::Random
+                    ) =>
+                {
+                    new_gateways.exit
+                }
+                GatewayPrecheckError::Entry { .. } | GatewayPrecheckError::Exit { .. } => {
+                    return Err(tunnel::Error::GatewayPrecheck(err).into());
+                }
+            };
+            excluded_gateways.push(*failed_gateway);
+
+            if excluded_gateways.len() >= MAX_PRECHECK_ATTEMPTS {
+                return Err(tunnel::Error::GatewayPrecheck(err).into());
+            }
+            tracing::info!("Selecting another gateway");
+        }
+    }
+
     async fn run_inner(&mut self) -> Result<Tombstone> {
         if self.tunnel_parameters.retry_attempt > 0 {
             let delay = wait_delay(self.tunnel_parameters.retry_attempt);
@@ -290,7 +408,17 @@ impl TunnelMonitor {
                 .map(u64::from),
         );
 
-        let mut gateway_config = self.tunnel_parameters.nym_config.gateway_config.clone();
+        let mut gateway_config = self
+            .tunnel_parameters
+            .nym_config
+            .gateway_config
+            .clone()
+            .with_custom_gateways(
+                self.tunnel_parameters
+                    .tunnel_settings
+                    .custom_gateways
+                    .clone(),
+            );
         match gateway_min_performance {
             Ok(gateway_min_performance) => {
                 gateway_config =
@@ -307,29 +435,7 @@ impl TunnelMonitor {
             if let Some(selected_gateways) = self.tunnel_parameters.selected_gateways.clone() {
                 selected_gateways
             } else {
-                let new_gateways = tunnel::select_gateways(
-                    gateway_config.clone(),
-                    self.tunnel_parameters.resolved_gateway_config.clone(),
-                    self.tunnel_parameters.tunnel_settings.tunnel_type,
-                    self.tunnel_parameters.tunnel_settings.entry_point.clone(),
-                    self.tunnel_parameters.tunnel_settings.exit_point.clone(),
-                    self.tunnel_parameters.tunnel_settings.user_agent.clone(),
-                    self.cancel_token.child_token(),
-                )
-                .await?;
-
-                let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
-                self.send_event(TunnelMonitorEvent::SelectedGateways {
-                    gateways: Box::new(new_gateways.clone()),
-                    reply_tx,
-                });
-
-                // Wait for reply before proceeding to connect to let state machine configure firewall.
-                if tokio::time::timeout(REPLY_TIMEOUT, reply_rx).await.is_err() {
-                    tracing::warn!("Failed to receive selected gateways reply in time");
-                }
-
-                new_gateways
+                self.select_gateways(&gateway_config).await?
             };
 
         let connect_options = MixnetConnectOptions {
@@ -391,6 +497,10 @@ impl TunnelMonitor {
         let status_listener_handle = connected_mixnet
             .start_event_listener(
                 self.mixnet_event_sender.clone(),
+                self.tunnel_parameters
+                    .tunnel_settings
+                    .enable_credentials_mode
+                    .then(|| self.account_controller_tx.clone()),
                 self.cancel_token.child_token(),
             )
             .await;
@@ -400,6 +510,8 @@ impl TunnelMonitor {
             tunnel_interface,
             tunnel_conn_data,
             mut tunnel_handle,
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            mtu_probe,
         } = match self.tunnel_parameters.tunnel_settings.tunnel_type {
             TunnelType::Mixnet => self.start_mixnet_tunnel(connected_mixnet).await?,
             TunnelType::Wireguard => {
@@ -469,6 +581,11 @@ impl TunnelMonitor {
             connection_data: Box::new(connection_data),
         });
 
+        // Probe in the background so that it doesn't hold back the connected state. The firewall
+        // allows the in-tunnel traffic once the interface is up.
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        let mtu_probe_handle = mtu_probe.map(|mtu_probe| tokio::spawn(mtu_probe.run()));
+
         let task_error = self
             .cancel_token
             .run_until_cancelled(tunnel_handle.recv_error())
@@ -478,6 +595,16 @@ impl TunnelMonitor {
             tracing::error!("Task manager quit with error: {}", task_error);
         }
 
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(mtu_probe_handle) = mtu_probe_handle {
+            mtu_probe_handle.abort();
+            if let Err(e) = mtu_probe_handle.await {
+                if !e.is_cancelled() {
+                    tracing::error!("Failed to join on MTU probe: {}", e);
+                }
+            }
+        }
+
         tracing::debug!("Wait for tunnel to exit");
         tunnel_handle.cancel().await;
 
@@ -512,6 +639,122 @@ impl TunnelMonitor {
         }
     }
 
+    fn ipv6_enabled(&self) -> bool {
+        self.tunnel_parameters
+            .tunnel_settings
+            .ip_family
+            .ipv6_enabled()
+    }
+
+    /// Returns the MTUs of the WireGuard tunnels.
+    async fn wireguard_mtu(&self, entry_endpoint: IpAddr) -> Result<TwoHopMtu> {
+        let mtu_option = self
+            .tunnel_parameters
+            .tunnel_settings
+            .wireguard_tunnel_options
+            .mtu;
+        let mtu = match mtu_option {
+            WireguardMtu::Default => TwoHopMtu::default(),
+            WireguardMtu::Manual { entry, exit } => {
+                TwoHopMtu::with_overrides(entry, exit).map_err(Error::InvalidWireguardMtu)?
+            }
+            WireguardMtu::Auto => match self.link_mtu(entry_endpoint).await {
+                Some(link_mtu) => TwoHopMtu::from_link_mtu(link_mtu),
+                None => {
+                    tracing::warn!(
+                        "Unknown MTU of the link to the entry gateway, starting from the default MTUs"
+                    );
+                    TwoHopMtu::default()
+                }
+            },
+        };
+
+        #[cfg(target_os = "windows")]
+        if mtu_option == WireguardMtu::Auto {
+            tracing::info!("The path MTU isn't probed on Windows, the MTUs only follow the route");
+        }
+
+        tracing::info!(
+            "WireGuard MTU ({}): entry {}, exit {}",
+            mtu_option,
+            mtu.entry,
+            mtu.exit
+        );
+        Ok(mtu)
+    }
+
+    /// Returns the MTU of the link used to reach the entry gateway.
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    async fn link_mtu(&self, entry_endpoint: IpAddr) -> Option<u16> {
+        self.route_handler
+            .get_mtu_for_route(entry_endpoint)
+            .await
+            .inspect_err(|e| {
+                tracing::warn!(
+                    "Failed to get the MTU of the route to the entry gateway: {}",
+                    e
+                );
+            })
+            .ok()
+    }
+
+    /// Returns the MTU of the link used to reach the entry gateway.
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    async fn link_mtu(&self, _entry_endpoint: IpAddr) -> Option<u16> {
+        tracing::info!("Reading the route MTU is not supported on this platform");
+        None
+    }
+
+    /// Returns the probe to run once the tunnel is up when the WireGuard MTU is detected
+    /// automatically. The probes are sent to the exit gateway, or to the first IPv4 DNS server in
+    /// the tunnel if the exit gateway has no IPv4 address.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn mtu_probe(
+        &self,
+        interface: &str,
+        outer_interface: Option<&str>,
+        mtu: u16,
+        exit_gateway: IpAddr,
+        tunnel_dns: &[IpAddr],
+    ) -> Option<MtuProbe> {
+        if self
+            .tunnel_parameters
+            .tunnel_settings
+            .wireguard_tunnel_options
+            .mtu
+            != WireguardMtu::Auto
+        {
+            return None;
+        }
+
+        let Some(destination) = std::iter::once(&exit_gateway)
+            .chain(tunnel_dns)
+            .find_map(|addr| match addr {
+                IpAddr::V4(addr) => Some(*addr),
+                IpAddr::V6(_) => None,
+            })
+        else {
+            tracing::warn!("No IPv4 address to probe through the tunnel, skipping the MTU probe");
+            return None;
+        };
+
+        Some(MtuProbe {
+            interface: interface.to_owned(),
+            outer_interface: outer_interface.map(ToOwned::to_owned),
+            destination,
+            mtu,
+        })
+    }
+
+    /// Returns the tunnel interface addresses of the IP families enabled in the tunnel.
+    fn interface_ips(&self, ipv4: Ipv4Addr, ipv6: Ipv6Addr) -> Vec<IpAddr> {
+        let mut ips = vec![IpAddr::V4(ipv4)];
+        if self.ipv6_enabled() {
+            ips.push(IpAddr::V6(ipv6));
+        }
+        ips
+    }
+
     async fn setup_account(&mut self) -> Result<()> {
         // Check if we have ticketbooks already stored, then we can sidestep the account and device
         // sync
@@ -592,7 +835,11 @@ impl TunnelMonitor {
             .unwrap_or(DEFAULT_TUN_MTU);
 
         #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-        let tun_device = Self::create_mixnet_device(assigned_addresses.interface_addresses, mtu)?;
+        let tun_device = Self::create_mixnet_device(
+            assigned_addresses.interface_addresses,
+            mtu,
+            self.ipv6_enabled(),
+        )?;
 
         #[cfg(any(target_os = "ios", target_os = "android"))]
         let tun_device = {
@@ -654,20 +901,23 @@ impl TunnelMonitor {
 
         let tunnel_metadata = TunnelMetadata {
             interface: tun_name,
-            ips: vec![
-                IpAddr::V4(assigned_addresses.interface_addresses.ipv4),
-                IpAddr::V6(assigned_addresses.interface_addresses.ipv6),
-            ],
+            ips: self.interface_ips(
+                assigned_addresses.interface_addresses.ipv4,
+                assigned_addresses.interface_addresses.ipv6,
+            ),
             ipv4_gateway: None,
             ipv6_gateway: None,
         };
 
-        let tunnel_handle = AnyTunnelHandle::from(connected_tunnel.run(tun_device).await);
+        let tunnel_handle =
+            AnyTunnelHandle::from(connected_tunnel.run(tun_device, self.ipv6_enabled()).await);
 
         Ok(StartTunnelResult {
             tunnel_interface: TunnelInterface::One(tunnel_metadata),
             tunnel_conn_data,
             tunnel_handle,
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            mtu_probe: None,
         })
     }
 
@@ -676,15 +926,18 @@ impl TunnelMonitor {
         &mut self,
         connected_mixnet: ConnectedMixnet,
     ) -> Result<StartTunnelResult> {
-        let connected_tunnel = connected_mixnet
+        let mut connected_tunnel = connected_mixnet
             .connect_wireguard_tunnel(
                 &self.tunnel_parameters.nym_config.network_env,
                 self.tunnel_parameters
                     .tunnel_settings
                     .enable_credentials_mode,
+                self.account_controller_tx.clone(),
                 self.cancel_token.clone(),
             )
             .await?;
+        let entry_endpoint = connected_tunnel.connection_data().entry.endpoint.ip();
+        connected_tunnel.set_mtu(self.wireguard_mtu(entry_endpoint).await?);
         let conn_data = connected_tunnel.connection_data();
 
         let exit_tun = Self::create_wireguard_device(
@@ -694,6 +947,7 @@ impl TunnelMonitor {
             },
             Some(conn_data.entry.private_ipv4),
             connected_tunnel.exit_mtu(),
+            self.ipv6_enabled(),
         )?;
         let exit_tun_name = exit_tun.get_ref().name().map_err(Error::GetTunDeviceName)?;
         tracing::info!("Created exit tun device: {}", exit_tun_name);
@@ -715,12 +969,19 @@ impl TunnelMonitor {
             .tunnel_parameters
             .tunnel_settings
             .dns
-            .to_dns_config()
+            .to_dns_config(self.tunnel_parameters.tunnel_settings.ip_family)
             .resolve(
                 &crate::DEFAULT_DNS_SERVERS,
                 #[cfg(target_os = "macos")]
                 53,
             );
+        let mtu_probe = self.mtu_probe(
+            &exit_tun_name,
+            None,
+            connected_tunnel.exit_mtu(),
+            conn_data.exit.endpoint.ip(),
+            dns_config.tunnel_config(),
+        );
         let tunnel_options = TunnelOptions::Netstack(NetstackTunnelOptions {
             exit_tun,
             dns: dns_config.tunnel_config().to_vec(),
@@ -728,12 +989,9 @@ impl TunnelMonitor {
 
         let tunnel_metadata = TunnelMetadata {
             interface: exit_tun_name,
-            ips: vec![
-                IpAddr::V4(conn_data.exit.private_ipv4),
-                IpAddr::V6(conn_data.exit.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.exit.private_ipv4, conn_data.exit.private_ipv6),
             ipv4_gateway: Some(conn_data.entry.private_ipv4),
-            ipv6_gateway: Some(conn_data.entry.private_ipv6),
+            ipv6_gateway: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
 
         let tunnel_handle = AnyTunnelHandle::from(connected_tunnel.run(tunnel_options).await?);
@@ -742,6 +1000,7 @@ impl TunnelMonitor {
             tunnel_interface: TunnelInterface::One(tunnel_metadata),
             tunnel_conn_data,
             tunnel_handle,
+            mtu_probe,
         })
     }
 
@@ -750,32 +1009,32 @@ impl TunnelMonitor {
         &mut self,
         connected_mixnet: ConnectedMixnet,
     ) -> Result<StartTunnelResult> {
-        let connected_tunnel = connected_mixnet
+        let mut connected_tunnel = connected_mixnet
             .connect_wireguard_tunnel(
                 &self.tunnel_parameters.nym_config.network_env,
                 self.tunnel_parameters
                     .tunnel_settings
                     .enable_credentials_mode,
+                self.account_controller_tx.clone(),
                 self.cancel_token.clone(),
             )
             .await?;
+        let entry_endpoint = connected_tunnel.connection_data().entry.endpoint.ip();
+        connected_tunnel.set_mtu(self.wireguard_mtu(entry_endpoint).await?);
         let conn_data = connected_tunnel.connection_data();
         let entry_gateway_address = conn_data.entry.endpoint.ip();
 
         let exit_adapter_config = WintunAdapterConfig {
             interface_ipv4: conn_data.exit.private_ipv4,
-            interface_ipv6: conn_data.exit.private_ipv6,
+            interface_ipv6: self.ipv6_enabled().then_some(conn_data.exit.private_ipv6),
             gateway_ipv4: Some(conn_data.entry.private_ipv4),
-            gateway_ipv6: Some(conn_data.entry.private_ipv6),
+            gateway_ipv6: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
         let mut tunnel_metadata = TunnelMetadata {
             interface: "".to_owned(),
-            ips: vec![
-                IpAddr::V4(conn_data.exit.private_ipv4),
-                IpAddr::V6(conn_data.exit.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.exit.private_ipv4, conn_data.exit.private_ipv6),
             ipv4_gateway: Some(conn_data.entry.private_ipv4),
-            ipv6_gateway: Some(conn_data.entry.private_ipv6),
+            ipv6_gateway: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
 
         let tunnel_conn_data = TunnelConnectionData::Wireguard(WireguardConnectionData {
@@ -787,7 +1046,7 @@ impl TunnelMonitor {
             .tunnel_parameters
             .tunnel_settings
             .dns
-            .to_dns_config()
+            .to_dns_config(self.tunnel_parameters.tunnel_settings.ip_family)
             .resolve(&crate::DEFAULT_DNS_SERVERS);
         let tunnel_options = TunnelOptions::Netstack(NetstackTunnelOptions {
             exit_tun_name: WG_EXIT_WINTUN_NAME.to_owned(),
@@ -834,15 +1093,18 @@ impl TunnelMonitor {
         &mut self,
         connected_mixnet: ConnectedMixnet,
     ) -> Result<StartTunnelResult> {
-        let connected_tunnel = connected_mixnet
+        let mut connected_tunnel = connected_mixnet
             .connect_wireguard_tunnel(
                 &self.tunnel_parameters.nym_config.network_env,
                 self.tunnel_parameters
                     .tunnel_settings
                     .enable_credentials_mode,
+                self.account_controller_tx.clone(),
                 self.cancel_token.clone(),
             )
             .await?;
+        let entry_endpoint = connected_tunnel.connection_data().entry.endpoint.ip();
+        connected_tunnel.set_mtu(self.wireguard_mtu(entry_endpoint).await?);
         let conn_data = connected_tunnel.connection_data();
 
         let entry_tun = Self::create_wireguard_device(
@@ -852,6 +1114,7 @@ impl TunnelMonitor {
             },
             None,
             connected_tunnel.entry_mtu(),
+            self.ipv6_enabled(),
         )?;
         let entry_tun_name = entry_tun
             .get_ref()
@@ -861,10 +1124,7 @@ impl TunnelMonitor {
 
         let entry_tunnel_metadata = TunnelMetadata {
             interface: entry_tun_name,
-            ips: vec![
-                IpAddr::V4(conn_data.entry.private_ipv4),
-                IpAddr::V6(conn_data.entry.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.entry.private_ipv4, conn_data.entry.private_ipv6),
             ipv4_gateway: None,
             ipv6_gateway: None,
         };
@@ -877,18 +1137,16 @@ impl TunnelMonitor {
             // todo: this needs to be able to set both destinations?
             Some(conn_data.entry.private_ipv4),
             connected_tunnel.exit_mtu(),
+            self.ipv6_enabled(),
         )?;
         let exit_tun_name = exit_tun.get_ref().name().map_err(Error::GetTunDeviceName)?;
         tracing::info!("Created exit tun device: {}", exit_tun_name);
 
         let exit_tunnel_metadata = TunnelMetadata {
             interface: exit_tun_name.clone(),
-            ips: vec![
-                IpAddr::V4(conn_data.exit.private_ipv4),
-                IpAddr::V6(conn_data.exit.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.exit.private_ipv4, conn_data.exit.private_ipv6),
             ipv4_gateway: Some(conn_data.entry.private_ipv4),
-            ipv6_gateway: Some(conn_data.entry.private_ipv6),
+            ipv6_gateway: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
 
         let routing_config = RoutingConfig::Wireguard {
@@ -909,12 +1167,19 @@ impl TunnelMonitor {
             .tunnel_parameters
             .tunnel_settings
             .dns
-            .to_dns_config()
+            .to_dns_config(self.tunnel_parameters.tunnel_settings.ip_family)
             .resolve(
                 &crate::DEFAULT_DNS_SERVERS,
                 #[cfg(target_os = "macos")]
                 53,
             );
+        let mtu_probe = self.mtu_probe(
+            &exit_tunnel_metadata.interface,
+            Some(&entry_tunnel_metadata.interface),
+            connected_tunnel.exit_mtu(),
+            conn_data.exit.endpoint.ip(),
+            dns_config.tunnel_config(),
+        );
         let tunnel_options = TunnelOptions::TunTun(TunTunTunnelOptions {
             entry_tun,
             exit_tun,
@@ -930,6 +1195,7 @@ impl TunnelMonitor {
             },
             tunnel_conn_data,
             tunnel_handle,
+            mtu_probe,
         })
     }
 
@@ -938,15 +1204,18 @@ impl TunnelMonitor {
         &mut self,
         connected_mixnet: ConnectedMixnet,
     ) -> Result<StartTunnelResult> {
-        let connected_tunnel = connected_mixnet
+        let mut connected_tunnel = connected_mixnet
             .connect_wireguard_tunnel(
                 &self.tunnel_parameters.nym_config.network_env,
                 self.tunnel_parameters
                     .tunnel_settings
                     .enable_credentials_mode,
+                self.account_controller_tx.clone(),
                 self.cancel_token.clone(),
             )
             .await?;
+        let entry_endpoint = connected_tunnel.connection_data().entry.endpoint.ip();
+        connected_tunnel.set_mtu(self.wireguard_mtu(entry_endpoint).await?);
         let conn_data = connected_tunnel.connection_data();
 
         let entry_gateway_address = conn_data.entry.endpoint.ip();
@@ -954,34 +1223,28 @@ impl TunnelMonitor {
 
         let entry_adapter_config = WintunAdapterConfig {
             interface_ipv4: conn_data.entry.private_ipv4,
-            interface_ipv6: conn_data.entry.private_ipv6,
+            interface_ipv6: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
             gateway_ipv4: None,
             gateway_ipv6: None,
         };
         let mut entry_tunnel_metadata = TunnelMetadata {
             interface: "".to_owned(),
-            ips: vec![
-                IpAddr::V4(conn_data.entry.private_ipv4),
-                IpAddr::V6(conn_data.entry.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.entry.private_ipv4, conn_data.entry.private_ipv6),
             ipv4_gateway: None,
             ipv6_gateway: None,
         };
 
         let exit_adapter_config = WintunAdapterConfig {
             interface_ipv4: conn_data.exit.private_ipv4,
-            interface_ipv6: conn_data.exit.private_ipv6,
+            interface_ipv6: self.ipv6_enabled().then_some(conn_data.exit.private_ipv6),
             gateway_ipv4: Some(conn_data.entry.private_ipv4),
-            gateway_ipv6: Some(conn_data.entry.private_ipv6),
+            gateway_ipv6: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
         let mut exit_tunnel_metadata = TunnelMetadata {
             interface: "".to_owned(),
-            ips: vec![
-                IpAddr::V4(conn_data.exit.private_ipv4),
-                IpAddr::V6(conn_data.exit.private_ipv6),
-            ],
+            ips: self.interface_ips(conn_data.exit.private_ipv4, conn_data.exit.private_ipv6),
             ipv4_gateway: Some(conn_data.entry.private_ipv4),
-            ipv6_gateway: Some(conn_data.entry.private_ipv6),
+            ipv6_gateway: self.ipv6_enabled().then_some(conn_data.entry.private_ipv6),
         };
 
         let tunnel_conn_data = TunnelConnectionData::Wireguard(WireguardConnectionData {
@@ -993,7 +1256,7 @@ impl TunnelMonitor {
             .tunnel_parameters
             .tunnel_settings
             .dns
-            .to_dns_config()
+            .to_dns_config(self.tunnel_parameters.tunnel_settings.ip_family)
             .resolve(&crate::DEFAULT_DNS_SERVERS);
         let tunnel_options = TunnelOptions::TunTun(TunTunTunnelOptions {
             entry_tun_name: WG_ENTRY_WINTUN_NAME.to_owned(),
@@ -1058,15 +1321,18 @@ impl TunnelMonitor {
         &self,
         connected_mixnet: ConnectedMixnet,
     ) -> Result<StartTunnelResult> {
-        let connected_tunnel = connected_mixnet
+        let mut connected_tunnel = connected_mixnet
             .connect_wireguard_tunnel(
                 &self.tunnel_parameters.nym_config.network_env,
                 self.tunnel_parameters
                     .tunnel_settings
                     .enable_credentials_mode,
+                self.account_controller_tx.clone(),
                 self.cancel_token.clone(),
             )
             .await?;
+        let entry_endpoint = connected_tunnel.connection_data().entry.endpoint.ip();
+        connected_tunnel.set_mtu(self.wireguard_mtu(entry_endpoint).await?);
 
         let conn_data = connected_tunnel.connection_data();
 
@@ -1130,8 +1396,39 @@ impl TunnelMonitor {
 
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
     async fn set_routes(&mut self, routing_config: RoutingConfig) -> Result<()> {
+        let tunnel_settings = &self.tunnel_parameters.tunnel_settings;
+        let dns_config = tunnel_settings
+            .dns
+            .to_dns_config(tunnel_settings.ip_family)
+            .resolve(
+                &crate::DEFAULT_DNS_SERVERS,
+                #[cfg(target_os = "macos")]
+                53,
+            );
+        let tunnel_routes = tunnel_settings
+            .tunnel_routes
+            .with_tunnel_dns(dns_config.tunnel_config());
+
+        // The firewall only allows non-tunnel traffic on Linux and macOS
+        #[cfg(windows)]
+        let tunnel_routes = if tunnel_routes != TunnelRoutes::All {
+            tracing::warn!("Custom tunnel routes are not supported on Windows");
+            TunnelRoutes::All
+        } else {
+            tunnel_routes
+        };
+        tracing::info!(
+            "Routing through the tunnel: {} ({})",
+            tunnel_routes,
+            tunnel_settings.ip_family
+        );
+
         self.route_handler
-            .add_routes(routing_config)
+            .add_routes(
+                routing_config,
+                &tunnel_routes,
+                tunnel_settings.ip_family.ipv6_enabled(),
+            )
             .await
             .map_err(Error::AddRoutes)?;
 
@@ -1139,7 +1436,11 @@ impl TunnelMonitor {
     }
 
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
-    fn create_mixnet_device(interface_addresses: IpPair, mtu: u16) -> Result<AsyncDevice> {
+    fn create_mixnet_device(
+        interface_addresses: IpPair,
+        mtu: u16,
+        ipv6_enabled: bool,
+    ) -> Result<AsyncDevice> {
         let mut tun_config = tun::Configuration::default();
 
         // rust-tun uses the same name for tunnel type.
@@ -1163,8 +1464,10 @@ impl TunnelMonitor {
             .name()
             .map_err(Error::GetTunDeviceName)?;
 
-        tun_ipv6::set_ipv6_addr(&tun_name, interface_addresses.ipv6)
-            .map_err(Error::SetTunDeviceIpv6Addr)?;
+        if ipv6_enabled {
+            tun_ipv6::set_ipv6_addr(&tun_name, interface_addresses.ipv6)
+                .map_err(Error::SetTunDeviceIpv6Addr)?;
+        }
 
         Ok(tun_device)
     }
@@ -1174,6 +1477,7 @@ impl TunnelMonitor {
         interface_addresses: IpPair,
         destination: Option<Ipv4Addr>,
         mtu: u16,
+        ipv6_enabled: bool,
     ) -> Result<AsyncDevice> {
         let mut tun_config = tun::Configuration::default();
 
@@ -1199,8 +1503,10 @@ impl TunnelMonitor {
             .name()
             .map_err(Error::GetTunDeviceName)?;
 
-        tun_ipv6::set_ipv6_addr(&tun_name, interface_addresses.ipv6)
-            .map_err(Error::SetTunDeviceIpv6Addr)?;
+        if ipv6_enabled {
+            tun_ipv6::set_ipv6_addr(&tun_name, interface_addresses.ipv6)
+                .map_err(Error::SetTunDeviceIpv6Addr)?;
+        }
 
         Ok(tun_device)
     }
@@ -1253,4 +1559,6 @@ pub struct StartTunnelResult {
     tunnel_interface: TunnelInterface,
     tunnel_conn_data: TunnelConnectionData,
     tunnel_handle: AnyTunnelHandle,
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    mtu_probe: Option<MtuProbe>,
 }
@@ -1,17 +1,20 @@
 // Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::time::Duration;
+
 use nym_vpn_lib_types::{
-    BandwidthEvent, ConnectionEvent, ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates,
-    TunnelEvent, TunnelState,
+    BandwidthEvent, BandwidthForecast, BandwidthGateway, ConnectionEvent,
+    ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates, TunnelEvent, TunnelState,
 };
 
 use crate::{
     conversions::ConversionError,
     mixnet_event::{
         bandwidth_event::{
-            Event as ProtoBanwidthEventEnum, NoBandwidth as ProtoNoBandwidth,
-            RemainingBandwidth as ProtoRemainingBandwidth,
+            depletion_forecast::Gateway as ProtoForecastGateway,
+            DepletionForecast as ProtoDepletionForecast, Event as ProtoBanwidthEventEnum,
+            NoBandwidth as ProtoNoBandwidth, RemainingBandwidth as ProtoRemainingBandwidth,
         },
         BandwidthEvent as ProtoBandwidthEvent, ConnectionEvent as ProtoConnectionEvent,
         ConnectionStatisticsEvent as ProtoConnectionStatisticsEvent, Event as ProtoMixnetEventEnum,
@@ -93,10 +96,37 @@ impl TryFrom<ProtoBandwidthEvent> for BandwidthEvent {
             ProtoBanwidthEventEnum::RemainingBandwidth(ProtoRemainingBandwidth { value }) => {
                 Self::RemainingBandwidth(value)
             }
+            ProtoBanwidthEventEnum::DepletionForecast(forecast) => {
+                Self::DepletionForecast(BandwidthForecast::try_from(forecast)?)
+            }
+        })
+    }
+}
+
+impl TryFrom<ProtoDepletionForecast> for BandwidthForecast {
+    type Error = ConversionError;
+
+    fn try_from(value: ProtoDepletionForecast) -> Result<Self, Self::Error> {
+        let gateway = ProtoForecastGateway::try_from(value.gateway)
+            .map_err(|e| ConversionError::Decode("DepletionForecast.gateway", e))?;
+        Ok(Self {
+            gateway: BandwidthGateway::from(gateway),
+            remaining_bandwidth: value.remaining_bandwidth,
+            depletion_rate: value.depletion_rate,
+            time_to_exhaustion: value.time_to_exhaustion_secs.map(Duration::from_secs),
         })
     }
 }
 
+impl From<ProtoForecastGateway> for BandwidthGateway {
+    fn from(value: ProtoForecastGateway) -> Self {
+        match value {
+            ProtoForecastGateway::Entry => Self::Entry,
+            ProtoForecastGateway::Exit => Self::Exit,
+        }
+    }
+}
+
 impl TryFrom<ProtoConnectionStatisticsEvent> for ConnectionStatisticsEvent {
     type Error = ConversionError;
 
@@ -105,6 +105,8 @@ impl TryFrom<crate::GatewayResponse> for nym_vpnd_types::gateway::Gateway {
             last_probe,
             wg_score,
             mixnet_score,
+            favourite: gateway.favourite,
+            blocked: gateway.blocked,
         })
     }
 }
@@ -2,15 +2,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use nym_vpn_lib_types::{
-    BandwidthEvent, ConnectionEvent, ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates,
-    TunnelEvent,
+    BandwidthEvent, BandwidthForecast, BandwidthGateway, ConnectionEvent,
+    ConnectionStatisticsEvent, MixnetEvent, SphinxPacketRates, TunnelEvent,
 };
 
 use crate::{
     mixnet_event::{
         bandwidth_event::{
-            Event as ProtoBanwidthEventEnum, NoBandwidth as ProtoNoBandwidth,
-            RemainingBandwidth as ProtoRemainingBandwidth,
+            depletion_forecast::Gateway as ProtoForecastGateway,
+            DepletionForecast as ProtoDepletionForecast, Event as ProtoBanwidthEventEnum,
+            NoBandwidth as ProtoNoBandwidth, RemainingBandwidth as ProtoRemainingBandwidth,
         },
         BandwidthEvent as ProtoBandwidthEvent, ConnectionEvent as ProtoConnectionEvent,
         ConnectionStatisticsEvent as ProtoConnectionStatisticsEvent, Event as ProtoMixnetEventEnum,
@@ -62,11 +63,34 @@ impl From<BandwidthEvent> for ProtoBandwidthEvent {
             BandwidthEvent::RemainingBandwidth(value) => {
                 ProtoBanwidthEventEnum::RemainingBandwidth(ProtoRemainingBandwidth { value })
             }
+            BandwidthEvent::DepletionForecast(forecast) => {
+                ProtoBanwidthEventEnum::DepletionForecast(ProtoDepletionForecast::from(forecast))
+            }
         };
         Self { event: Some(event) }
     }
 }
 
+impl From<BandwidthForecast> for ProtoDepletionForecast {
+    fn from(value: BandwidthForecast) -> Self {
+        Self {
+            gateway: ProtoForecastGateway::from(value.gateway) as i32,
+            remaining_bandwidth: value.remaining_bandwidth,
+            depletion_rate: value.depletion_rate,
+            time_to_exhaustion_secs: value.time_to_exhaustion.map(|d| d.as_secs()),
+        }
+    }
+}
+
+impl From<BandwidthGateway> for ProtoForecastGateway {
+    fn from(value: BandwidthGateway) -> Self {
+        match value {
+            BandwidthGateway::Entry => Self::Entry,
+            BandwidthGateway::Exit => Self::Exit,
+        }
+    }
+}
+
 impl From<ConnectionEvent> for ProtoConnectionEvent {
     fn from(value: ConnectionEvent) -> Self {
         match value {
@@ -93,6 +93,8 @@ impl From<nym_vpnd_types::gateway::Gateway> for crate::GatewayResponse {
                 .mixnet_score
                 .map(|score| crate::Score::from(score) as i32),
             moniker,
+            favourite: gateway.favourite,
+            blocked: gateway.blocked,
         }
     }
 }
@@ -1,10 +1,10 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::net::IpAddr;
+use std::{net::IpAddr, path::PathBuf};
 
 use anyhow::{anyhow, Result};
-use clap::{ArgAction, Args, Parser, Subcommand};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use nym_gateway_directory::{EntryPoint, ExitPoint, NodeIdentity, Recipient};
 
 #[derive(Parser)]
@@ -14,6 +14,26 @@ pub struct CliArgs {
     #[arg(long)]
     pub http: bool,
 
+    /// Address of the daemon remote management listener, used with --http.
+    #[arg(long, requires = "http")]
+    pub endpoint: Option<String>,
+
+    /// Bearer token generated by `nym-vpnd --generate-remote-token`, used with --http.
+    #[arg(long, env = "NYM_VPND_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// PEM encoded CA certificate used to verify the daemon, used with --http.
+    #[arg(long, requires = "http")]
+    pub ca_certificate: Option<PathBuf>,
+
+    /// PEM encoded client certificate for mutual TLS, used with --http.
+    #[arg(long, requires_all = ["http", "client_key"])]
+    pub client_certificate: Option<PathBuf>,
+
+    /// PEM encoded private key of the client certificate.
+    #[arg(long, requires = "client_certificate")]
+    pub client_key: Option<PathBuf>,
+
     #[arg(long)]
     pub verbose: bool,
 
@@ -106,11 +126,86 @@ pub enum Command {
     /// List the set of countries with available entry and exit gateways for dVPN mode.
     ListVpnCountries(ListCountriesArgs),
 
+    /// Show the DNS backend and servers applied by the daemon, and check that the system
+    /// resolver uses them.
+    GetDnsStatus,
+
+    /// Show the number of queries blocked by the DNS filter, per category.
+    GetDnsFilterStats,
+
+    /// Set which destinations are routed through the tunnel. Applied on the next connect.
+    SetRoutes(SetRoutesArgs),
+
+    /// Show which destinations are routed through the tunnel.
+    GetRoutes,
+
+    /// Add a gateway that isn't listed in the directory, e.g. a self-hosted nym-node, for use
+    /// in dVPN mode. Replaces the custom gateway with the same identity.
+    AddCustomGateway(AddCustomGatewayArgs),
+
+    /// Remove a custom gateway.
+    RemoveCustomGateway {
+        /// The identity of the gateway.
+        identity: String,
+    },
+
+    /// List the custom gateways.
+    ListCustomGateways,
+
+    /// Mark a gateway or a country as favourite, or as blocked with --blocked. Replaces the
+    /// favourite for the same gateway or country.
+    SetFavourite(SetFavouriteArgs),
+
+    /// Remove a favourite or blocked gateway or country.
+    RemoveFavourite(FavouriteTargetArgs),
+
+    /// List the favourite and blocked gateways and countries.
+    ListFavourites,
+
+    /// Connect or disconnect automatically when joining a network matching all the given
+    /// conditions. Replaces the rule with the same name.
+    SetNetworkRule(SetNetworkRuleArgs),
+
+    /// Remove a network rule.
+    RemoveNetworkRule {
+        /// The name of the rule.
+        name: String,
+    },
+
+    /// List the network rules, in the order they are evaluated.
+    ListNetworkRules,
+
+    /// Show the network the host is connected to and the rule matching it.
+    GetCurrentNetwork,
+
+    /// Check that a gateway accepts mixnet clients, ip packet router connections and WireGuard
+    /// registrations.
+    ProbeGateway {
+        /// The identity of the gateway.
+        identity: String,
+    },
+
+    /// Measure throughput, round trip times and packet loss through the active tunnel against a
+    /// benchmark server, started with `nym-gateway-probe benchmark-server`. Runs on this machine,
+    /// so it isn't available with --http.
+    Benchmark(BenchmarkArgs),
+
+    /// Run diagnostics against the active connection.
+    #[clap(subcommand)]
+    Diagnose(Diagnose),
+
     /// Internal commands for development and debugging.
     #[clap(subcommand, hide = true)]
     Internal(Internal),
 }
 
+#[derive(Subcommand)]
+pub enum Diagnose {
+    /// Check that DNS queries and traffic leave through the tunnel. Exits with an error if a
+    /// leak is found.
+    Leaks,
+}
+
 #[derive(Subcommand)]
 pub enum Internal {
     /// Get the list of system messages provided by the nym-vpn-api.
@@ -156,6 +251,17 @@ pub enum Internal {
 
     /// List the available zknym ticketbooks in the local credential store.
     GetAvailableTickets,
+
+    /// Export the unspent zknym ticketbooks to an encrypted file, for importing on another
+    /// device logged into the same account. Once the file is written the exported tickets are
+    /// marked as spent locally.
+    ExportTicketbooks(ExportTicketbooksArgs),
+
+    /// Import zknym ticketbooks from a file exported on another device of the same account.
+    ImportTicketbooks(ImportTicketbooksArgs),
+
+    /// Get the most recent entries of the credential spend log.
+    GetCredentialSpendHistory(GetCredentialSpendHistoryArgs),
 }
 
 #[derive(Args)]
@@ -209,6 +315,36 @@ pub struct ConnectArgs {
     /// consider a gateway for routing traffic.
     #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
     pub min_gateway_vpn_performance: Option<u8>,
+
+    /// IP families to configure inside the tunnel. With ipv4-only all IPv6 traffic is dropped,
+    /// with ipv6-preferred the IPv6 DNS servers are used first.
+    #[arg(long, value_enum, default_value_t = CliIpFamily::DualStack)]
+    pub ip_family: CliIpFamily,
+
+    /// Override the MTU of the WireGuard entry tunnel.
+    #[arg(long, requires = "enable_two_hop", value_parser = clap::value_parser!(u16).range(1280..=9000))]
+    pub entry_mtu: Option<u16>,
+
+    /// Override the MTU of the WireGuard exit tunnel. Derived from the entry MTU when not set.
+    #[arg(long, requires = "enable_two_hop", value_parser = clap::value_parser!(u16).range(1280..=9000))]
+    pub exit_mtu: Option<u16>,
+
+    /// Detect the WireGuard MTUs from the route to the entry gateway and by probing through the
+    /// tunnel. Only fully supported on Linux.
+    #[arg(long, requires = "enable_two_hop", conflicts_with_all = ["entry_mtu", "exit_mtu"])]
+    pub auto_mtu: bool,
+
+    /// Probe the selected gateways before connecting and replace the ones failing with other
+    /// candidates. Slows down connecting.
+    #[arg(long)]
+    pub precheck: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliIpFamily {
+    DualStack,
+    Ipv4Only,
+    Ipv6Preferred,
 }
 
 #[derive(Args)]
@@ -257,6 +393,164 @@ pub struct SetNetworkArgs {
     pub network: String,
 }
 
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct SetRoutesArgs {
+    /// Route all traffic through the tunnel.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Route all traffic through the tunnel, except traffic to these networks, e.g.
+    /// 10.20.0.0/16.
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Route only traffic to these networks through the tunnel.
+    #[arg(long, value_delimiter = ',')]
+    pub include_only: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct AddCustomGatewayArgs {
+    /// The identity of the gateway.
+    #[arg(long)]
+    pub identity: String,
+
+    /// IP addresses of the gateway.
+    #[arg(long, required = true, value_delimiter = ',')]
+    pub ips: Vec<String>,
+
+    /// Nym address of the authenticator of the gateway.
+    #[arg(long)]
+    pub authenticator_address: String,
+
+    /// Nym address of the IP packet router of the gateway.
+    #[arg(long)]
+    pub ipr_address: Option<String>,
+
+    /// Hostname of the gateway, defaults to the first IP address.
+    #[arg(long)]
+    pub host: Option<String>,
+
+    /// Websocket port for clients.
+    #[arg(long)]
+    pub clients_ws_port: Option<u16>,
+
+    /// Secure websocket port for clients.
+    #[arg(long)]
+    pub clients_wss_port: Option<u16>,
+
+    /// Two letter ISO country code of the gateway.
+    #[arg(long)]
+    pub country: Option<String>,
+
+    /// Version of the nym-node running on the gateway, e.g. 1.6.2.
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Name shown for the gateway.
+    #[arg(long, default_value = "")]
+    pub moniker: String,
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct FavouriteTargetArgs {
+    /// The identity of the gateway.
+    #[arg(long)]
+    pub gateway: Option<String>,
+
+    /// Two letter ISO country code, covering all the gateways in the country.
+    #[arg(long)]
+    pub country: Option<String>,
+}
+
+#[derive(Args)]
+pub struct SetFavouriteArgs {
+    #[command(flatten)]
+    pub target: FavouriteTargetArgs,
+
+    /// Name shown for the favourite.
+    #[arg(long)]
+    pub label: Option<String>,
+
+    /// Free text note.
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// Only apply to the gateways when used in this role.
+    #[arg(long)]
+    pub role: Option<CliGatewayRole>,
+
+    /// Mark as blocked instead of favourite.
+    #[arg(long)]
+    pub blocked: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliGatewayRole {
+    Entry,
+    Exit,
+}
+
+#[derive(Args)]
+pub struct SetNetworkRuleArgs {
+    /// The name of the rule.
+    pub name: String,
+
+    /// What to do when joining a matching network.
+    #[arg(long)]
+    pub action: CliNetworkRuleAction,
+
+    /// Only match networks of this kind.
+    #[arg(long)]
+    pub kind: Option<CliNetworkKind>,
+
+    /// Only match the Wi-Fi network with this SSID.
+    #[arg(long)]
+    pub ssid: Option<String>,
+
+    /// Only match networks on this interface, e.g. eth0.
+    #[arg(long)]
+    pub interface: Option<String>,
+
+    /// Only match networks where one of the addresses is in this subnet, e.g. 192.168.1.0/24.
+    #[arg(long)]
+    pub subnet: Option<String>,
+
+    /// Only match networks with the default gateway at this MAC address.
+    #[arg(long)]
+    pub gateway_mac: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliNetworkRuleAction {
+    Connect,
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CliNetworkKind {
+    Wifi,
+    Wired,
+    Other,
+}
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Address of the benchmark server, as host:port.
+    #[arg(long)]
+    pub server: String,
+
+    /// Duration of the upload and of the download, in seconds.
+    #[arg(long, default_value_t = 10)]
+    pub duration_sec: u64,
+
+    /// Number of UDP packets sent to measure round trip times and packet loss.
+    #[arg(long, default_value_t = 100)]
+    pub rtt_probes: u32,
+}
+
 #[derive(Args)]
 pub struct StoreAccountArgs {
     /// The account mnemonic to be stored.
@@ -286,6 +580,14 @@ pub struct ListGatewaysArgs {
     /// consider a gateway for routing traffic.
     #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
     pub min_vpn_performance: Option<u8>,
+
+    /// Only list the favourite gateways.
+    #[arg(long)]
+    pub favourites: bool,
+
+    /// Don't list the blocked gateways.
+    #[arg(long)]
+    pub hide_blocked: bool,
 }
 
 #[derive(Args)]
@@ -322,6 +624,27 @@ pub struct ConfirmZkNymDownloadedArgs {
     pub id: String,
 }
 
+#[derive(Args)]
+pub struct ExportTicketbooksArgs {
+    /// The file to write the encrypted ticketbook bundle to.
+    #[arg(short, long)]
+    pub output: PathBuf,
+}
+
+#[derive(Args)]
+pub struct ImportTicketbooksArgs {
+    /// The encrypted ticketbook bundle to import.
+    #[arg(short, long)]
+    pub input: PathBuf,
+}
+
+#[derive(Args)]
+pub struct GetCredentialSpendHistoryArgs {
+    /// The maximum number of entries to show, newest first.
+    #[arg(short, long)]
+    pub limit: Option<u32>,
+}
+
 pub fn parse_entry_point(args: &ConnectArgs) -> Result<Option<EntryPoint>> {
     if let Some(ref entry_gateway_id) = args.entry.entry_gateway_id {
         Ok(Some(EntryPoint::Gateway {
@@ -9,14 +9,17 @@ mod vpnd_client;
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use cli::Internal;
+use cli::{Diagnose, Internal};
 use itertools::Itertools;
 use nym_gateway_directory::GatewayType;
-use nym_vpn_lib_types::TunnelState;
+use nym_vpn_lib_types::{TunnelConnectionData, TunnelState};
 use nym_vpn_proto::{
-    ConfirmZkNymDownloadedRequest, ConnectRequest, GetAccountLinksRequest, GetZkNymByIdRequest,
-    InfoResponse, ListCountriesRequest, ListGatewaysRequest, ResetDeviceIdentityRequest,
-    SetNetworkRequest, StoreAccountRequest, UserAgent,
+    favourite_target, AddCustomGatewayRequest, ConfirmZkNymDownloadedRequest, ConnectRequest,
+    GetAccountLinksRequest, GetCredentialSpendHistoryRequest, GetZkNymByIdRequest,
+    ImportTicketbooksRequest, InfoResponse, ListCountriesRequest, ListGatewaysRequest,
+    ProbeGatewayRequest, RemoveCustomGatewayRequest, RemoveFavouriteRequest,
+    RemoveNetworkRuleRequest, ResetDeviceIdentityRequest, SetFavouriteRequest, SetNetworkRequest,
+    SetNetworkRuleRequest, SetTunnelRoutesRequest, StoreAccountRequest, TunnelRoutes, UserAgent,
 };
 use protobuf_conversion::into_gateway_type;
 use sysinfo::System;
@@ -24,7 +27,7 @@ use vpnd_client::ClientType;
 
 use crate::{
     cli::Command,
-    protobuf_conversion::{into_entry_point, into_exit_point},
+    protobuf_conversion::{into_entry_point, into_exit_point, into_ip_family},
 };
 
 #[derive(Clone, Debug)]
@@ -38,7 +41,12 @@ struct CliOptions {
 async fn main() -> Result<()> {
     let args = cli::CliArgs::parse();
     let client_type = if args.http {
-        vpnd_client::ClientType::Http
+        vpnd_client::ClientType::Http(vpnd_client::HttpOptions {
+            endpoint: args.endpoint.unwrap_or_else(config::default_endpoint),
+            token: args.token,
+            ca_certificate: args.ca_certificate,
+            client_identity: args.client_certificate.zip(args.client_key),
+        })
     } else {
         vpnd_client::ClientType::Ipc
     };
@@ -79,6 +87,29 @@ async fn main() -> Result<()> {
             list_countries(opts, list_args, GatewayType::Wg).await?
         }
         Command::GetDeviceId => get_device_id(opts.client_type).await?,
+        Command::GetDnsStatus => get_dns_status(opts.client_type).await?,
+        Command::GetDnsFilterStats => get_dns_filter_stats(opts.client_type).await?,
+        Command::SetRoutes(ref args) => set_routes(opts.client_type, args).await?,
+        Command::GetRoutes => get_routes(opts.client_type).await?,
+        Command::AddCustomGateway(ref args) => add_custom_gateway(opts.client_type, args).await?,
+        Command::RemoveCustomGateway { ref identity } => {
+            remove_custom_gateway(opts.client_type, identity).await?
+        }
+        Command::ListCustomGateways => list_custom_gateways(opts.client_type).await?,
+        Command::SetFavourite(ref args) => set_favourite(opts.client_type, args).await?,
+        Command::RemoveFavourite(ref args) => remove_favourite(opts.client_type, args).await?,
+        Command::ListFavourites => list_favourites(opts.client_type).await?,
+        Command::SetNetworkRule(ref args) => set_network_rule(opts.client_type, args).await?,
+        Command::RemoveNetworkRule { ref name } => {
+            remove_network_rule(opts.client_type, name).await?
+        }
+        Command::ListNetworkRules => list_network_rules(opts.client_type).await?,
+        Command::GetCurrentNetwork => get_current_network(opts.client_type).await?,
+        Command::ProbeGateway { ref identity } => probe_gateway(opts.client_type, identity).await?,
+        Command::Benchmark(ref args) => benchmark(opts.client_type, args).await?,
+        Command::Diagnose(diagnose) => match diagnose {
+            Diagnose::Leaks => diagnose_leaks(opts.client_type).await?,
+        },
         Command::Internal(internal) => match internal {
             Internal::GetSystemMessages => get_system_messages(opts.client_type).await?,
             Internal::GetFeatureFlags => get_feature_flags(opts.client_type).await?,
@@ -100,6 +131,11 @@ async fn main() -> Result<()> {
                 confirm_zk_nym_downloaded(opts.client_type, args).await?
             }
             Internal::GetAvailableTickets => get_available_tickets(opts.client_type).await?,
+            Internal::ExportTicketbooks(args) => export_ticketbooks(opts.client_type, args).await?,
+            Internal::ImportTicketbooks(args) => import_ticketbooks(opts.client_type, args).await?,
+            Internal::GetCredentialSpendHistory(args) => {
+                get_credential_spend_history(opts.client_type, args).await?
+            }
         },
     }
     Ok(())
@@ -158,6 +194,13 @@ async fn connect(opts: CliOptions, connect_args: &cli::ConnectArgs) -> Result<()
         min_gateway_vpn_performance: connect_args
             .min_gateway_vpn_performance
             .map(nym_vpn_proto::Threshold::from),
+        ip_family: into_ip_family(connect_args.ip_family).into(),
+        wireguard_mtu: Some(nym_vpn_proto::WireguardMtu {
+            entry: connect_args.entry_mtu.map(u32::from),
+            exit: connect_args.exit_mtu.map(u32::from),
+            auto: connect_args.auto_mtu,
+        }),
+        precheck: connect_args.precheck,
     });
 
     let response = client.vpn_connect(request).await?.into_inner();
@@ -497,6 +540,496 @@ async fn confirm_zk_nym_downloaded(
     Ok(())
 }
 
+async fn get_dns_status(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.get_dns_status(()).await?.into_inner();
+    println!("{:#?}", response);
+    Ok(())
+}
+
+async fn get_dns_filter_stats(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.get_dns_filter_stats(()).await?.into_inner();
+    if !response.enabled {
+        println!("DNS filtering is disabled");
+        return Ok(());
+    }
+
+    println!(
+        "Blocked {} of {} queries, {} allowlist rules",
+        response.blocked, response.queries, response.allowlist_rules
+    );
+    for category in response.categories {
+        println!(
+            "  {:<10} {:<9} {:>8} rules {:>8} blocked",
+            category.category,
+            if category.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            category.rules,
+            category.blocked
+        );
+    }
+    Ok(())
+}
+
+async fn set_routes(client_type: ClientType, args: &cli::SetRoutesArgs) -> Result<()> {
+    use nym_vpn_proto::tunnel_routes::Mode;
+
+    let (mode, networks) = if !args.exclude.is_empty() {
+        (Mode::Exclude, args.exclude.clone())
+    } else if !args.include_only.is_empty() {
+        (Mode::IncludeOnly, args.include_only.clone())
+    } else {
+        (Mode::All, Vec::new())
+    };
+
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(SetTunnelRoutesRequest {
+        routes: Some(TunnelRoutes {
+            mode: mode as i32,
+            networks,
+        }),
+    });
+    let response = client.set_tunnel_routes(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to set tunnel routes: {}", error.message);
+    }
+    println!("Tunnel routes updated, reconnect to apply them");
+    Ok(())
+}
+
+async fn get_routes(client_type: ClientType) -> Result<()> {
+    use nym_vpn_proto::tunnel_routes::Mode;
+
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.get_tunnel_routes(()).await?.into_inner();
+    let routes = response.routes.unwrap_or_default();
+    match Mode::try_from(routes.mode).context("failed to parse tunnel routes mode")? {
+        Mode::All => println!("All traffic is routed through the tunnel"),
+        Mode::Exclude => println!(
+            "All traffic is routed through the tunnel, except to: {}",
+            routes.networks.join(", ")
+        ),
+        Mode::IncludeOnly => println!(
+            "Only traffic to these networks is routed through the tunnel: {}",
+            routes.networks.join(", ")
+        ),
+    }
+    Ok(())
+}
+
+async fn add_custom_gateway(
+    client_type: ClientType,
+    args: &cli::AddCustomGatewayArgs,
+) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(AddCustomGatewayRequest {
+        gateway: Some(nym_vpn_proto::GatewayDescriptor {
+            identity: args.identity.clone(),
+            moniker: args.moniker.clone(),
+            host: args.host.clone(),
+            ips: args.ips.clone(),
+            authenticator_address: args.authenticator_address.clone(),
+            ipr_address: args.ipr_address.clone(),
+            clients_ws_port: args.clients_ws_port.map(u32::from),
+            clients_wss_port: args.clients_wss_port.map(u32::from),
+            country: args.country.clone(),
+            version: args.version.clone(),
+        }),
+    });
+    let response = client.add_custom_gateway(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to add custom gateway: {}", error.message);
+    }
+    println!("Custom gateway added, reconnect to use it");
+    Ok(())
+}
+
+async fn remove_custom_gateway(client_type: ClientType, identity: &str) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(RemoveCustomGatewayRequest {
+        identity: identity.to_string(),
+    });
+    let response = client.remove_custom_gateway(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to remove custom gateway: {}", error.message);
+    }
+    println!("Custom gateway removed");
+    Ok(())
+}
+
+async fn list_custom_gateways(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.list_custom_gateways(()).await?.into_inner();
+    if response.gateways.is_empty() {
+        println!("No custom gateways");
+    }
+    for gateway in response.gateways {
+        println!(
+            "{} {} ips: {} country: {}",
+            gateway.identity,
+            gateway.moniker,
+            gateway.ips.join(", "),
+            gateway.country.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+fn into_favourite_target(args: &cli::FavouriteTargetArgs) -> nym_vpn_proto::FavouriteTarget {
+    let target = match (&args.gateway, &args.country) {
+        (Some(gateway), _) => favourite_target::Target::GatewayId(gateway.clone()),
+        (None, country) => {
+            favourite_target::Target::CountryCode(country.clone().unwrap_or_default())
+        }
+    };
+    nym_vpn_proto::FavouriteTarget {
+        target: Some(target),
+    }
+}
+
+async fn set_favourite(client_type: ClientType, args: &cli::SetFavouriteArgs) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let pinned_role = match args.role {
+        None => nym_vpn_proto::GatewayRole::Unspecified,
+        Some(cli::CliGatewayRole::Entry) => nym_vpn_proto::GatewayRole::Entry,
+        Some(cli::CliGatewayRole::Exit) => nym_vpn_proto::GatewayRole::Exit,
+    };
+    let request = tonic::Request::new(SetFavouriteRequest {
+        favourite: Some(nym_vpn_proto::FavouriteGateway {
+            target: Some(into_favourite_target(&args.target)),
+            label: args.label.clone(),
+            note: args.note.clone(),
+            pinned_role: pinned_role as i32,
+            blocked: args.blocked,
+        }),
+    });
+    let response = client.set_favourite(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to set favourite: {}", error.message);
+    }
+    if args.blocked {
+        println!("Blocked");
+    } else {
+        println!("Favourite set");
+    }
+    Ok(())
+}
+
+async fn remove_favourite(client_type: ClientType, args: &cli::FavouriteTargetArgs) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(RemoveFavouriteRequest {
+        target: Some(into_favourite_target(args)),
+    });
+    let response = client.remove_favourite(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to remove favourite: {}", error.message);
+    }
+    println!("Favourite removed");
+    Ok(())
+}
+
+async fn list_favourites(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.list_favourites(()).await?.into_inner();
+    if response.favourites.is_empty() {
+        println!("No favourites");
+    }
+    for favourite in response.favourites {
+        let target = match favourite.target.and_then(|target| target.target) {
+            Some(favourite_target::Target::GatewayId(id)) => format!("gateway {id}"),
+            Some(favourite_target::Target::CountryCode(code)) => format!("country {code}"),
+            None => continue,
+        };
+        let role = match nym_vpn_proto::GatewayRole::try_from(favourite.pinned_role) {
+            Ok(nym_vpn_proto::GatewayRole::Entry) => "entry",
+            Ok(nym_vpn_proto::GatewayRole::Exit) => "exit",
+            _ => "any",
+        };
+        println!(
+            "{} {target} role: {role} label: {} note: {}",
+            if favourite.blocked {
+                "blocked"
+            } else {
+                "favourite"
+            },
+            favourite.label.as_deref().unwrap_or("-"),
+            favourite.note.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+async fn set_network_rule(client_type: ClientType, args: &cli::SetNetworkRuleArgs) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let action = match args.action {
+        cli::CliNetworkRuleAction::Connect => nym_vpn_proto::NetworkRuleAction::Connect,
+        cli::CliNetworkRuleAction::Disconnect => nym_vpn_proto::NetworkRuleAction::Disconnect,
+    };
+    let kind = match args.kind {
+        None => nym_vpn_proto::NetworkKind::Unspecified,
+        Some(cli::CliNetworkKind::Wifi) => nym_vpn_proto::NetworkKind::Wifi,
+        Some(cli::CliNetworkKind::Wired) => nym_vpn_proto::NetworkKind::Wired,
+        Some(cli::CliNetworkKind::Other) => nym_vpn_proto::NetworkKind::Other,
+    };
+    let request = tonic::Request::new(SetNetworkRuleRequest {
+        rule: Some(nym_vpn_proto::NetworkRule {
+            name: args.name.clone(),
+            action: action as i32,
+            kind: kind as i32,
+            ssid: args.ssid.clone(),
+            interface: args.interface.clone(),
+            subnet: args.subnet.clone(),
+            gateway_mac: args.gateway_mac.clone(),
+        }),
+    });
+    let response = client.set_network_rule(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to set network rule: {}", error.message);
+    }
+    println!("Network rule set");
+    Ok(())
+}
+
+async fn remove_network_rule(client_type: ClientType, name: &str) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(RemoveNetworkRuleRequest {
+        name: name.to_string(),
+    });
+    let response = client.remove_network_rule(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to remove network rule: {}", error.message);
+    }
+    println!("Network rule removed");
+    Ok(())
+}
+
+fn network_kind_name(kind: i32) -> &'static str {
+    match nym_vpn_proto::NetworkKind::try_from(kind) {
+        Ok(nym_vpn_proto::NetworkKind::Wifi) => "wifi",
+        Ok(nym_vpn_proto::NetworkKind::Wired) => "wired",
+        Ok(nym_vpn_proto::NetworkKind::Other) => "other",
+        _ => "any",
+    }
+}
+
+fn print_network_rule(rule: &nym_vpn_proto::NetworkRule) {
+    let action = match nym_vpn_proto::NetworkRuleAction::try_from(rule.action) {
+        Ok(nym_vpn_proto::NetworkRuleAction::Connect) => "connect",
+        Ok(nym_vpn_proto::NetworkRuleAction::Disconnect) => "disconnect",
+        _ => "unknown",
+    };
+    println!(
+        "{}: {action} kind: {} ssid: {} interface: {} subnet: {} gateway mac: {}",
+        rule.name,
+        network_kind_name(rule.kind),
+        rule.ssid.as_deref().unwrap_or("-"),
+        rule.interface.as_deref().unwrap_or("-"),
+        rule.subnet.as_deref().unwrap_or("-"),
+        rule.gateway_mac.as_deref().unwrap_or("-"),
+    );
+}
+
+async fn list_network_rules(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.list_network_rules(()).await?.into_inner();
+    if response.rules.is_empty() {
+        println!("No network rules");
+    }
+    for rule in &response.rules {
+        print_network_rule(rule);
+    }
+    Ok(())
+}
+
+async fn get_current_network(client_type: ClientType) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.get_current_network(()).await?.into_inner();
+    let Some(network) = response.network else {
+        println!("No network detected");
+        return Ok(());
+    };
+    println!("kind: {}", network_kind_name(network.kind));
+    println!("interface: {}", network.interface.as_deref().unwrap_or("-"));
+    println!("ssid: {}", network.ssid.as_deref().unwrap_or("-"));
+    println!("addresses: {}", network.addresses.join(", "));
+    println!(
+        "gateway mac: {}",
+        network.gateway_mac.as_deref().unwrap_or("-")
+    );
+    match response.matching_rule {
+        Some(rule) => {
+            print!("matching rule: ");
+            print_network_rule(&rule);
+        }
+        None => println!("matching rule: -"),
+    }
+    Ok(())
+}
+
+async fn probe_gateway(client_type: ClientType, identity: &str) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(ProbeGatewayRequest {
+        identity: identity.to_string(),
+    });
+    println!("Probing {identity}, this can take up to a minute...");
+    let response = client.probe_gateway(request).await?.into_inner();
+    if let Some(error) = response.error {
+        bail!("failed to probe gateway: {}", error.message);
+    }
+    let Some(health) = response.health else {
+        bail!("failed to probe gateway: empty response");
+    };
+
+    let check = |result: Option<bool>| match result {
+        Some(true) => "ok",
+        Some(false) => "failed",
+        None => "not tested",
+    };
+    println!("mixnet connection: {}", check(Some(health.can_connect)));
+    println!("ip packet router: {}", check(health.can_connect_ipr));
+    println!(
+        "wireguard registration: {}",
+        check(health.can_register_wireguard)
+    );
+    Ok(())
+}
+
+async fn benchmark(client_type: ClientType, args: &cli::BenchmarkArgs) -> Result<()> {
+    if matches!(client_type, ClientType::Http(_)) {
+        bail!("the benchmark measures the tunnel of this machine and can't be used with --http");
+    }
+
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let tunnel_state = TunnelState::try_from(client.get_tunnel_state(()).await?.into_inner())?;
+    let TunnelState::Connected { connection_data } = tunnel_state else {
+        bail!("the tunnel isn't connected: {tunnel_state}");
+    };
+    let tunnel_type = match connection_data.tunnel {
+        TunnelConnectionData::Mixnet(_) => "mixnet",
+        TunnelConnectionData::Wireguard(_) => "wireguard",
+    };
+
+    let options = nym_tunnel_benchmark::BenchmarkOptions {
+        duration: std::time::Duration::from_secs(args.duration_sec),
+        rtt_probes: args.rtt_probes,
+        ..Default::default()
+    };
+    println!(
+        "Benchmarking the {tunnel_type} tunnel through {} against {}...",
+        connection_data.exit_gateway.id, args.server
+    );
+    let result = nym_tunnel_benchmark::run(&args.server, &options)
+        .await
+        .context("benchmark failed")?;
+    println!("{result}");
+    Ok(())
+}
+
+async fn diagnose_leaks(client_type: ClientType) -> Result<()> {
+    use nym_vpn_proto::diagnose_leaks_response::Resp;
+
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.diagnose_leaks(()).await?.into_inner();
+    let report = match response.resp {
+        Some(Resp::Report(report)) => report,
+        Some(Resp::Error(err)) => bail!("leak test failed: {}", err.message),
+        None => bail!("leak test failed: empty response"),
+    };
+
+    print_leak_report(&report);
+    if report.leaking {
+        bail!("traffic is leaking outside of the tunnel");
+    }
+    Ok(())
+}
+
+fn print_leak_report(report: &nym_vpn_proto::LeakReport) {
+    use nym_vpn_proto::{
+        dns_leak_report::Verdict as DnsVerdict, dns_server_check::Verdict as ServerVerdict,
+        dns_verification::Result as ResolverResult, ip_leak_report::Verdict as IpVerdict,
+    };
+
+    let with_details = |name: &str, details: &Option<String>| match details {
+        Some(details) => format!("{name} ({details})"),
+        None => name.to_owned(),
+    };
+
+    if let Some(dns) = &report.dns {
+        println!("DNS");
+        println!("  tunnel interface: {}", dns.tunnel_interface);
+        println!("  tunnel servers: {}", dns.tunnel_servers.join(", "));
+        if let Some(resolver) = &dns.resolver {
+            let result = ResolverResult::try_from(resolver.result)
+                .map(|result| result.as_str_name())
+                .unwrap_or("UNKNOWN");
+            println!(
+                "  system resolver: {}",
+                with_details(result, &resolver.details)
+            );
+            if !resolver.resolver_servers.is_empty() {
+                println!(
+                    "  system resolver servers: {}",
+                    resolver.resolver_servers.join(", ")
+                );
+            }
+        }
+        for query in &dns.queries {
+            match &query.error {
+                Some(error) => println!("  query {}: failed ({error})", query.hostname),
+                None => println!("  query {}: {}", query.hostname, query.addresses.join(", ")),
+            }
+        }
+        for server in &dns.servers {
+            let verdict = ServerVerdict::try_from(server.verdict)
+                .map(|verdict| verdict.as_str_name())
+                .unwrap_or("UNKNOWN");
+            println!(
+                "  server {} via {}: {}",
+                server.server,
+                server.interface.as_deref().unwrap_or("unknown interface"),
+                with_details(verdict, &server.details)
+            );
+        }
+        let verdict = DnsVerdict::try_from(dns.verdict)
+            .map(|verdict| verdict.as_str_name())
+            .unwrap_or("UNKNOWN");
+        println!("  verdict: {}", with_details(verdict, &dns.details));
+    }
+
+    if let Some(ip) = &report.ip {
+        let verdict = IpVerdict::try_from(ip.verdict)
+            .map(|verdict| verdict.as_str_name())
+            .unwrap_or("UNKNOWN");
+        println!("IP");
+        println!("  exit gateway: {}", ip.exit_ips.join(", "));
+        println!(
+            "  observed: {}",
+            ip.observed_ip.as_deref().unwrap_or("unknown")
+        );
+        println!("  verdict: {}", with_details(verdict, &ip.details));
+    }
+
+    let inconclusive = report
+        .dns
+        .as_ref()
+        .is_some_and(|dns| dns.verdict == DnsVerdict::Inconclusive as i32)
+        || report
+            .ip
+            .as_ref()
+            .is_some_and(|ip| ip.verdict == IpVerdict::Inconclusive as i32);
+    if report.leaking {
+        println!("Result: LEAKING");
+    } else if inconclusive {
+        println!("Result: no leaks detected, but some checks were inconclusive");
+    } else {
+        println!("Result: no leaks detected");
+    }
+}
+
 async fn get_available_tickets(client_type: ClientType) -> Result<()> {
     let mut client = vpnd_client::get_client(&client_type).await?;
     let response = client.get_available_tickets(()).await?.into_inner();
@@ -504,6 +1037,67 @@ async fn get_available_tickets(client_type: ClientType) -> Result<()> {
     Ok(())
 }
 
+async fn export_ticketbooks(
+    client_type: ClientType,
+    args: cli::ExportTicketbooksArgs,
+) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let response = client.export_ticketbooks(()).await?.into_inner();
+    match response.resp {
+        Some(nym_vpn_proto::export_ticketbooks_response::Resp::Bundle(bundle)) => {
+            std::fs::write(&args.output, bundle).with_context(|| {
+                format!("failed to write ticketbooks to {}", args.output.display())
+            })?;
+            // Only give up the local tickets once they are safely stored in the file
+            let response = client.confirm_ticketbooks_exported(()).await?.into_inner();
+            if let Some(error) = response.error {
+                // The bundle can't be imported safely if the tickets weren't given up locally
+                let _ = std::fs::remove_file(&args.output);
+                bail!(
+                    "failed to mark exported ticketbooks as spent, removed {}: {}",
+                    args.output.display(),
+                    error.message
+                );
+            }
+            println!("Ticketbooks exported to {}", args.output.display());
+        }
+        Some(nym_vpn_proto::export_ticketbooks_response::Resp::Error(error)) => {
+            bail!("failed to export ticketbooks: {}", error.message);
+        }
+        None => bail!("failed to export ticketbooks: empty response"),
+    }
+    Ok(())
+}
+
+async fn import_ticketbooks(
+    client_type: ClientType,
+    args: cli::ImportTicketbooksArgs,
+) -> Result<()> {
+    let bundle = std::fs::read(&args.input)
+        .with_context(|| format!("failed to read ticketbooks from {}", args.input.display()))?;
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(ImportTicketbooksRequest { bundle });
+    let response = client.import_ticketbooks(request).await?.into_inner();
+    println!("{:#?}", response);
+    Ok(())
+}
+
+async fn get_credential_spend_history(
+    client_type: ClientType,
+    args: cli::GetCredentialSpendHistoryArgs,
+) -> Result<()> {
+    let mut client = vpnd_client::get_client(&client_type).await?;
+    let request = tonic::Request::new(GetCredentialSpendHistoryRequest {
+        limit: args.limit.unwrap_or_default(),
+    });
+    let response = client
+        .get_credential_spend_history(request)
+        .await?
+        .into_inner();
+    println!("{:#?}", response);
+    Ok(())
+}
+
 async fn list_gateways(
     opts: CliOptions,
     list_args: &cli::ListGatewaysArgs,
@@ -528,9 +1122,15 @@ async fn list_gateways(
     if opts.verbose {
         println!("{:#?}", response);
     }
+    let gateways = response
+        .gateways
+        .into_iter()
+        .filter(|gateway| !list_args.favourites || gateway.favourite)
+        .filter(|gateway| !list_args.hide_blocked || !gateway.blocked)
+        .collect::<Vec<_>>();
     println!("Gateways available for: {gw_type}");
-    println!("Total gateways: {}", response.gateways.len());
-    for gateway in response.gateways.clone() {
+    println!("Total gateways: {}", gateways.len());
+    for gateway in gateways {
         if let Ok(gateway) = nym_vpnd_types::gateway::Gateway::try_from(gateway)
             .inspect_err(|e| println!("Failed to parse gateway: {e}"))
         {
@@ -3,6 +3,8 @@
 
 use nym_gateway_directory::{EntryPoint, ExitPoint, GatewayType};
 
+use crate::cli::CliIpFamily;
+
 pub fn into_entry_point(entry: EntryPoint) -> nym_vpn_proto::EntryNode {
     match entry {
         EntryPoint::Gateway { identity } => nym_vpn_proto::EntryNode::from(&identity),
@@ -28,3 +30,11 @@ pub fn into_gateway_type(gateway_type: GatewayType) -> nym_vpn_proto::GatewayTyp
         GatewayType::Wg => nym_vpn_proto::GatewayType::Wg,
     }
 }
+
+pub fn into_ip_family(ip_family: CliIpFamily) -> nym_vpn_proto::IpFamily {
+    match ip_family {
+        CliIpFamily::DualStack => nym_vpn_proto::IpFamily::DualStack,
+        CliIpFamily::Ipv4Only => nym_vpn_proto::IpFamily::Ipv4Only,
+        CliIpFamily::Ipv6Preferred => nym_vpn_proto::IpFamily::Ipv6Preferred,
+    }
+}
@@ -5,19 +5,53 @@ use std::path::PathBuf;
 
 use anyhow::Context;
 use nym_vpn_proto::nym_vpnd_client::NymVpndClient;
-use tonic::transport::{Channel as TonicChannel, Endpoint as TonicEndpoint};
+use tonic::{
+    metadata::{Ascii, MetadataValue},
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{
+        Certificate, Channel as TonicChannel, ClientTlsConfig, Endpoint as TonicEndpoint, Identity,
+    },
+};
 
 use crate::config;
 
+pub type VpndClient = NymVpndClient<InterceptedService<TonicChannel, BearerToken>>;
+
 #[derive(Debug, Clone)]
 pub enum ClientType {
-    Http,
+    Http(HttpOptions),
     Ipc,
 }
 
-pub async fn get_client(client_type: &ClientType) -> anyhow::Result<NymVpndClient<TonicChannel>> {
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    pub endpoint: String,
+    pub token: Option<String>,
+    pub ca_certificate: Option<PathBuf>,
+    pub client_identity: Option<(PathBuf, PathBuf)>,
+}
+
+/// Attaches the bearer token expected by the daemon remote management listener.
+#[derive(Clone)]
+pub struct BearerToken(Option<MetadataValue<Ascii>>);
+
+impl Interceptor for BearerToken {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        if let Some(token) = &self.0 {
+            request
+                .metadata_mut()
+                .insert("authorization", token.clone());
+        }
+        Ok(request)
+    }
+}
+
+pub async fn get_client(client_type: &ClientType) -> anyhow::Result<VpndClient> {
     match client_type {
-        ClientType::Http => get_http_client().await,
+        ClientType::Http(options) => get_http_client(options).await,
         ClientType::Ipc => get_ipc_client().await,
     }
     .with_context(|| "failed to connect to `nym-vpnd`. Is it running?")
@@ -32,19 +66,53 @@ async fn get_channel(socket_path: PathBuf) -> anyhow::Result<TonicChannel> {
         .await?)
 }
 
-async fn get_http_client() -> anyhow::Result<NymVpndClient<TonicChannel>> {
-    let endpoint = config::default_endpoint();
-    let client = NymVpndClient::connect(endpoint.clone())
+fn tls_config(options: &HttpOptions) -> anyhow::Result<Option<ClientTlsConfig>> {
+    if options.ca_certificate.is_none() && options.client_identity.is_none() {
+        return Ok(None);
+    }
+
+    let mut tls = ClientTlsConfig::new();
+    if let Some(ca_certificate) = &options.ca_certificate {
+        let pem = std::fs::read(ca_certificate)
+            .with_context(|| format!("failed to read: {}", ca_certificate.display()))?;
+        tls = tls.ca_certificate(Certificate::from_pem(pem));
+    }
+    if let Some((certificate, key)) = &options.client_identity {
+        let certificate_pem = std::fs::read(certificate)
+            .with_context(|| format!("failed to read: {}", certificate.display()))?;
+        let key_pem =
+            std::fs::read(key).with_context(|| format!("failed to read: {}", key.display()))?;
+        tls = tls.identity(Identity::from_pem(certificate_pem, key_pem));
+    }
+    Ok(Some(tls))
+}
+
+async fn get_http_client(options: &HttpOptions) -> anyhow::Result<VpndClient> {
+    let token = options
+        .token
+        .as_deref()
+        .map(|token| format!("Bearer {token}").parse())
+        .transpose()
+        .with_context(|| "invalid bearer token")?;
+
+    let mut endpoint = TonicEndpoint::from_shared(options.endpoint.clone())
+        .with_context(|| format!("invalid endpoint: {}", options.endpoint))?;
+    if let Some(tls) = tls_config(options)? {
+        endpoint = endpoint.tls_config(tls)?;
+    }
+
+    let channel = endpoint
+        .connect()
         .await
-        .with_context(|| format!("failed to connect to: {}", endpoint))?;
-    Ok(client)
+        .with_context(|| format!("failed to connect to: {}", options.endpoint))?;
+    Ok(NymVpndClient::with_interceptor(channel, BearerToken(token)))
 }
 
-async fn get_ipc_client() -> anyhow::Result<NymVpndClient<TonicChannel>> {
+async fn get_ipc_client() -> anyhow::Result<VpndClient> {
     let socket_path = config::get_socket_path();
     let channel = get_channel(socket_path.clone())
         .await
         .with_context(|| format!("failed to connect to: {:?}", socket_path))?;
-    let client = NymVpndClient::new(channel);
+    let client = NymVpndClient::with_interceptor(channel, BearerToken(None));
     Ok(client)
 }
@@ -0,0 +1,212 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::gateway::Gateway;
+
+/// A gateway or a country the user marked as favourite, or as blocked to avoid it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FavouriteGateway {
+    pub target: FavouriteTarget,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Only applies to the gateways when used in this role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_role: Option<GatewayRole>,
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FavouriteTarget {
+    /// Base58 encoded identity key of the gateway
+    Gateway(String),
+    /// Two letter ISO country code, covering all the gateways in the country
+    Country(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayRole {
+    Entry,
+    Exit,
+}
+
+impl FavouriteGateway {
+    /// Whether the favourite applies to gateways listed for `role`, `None` meaning either role.
+    fn applies_to_role(&self, role: Option<GatewayRole>) -> bool {
+        match (self.pinned_role, role) {
+            (Some(pinned), Some(role)) => pinned == role,
+            _ => true,
+        }
+    }
+}
+
+/// Set the favourite and blocked flags of the gateways listed for `role`. A favourite naming a
+/// gateway takes precedence over one naming its country.
+pub fn annotate_gateways(
+    favourites: &[FavouriteGateway],
+    gateways: &mut [Gateway],
+    role: Option<GatewayRole>,
+) {
+    let favourites = favourites
+        .iter()
+        .filter(|favourite| favourite.applies_to_role(role))
+        .collect::<Vec<_>>();
+
+    for gateway in gateways {
+        let by_identity = favourites.iter().find(|favourite| {
+            matches!(&favourite.target, FavouriteTarget::Gateway(id) if *id == gateway.identity_key)
+        });
+        let by_country = || {
+            let country = gateway.location.as_ref()?;
+            favourites.iter().find(|favourite| {
+                matches!(
+                    &favourite.target,
+                    FavouriteTarget::Country(code)
+                        if code.eq_ignore_ascii_case(&country.two_letter_iso_country_code)
+                )
+            })
+        };
+        if let Some(favourite) = by_identity.or_else(by_country) {
+            gateway.favourite = !favourite.blocked;
+            gateway.blocked = favourite.blocked;
+        }
+    }
+}
+
+impl fmt::Display for FavouriteTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FavouriteTarget::Gateway(identity) => write!(f, "gateway {identity}"),
+            FavouriteTarget::Country(code) => write!(f, "country {code}"),
+        }
+    }
+}
+
+impl fmt::Display for GatewayRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayRole::Entry => write!(f, "entry"),
+            GatewayRole::Exit => write!(f, "exit"),
+        }
+    }
+}
+
+impl fmt::Display for FavouriteGateway {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            if self.blocked { "blocked" } else { "favourite" },
+            self.target
+        )?;
+        if let Some(role) = self.pinned_role {
+            write!(f, " as {role}")?;
+        }
+        if let Some(label) = &self.label {
+            write!(f, ", {label}")?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, " ({note})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::Location;
+
+    fn gateway(identity_key: &str, country: &str) -> Gateway {
+        Gateway {
+            identity_key: identity_key.to_owned(),
+            moniker: String::new(),
+            location: Some(Location {
+                two_letter_iso_country_code: country.to_owned(),
+                latitude: None,
+                longitude: None,
+            }),
+            last_probe: None,
+            mixnet_score: None,
+            wg_score: None,
+            favourite: false,
+            blocked: false,
+        }
+    }
+
+    fn favourite(target: FavouriteTarget, blocked: bool) -> FavouriteGateway {
+        FavouriteGateway {
+            target,
+            label: None,
+            note: None,
+            pinned_role: None,
+            blocked,
+        }
+    }
+
+    fn flags(gateways: &[Gateway]) -> Vec<(bool, bool)> {
+        gateways
+            .iter()
+            .map(|gw| (gw.favourite, gw.blocked))
+            .collect()
+    }
+
+    #[test]
+    fn identity_takes_precedence_over_country() {
+        let favourites = [
+            favourite(FavouriteTarget::Country("de".to_owned()), true),
+            favourite(FavouriteTarget::Gateway("gw1".to_owned()), false),
+        ];
+        let mut gateways = [
+            gateway("gw1", "DE"),
+            gateway("gw2", "DE"),
+            gateway("gw3", "FR"),
+        ];
+
+        annotate_gateways(&favourites, &mut gateways, None);
+
+        assert_eq!(
+            flags(&gateways),
+            [(true, false), (false, true), (false, false)]
+        );
+    }
+
+    #[test]
+    fn pinned_role_only_applies_to_that_role() {
+        let favourites = [FavouriteGateway {
+            pinned_role: Some(GatewayRole::Entry),
+            ..favourite(FavouriteTarget::Gateway("gw1".to_owned()), false)
+        }];
+
+        let mut exits = [gateway("gw1", "DE")];
+        annotate_gateways(&favourites, &mut exits, Some(GatewayRole::Exit));
+        assert_eq!(flags(&exits), [(false, false)]);
+
+        let mut entries = [gateway("gw1", "DE")];
+        annotate_gateways(&favourites, &mut entries, Some(GatewayRole::Entry));
+        assert_eq!(flags(&entries), [(true, false)]);
+
+        // without a role, e.g. the wireguard gateways, pinned favourites apply too
+        let mut any = [gateway("gw1", "DE")];
+        annotate_gateways(&favourites, &mut any, None);
+        assert_eq!(flags(&any), [(true, false)]);
+    }
+
+    #[test]
+    fn blocked_gateway_is_not_favourite() {
+        let favourites = [favourite(FavouriteTarget::Gateway("gw1".to_owned()), true)];
+        let mut gateways = [gateway("gw1", "DE"), gateway("gw2", "DE")];
+
+        annotate_gateways(&favourites, &mut gateways, Some(GatewayRole::Exit));
+
+        assert_eq!(flags(&gateways), [(false, true), (false, false)]);
+    }
+}
@@ -15,6 +15,12 @@ pub struct Gateway {
     pub last_probe: Option<Probe>,
     pub mixnet_score: Option<Score>,
     pub wg_score: Option<Score>,
+    /// Marked as favourite, directly or through its country.
+    #[serde(default)]
+    pub favourite: bool,
+    /// Marked as blocked, directly or through its country.
+    #[serde(default)]
+    pub blocked: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -111,7 +117,14 @@ impl fmt::Display for Gateway {
             .map(|p| p.to_string())
             .unwrap_or("not set".to_string());
 
-        write!(f, "{}, {}, {}", self.identity_key, location, last_probe)
+        write!(f, "{}, {}, {}", self.identity_key, location, last_probe)?;
+        if self.favourite {
+            write!(f, ", favourite")?;
+        }
+        if self.blocked {
+            write!(f, ", blocked")?;
+        }
+        Ok(())
     }
 }
 
@@ -143,6 +156,8 @@ impl From<nym_validator_client::models::NymNodeDescription> for Gateway {
             last_probe: None,
             wg_score: None,
             mixnet_score: None,
+            favourite: false,
+            blocked: false,
         }
     }
 }
@@ -216,6 +231,8 @@ impl From<nym_vpn_lib::gateway_directory::Gateway> for Gateway {
             last_probe: gateway.last_probe.map(Probe::from),
             wg_score: gateway.wg_score.map(Score::from),
             mixnet_score: gateway.mixnet_score.map(Score::from),
+            favourite: false,
+            blocked: false,
         }
     }
 }
@@ -60,6 +60,10 @@ pub struct Command {
 
     #[arg(long)]
     pub run_as_service: bool,
+
+    /// Generate a new bearer token for the remote management listener and exit.
+    #[arg(long)]
+    pub generate_remote_token: bool,
 }
 
 fn check_path(path: &str) -> Result<PathBuf, String> {
@@ -0,0 +1,479 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Authorization of calls made over the command interface socket.
+//!
+//! The socket is reachable by every local user. Once a control group is configured, each call on
+//! unix is checked against the credentials of the connecting peer (`SO_PEERCRED`): read-only
+//! calls are open to everyone, control calls require membership of the control group, and calls
+//! that change or reveal the account require root. Without a control group every call is allowed.
+//!
+//! Clients of the remote management listener have no peer credentials. Once they are
+//! authenticated they are granted the permission configured for remote management instead.
+
+// The policy is only enforced on unix, windows relies on the named pipe security descriptor.
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::{self, Either, Ready};
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+
+const SERVICE_NAME: &str = "nym.vpn.NymVpnd";
+
+/// The permission level required for, or granted to, a caller.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Permission {
+    /// Querying state, gateways and events.
+    ReadOnly,
+    /// Connecting, disconnecting and other operations that don't touch the account secrets.
+    Control,
+    /// Storing, forgetting or exporting account and device material.
+    Admin,
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::ReadOnly => write!(f, "read-only"),
+            Permission::Control => write!(f, "control"),
+            Permission::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+/// Returns the permission required to call the given RPC method.
+///
+/// Unknown methods require admin, so that newly added RPCs are locked down until they are
+/// explicitly classified here.
+pub fn required_permission(method: &str) -> Permission {
+    match method {
+        "Info"
+        | "GetSystemMessages"
+        | "GetNetworkCompatibility"
+        | "GetFeatureFlags"
+        | "GetTunnelState"
+        | "ListenToTunnelState"
+        | "ListenToEvents"
+        | "ListGateways"
+        | "ListCountries"
+        | "IsAccountStored"
+        | "GetAccountIdentity"
+        | "GetAccountLinks"
+        | "GetAccountState"
+        | "GetAccountUsage"
+        | "GetDeviceIdentity"
+        | "GetDevices"
+        | "GetActiveDevices"
+        | "GetDeviceZkNyms"
+        | "GetZkNymsAvailableForDownload"
+        | "GetZkNymById"
+        | "GetAvailableTickets"
+        | "GetCredentialSpendHistory"
+        | "GetLogPath"
+        | "GetDnsStatus"
+        | "DiagnoseLeaks"
+        | "GetDnsFilterStats"
+        | "GetTunnelRoutes"
+        | "ListCustomGateways"
+        | "ListFavourites"
+        | "ListNetworkRules"
+        | "GetCurrentNetwork" => Permission::ReadOnly,
+
+        "SetNetwork"
+        | "SetTunnelRoutes"
+        | "VpnConnect"
+        | "VpnDisconnect"
+        | "RefreshAccountState"
+        | "RegisterDevice"
+        | "RequestZkNym"
+        | "SetFavourite"
+        | "RemoveFavourite"
+        | "SetNetworkRule"
+        | "RemoveNetworkRule"
+        | "DeleteLogFile"
+        | "ProbeGateway" => Permission::Control,
+
+        "StoreAccount"
+        | "ForgetAccount"
+        | "ResetDeviceIdentity"
+        | "ConfirmZkNymDownloaded"
+        | "ExportTicketbooks"
+        | "ConfirmTicketbooksExported"
+        | "ImportTicketbooks"
+        | "AddCustomGateway"
+        | "RemoveCustomGateway" => Permission::Admin,
+
+        _ => Permission::Admin,
+    }
+}
+
+// Splits a gRPC request path of the form `/<service>/<method>` into the method name, if the path
+// belongs to the vpnd service.
+fn method_name(path: &str) -> Option<&str> {
+    let (service, method) = path.trim_start_matches('/').split_once('/')?;
+    (service == SERVICE_NAME && !method.is_empty()).then_some(method)
+}
+
+/// The credentials of the process on the other end of the socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCredentials {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// Decides which permission a peer is granted.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorizationPolicy {
+    control_group: Option<String>,
+    remote_permission: Option<Permission>,
+}
+
+impl AuthorizationPolicy {
+    /// Creates a policy granting control permission to root and the members of `control_group`.
+    /// Without a control group the policy is not enforced and every peer is granted admin.
+    pub fn new(control_group: Option<String>) -> Self {
+        Self {
+            control_group: control_group.filter(|group| !group.is_empty()),
+            remote_permission: None,
+        }
+    }
+
+    /// Creates a policy granting `permission` to every caller, for clients that have already
+    /// authenticated with the remote management listener.
+    pub fn remote(permission: Permission) -> Self {
+        Self {
+            control_group: None,
+            remote_permission: Some(permission),
+        }
+    }
+
+    /// Whether calls are checked against the credentials of the peer at all.
+    pub fn is_enforced(&self) -> bool {
+        self.control_group.is_some() || self.remote_permission.is_some()
+    }
+
+    /// Returns the permission granted to the peer.
+    pub fn granted(&self, peer: Option<PeerCredentials>) -> Permission {
+        if let Some(permission) = self.remote_permission {
+            return permission;
+        }
+        if self.control_group.is_none() {
+            return Permission::Admin;
+        }
+
+        let Some(peer) = peer else {
+            return Permission::ReadOnly;
+        };
+
+        if peer.uid == 0 {
+            Permission::Admin
+        } else if self.is_control_group_member(peer) {
+            Permission::Control
+        } else {
+            Permission::ReadOnly
+        }
+    }
+
+    /// Checks whether the peer is allowed to call the given request path.
+    pub fn authorize(
+        &self,
+        path: &str,
+        peer: Option<PeerCredentials>,
+    ) -> Result<(), Box<tonic::Status>> {
+        let required = method_name(path)
+            .map(required_permission)
+            .unwrap_or(Permission::Admin);
+        let granted = self.granted(peer);
+
+        if granted >= required {
+            Ok(())
+        } else {
+            let method = method_name(path).unwrap_or(path);
+            tracing::warn!(
+                "Denied {method} for peer {peer:?}: requires {required} permission, peer has {granted}"
+            );
+            Err(Box::new(tonic::Status::permission_denied(
+                self.denied_message(method, required),
+            )))
+        }
+    }
+
+    fn denied_message(&self, method: &str, required: Permission) -> String {
+        if self.remote_permission.is_some() {
+            return format!(
+                "{method} requires {required} permission, which isn't granted to remote clients"
+            );
+        }
+        match (required, &self.control_group) {
+            (Permission::Control, Some(group)) => format!(
+                "{method} requires control permission: run as root or as a member of the '{group}' group"
+            ),
+            _ => format!("{method} requires {required} permission: run as root"),
+        }
+    }
+
+    #[cfg(unix)]
+    fn is_control_group_member(&self, peer: PeerCredentials) -> bool {
+        use nix::unistd::{Gid, Group, Uid, User};
+
+        let Some(group_name) = self.control_group.as_deref() else {
+            return false;
+        };
+
+        // Resolved on every call so that membership changes apply without restarting the daemon
+        let group = match Group::from_name(group_name) {
+            Ok(Some(group)) => group,
+            Ok(None) => {
+                tracing::debug!("Control group '{group_name}' does not exist");
+                return false;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to look up control group '{group_name}': {err}");
+                return false;
+            }
+        };
+
+        if group.gid == Gid::from_raw(peer.gid) {
+            return true;
+        }
+
+        // SO_PEERCRED only carries the primary group, so supplementary membership is checked
+        // against the group database.
+        match User::from_uid(Uid::from_raw(peer.uid)) {
+            Ok(Some(user)) => user.gid == group.gid || group.mem.contains(&user.name),
+            Ok(None) => false,
+            Err(err) => {
+                tracing::warn!("Failed to look up user {}: {err}", peer.uid);
+                false
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn is_control_group_member(&self, _peer: PeerCredentials) -> bool {
+        false
+    }
+}
+
+#[cfg(unix)]
+fn peer_credentials(extensions: &http::Extensions) -> Option<PeerCredentials> {
+    extensions
+        .get::<tonic::transport::server::UdsConnectInfo>()
+        .and_then(|info| info.peer_cred)
+        .map(|cred| PeerCredentials {
+            uid: cred.uid(),
+            gid: cred.gid(),
+        })
+}
+
+/// Layer rejecting calls the connecting peer is not allowed to make.
+#[derive(Debug, Clone)]
+pub struct AuthorizationLayer {
+    policy: Arc<AuthorizationPolicy>,
+}
+
+impl AuthorizationLayer {
+    pub fn new(policy: AuthorizationPolicy) -> Self {
+        Self {
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthorizationLayer {
+    type Service = Authorization<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Authorization {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Authorization<S> {
+    inner: S,
+    policy: Arc<AuthorizationPolicy>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for Authorization<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Self::Response, Self::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        #[cfg(unix)]
+        let result = self
+            .policy
+            .authorize(req.uri().path(), peer_credentials(req.extensions()));
+        #[cfg(not(unix))]
+        let result = if self.policy.remote_permission.is_some() {
+            self.policy.authorize(req.uri().path(), None)
+        } else {
+            Ok(())
+        };
+
+        match result {
+            Ok(()) => Either::Left(self.inner.call(req)),
+            Err(status) => Either::Right(future::ready(Ok(status.into_http()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: PeerCredentials = PeerCredentials {
+        uid: 4242,
+        gid: 4242,
+    };
+
+    const ROOT: PeerCredentials = PeerCredentials { uid: 0, gid: 0 };
+
+    // A group that doesn't exist, so that USER is never a member
+    const CONTROL_GROUP: &str = "nym-vpn-test-control";
+
+    fn enforced_policy() -> AuthorizationPolicy {
+        AuthorizationPolicy::new(Some(CONTROL_GROUP.to_owned()))
+    }
+
+    fn path(method: &str) -> String {
+        format!("/{SERVICE_NAME}/{method}")
+    }
+
+    #[test]
+    fn method_name_from_path() {
+        assert_eq!(
+            method_name("/nym.vpn.NymVpnd/VpnConnect"),
+            Some("VpnConnect")
+        );
+        assert_eq!(method_name("/other.Service/VpnConnect"), None);
+        assert_eq!(method_name("/nym.vpn.NymVpnd/"), None);
+        assert_eq!(method_name("/"), None);
+    }
+
+    #[test]
+    fn classifies_methods() {
+        assert_eq!(required_permission("GetTunnelState"), Permission::ReadOnly);
+        assert_eq!(required_permission("ListGateways"), Permission::ReadOnly);
+        assert_eq!(required_permission("ListenToEvents"), Permission::ReadOnly);
+        assert_eq!(required_permission("VpnConnect"), Permission::Control);
+        assert_eq!(required_permission("VpnDisconnect"), Permission::Control);
+        assert_eq!(required_permission("ProbeGateway"), Permission::Control);
+        assert_eq!(required_permission("ListFavourites"), Permission::ReadOnly);
+        assert_eq!(required_permission("SetFavourite"), Permission::Control);
+        assert_eq!(
+            required_permission("GetCurrentNetwork"),
+            Permission::ReadOnly
+        );
+        assert_eq!(required_permission("SetNetworkRule"), Permission::Control);
+        assert_eq!(required_permission("StoreAccount"), Permission::Admin);
+        assert_eq!(required_permission("ForgetAccount"), Permission::Admin);
+        assert_eq!(
+            required_permission("ResetDeviceIdentity"),
+            Permission::Admin
+        );
+        assert_eq!(required_permission("SomeFutureRpc"), Permission::Admin);
+    }
+
+    #[test]
+    fn root_is_admin() {
+        let policy = enforced_policy();
+        assert!(policy.is_enforced());
+        assert_eq!(policy.granted(Some(ROOT)), Permission::Admin);
+        assert!(policy.authorize(&path("ForgetAccount"), Some(ROOT)).is_ok());
+    }
+
+    #[test]
+    fn unprivileged_user_is_read_only() {
+        let policy = enforced_policy();
+        assert_eq!(policy.granted(Some(USER)), Permission::ReadOnly);
+        assert!(policy
+            .authorize(&path("GetTunnelState"), Some(USER))
+            .is_ok());
+
+        let status = policy
+            .authorize(&path("VpnDisconnect"), Some(USER))
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let status = policy
+            .authorize(&path("StoreAccount"), Some(USER))
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[test]
+    fn unknown_peer_is_read_only() {
+        let policy = enforced_policy();
+        assert_eq!(policy.granted(None), Permission::ReadOnly);
+        assert!(policy.authorize(&path("VpnConnect"), None).is_err());
+    }
+
+    #[test]
+    fn foreign_paths_require_admin() {
+        let policy = enforced_policy();
+        assert!(policy.authorize("/other.Service/Info", Some(USER)).is_err());
+        assert!(policy.authorize("/other.Service/Info", Some(ROOT)).is_ok());
+    }
+
+    #[test]
+    fn denied_message_names_control_group() {
+        let status = enforced_policy()
+            .authorize(&path("VpnConnect"), Some(USER))
+            .unwrap_err();
+        assert!(status
+            .message()
+            .contains(&format!("'{CONTROL_GROUP}' group")));
+    }
+
+    #[test]
+    fn not_enforced_without_control_group() {
+        for policy in [
+            AuthorizationPolicy::default(),
+            AuthorizationPolicy::new(Some(String::new())),
+        ] {
+            assert!(!policy.is_enforced());
+            assert_eq!(policy.granted(Some(USER)), Permission::Admin);
+            assert_eq!(policy.granted(None), Permission::Admin);
+            assert!(policy.authorize(&path("StoreAccount"), Some(USER)).is_ok());
+        }
+    }
+
+    #[test]
+    fn remote_clients_get_the_configured_permission() {
+        // The default for remote management, whether or not a control group is set locally
+        let policy = AuthorizationPolicy::remote(Permission::Control);
+        assert!(policy.is_enforced());
+        assert_eq!(policy.granted(None), Permission::Control);
+        assert!(policy.authorize(&path("VpnConnect"), None).is_ok());
+        assert!(policy.authorize(&path("VpnDisconnect"), None).is_ok());
+        let status = policy.authorize(&path("StoreAccount"), None).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        assert!(policy.authorize(&path("ExportTicketbooks"), None).is_err());
+
+        let policy = AuthorizationPolicy::remote(Permission::ReadOnly);
+        assert!(policy.authorize(&path("GetTunnelState"), None).is_ok());
+        assert!(policy.authorize(&path("VpnConnect"), None).is_err());
+
+        let policy = AuthorizationPolicy::remote(Permission::Admin);
+        assert!(policy.authorize(&path("StoreAccount"), None).is_ok());
+    }
+}
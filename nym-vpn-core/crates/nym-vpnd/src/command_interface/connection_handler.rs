@@ -4,22 +4,32 @@
 use tokio::sync::{mpsc::UnboundedSender, oneshot, watch};
 use zeroize::Zeroizing;
 
-use nym_vpn_account_controller::{AccountStateSummary, AvailableTicketbooks};
+use nym_vpn_account_controller::{
+    AccountStateSummary, AvailableTicketbooks, CredentialSpend, TicketbookImportSummary,
+};
 use nym_vpn_api_client::{
     response::{NymVpnDevice, NymVpnUsage},
     NetworkCompatibility,
 };
-use nym_vpn_lib::gateway_directory::{EntryPoint, ExitPoint, GatewayClient, GatewayType};
+use nym_vpn_lib::{
+    dns::{DnsStatus, DnsVerification},
+    gateway_directory::{EntryPoint, ExitPoint, GatewayClient, GatewayDescriptor, GatewayType},
+    tunnel_state_machine::{DnsFilterStats, LeakReport, LeakTestError, TunnelRoutes},
+};
 use nym_vpn_lib_types::TunnelState;
 use nym_vpn_network_config::{FeatureFlags, ParsedAccountLinks, SystemMessages};
-use nym_vpnd_types::gateway;
+use nym_vpnd_types::{
+    favourite::{FavouriteGateway, FavouriteTarget},
+    gateway,
+};
 
 use super::protobuf::error::VpnCommandSendError;
 use crate::logging::LogPath;
 use crate::service::{
-    AccountError, ConnectArgs, ConnectOptions, SetNetworkError, VpnServiceCommand,
-    VpnServiceConnectError, VpnServiceDeleteLogFileError, VpnServiceDisconnectError,
-    VpnServiceInfo,
+    AccountError, ConnectArgs, ConnectOptions, CustomGatewayError, FavouriteError, GatewayHealth,
+    NetworkIdentity, NetworkRule, NetworkRuleError, ProbeGatewayError, SetNetworkError,
+    SetTunnelRoutesError, VpnServiceCommand, VpnServiceConnectError, VpnServiceDeleteLogFileError,
+    VpnServiceDisconnectError, VpnServiceInfo,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -282,6 +292,36 @@ impl CommandInterfaceConnectionHandler {
             .await
     }
 
+    pub async fn handle_export_ticketbooks(
+        &self,
+    ) -> Result<Result<Vec<u8>, AccountError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ExportTicketbooks, ())
+            .await
+    }
+
+    pub async fn handle_confirm_ticketbooks_exported(
+        &self,
+    ) -> Result<Result<(), AccountError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ConfirmTicketbooksExported, ())
+            .await
+    }
+
+    pub async fn handle_import_ticketbooks(
+        &self,
+        bundle: Vec<u8>,
+    ) -> Result<Result<TicketbookImportSummary, AccountError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ImportTicketbooks, bundle)
+            .await
+    }
+
+    pub async fn handle_get_credential_spend_history(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Result<Vec<CredentialSpend>, AccountError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::GetCredentialSpendHistory, limit)
+            .await
+    }
+
     pub(crate) async fn handle_delete_log_file(
         &self,
     ) -> Result<Result<(), VpnServiceDeleteLogFileError>, VpnCommandSendError> {
@@ -293,6 +333,122 @@ impl CommandInterfaceConnectionHandler {
         self.send_and_wait(VpnServiceCommand::GetLogPath, ()).await
     }
 
+    pub async fn handle_get_dns_status(
+        &self,
+    ) -> Result<(DnsStatus, DnsVerification), VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::GetDnsStatus, ())
+            .await
+    }
+
+    pub async fn handle_diagnose_leaks(
+        &self,
+    ) -> Result<Result<LeakReport, LeakTestError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::DiagnoseLeaks, ())
+            .await
+    }
+
+    pub async fn handle_get_dns_filter_stats(
+        &self,
+    ) -> Result<Option<DnsFilterStats>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::GetDnsFilterStats, ())
+            .await
+    }
+
+    pub async fn handle_set_tunnel_routes(
+        &self,
+        tunnel_routes: TunnelRoutes,
+    ) -> Result<Result<(), SetTunnelRoutesError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::SetTunnelRoutes, tunnel_routes)
+            .await
+    }
+
+    pub async fn handle_get_tunnel_routes(&self) -> Result<TunnelRoutes, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::GetTunnelRoutes, ())
+            .await
+    }
+
+    pub async fn handle_add_custom_gateway(
+        &self,
+        descriptor: GatewayDescriptor,
+    ) -> Result<Result<(), CustomGatewayError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::AddCustomGateway, descriptor)
+            .await
+    }
+
+    pub async fn handle_remove_custom_gateway(
+        &self,
+        identity: String,
+    ) -> Result<Result<(), CustomGatewayError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::RemoveCustomGateway, identity)
+            .await
+    }
+
+    pub async fn handle_list_custom_gateways(
+        &self,
+    ) -> Result<Vec<GatewayDescriptor>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ListCustomGateways, ())
+            .await
+    }
+
+    pub async fn handle_set_favourite(
+        &self,
+        favourite: FavouriteGateway,
+    ) -> Result<Result<(), FavouriteError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::SetFavourite, favourite)
+            .await
+    }
+
+    pub async fn handle_remove_favourite(
+        &self,
+        target: FavouriteTarget,
+    ) -> Result<Result<(), FavouriteError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::RemoveFavourite, target)
+            .await
+    }
+
+    pub async fn handle_list_favourites(
+        &self,
+    ) -> Result<Vec<FavouriteGateway>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ListFavourites, ())
+            .await
+    }
+
+    pub async fn handle_set_network_rule(
+        &self,
+        rule: NetworkRule,
+    ) -> Result<Result<(), NetworkRuleError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::SetNetworkRule, rule)
+            .await
+    }
+
+    pub async fn handle_remove_network_rule(
+        &self,
+        name: String,
+    ) -> Result<Result<(), NetworkRuleError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::RemoveNetworkRule, name)
+            .await
+    }
+
+    pub async fn handle_list_network_rules(&self) -> Result<Vec<NetworkRule>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ListNetworkRules, ())
+            .await
+    }
+
+    pub async fn handle_get_current_network(
+        &self,
+    ) -> Result<(Option<NetworkIdentity>, Option<NetworkRule>), VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::GetCurrentNetwork, ())
+            .await
+    }
+
+    pub async fn handle_probe_gateway(
+        &self,
+        identity: String,
+    ) -> Result<Result<GatewayHealth, ProbeGatewayError>, VpnCommandSendError> {
+        self.send_and_wait(VpnServiceCommand::ProbeGateway, identity)
+            .await
+    }
+
     async fn send_and_wait<R, F, O>(&self, command: F, opts: O) -> Result<R, VpnCommandSendError>
     where
         F: FnOnce(oneshot::Sender<R>, O) -> VpnServiceCommand,
@@ -1,3 +1,5 @@
+use nym_vpn_lib::tunnel_state_machine::tunnel::wireguard::two_hop_config::MtuMismatch;
+
 #[derive(Debug, thiserror::Error)]
 pub enum CommandInterfaceError {
     #[error("failed to parse DNS IP address: {ip}")]
@@ -5,4 +7,51 @@ pub enum CommandInterfaceError {
         ip: String,
         source: std::net::AddrParseError,
     },
+
+    #[error("invalid IP family: {0}")]
+    InvalidIpFamily(i32),
+
+    #[error("invalid MTU: {0}")]
+    InvalidMtu(u32),
+
+    #[error("MTU overrides can't be combined with automatic MTU detection")]
+    ConflictingMtuOptions,
+
+    #[error(transparent)]
+    MtuMismatch(#[from] MtuMismatch),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteListenerError {
+    #[error("failed to read {}", path.display())]
+    ReadFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to write {}", path.display())]
+    WriteFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("tls_certificate and tls_key must be configured together")]
+    IncompleteTlsIdentity,
+
+    #[error("client_ca_certificate requires tls_certificate and tls_key to be configured")]
+    MutualTlsWithoutIdentity,
+
+    #[error(
+        "no client CA is configured and no bearer token exists at {}, generate one with `nym-vpnd --generate-remote-token`",
+        path.display()
+    )]
+    MissingToken { path: std::path::PathBuf },
+
+    #[error("remote management on {listen_address} requires TLS unless it listens on a loopback address")]
+    PlainTextOnNonLoopback {
+        listen_address: std::net::SocketAddr,
+    },
+
+    #[error(transparent)]
+    Transport(#[from] tonic::transport::Error),
 }
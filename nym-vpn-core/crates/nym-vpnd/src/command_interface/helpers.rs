@@ -1,11 +1,16 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use ipnetwork::IpNetwork;
 use nym_vpn_api_client::types::Percent;
 use nym_vpn_lib::{
-    gateway_directory::{EntryPoint, ExitPoint},
+    gateway_directory::{EntryPoint, ExitPoint, GatewayDescriptor},
+    tunnel_state_machine::TunnelRoutes,
     NodeIdentity, Recipient,
 };
+use nym_vpnd_types::favourite::{FavouriteGateway, FavouriteTarget, GatewayRole};
+
+use crate::service::{format_mac_address, NetworkKind, NetworkRule, NetworkRuleAction};
 
 // For the future: these functions should be moved to the nym-vpn-proto crate
 
@@ -41,6 +46,179 @@ pub(super) fn parse_entry_point(
     })
 }
 
+pub(super) fn parse_tunnel_routes(
+    routes: nym_vpn_proto::TunnelRoutes,
+) -> Result<TunnelRoutes, tonic::Status> {
+    use nym_vpn_proto::tunnel_routes::Mode;
+
+    let networks = routes
+        .networks
+        .iter()
+        .map(|network| {
+            network.parse::<IpNetwork>().map_err(|err| {
+                tracing::error!("Failed to parse network {network}: {:?}", err);
+                tonic::Status::invalid_argument(format!("Invalid network: {network}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mode = Mode::try_from(routes.mode)
+        .map_err(|_| tonic::Status::invalid_argument("Invalid tunnel routes mode"))?;
+    match mode {
+        Mode::All => Ok(TunnelRoutes::All),
+        Mode::Exclude | Mode::IncludeOnly if networks.is_empty() => Err(
+            tonic::Status::invalid_argument("At least one network is required"),
+        ),
+        Mode::Exclude => Ok(TunnelRoutes::Exclude(networks)),
+        Mode::IncludeOnly => Ok(TunnelRoutes::IncludeOnly(networks)),
+    }
+}
+
+pub(super) fn parse_gateway_descriptor(
+    gateway: nym_vpn_proto::GatewayDescriptor,
+) -> Result<GatewayDescriptor, tonic::Status> {
+    let ips = gateway
+        .ips
+        .iter()
+        .map(|ip| {
+            ip.parse().map_err(|err| {
+                tracing::error!("Failed to parse ip address {ip}: {:?}", err);
+                tonic::Status::invalid_argument(format!("Invalid ip address: {ip}"))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let port = |port: Option<u32>| {
+        port.map(u16::try_from)
+            .transpose()
+            .map_err(|_| tonic::Status::invalid_argument("Invalid port"))
+    };
+
+    Ok(GatewayDescriptor {
+        identity: gateway.identity,
+        moniker: gateway.moniker,
+        host: gateway.host,
+        ips,
+        authenticator_address: gateway.authenticator_address,
+        ipr_address: gateway.ipr_address,
+        clients_ws_port: port(gateway.clients_ws_port)?,
+        clients_wss_port: port(gateway.clients_wss_port)?,
+        country: gateway.country,
+        version: gateway.version,
+    })
+}
+
+pub(super) fn parse_favourite_target(
+    target: Option<nym_vpn_proto::FavouriteTarget>,
+) -> Result<FavouriteTarget, tonic::Status> {
+    use nym_vpn_proto::favourite_target::Target;
+
+    match target.and_then(|target| target.target) {
+        Some(Target::GatewayId(id)) => {
+            NodeIdentity::from_base58_string(&id).map_err(|err| {
+                tracing::error!("Failed to parse gateway id: {:?}", err);
+                tonic::Status::invalid_argument("Invalid gateway id")
+            })?;
+            Ok(FavouriteTarget::Gateway(id))
+        }
+        Some(Target::CountryCode(code)) => {
+            if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(tonic::Status::invalid_argument(format!(
+                    "Invalid country code: {code}"
+                )));
+            }
+            Ok(FavouriteTarget::Country(code.to_ascii_uppercase()))
+        }
+        None => Err(tonic::Status::invalid_argument(
+            "Missing gateway or country",
+        )),
+    }
+}
+
+pub(super) fn parse_favourite(
+    favourite: nym_vpn_proto::FavouriteGateway,
+) -> Result<FavouriteGateway, tonic::Status> {
+    let pinned_role = match nym_vpn_proto::GatewayRole::try_from(favourite.pinned_role) {
+        Ok(nym_vpn_proto::GatewayRole::Unspecified) => None,
+        Ok(nym_vpn_proto::GatewayRole::Entry) => Some(GatewayRole::Entry),
+        Ok(nym_vpn_proto::GatewayRole::Exit) => Some(GatewayRole::Exit),
+        Err(_) => return Err(tonic::Status::invalid_argument("Invalid gateway role")),
+    };
+    // Empty strings clear the label and note
+    let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+
+    Ok(FavouriteGateway {
+        target: parse_favourite_target(favourite.target)?,
+        label: non_empty(favourite.label),
+        note: non_empty(favourite.note),
+        pinned_role,
+        blocked: favourite.blocked,
+    })
+}
+
+pub(super) fn parse_network_rule(
+    rule: nym_vpn_proto::NetworkRule,
+) -> Result<NetworkRule, tonic::Status> {
+    if rule.name.trim().is_empty() {
+        return Err(tonic::Status::invalid_argument("Missing rule name"));
+    }
+    let action = match nym_vpn_proto::NetworkRuleAction::try_from(rule.action) {
+        Ok(nym_vpn_proto::NetworkRuleAction::Connect) => NetworkRuleAction::Connect,
+        Ok(nym_vpn_proto::NetworkRuleAction::Disconnect) => NetworkRuleAction::Disconnect,
+        Ok(nym_vpn_proto::NetworkRuleAction::Unspecified) | Err(_) => {
+            return Err(tonic::Status::invalid_argument("Invalid rule action"))
+        }
+    };
+    let kind = match nym_vpn_proto::NetworkKind::try_from(rule.kind) {
+        Ok(nym_vpn_proto::NetworkKind::Unspecified) => None,
+        Ok(nym_vpn_proto::NetworkKind::Wifi) => Some(NetworkKind::Wifi),
+        Ok(nym_vpn_proto::NetworkKind::Wired) => Some(NetworkKind::Wired),
+        Ok(nym_vpn_proto::NetworkKind::Other) => Some(NetworkKind::Other),
+        Err(_) => return Err(tonic::Status::invalid_argument("Invalid network kind")),
+    };
+    let subnet = rule
+        .subnet
+        .map(|subnet| {
+            subnet.parse::<IpNetwork>().map_err(|err| {
+                tonic::Status::invalid_argument(format!("Invalid subnet {subnet}: {err}"))
+            })
+        })
+        .transpose()?;
+    let gateway_mac = rule
+        .gateway_mac
+        .map(|mac| {
+            parse_mac_address(&mac)
+                .map(format_mac_address)
+                .ok_or_else(|| {
+                    tonic::Status::invalid_argument(format!("Invalid MAC address {mac}"))
+                })
+        })
+        .transpose()?;
+    let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+
+    Ok(NetworkRule {
+        name: rule.name.trim().to_owned(),
+        action,
+        kind,
+        ssid: non_empty(rule.ssid),
+        interface: non_empty(rule.interface),
+        subnet,
+        gateway_mac,
+    })
+}
+
+// Accepts colon or dash separated hex bytes, e.g. 3C:22:FB:00:11:22 or 3c-22-fb-00-11-22
+fn parse_mac_address(mac: &str) -> Option<[u8; 6]> {
+    let bytes = mac
+        .split([':', '-'])
+        .map(|byte| {
+            (byte.len() == 2)
+                .then(|| u8::from_str_radix(byte, 16).ok())
+                .flatten()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    bytes.try_into().ok()
+}
+
 pub(super) fn parse_exit_point(
     exit: nym_vpn_proto::exit_node::ExitNodeEnum,
 ) -> Result<ExitPoint, tonic::Status> {
@@ -7,31 +7,52 @@ use nym_vpn_network_config::Network;
 use tokio::sync::{broadcast, mpsc::UnboundedSender};
 
 use nym_vpn_api_client::types::{GatewayMinPerformance, ScoreThresholds};
+use nym_vpn_lib::tunnel_state_machine::{
+    tunnel::wireguard::two_hop_config::TwoHopMtu, IpFamily, WireguardMtu,
+};
 use nym_vpn_lib_types::TunnelEvent;
 use nym_vpn_proto::{
-    conversions::ConversionError, nym_vpnd_server::NymVpnd, AccountError,
-    ConfirmZkNymDownloadedRequest, ConfirmZkNymDownloadedResponse, ConnectRequest, ConnectResponse,
-    DeleteLogFileResponse, DisconnectResponse, ForgetAccountResponse, GetAccountIdentityResponse,
-    GetAccountLinksRequest, GetAccountLinksResponse, GetAccountStateResponse,
-    GetAccountUsageResponse, GetAvailableTicketsResponse, GetDeviceIdentityResponse,
-    GetDeviceZkNymsResponse, GetDevicesResponse, GetFeatureFlagsResponse, GetLogPathResponse,
-    GetNetworkCompatibilityResponse, GetSystemMessagesResponse, GetZkNymByIdRequest,
-    GetZkNymByIdResponse, GetZkNymsAvailableForDownloadResponse, InfoResponse,
-    IsAccountStoredResponse, ListCountriesRequest, ListCountriesResponse, ListGatewaysRequest,
-    ListGatewaysResponse, RefreshAccountStateResponse, RegisterDeviceResponse,
+    conversions::ConversionError, nym_vpnd_server::NymVpnd, AccountError, AddCustomGatewayRequest,
+    AddCustomGatewayResponse, ConfirmTicketbooksExportedResponse, ConfirmZkNymDownloadedRequest,
+    ConfirmZkNymDownloadedResponse, ConnectRequest, ConnectResponse, DeleteLogFileResponse,
+    DiagnoseLeaksResponse, DisconnectResponse, ExportTicketbooksResponse, ForgetAccountResponse,
+    GetAccountIdentityResponse, GetAccountLinksRequest, GetAccountLinksResponse,
+    GetAccountStateResponse, GetAccountUsageResponse, GetAvailableTicketsResponse,
+    GetCredentialSpendHistoryRequest, GetCredentialSpendHistoryResponse, GetCurrentNetworkResponse,
+    GetDeviceIdentityResponse, GetDeviceZkNymsResponse, GetDevicesResponse,
+    GetDnsFilterStatsResponse, GetDnsStatusResponse, GetFeatureFlagsResponse, GetLogPathResponse,
+    GetNetworkCompatibilityResponse, GetSystemMessagesResponse, GetTunnelRoutesResponse,
+    GetZkNymByIdRequest, GetZkNymByIdResponse, GetZkNymsAvailableForDownloadResponse,
+    ImportTicketbooksRequest, ImportTicketbooksResponse, InfoResponse, IsAccountStoredResponse,
+    ListCountriesRequest, ListCountriesResponse, ListCustomGatewaysResponse,
+    ListFavouritesResponse, ListGatewaysRequest, ListGatewaysResponse, ListNetworkRulesResponse,
+    ProbeGatewayRequest, ProbeGatewayResponse, RefreshAccountStateResponse, RegisterDeviceResponse,
+    RemoveCustomGatewayRequest, RemoveCustomGatewayResponse, RemoveFavouriteRequest,
+    RemoveFavouriteResponse, RemoveNetworkRuleRequest, RemoveNetworkRuleResponse,
     RequestZkNymResponse, ResetDeviceIdentityRequest, ResetDeviceIdentityResponse,
-    SetNetworkRequest, SetNetworkResponse, StoreAccountRequest, StoreAccountResponse, TunnelState,
+    SetFavouriteRequest, SetFavouriteResponse, SetNetworkRequest, SetNetworkResponse,
+    SetNetworkRuleRequest, SetNetworkRuleResponse, SetTunnelRoutesRequest, SetTunnelRoutesResponse,
+    StoreAccountRequest, StoreAccountResponse, TunnelState,
 };
+use nym_vpnd_types::favourite::{annotate_gateways, GatewayRole};
 use zeroize::Zeroizing;
 
 use super::{
     connection_handler::CommandInterfaceConnectionHandler,
     error::CommandInterfaceError,
-    helpers::{parse_entry_point, parse_exit_point, threshold_into_percent},
+    helpers::{
+        parse_entry_point, parse_exit_point, parse_favourite, parse_favourite_target,
+        parse_gateway_descriptor, parse_network_rule, parse_tunnel_routes, threshold_into_percent,
+    },
 };
 use crate::logging::LogPath;
 use crate::{
-    command_interface::protobuf::info_response::into_proto_available_tickets,
+    command_interface::protobuf::info_response::{
+        into_proto_available_tickets, into_proto_credential_spend, into_proto_current_network,
+        into_proto_dns_filter_stats, into_proto_dns_status, into_proto_favourite,
+        into_proto_gateway_descriptor, into_proto_gateway_health, into_proto_leak_report,
+        into_proto_network_rule, into_proto_ticketbook_import_summary, into_proto_tunnel_routes,
+    },
     service::{ConnectOptions, VpnServiceCommand},
 };
 
@@ -302,6 +323,8 @@ impl NymVpnd for CommandInterface {
                     medium: sc.wg_thresholds.medium,
                     low: sc.wg_thresholds.low,
                 });
+        let handler = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone());
+        let custom_gateways = handler.handle_list_custom_gateways().await?;
         let directory_config = nym_vpn_lib::gateway_directory::Config {
             nyxd_url: self.network_env.nyxd_url(),
             api_url: self.network_env.api_url(),
@@ -309,9 +332,16 @@ impl NymVpnd for CommandInterface {
             min_gateway_performance,
             mix_score_thresholds,
             wg_score_thresholds,
+            custom_gateways,
         };
 
-        let gateways = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+        let role = match gw_type {
+            nym_vpn_lib::gateway_directory::GatewayType::MixnetEntry => Some(GatewayRole::Entry),
+            nym_vpn_lib::gateway_directory::GatewayType::MixnetExit => Some(GatewayRole::Exit),
+            nym_vpn_lib::gateway_directory::GatewayType::Wg => None,
+        };
+
+        let mut gateways = handler
             .handle_list_gateways(gw_type, user_agent, directory_config)
             .await
             .map_err(|err| {
@@ -319,6 +349,8 @@ impl NymVpnd for CommandInterface {
                 tracing::error!(msg);
                 tonic::Status::internal(msg)
             })?;
+        let favourites = handler.handle_list_favourites().await?;
+        annotate_gateways(&favourites, &mut gateways, role);
 
         let response = ListGatewaysResponse {
             gateways: gateways
@@ -388,6 +420,7 @@ impl NymVpnd for CommandInterface {
             min_gateway_performance,
             mix_score_thresholds,
             wg_score_thresholds,
+            custom_gateways: Vec::new(),
         };
 
         let countries = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
@@ -874,6 +907,118 @@ impl NymVpnd for CommandInterface {
         Ok(tonic::Response::new(response))
     }
 
+    async fn export_ticketbooks(
+        &self,
+        _request: tonic::Request<()>,
+    ) -> Result<tonic::Response<ExportTicketbooksResponse>, tonic::Status> {
+        tracing::debug!("Got export ticketbooks request");
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_export_ticketbooks()
+            .await
+            .map_err(|err| {
+                tracing::error!("Failed to export ticketbooks: {:?}", err);
+                tonic::Status::internal("Failed to export ticketbooks")
+            })?;
+
+        let response = match result {
+            Ok(bundle) => ExportTicketbooksResponse {
+                resp: Some(nym_vpn_proto::export_ticketbooks_response::Resp::Bundle(
+                    bundle,
+                )),
+            },
+            Err(err) => ExportTicketbooksResponse {
+                resp: Some(nym_vpn_proto::export_ticketbooks_response::Resp::Error(
+                    nym_vpn_proto::AccountError::from(err),
+                )),
+            },
+        };
+
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn confirm_ticketbooks_exported(
+        &self,
+        _request: tonic::Request<()>,
+    ) -> Result<tonic::Response<ConfirmTicketbooksExportedResponse>, tonic::Status> {
+        tracing::debug!("Got confirm ticketbooks exported request");
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_confirm_ticketbooks_exported()
+            .await?;
+
+        let response = match result {
+            Ok(()) => ConfirmTicketbooksExportedResponse { error: None },
+            Err(err) => ConfirmTicketbooksExportedResponse {
+                error: Some(AccountError::from(err)),
+            },
+        };
+
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn import_ticketbooks(
+        &self,
+        request: tonic::Request<ImportTicketbooksRequest>,
+    ) -> Result<tonic::Response<ImportTicketbooksResponse>, tonic::Status> {
+        tracing::debug!("Got import ticketbooks request");
+
+        let bundle = request.into_inner().bundle;
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_import_ticketbooks(bundle)
+            .await
+            .map_err(|err| {
+                tracing::error!("Failed to import ticketbooks: {:?}", err);
+                tonic::Status::internal("Failed to import ticketbooks")
+            })?;
+
+        let response = match result {
+            Ok(summary) => ImportTicketbooksResponse {
+                resp: Some(nym_vpn_proto::import_ticketbooks_response::Resp::Summary(
+                    into_proto_ticketbook_import_summary(summary),
+                )),
+            },
+            Err(err) => ImportTicketbooksResponse {
+                resp: Some(nym_vpn_proto::import_ticketbooks_response::Resp::Error(
+                    nym_vpn_proto::AccountError::from(err),
+                )),
+            },
+        };
+
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_credential_spend_history(
+        &self,
+        request: tonic::Request<GetCredentialSpendHistoryRequest>,
+    ) -> Result<tonic::Response<GetCredentialSpendHistoryResponse>, tonic::Status> {
+        tracing::debug!("Got get credential spend history request");
+
+        // Zero means no limit was given
+        let limit = Some(request.into_inner().limit).filter(|limit| *limit > 0);
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_get_credential_spend_history(limit)
+            .await
+            .map_err(|err| {
+                tracing::error!("Failed to get credential spend history: {:?}", err);
+                tonic::Status::internal("Failed to get credential spend history")
+            })?;
+
+        use nym_vpn_proto::get_credential_spend_history_response::{CredentialSpendHistory, Resp};
+        let resp = match result {
+            Ok(spends) => Resp::History(CredentialSpendHistory {
+                spends: spends
+                    .into_iter()
+                    .map(into_proto_credential_spend)
+                    .collect(),
+            }),
+            Err(err) => Resp::Error(nym_vpn_proto::AccountError::from(err)),
+        };
+        let response = GetCredentialSpendHistoryResponse { resp: Some(resp) };
+
+        Ok(tonic::Response::new(response))
+    }
+
     async fn delete_log_file(
         &self,
         _request: tonic::Request<()>,
@@ -918,6 +1063,296 @@ impl NymVpnd for CommandInterface {
         tracing::debug!("log dir path: {}", log_path.dir.display());
         Ok(tonic::Response::new(log_path.into()))
     }
+
+    async fn get_dns_status(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<GetDnsStatusResponse>, tonic::Status> {
+        let (status, verification) =
+            CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+                .handle_get_dns_status()
+                .await?;
+        Ok(tonic::Response::new(into_proto_dns_status(
+            status,
+            verification,
+        )))
+    }
+
+    async fn diagnose_leaks(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<DiagnoseLeaksResponse>, tonic::Status> {
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_diagnose_leaks()
+            .await?;
+
+        let response = match result {
+            Ok(report) => DiagnoseLeaksResponse {
+                resp: Some(nym_vpn_proto::diagnose_leaks_response::Resp::Report(
+                    into_proto_leak_report(report),
+                )),
+            },
+            Err(err) => DiagnoseLeaksResponse {
+                resp: Some(nym_vpn_proto::diagnose_leaks_response::Resp::Error(
+                    nym_vpn_proto::DiagnoseLeaksError {
+                        message: err.to_string(),
+                    },
+                )),
+            },
+        };
+
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_dns_filter_stats(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<GetDnsFilterStatsResponse>, tonic::Status> {
+        let stats = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_get_dns_filter_stats()
+            .await?;
+        Ok(tonic::Response::new(into_proto_dns_filter_stats(stats)))
+    }
+
+    async fn set_tunnel_routes(
+        &self,
+        request: tonic::Request<SetTunnelRoutesRequest>,
+    ) -> Result<tonic::Response<SetTunnelRoutesResponse>, tonic::Status> {
+        let routes = request
+            .into_inner()
+            .routes
+            .ok_or_else(|| tonic::Status::invalid_argument("Missing tunnel routes"))?;
+        let tunnel_routes = parse_tunnel_routes(routes)?;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_set_tunnel_routes(tunnel_routes)
+            .await?;
+
+        let response = SetTunnelRoutesResponse {
+            error: result.err().map(|err| nym_vpn_proto::SetTunnelRoutesError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning set tunnel routes response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_tunnel_routes(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<GetTunnelRoutesResponse>, tonic::Status> {
+        let tunnel_routes = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_get_tunnel_routes()
+            .await?;
+        Ok(tonic::Response::new(GetTunnelRoutesResponse {
+            routes: Some(into_proto_tunnel_routes(tunnel_routes)),
+        }))
+    }
+
+    async fn add_custom_gateway(
+        &self,
+        request: tonic::Request<AddCustomGatewayRequest>,
+    ) -> Result<tonic::Response<AddCustomGatewayResponse>, tonic::Status> {
+        let gateway = request
+            .into_inner()
+            .gateway
+            .ok_or_else(|| tonic::Status::invalid_argument("Missing gateway"))?;
+        let descriptor = parse_gateway_descriptor(gateway)?;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_add_custom_gateway(descriptor)
+            .await?;
+
+        let response = AddCustomGatewayResponse {
+            error: result.err().map(|err| nym_vpn_proto::CustomGatewayError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning add custom gateway response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn remove_custom_gateway(
+        &self,
+        request: tonic::Request<RemoveCustomGatewayRequest>,
+    ) -> Result<tonic::Response<RemoveCustomGatewayResponse>, tonic::Status> {
+        let identity = request.into_inner().identity;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_remove_custom_gateway(identity)
+            .await?;
+
+        let response = RemoveCustomGatewayResponse {
+            error: result.err().map(|err| nym_vpn_proto::CustomGatewayError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning remove custom gateway response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn list_custom_gateways(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<ListCustomGatewaysResponse>, tonic::Status> {
+        let gateways = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_list_custom_gateways()
+            .await?;
+        Ok(tonic::Response::new(ListCustomGatewaysResponse {
+            gateways: gateways
+                .into_iter()
+                .map(into_proto_gateway_descriptor)
+                .collect(),
+        }))
+    }
+
+    async fn probe_gateway(
+        &self,
+        request: tonic::Request<ProbeGatewayRequest>,
+    ) -> Result<tonic::Response<ProbeGatewayResponse>, tonic::Status> {
+        let identity = request.into_inner().identity;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_probe_gateway(identity)
+            .await?;
+
+        let response = match result {
+            Ok(health) => ProbeGatewayResponse {
+                health: Some(into_proto_gateway_health(health)),
+                error: None,
+            },
+            Err(err) => ProbeGatewayResponse {
+                health: None,
+                error: Some(nym_vpn_proto::ProbeGatewayError {
+                    message: err.to_string(),
+                }),
+            },
+        };
+        tracing::debug!("Returning probe gateway response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn set_favourite(
+        &self,
+        request: tonic::Request<SetFavouriteRequest>,
+    ) -> Result<tonic::Response<SetFavouriteResponse>, tonic::Status> {
+        let favourite = request
+            .into_inner()
+            .favourite
+            .ok_or_else(|| tonic::Status::invalid_argument("Missing favourite"))?;
+        let favourite = parse_favourite(favourite)?;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_set_favourite(favourite)
+            .await?;
+
+        let response = SetFavouriteResponse {
+            error: result.err().map(|err| nym_vpn_proto::FavouriteError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning set favourite response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn remove_favourite(
+        &self,
+        request: tonic::Request<RemoveFavouriteRequest>,
+    ) -> Result<tonic::Response<RemoveFavouriteResponse>, tonic::Status> {
+        let target = parse_favourite_target(request.into_inner().target)?;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_remove_favourite(target)
+            .await?;
+
+        let response = RemoveFavouriteResponse {
+            error: result.err().map(|err| nym_vpn_proto::FavouriteError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning remove favourite response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn list_favourites(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<ListFavouritesResponse>, tonic::Status> {
+        let favourites = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_list_favourites()
+            .await?;
+        Ok(tonic::Response::new(ListFavouritesResponse {
+            favourites: favourites.into_iter().map(into_proto_favourite).collect(),
+        }))
+    }
+
+    async fn set_network_rule(
+        &self,
+        request: tonic::Request<SetNetworkRuleRequest>,
+    ) -> Result<tonic::Response<SetNetworkRuleResponse>, tonic::Status> {
+        let rule = request
+            .into_inner()
+            .rule
+            .ok_or_else(|| tonic::Status::invalid_argument("Missing network rule"))?;
+        let rule = parse_network_rule(rule)?;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_set_network_rule(rule)
+            .await?;
+
+        let response = SetNetworkRuleResponse {
+            error: result.err().map(|err| nym_vpn_proto::NetworkRuleError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning set network rule response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn remove_network_rule(
+        &self,
+        request: tonic::Request<RemoveNetworkRuleRequest>,
+    ) -> Result<tonic::Response<RemoveNetworkRuleResponse>, tonic::Status> {
+        let name = request.into_inner().name;
+
+        let result = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_remove_network_rule(name)
+            .await?;
+
+        let response = RemoveNetworkRuleResponse {
+            error: result.err().map(|err| nym_vpn_proto::NetworkRuleError {
+                message: err.to_string(),
+            }),
+        };
+        tracing::debug!("Returning remove network rule response: {:?}", response);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn list_network_rules(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<ListNetworkRulesResponse>, tonic::Status> {
+        let rules = CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+            .handle_list_network_rules()
+            .await?;
+        Ok(tonic::Response::new(ListNetworkRulesResponse {
+            rules: rules.into_iter().map(into_proto_network_rule).collect(),
+        }))
+    }
+
+    async fn get_current_network(
+        &self,
+        _: tonic::Request<()>,
+    ) -> Result<tonic::Response<GetCurrentNetworkResponse>, tonic::Status> {
+        let (network, matching_rule) =
+            CommandInterfaceConnectionHandler::new(self.vpn_command_tx.clone())
+                .handle_get_current_network()
+                .await?;
+        Ok(tonic::Response::new(GetCurrentNetworkResponse {
+            network: network.map(into_proto_current_network),
+            matching_rule: matching_rule.map(into_proto_network_rule),
+        }))
+    }
 }
 
 impl TryFrom<ConnectRequest> for ConnectOptions {
@@ -957,6 +1392,19 @@ impl TryFrom<ConnectRequest> for ConnectOptions {
             .map(nym_vpn_lib::UserAgent::from)
             .or(Some(crate::util::construct_user_agent()));
 
+        let ip_family = match nym_vpn_proto::IpFamily::try_from(request.ip_family) {
+            Ok(nym_vpn_proto::IpFamily::DualStack) => IpFamily::DualStack,
+            Ok(nym_vpn_proto::IpFamily::Ipv4Only) => IpFamily::Ipv4Only,
+            Ok(nym_vpn_proto::IpFamily::Ipv6Preferred) => IpFamily::Ipv6Preferred,
+            Err(_) => return Err(CommandInterfaceError::InvalidIpFamily(request.ip_family)),
+        };
+
+        let wireguard_mtu = request
+            .wireguard_mtu
+            .map(parse_wireguard_mtu)
+            .transpose()?
+            .unwrap_or_default();
+
         Ok(ConnectOptions {
             dns,
             enable_two_hop: request.enable_two_hop,
@@ -968,10 +1416,40 @@ impl TryFrom<ConnectRequest> for ConnectOptions {
             min_gateway_mixnet_performance,
             min_gateway_vpn_performance,
             user_agent,
+            ip_family,
+            wireguard_mtu,
+            precheck: request.precheck,
         })
     }
 }
 
+fn parse_wireguard_mtu(
+    mtu: nym_vpn_proto::WireguardMtu,
+) -> Result<WireguardMtu, CommandInterfaceError> {
+    // Anything below the minimum IPv6 MTU can't carry the tunnel traffic
+    const MIN_MTU: u16 = 1280;
+    // Jumbo frames
+    const MAX_MTU: u16 = 9000;
+    let parse_mtu = |mtu: u32| {
+        u16::try_from(mtu)
+            .ok()
+            .filter(|mtu| (MIN_MTU..=MAX_MTU).contains(mtu))
+            .ok_or(CommandInterfaceError::InvalidMtu(mtu))
+    };
+
+    match (mtu.auto, mtu.entry, mtu.exit) {
+        (true, None, None) => Ok(WireguardMtu::Auto),
+        (true, _, _) => Err(CommandInterfaceError::ConflictingMtuOptions),
+        (false, None, None) => Ok(WireguardMtu::Default),
+        (false, entry, exit) => {
+            let entry = entry.map(parse_mtu).transpose()?;
+            let exit = exit.map(parse_mtu).transpose()?;
+            TwoHopMtu::with_overrides(entry, exit)?;
+            Ok(WireguardMtu::Manual { entry, exit })
+        }
+    }
+}
+
 impl From<LogPath> for GetLogPathResponse {
     fn from(log_path: LogPath) -> Self {
         GetLogPathResponse {
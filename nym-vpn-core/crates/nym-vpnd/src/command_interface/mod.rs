@@ -1,12 +1,16 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+mod authorization;
 mod config;
 mod connection_handler;
 mod error;
 mod helpers;
 mod listener;
 mod protobuf;
+mod remote;
 mod start;
 
-pub use start::start_command_interface;
+pub use authorization::{AuthorizationPolicy, Permission};
+pub use remote::generate_remote_token;
+pub use start::{start_command_interface, CommandInterfaceOptions};
@@ -2,8 +2,19 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use nym_credentials_interface::TicketType;
+use nym_vpn_account_controller::{CredentialSpend, CredentialSpendOutcome};
+use nym_vpn_lib::{
+    dns::{DnsStatus, DnsVerification},
+    gateway_directory::GatewayDescriptor,
+    tunnel_state_machine::{
+        DnsFilterStats, DnsLeakVerdict, DnsServerVerdict, IpLeakVerdict, LeakReport, TunnelRoutes,
+    },
+};
+use nym_vpnd_types::favourite::{FavouriteGateway, FavouriteTarget, GatewayRole};
 
-use crate::service::VpnServiceInfo;
+use crate::service::{
+    GatewayHealth, NetworkIdentity, NetworkKind, NetworkRule, NetworkRuleAction, VpnServiceInfo,
+};
 
 impl From<VpnServiceInfo> for nym_vpn_proto::InfoResponse {
     fn from(info: VpnServiceInfo) -> Self {
@@ -48,3 +59,282 @@ pub fn into_proto_available_tickets(
         vpn_exit_data_si: ticketbooks.remaining_data_si(TicketType::V1WireguardExit),
     }
 }
+
+pub fn into_proto_ticketbook_import_summary(
+    summary: nym_vpn_account_controller::TicketbookImportSummary,
+) -> nym_vpn_proto::import_ticketbooks_response::ImportSummary {
+    nym_vpn_proto::import_ticketbooks_response::ImportSummary {
+        imported: summary.imported,
+        already_present: summary.already_present,
+        expired: summary.expired,
+    }
+}
+
+pub fn into_proto_credential_spend(spend: CredentialSpend) -> nym_vpn_proto::CredentialSpend {
+    let (success, wasteful, error) = match spend.outcome {
+        CredentialSpendOutcome::Success => (true, false, None),
+        CredentialSpendOutcome::Failure { wasteful, error } => (false, wasteful, Some(error)),
+    };
+    nym_vpn_proto::CredentialSpend {
+        ticket_type: spend.ticket_type.to_string(),
+        gateway_id: spend.gateway_id,
+        timestamp: Some(
+            nym_vpn_proto::conversions::prost::offset_datetime_into_proto_timestamp(
+                spend.timestamp,
+            ),
+        ),
+        tickets: spend.tickets,
+        bytes_granted: spend.bytes_granted,
+        remaining_bandwidth: spend.remaining_bandwidth,
+        success,
+        wasteful,
+        error,
+    }
+}
+
+fn into_proto_dns_verification(verification: DnsVerification) -> nym_vpn_proto::DnsVerification {
+    use nym_vpn_proto::dns_verification::Result as VerificationResult;
+
+    match verification {
+        DnsVerification::Verified => nym_vpn_proto::DnsVerification {
+            result: VerificationResult::Verified as i32,
+            resolver_servers: Vec::new(),
+            details: None,
+        },
+        DnsVerification::Mismatch { resolver_servers } => nym_vpn_proto::DnsVerification {
+            result: VerificationResult::Mismatch as i32,
+            resolver_servers: resolver_servers.iter().map(ToString::to_string).collect(),
+            details: None,
+        },
+        DnsVerification::Inconclusive(details) => nym_vpn_proto::DnsVerification {
+            result: VerificationResult::Inconclusive as i32,
+            resolver_servers: Vec::new(),
+            details: Some(details),
+        },
+    }
+}
+
+pub fn into_proto_dns_status(
+    status: DnsStatus,
+    verification: DnsVerification,
+) -> nym_vpn_proto::GetDnsStatusResponse {
+    nym_vpn_proto::GetDnsStatusResponse {
+        configured_backend: status.configured_backend.to_string(),
+        active_backend: status.active_backend.map(|backend| backend.to_string()),
+        interface: status.interface,
+        servers: status.servers.iter().map(ToString::to_string).collect(),
+        verification: Some(into_proto_dns_verification(verification)),
+    }
+}
+
+pub fn into_proto_dns_filter_stats(
+    stats: Option<DnsFilterStats>,
+) -> nym_vpn_proto::GetDnsFilterStatsResponse {
+    let Some(stats) = stats else {
+        return nym_vpn_proto::GetDnsFilterStatsResponse::default();
+    };
+
+    nym_vpn_proto::GetDnsFilterStatsResponse {
+        enabled: true,
+        queries: stats.queries,
+        blocked: stats.blocked(),
+        allowlist_rules: stats.allowlist_rules as u64,
+        categories: stats
+            .categories
+            .into_iter()
+            .map(|category| nym_vpn_proto::DnsFilterCategoryStats {
+                category: category.category.to_string(),
+                enabled: category.enabled,
+                rules: category.rules as u64,
+                blocked: category.blocked,
+            })
+            .collect(),
+    }
+}
+
+pub fn into_proto_tunnel_routes(tunnel_routes: TunnelRoutes) -> nym_vpn_proto::TunnelRoutes {
+    use nym_vpn_proto::tunnel_routes::Mode;
+
+    let (mode, networks) = match tunnel_routes {
+        TunnelRoutes::All => (Mode::All, Vec::new()),
+        TunnelRoutes::Exclude(networks) => (Mode::Exclude, networks),
+        TunnelRoutes::IncludeOnly(networks) => (Mode::IncludeOnly, networks),
+    };
+
+    nym_vpn_proto::TunnelRoutes {
+        mode: mode as i32,
+        networks: networks.iter().map(ToString::to_string).collect(),
+    }
+}
+
+pub fn into_proto_gateway_descriptor(
+    descriptor: GatewayDescriptor,
+) -> nym_vpn_proto::GatewayDescriptor {
+    nym_vpn_proto::GatewayDescriptor {
+        identity: descriptor.identity,
+        moniker: descriptor.moniker,
+        host: descriptor.host,
+        ips: descriptor.ips.iter().map(ToString::to_string).collect(),
+        authenticator_address: descriptor.authenticator_address,
+        ipr_address: descriptor.ipr_address,
+        clients_ws_port: descriptor.clients_ws_port.map(u32::from),
+        clients_wss_port: descriptor.clients_wss_port.map(u32::from),
+        country: descriptor.country,
+        version: descriptor.version,
+    }
+}
+
+pub fn into_proto_favourite(favourite: FavouriteGateway) -> nym_vpn_proto::FavouriteGateway {
+    use nym_vpn_proto::favourite_target::Target;
+
+    let target = match favourite.target {
+        FavouriteTarget::Gateway(identity) => Target::GatewayId(identity),
+        FavouriteTarget::Country(code) => Target::CountryCode(code),
+    };
+    let pinned_role = match favourite.pinned_role {
+        None => nym_vpn_proto::GatewayRole::Unspecified,
+        Some(GatewayRole::Entry) => nym_vpn_proto::GatewayRole::Entry,
+        Some(GatewayRole::Exit) => nym_vpn_proto::GatewayRole::Exit,
+    };
+    nym_vpn_proto::FavouriteGateway {
+        target: Some(nym_vpn_proto::FavouriteTarget {
+            target: Some(target),
+        }),
+        label: favourite.label,
+        note: favourite.note,
+        pinned_role: pinned_role as i32,
+        blocked: favourite.blocked,
+    }
+}
+
+fn into_proto_network_kind(kind: Option<NetworkKind>) -> nym_vpn_proto::NetworkKind {
+    match kind {
+        None => nym_vpn_proto::NetworkKind::Unspecified,
+        Some(NetworkKind::Wifi) => nym_vpn_proto::NetworkKind::Wifi,
+        Some(NetworkKind::Wired) => nym_vpn_proto::NetworkKind::Wired,
+        Some(NetworkKind::Other) => nym_vpn_proto::NetworkKind::Other,
+    }
+}
+
+pub fn into_proto_network_rule(rule: NetworkRule) -> nym_vpn_proto::NetworkRule {
+    let action = match rule.action {
+        NetworkRuleAction::Connect => nym_vpn_proto::NetworkRuleAction::Connect,
+        NetworkRuleAction::Disconnect => nym_vpn_proto::NetworkRuleAction::Disconnect,
+    };
+    nym_vpn_proto::NetworkRule {
+        name: rule.name,
+        action: action as i32,
+        kind: into_proto_network_kind(rule.kind) as i32,
+        ssid: rule.ssid,
+        interface: rule.interface,
+        subnet: rule.subnet.map(|subnet| subnet.to_string()),
+        gateway_mac: rule.gateway_mac,
+    }
+}
+
+pub fn into_proto_current_network(network: NetworkIdentity) -> nym_vpn_proto::CurrentNetwork {
+    nym_vpn_proto::CurrentNetwork {
+        kind: into_proto_network_kind(Some(network.kind)) as i32,
+        interface: network.interface,
+        ssid: network.ssid,
+        addresses: network
+            .addresses
+            .iter()
+            .map(|address| address.to_string())
+            .collect(),
+        gateway_mac: network.gateway_mac,
+    }
+}
+
+pub fn into_proto_gateway_health(health: GatewayHealth) -> nym_vpn_proto::GatewayHealth {
+    nym_vpn_proto::GatewayHealth {
+        can_connect: health.can_connect,
+        can_connect_ipr: health.can_connect_ipr,
+        can_register_wireguard: health.can_register_wireguard,
+    }
+}
+
+pub fn into_proto_leak_report(report: LeakReport) -> nym_vpn_proto::LeakReport {
+    use nym_vpn_proto::{
+        dns_leak_report::Verdict as DnsVerdict, dns_server_check::Verdict as ServerVerdict,
+        ip_leak_report::Verdict,
+    };
+
+    let leaking = report.is_leaking();
+    let (dns_verdict, dns_details) = match report.dns.verdict() {
+        DnsLeakVerdict::Tunnel => (DnsVerdict::Tunnel, None),
+        DnsLeakVerdict::Leak => (DnsVerdict::Leak, None),
+        DnsLeakVerdict::Inconclusive(details) => (DnsVerdict::Inconclusive, Some(details)),
+    };
+
+    let queries = report
+        .dns
+        .queries
+        .into_iter()
+        .map(|query| {
+            let (addresses, error) = match query.result {
+                Ok(addresses) => (addresses.iter().map(ToString::to_string).collect(), None),
+                Err(error) => (Vec::new(), Some(error)),
+            };
+            nym_vpn_proto::DnsQueryCheck {
+                hostname: query.hostname,
+                addresses,
+                error,
+            }
+        })
+        .collect();
+
+    let servers = report
+        .dns
+        .servers
+        .into_iter()
+        .map(|server| {
+            let (verdict, details) = match server.verdict {
+                DnsServerVerdict::Tunnel => (ServerVerdict::Tunnel, None),
+                DnsServerVerdict::AllowedOutsideTunnel => {
+                    (ServerVerdict::AllowedOutsideTunnel, None)
+                }
+                DnsServerVerdict::Leak => (ServerVerdict::Leak, None),
+                DnsServerVerdict::Inconclusive(details) => {
+                    (ServerVerdict::Inconclusive, Some(details))
+                }
+            };
+            nym_vpn_proto::DnsServerCheck {
+                server: server.server.to_string(),
+                interface: server.interface,
+                verdict: verdict as i32,
+                details,
+            }
+        })
+        .collect();
+
+    let (verdict, details) = match report.ip.verdict {
+        IpLeakVerdict::Exit => (Verdict::Exit, None),
+        IpLeakVerdict::Leak => (Verdict::Leak, None),
+        IpLeakVerdict::Inconclusive(details) => (Verdict::Inconclusive, Some(details)),
+    };
+
+    nym_vpn_proto::LeakReport {
+        leaking,
+        dns: Some(nym_vpn_proto::DnsLeakReport {
+            tunnel_interface: report.dns.tunnel_interface,
+            tunnel_servers: report
+                .dns
+                .tunnel_servers
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            resolver: Some(into_proto_dns_verification(report.dns.resolver)),
+            queries,
+            servers,
+            verdict: dns_verdict as i32,
+            details: dns_details,
+        }),
+        ip: Some(nym_vpn_proto::IpLeakReport {
+            exit_ips: report.ip.exit_ips.iter().map(ToString::to_string).collect(),
+            observed_ip: report.ip.observed_ip.map(|ip| ip.to_string()),
+            verdict: verdict as i32,
+            details,
+        }),
+    }
+}
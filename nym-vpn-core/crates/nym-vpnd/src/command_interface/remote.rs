@@ -0,0 +1,317 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Authentication of clients connecting to the command interface over TCP.
+//!
+//! Remote clients either present a TLS client certificate signed by the configured CA, or a bearer
+//! token generated by the daemon. Without TLS the listener is restricted to loopback addresses.
+//! Authenticated remote clients are subject to the same authorization policy as the socket, and
+//! as they have no peer credentials they are limited to read-only calls once it is enforced.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::{self, Either, Ready};
+use rand::RngCore;
+use tonic::{
+    body::BoxBody,
+    transport::{Certificate, Identity, ServerTlsConfig},
+};
+use tower::{Layer, Service};
+use zeroize::Zeroizing;
+
+use super::error::RemoteListenerError;
+use crate::config::RemoteManagementConfig;
+
+const REMOTE_TOKEN_FILE: &str = "remote-token";
+const TOKEN_LENGTH: usize = 32;
+
+pub fn remote_token_path() -> PathBuf {
+    crate::service::config_dir().join(REMOTE_TOKEN_FILE)
+}
+
+/// Generates a new bearer token for remote clients, replacing the previous one.
+pub fn generate_remote_token() -> Result<(PathBuf, Zeroizing<String>), RemoteListenerError> {
+    let mut bytes = Zeroizing::new([0u8; TOKEN_LENGTH]);
+    rand::thread_rng().fill_bytes(&mut bytes[..]);
+    let token = Zeroizing::new(hex::encode(&bytes[..]));
+
+    let path = remote_token_path();
+    write_secret_file(&path, token.as_bytes()).map_err(|source| {
+        RemoteListenerError::WriteFile {
+            path: path.clone(),
+            source,
+        }
+    })?;
+
+    Ok((path, token))
+}
+
+fn write_secret_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    // The mode above only applies when the file is created
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, RemoteListenerError> {
+    fs::read(path).map_err(|source| RemoteListenerError::ReadFile {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// How remote clients are authenticated, derived from the remote management config.
+pub(super) struct RemoteAuthentication {
+    pub(super) tls: Option<ServerTlsConfig>,
+    pub(super) token: Option<Zeroizing<String>>,
+}
+
+impl RemoteAuthentication {
+    pub(super) fn from_config(
+        config: &RemoteManagementConfig,
+    ) -> Result<Self, RemoteListenerError> {
+        let identity = match (&config.tls_certificate, &config.tls_key) {
+            (Some(cert), Some(key)) => Some(Identity::from_pem(read_file(cert)?, read_file(key)?)),
+            (None, None) => None,
+            _ => return Err(RemoteListenerError::IncompleteTlsIdentity),
+        };
+
+        match (&config.client_ca_certificate, identity) {
+            // Client certificates are verified during the TLS handshake
+            (Some(client_ca), Some(identity)) => {
+                let tls = ServerTlsConfig::new()
+                    .identity(identity)
+                    .client_ca_root(Certificate::from_pem(read_file(client_ca)?));
+                Ok(Self {
+                    tls: Some(tls),
+                    token: None,
+                })
+            }
+            (Some(_), None) => Err(RemoteListenerError::MutualTlsWithoutIdentity),
+            (None, identity) => {
+                if identity.is_none() && !config.listen_address.ip().is_loopback() {
+                    return Err(RemoteListenerError::PlainTextOnNonLoopback {
+                        listen_address: config.listen_address,
+                    });
+                }
+
+                let path = remote_token_path();
+                let token = match fs::read_to_string(&path) {
+                    Ok(token) => Zeroizing::new(token.trim().to_owned()),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                        return Err(RemoteListenerError::MissingToken { path })
+                    }
+                    Err(source) => return Err(RemoteListenerError::ReadFile { path, source }),
+                };
+                if token.is_empty() {
+                    return Err(RemoteListenerError::MissingToken { path });
+                }
+
+                Ok(Self {
+                    tls: identity.map(|identity| ServerTlsConfig::new().identity(identity)),
+                    token: Some(token),
+                })
+            }
+        }
+    }
+}
+
+/// Layer rejecting requests without the expected bearer token.
+#[derive(Clone)]
+pub(super) struct BearerTokenLayer {
+    token: Option<Arc<Zeroizing<String>>>,
+}
+
+impl BearerTokenLayer {
+    pub(super) fn new(token: Option<Zeroizing<String>>) -> Self {
+        Self {
+            token: token.map(Arc::new),
+        }
+    }
+}
+
+impl<S> Layer<S> for BearerTokenLayer {
+    type Service = BearerToken<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BearerToken {
+            inner,
+            token: self.token.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct BearerToken<S> {
+    inner: S,
+    token: Option<Arc<Zeroizing<String>>>,
+}
+
+impl<S, ReqBody> Service<http::Request<ReqBody>> for BearerToken<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Self::Response, Self::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        let Some(expected) = self.token.as_deref() else {
+            return Either::Left(self.inner.call(req));
+        };
+
+        let presented = req
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        match presented {
+            Some(presented) if constant_time_eq(presented.as_bytes(), expected.as_bytes()) => {
+                Either::Left(self.inner.call(req))
+            }
+            _ => {
+                tracing::warn!("Rejected remote request to {}", req.uri().path());
+                let status = tonic::Status::unauthenticated("missing or invalid bearer token");
+                Either::Right(future::ready(Ok(status.into_http())))
+            }
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        net::{Ipv4Addr, SocketAddr},
+    };
+
+    use super::*;
+
+    const TOKEN: &str = "0123456789abcdef";
+
+    // Inner service standing in for the command interface
+    struct Accept;
+
+    impl Service<http::Request<()>> for Accept {
+        type Response = http::Response<BoxBody>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: http::Request<()>) -> Self::Future {
+            future::ready(Ok(http::Response::new(tonic::body::empty_body())))
+        }
+    }
+
+    async fn call_with_authorization(authorization: Option<&str>) -> Option<tonic::Code> {
+        let mut service =
+            BearerTokenLayer::new(Some(Zeroizing::new(TOKEN.to_owned()))).layer(Accept);
+        let mut request = http::Request::builder().uri("/nym.vpn.NymVpnd/VpnConnect");
+        if let Some(authorization) = authorization {
+            request = request.header(http::header::AUTHORIZATION, authorization);
+        }
+        let response = service.call(request.body(()).unwrap()).await.unwrap();
+        tonic::Status::from_header_map(response.headers()).map(|status| status.code())
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_bearer_token() {
+        let authorization = format!("Bearer {TOKEN}");
+        assert_eq!(call_with_authorization(Some(&authorization)).await, None);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_bearer_token() {
+        for authorization in [None, Some("Bearer wrong"), Some(TOKEN), Some("Basic 0123")] {
+            assert_eq!(
+                call_with_authorization(authorization).await,
+                Some(tonic::Code::Unauthenticated),
+                "{authorization:?}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn secret_file_is_private_when_overwritten() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(REMOTE_TOKEN_FILE);
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_secret_file(&path, b"new").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn refuses_plain_text_on_non_loopback_address() {
+        let listen_address = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 53181);
+        let config = RemoteManagementConfig {
+            listen_address,
+            ..Default::default()
+        };
+        assert!(matches!(
+            RemoteAuthentication::from_config(&config),
+            Err(RemoteListenerError::PlainTextOnNonLoopback { listen_address: address })
+                if address == listen_address
+        ));
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokex"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(!constant_time_eq(b"", b"token"));
+    }
+
+    #[test]
+    fn rejects_incomplete_tls_identity() {
+        let config = RemoteManagementConfig {
+            tls_certificate: Some(PathBuf::from("/nonexistent/cert.pem")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            RemoteAuthentication::from_config(&config),
+            Err(RemoteListenerError::IncompleteTlsIdentity)
+        ));
+    }
+}
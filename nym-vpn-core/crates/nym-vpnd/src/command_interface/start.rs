@@ -16,12 +16,25 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 use tonic::transport::Server;
 
-use super::{config::default_socket_path, listener::CommandInterface};
-use crate::service::VpnServiceCommand;
+use super::{
+    authorization::{AuthorizationLayer, AuthorizationPolicy},
+    config::default_socket_path,
+    error::RemoteListenerError,
+    listener::CommandInterface,
+    remote::{BearerTokenLayer, RemoteAuthentication},
+};
+use crate::{config::RemoteManagementConfig, service::VpnServiceCommand};
 
 // If the shutdown signal is received, we give the listeners a little extra time to finish
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
 
+/// Options controlling who can reach the command interface.
+#[derive(Debug, Clone, Default)]
+pub struct CommandInterfaceOptions {
+    pub authorization: AuthorizationPolicy,
+    pub remote_management: Option<RemoteManagementConfig>,
+}
+
 fn grpc_span(req: &http::Request<()>) -> tracing::Span {
     let service = req.uri().path().trim_start_matches('/');
     let method = service.split('/').next_back().unwrap_or(service);
@@ -36,6 +49,7 @@ async fn run_socket_listener(
     socket_path: PathBuf,
     shutdown_token: CancellationToken,
     network_env: Network,
+    authorization: AuthorizationPolicy,
 ) -> Result<(), tonic::transport::Error> {
     let command_interface = CommandInterface::new(vpn_command_tx, tunnel_event_rx, network_env);
 
@@ -43,20 +57,62 @@ async fn run_socket_listener(
     #[cfg(unix)]
     remove_previous_socket_file(&socket_path).await;
     tracing::info!("Starting socket listener on: {}", socket_path.display());
+    #[cfg(unix)]
+    if !authorization.is_enforced() {
+        tracing::warn!(
+            "No control_group configured, every local user can control the tunnel and manage the account"
+        );
+    }
 
     // Wrap the unix socket into a stream that can be used by tonic
     let incoming = nym_ipc::server::create_incoming(socket_path).unwrap();
 
     Server::builder()
         .trace_fn(grpc_span)
+        .layer(AuthorizationLayer::new(authorization))
         .add_service(NymVpndServer::new(command_interface))
         .serve_with_incoming_shutdown(incoming, shutdown_token.cancelled_owned())
         .await
 }
 
+async fn run_tcp_listener(
+    vpn_command_tx: UnboundedSender<VpnServiceCommand>,
+    tunnel_event_rx: broadcast::Receiver<TunnelEvent>,
+    config: RemoteManagementConfig,
+    shutdown_token: CancellationToken,
+    network_env: Network,
+) -> Result<(), RemoteListenerError> {
+    let authentication = RemoteAuthentication::from_config(&config)?;
+    let command_interface = CommandInterface::new(vpn_command_tx, tunnel_event_rx, network_env);
+
+    tracing::info!(
+        "Starting remote management listener on: {} (tls: {}, bearer token: {}, permission: {})",
+        config.listen_address,
+        authentication.tls.is_some(),
+        authentication.token.is_some(),
+        config.permission,
+    );
+
+    let mut builder = Server::builder().trace_fn(grpc_span);
+    if let Some(tls) = authentication.tls {
+        builder = builder.tls_config(tls)?;
+    }
+
+    builder
+        .layer(BearerTokenLayer::new(authentication.token))
+        .layer(AuthorizationLayer::new(AuthorizationPolicy::remote(
+            config.permission,
+        )))
+        .add_service(NymVpndServer::new(command_interface))
+        .serve_with_shutdown(config.listen_address, shutdown_token.cancelled_owned())
+        .await
+        .map_err(Into::into)
+}
+
 pub fn start_command_interface(
     tunnel_event_rx: broadcast::Receiver<TunnelEvent>,
     network_env: Network,
+    options: CommandInterfaceOptions,
     shutdown_token: CancellationToken,
 ) -> (JoinHandle<()>, UnboundedReceiver<VpnServiceCommand>) {
     tracing::debug!("Starting command interface");
@@ -64,6 +120,31 @@ pub fn start_command_interface(
     let (vpn_command_tx, vpn_command_rx) = mpsc::unbounded_channel();
 
     let handle = tokio::spawn(async move {
+        let tcp_listener_handle = options.remote_management.map(|config| {
+            let vpn_command_tx = vpn_command_tx.clone();
+            let tunnel_event_rx = tunnel_event_rx.resubscribe();
+            let network_env = network_env.clone();
+            let child_token = shutdown_token.child_token();
+            tokio::spawn(async move {
+                match run_tcp_listener(
+                    vpn_command_tx,
+                    tunnel_event_rx,
+                    config,
+                    child_token,
+                    network_env,
+                )
+                .await
+                {
+                    Ok(()) => {
+                        tracing::info!("Remote management listener has finished");
+                    }
+                    Err(e) => {
+                        tracing::error!("Remote management listener exited with error: {}", e);
+                    }
+                }
+            })
+        });
+
         let child_token = shutdown_token.child_token();
         let socket_listener_handle = tokio::spawn(async move {
             match run_socket_listener(
@@ -72,6 +153,7 @@ pub fn start_command_interface(
                 default_socket_path(),
                 child_token,
                 network_env.clone(),
+                options.authorization,
             )
             .await
             {
@@ -91,6 +173,16 @@ pub fn start_command_interface(
             Err(_) => tracing::warn!("Socket listener did not finish in time"),
         }
 
+        if let Some(tcp_listener_handle) = tcp_listener_handle {
+            match tokio::time::timeout(SHUTDOWN_TIMEOUT, tcp_listener_handle).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    tracing::error!("Failed to join on remote management listener: {}", e)
+                }
+                Err(_) => tracing::warn!("Remote management listener did not finish in time"),
+            }
+        }
+
         tracing::info!("Command interface exiting");
     });
 
@@ -1,22 +1,305 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use nym_vpn_lib::nym_config::defaults::NymNetworkDetails;
+use std::{
+    net::{Ipv6Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use ipnetwork::IpNetwork;
+use nym_vpn_lib::{
+    dns::{DnsBackend, DomainRoute},
+    gateway_directory::GatewayDescriptor,
+    nym_config::defaults::NymNetworkDetails,
+    tunnel_state_machine::{DnsFilter, FilterCategory, TunnelRoutes},
+};
+use nym_vpnd_types::favourite::FavouriteGateway;
+use url::Url;
+
+use crate::{
+    command_interface::{AuthorizationPolicy, CommandInterfaceOptions, Permission},
+    service::NetworkRule,
+};
+
+const DEFAULT_REMOTE_MANAGEMENT_PORT: u16 = 53181;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct GlobalConfigFile {
     pub network_name: String,
+
+    /// Restricts control of the tunnel over the command interface to root and the members of
+    /// this group. Unset by default, which leaves the command interface open to all local users.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_group: Option<String>,
+
+    /// Optional TCP listener for managing the daemon over the network.
+    #[serde(default)]
+    pub remote_management: RemoteManagementConfig,
+
+    /// The mechanism used to manage the system DNS: auto, systemd-resolved, network-manager,
+    /// resolvconf or static-file. Only has an effect on Linux.
+    #[serde(default)]
+    pub dns_backend: DnsBackend,
+
+    /// Service asked for the address traffic is seen coming from by `nym-vpnc diagnose leaks`.
+    /// It must reply with the address as plain text. Defaults to https://api.ipify.org.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leak_test_ip_url: Option<Url>,
+
+    /// Filtering of DNS queries by the local resolver. Only has an effect on Linux and macOS.
+    #[serde(default)]
+    pub dns_filter: DnsFilterConfig,
+
+    /// Domains resolved by their own servers outside the tunnel, e.g. a corporate resolver on
+    /// the LAN. Only has an effect on Linux and macOS.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns_routes: Vec<DomainRoute>,
+
+    /// Destinations routed through the tunnel. Only has an effect on Linux and macOS.
+    #[serde(default)]
+    pub tunnel_routes: TunnelRoutesConfig,
+
+    /// Gateways which aren't listed in the directory, e.g. self-hosted nym-nodes, available in
+    /// WireGuard mode. Managed with `nym-vpnc add-custom-gateway`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_gateways: Vec<GatewayDescriptor>,
+
+    /// Gateways and countries marked as favourite or blocked, flagged in the gateway lists.
+    /// Managed with `nym-vpnc set-favourite`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub favourites: Vec<FavouriteGateway>,
+
+    /// Rules connecting or disconnecting when the host joins a matching network, evaluated in
+    /// order. Managed with `nym-vpnc set-network-rule`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_rules: Vec<NetworkRule>,
+
+    /// Harbour master whose probe results are used to skip gateways which aren't fully
+    /// operational, e.g. https://harbourmaster.nymtech.net. Gateways are selected from the
+    /// directory alone when unset or unreachable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub harbour_master_url: Option<String>,
+
+    /// Connect when the daemon starts, once the host is online and the account is ready, with
+    /// the gateways and options of the last connection.
+    #[serde(default)]
+    pub auto_connect: bool,
 }
 
 impl Default for GlobalConfigFile {
     fn default() -> Self {
         Self {
             network_name: NymNetworkDetails::default().network_name,
+            control_group: None,
+            remote_management: RemoteManagementConfig::default(),
+            dns_backend: DnsBackend::default(),
+            leak_test_ip_url: None,
+            dns_filter: DnsFilterConfig::default(),
+            dns_routes: Vec::new(),
+            tunnel_routes: TunnelRoutesConfig::default(),
+            custom_gateways: Vec::new(),
+            favourites: Vec::new(),
+            network_rules: Vec::new(),
+            harbour_master_url: None,
+            auto_connect: false,
         }
     }
 }
 
+/// Configuration of the TCP listener serving the command interface.
+///
+/// Clients have to authenticate either with a TLS client certificate signed by
+/// `client_ca_certificate`, or with the bearer token generated by
+/// `nym-vpnd --generate-remote-token` when no client CA is configured. Without TLS the listener
+/// only starts on a loopback address. Authenticated clients are granted `permission`, regardless
+/// of `control_group`.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RemoteManagementConfig {
+    pub enabled: bool,
+    pub listen_address: SocketAddr,
+
+    /// PEM encoded server certificate, enables TLS together with `tls_key`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_certificate: Option<PathBuf>,
+
+    /// PEM encoded private key of the server certificate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM encoded CA used to verify client certificates, enables mutual TLS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ca_certificate: Option<PathBuf>,
+
+    /// Permission granted to authenticated clients: read-only, control or admin. Defaults to
+    /// control, which allows connecting and disconnecting but not managing the account.
+    pub permission: Permission,
+}
+
+impl Default for RemoteManagementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: SocketAddr::new(
+                Ipv6Addr::LOCALHOST.into(),
+                DEFAULT_REMOTE_MANAGEMENT_PORT,
+            ),
+            tls_certificate: None,
+            tls_key: None,
+            client_ca_certificate: None,
+            permission: Permission::Control,
+        }
+    }
+}
+
+/// Configuration of the DNS filter.
+///
+/// Queries for domains on the blocklists get an empty response, everything else is forwarded to
+/// the tunnel DNS. Blocklists are read once when the daemon starts.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DnsFilterConfig {
+    pub enabled: bool,
+
+    /// Blocklists in hosts-file, AdGuard or plain domain list format.
+    pub blocklists: Vec<BlocklistConfig>,
+
+    /// Domains which are never blocked, including their subdomains.
+    pub allowlist: Vec<String>,
+
+    /// Whether the blocklists of each category are applied.
+    pub categories: FilterCategoriesConfig,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BlocklistConfig {
+    pub path: PathBuf,
+
+    /// One of ads, trackers, malware or custom.
+    #[serde(default = "default_blocklist_category")]
+    pub category: String,
+}
+
+fn default_blocklist_category() -> String {
+    FilterCategory::Custom.to_string()
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FilterCategoriesConfig {
+    pub ads: bool,
+    pub trackers: bool,
+    pub malware: bool,
+    pub custom: bool,
+}
+
+impl Default for FilterCategoriesConfig {
+    fn default() -> Self {
+        Self {
+            ads: true,
+            trackers: true,
+            malware: true,
+            custom: true,
+        }
+    }
+}
+
+impl DnsFilterConfig {
+    /// Loads the blocklists. Returns `None` when filtering is disabled. Lists which can't be
+    /// read are skipped.
+    pub fn build(&self) -> Option<Arc<DnsFilter>> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut filter = DnsFilter::new();
+        filter.set_category_enabled(FilterCategory::Ads, self.categories.ads);
+        filter.set_category_enabled(FilterCategory::Trackers, self.categories.trackers);
+        filter.set_category_enabled(FilterCategory::Malware, self.categories.malware);
+        filter.set_category_enabled(FilterCategory::Custom, self.categories.custom);
+
+        for blocklist in &self.blocklists {
+            let category = match blocklist.category.parse::<FilterCategory>() {
+                Ok(category) => category,
+                Err(err) => {
+                    tracing::warn!("Skipping {}: {err}", blocklist.path.display());
+                    continue;
+                }
+            };
+            match filter.load_blocklist(category, &blocklist.path) {
+                Ok(rules) => tracing::info!(
+                    "Loaded {rules} rules from {} ({category})",
+                    blocklist.path.display()
+                ),
+                Err(err) => tracing::error!(
+                    "Failed to read blocklist {}: {err}",
+                    blocklist.path.display()
+                ),
+            }
+        }
+
+        for domain in &self.allowlist {
+            filter.allow(domain);
+        }
+
+        Some(Arc::new(filter))
+    }
+}
+
+/// Configuration of the destinations routed through the tunnel, set with `nym-vpnc set-routes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TunnelRoutesConfig {
+    pub mode: TunnelRoutesMode,
+
+    /// Networks excluded from the tunnel, or the only networks routed through it.
+    pub networks: Vec<IpNetwork>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TunnelRoutesMode {
+    #[default]
+    All,
+    Exclude,
+    IncludeOnly,
+}
+
+impl From<&TunnelRoutesConfig> for TunnelRoutes {
+    fn from(config: &TunnelRoutesConfig) -> Self {
+        match config.mode {
+            TunnelRoutesMode::All => TunnelRoutes::All,
+            TunnelRoutesMode::Exclude => TunnelRoutes::Exclude(config.networks.clone()),
+            TunnelRoutesMode::IncludeOnly => TunnelRoutes::IncludeOnly(config.networks.clone()),
+        }
+    }
+}
+
+impl From<&TunnelRoutes> for TunnelRoutesConfig {
+    fn from(tunnel_routes: &TunnelRoutes) -> Self {
+        let (mode, networks) = match tunnel_routes {
+            TunnelRoutes::All => (TunnelRoutesMode::All, Vec::new()),
+            TunnelRoutes::Exclude(networks) => (TunnelRoutesMode::Exclude, networks.clone()),
+            TunnelRoutes::IncludeOnly(networks) => {
+                (TunnelRoutesMode::IncludeOnly, networks.clone())
+            }
+        };
+        Self { mode, networks }
+    }
+}
+
 impl GlobalConfigFile {
+    pub fn command_interface_options(&self) -> CommandInterfaceOptions {
+        CommandInterfaceOptions {
+            authorization: AuthorizationPolicy::new(self.control_group.clone()),
+            remote_management: self
+                .remote_management
+                .enabled
+                .then(|| self.remote_management.clone()),
+        }
+    }
+
     pub fn read_from_file() -> anyhow::Result<Self> {
         let global_config_file_path =
             crate::service::config_dir().join(crate::service::DEFAULT_GLOBAL_CONFIG_FILE);
@@ -27,6 +27,9 @@ fn main() -> anyhow::Result<()> {
 #[cfg(unix)]
 fn run() -> anyhow::Result<()> {
     let args = CliArgs::parse();
+    if args.command.generate_remote_token {
+        return generate_remote_token();
+    }
 
     let options = logging::Options {
         verbosity_level: args.verbosity_level(),
@@ -63,6 +66,8 @@ fn run() -> anyhow::Result<()> {
         );
         service::windows_service::start_service()?;
         Ok(())
+    } else if args.command.generate_remote_token {
+        generate_remote_token()
     } else if args.command.run_as_service {
         // TODO: enable this through setting or flag
         // println!("Configuring logging source...");
@@ -93,6 +98,13 @@ fn run() -> anyhow::Result<()> {
     }
 }
 
+fn generate_remote_token() -> anyhow::Result<()> {
+    let (path, token) = command_interface::generate_remote_token()?;
+    println!("Remote management token written to {}", path.display());
+    println!("{}", token.as_str());
+    Ok(())
+}
+
 fn setup_global_config(network: Option<&str>) -> anyhow::Result<GlobalConfigFile> {
     let mut global_config_file = GlobalConfigFile::read_from_file()?;
     if let Some(network) = network {
@@ -111,12 +123,13 @@ fn run_inner(
         let network_env =
             environment::setup_environment(&global_config_file, args.config_env_file.as_deref())
                 .await?;
-        run_inner_async(args, network_env, logging_setup).await
+        run_inner_async(args, global_config_file, network_env, logging_setup).await
     })
 }
 
 async fn run_inner_async(
     args: CliArgs,
+    global_config_file: GlobalConfigFile,
     network_env: Network,
     logging_setup: Option<LoggingSetup>,
 ) -> anyhow::Result<()> {
@@ -143,6 +156,7 @@ async fn run_inner_async(
     let (command_handle, vpn_command_rx) = command_interface::start_command_interface(
         tunnel_event_rx,
         network_env.clone(),
+        global_config_file.command_interface_options(),
         shutdown_token.child_token(),
     );
 
@@ -159,6 +173,7 @@ async fn run_inner_async(
         network_env,
         user_agent,
         log_path,
+        global_config_file,
     );
 
     let mut shutdown_join_set = shutdown_handler::install(shutdown_token);
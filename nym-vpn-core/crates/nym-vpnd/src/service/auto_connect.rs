@@ -0,0 +1,174 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{future::Future, time::Duration};
+
+use nym_vpn_account_controller::AccountControllerCommander;
+use nym_vpn_lib_types::{AccountCommandError, TunnelState};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+/// Time after which waiting for the account to be ready counts as a failed attempt.
+const ACCOUNT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay before the first retry, doubled after each failed attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Waits until the host is online and the account is ready to connect, then signals `ready_tx`.
+/// Nothing is signalled when there is no account to connect with.
+pub(super) fn spawn(
+    ready_tx: mpsc::UnboundedSender<()>,
+    tunnel_state: watch::Receiver<TunnelState>,
+    account_command_tx: AccountControllerCommander,
+    credential_mode: bool,
+    shutdown_token: CancellationToken,
+) {
+    tokio::spawn(async move {
+        tokio::select! {
+            ready = wait_until_ready(tunnel_state, || {
+                let account_command_tx = account_command_tx.clone();
+                async move {
+                    account_command_tx
+                        .wait_for_account_ready_to_connect(credential_mode)
+                        .await
+                }
+            }) => {
+                if ready {
+                    let _ = ready_tx.send(());
+                }
+            }
+            _ = shutdown_token.cancelled() => {}
+        }
+    });
+}
+
+async fn wait_until_ready<F, Fut>(
+    mut tunnel_state: watch::Receiver<TunnelState>,
+    mut account_ready: F,
+) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), AccountCommandError>>,
+{
+    let mut retry_delay = INITIAL_RETRY_DELAY;
+    loop {
+        // The state machine stays offline until the offline monitor reports connectivity
+        if tunnel_state
+            .wait_for(|state| !matches!(state, TunnelState::Offline { .. }))
+            .await
+            .is_err()
+        {
+            return false;
+        }
+
+        let result = tokio::time::timeout(ACCOUNT_READY_TIMEOUT, account_ready()).await;
+        match result {
+            Ok(Ok(())) => return true,
+            Ok(Err(AccountCommandError::NoAccountStored)) => {
+                tracing::info!("Not connecting on start, no account stored");
+                return false;
+            }
+            Ok(Err(err)) => {
+                tracing::warn!(
+                    "Account not ready to connect on start, retrying in {}s: {err}",
+                    retry_delay.as_secs()
+                );
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Timed out waiting for the account to be ready, retrying in {}s",
+                    retry_delay.as_secs()
+                );
+            }
+        }
+        tokio::time::sleep(retry_delay).await;
+        retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_with_doubling_delay() {
+        let (_state_tx, state_rx) = watch::channel(TunnelState::Disconnected);
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+
+        let ready = wait_until_ready(state_rx, || {
+            let attempts = attempts.clone();
+            async move {
+                let mut attempts = attempts.lock().unwrap();
+                attempts.push(Instant::now());
+                if attempts.len() < 9 {
+                    Err(AccountCommandError::Internal("not ready".to_owned()))
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await;
+        assert!(ready);
+
+        let attempts = attempts.lock().unwrap();
+        let delays = attempts
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [5, 10, 20, 40, 80, 160, 300, 300]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn counts_timeouts_as_failed_attempts() {
+        let (_state_tx, state_rx) = watch::channel(TunnelState::Disconnected);
+        let start = Instant::now();
+        let mut attempts = 0;
+
+        let ready = wait_until_ready(state_rx, || {
+            attempts += 1;
+            let first = attempts == 1;
+            async move {
+                if first {
+                    std::future::pending::<()>().await;
+                }
+                Ok(())
+            }
+        })
+        .await;
+        assert!(ready);
+        assert_eq!(start.elapsed(), ACCOUNT_READY_TIMEOUT + INITIAL_RETRY_DELAY);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_without_an_account() {
+        let (_state_tx, state_rx) = watch::channel(TunnelState::Disconnected);
+
+        let ready = wait_until_ready(state_rx, || async {
+            Err(AccountCommandError::NoAccountStored)
+        })
+        .await;
+        assert!(!ready);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_until_online() {
+        let (state_tx, state_rx) = watch::channel(TunnelState::Offline { reconnect: false });
+        let start = Instant::now();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            state_tx.send_replace(TunnelState::Disconnected);
+            state_tx.closed().await;
+        });
+
+        let ready = wait_until_ready(state_rx, || async { Ok(()) }).await;
+        assert!(ready);
+        assert_eq!(start.elapsed(), Duration::from_secs(30));
+    }
+}
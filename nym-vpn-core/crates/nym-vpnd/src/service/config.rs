@@ -8,6 +8,8 @@ use std::{fmt, fs, path::PathBuf};
 use nym_vpn_lib::gateway_directory;
 use serde::{de::DeserializeOwned, Serialize};
 
+use super::vpn_service::ConnectOptions;
+
 #[cfg(not(windows))]
 const DEFAULT_DATA_DIR: &str = "/var/lib/nym-vpnd";
 #[cfg(not(windows))]
@@ -134,6 +136,9 @@ pub enum ConfigSetupError {
 pub struct NymVpnServiceConfig {
     pub(super) entry_point: gateway_directory::EntryPoint,
     pub(super) exit_point: gateway_directory::ExitPoint,
+    /// Options of the last connection, reused when connecting on start or by a network rule.
+    #[serde(default)]
+    pub(super) connect_options: ConnectOptions,
 }
 
 impl fmt::Display for NymVpnServiceConfig {
@@ -151,6 +156,7 @@ impl Default for NymVpnServiceConfig {
         Self {
             entry_point: gateway_directory::EntryPoint::Random,
             exit_point: gateway_directory::ExitPoint::Random,
+            connect_options: ConnectOptions::default(),
         }
     }
 }
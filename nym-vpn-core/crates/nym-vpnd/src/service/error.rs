@@ -3,6 +3,7 @@
 
 use nym_vpn_lib::tunnel_state_machine::Error as TunnelStateMachineError;
 use nym_vpn_lib_types::AccountCommandError;
+use nym_vpnd_types::favourite::FavouriteTarget;
 use tokio::sync::{mpsc::error::SendError, oneshot::error::RecvError};
 use tracing::error;
 
@@ -133,6 +134,99 @@ pub enum SetNetworkError {
     NetworkNotFound(String),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum SetTunnelRoutesError {
+    #[error("failed to read config")]
+    ReadConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("failed to write config")]
+    WriteConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CustomGatewayError {
+    #[error("invalid gateway: {0}")]
+    InvalidGateway(#[source] nym_vpn_lib::gateway_directory::Error),
+
+    #[error("no custom gateway with identity {0}")]
+    NotFound(String),
+
+    #[error("failed to read config")]
+    ReadConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("failed to write config")]
+    WriteConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FavouriteError {
+    #[error("no favourite for {0}")]
+    NotFound(FavouriteTarget),
+
+    #[error("failed to read config")]
+    ReadConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("failed to write config")]
+    WriteConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkRuleError {
+    #[error("no network rule named {0}")]
+    NotFound(String),
+
+    #[error("failed to read config")]
+    ReadConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("failed to write config")]
+    WriteConfig {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkIdentityError {
+    #[cfg(target_os = "linux")]
+    #[error("failed to query NetworkManager")]
+    NetworkManager(#[from] nym_dbus::network_manager::Error),
+
+    #[cfg(any(target_os = "macos", windows))]
+    #[error("failed to read the routing table")]
+    Routing(#[from] nym_routing::Error),
+
+    #[error("network identification task failed")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProbeGatewayError {
+    #[error("invalid gateway identity: {0}")]
+    InvalidIdentity(String),
+
+    #[error("failed to lookup gateways: {0}")]
+    LookupGateways(#[source] nym_vpn_lib::gateway_directory::Error),
+
+    #[error("no gateway with identity {0}")]
+    NotFound(String),
+
+    #[error("probe failed: {0}")]
+    Probe(String),
+}
+
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum VpnServiceDeleteLogFileError {
     #[error("internal error: {0}")]
@@ -0,0 +1,272 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt, time::Duration};
+
+use nym_gateway_probe::{CredentialArgs, Entry, NetstackArgs, Probe, ProbeOutcome, TestedNode};
+use nym_vpn_lib::{
+    gateway_directory::{self, EntryPoint, Gateway, GatewayClient, GatewayList},
+    tunnel_state_machine::{GatewayPrecheck, GatewayPrecheckError},
+    UserAgent,
+};
+use nym_vpn_lib_types::TunnelType;
+
+use super::error::ProbeGatewayError;
+
+/// Time after which a single probe is abandoned.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Outcome of probing a gateway. Checks that weren't run are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayHealth {
+    /// A mixnet client could connect through the entry gateway.
+    pub can_connect: bool,
+
+    /// The ip packet router of the gateway accepted a connection.
+    pub can_connect_ipr: Option<bool>,
+
+    /// The authenticator of the gateway registered a WireGuard peer.
+    pub can_register_wireguard: Option<bool>,
+}
+
+impl GatewayHealth {
+    fn new(gateway: &Gateway, outcome: &ProbeOutcome) -> Self {
+        let can_connect = match &outcome.as_entry {
+            Entry::Tested(entry) => entry.can_connect,
+            Entry::NotTested => true,
+            Entry::EntryFailure => false,
+        };
+
+        Self {
+            can_connect,
+            can_connect_ipr: outcome
+                .as_exit
+                .as_ref()
+                .filter(|_| gateway.has_ipr_address())
+                .map(|exit| exit.can_connect),
+            can_register_wireguard: outcome
+                .wg
+                .as_ref()
+                .filter(|_| can_connect && gateway.has_authenticator_address())
+                .map(|wg| wg.can_register),
+        }
+    }
+
+    fn failure(&self) -> Option<&'static str> {
+        if !self.can_connect {
+            Some("mixnet client could not connect")
+        } else if self.can_connect_ipr == Some(false) {
+            Some("ip packet router did not accept the connection")
+        } else if self.can_register_wireguard == Some(false) {
+            Some("authenticator did not register the WireGuard peer")
+        } else {
+            None
+        }
+    }
+}
+
+/// Lightweight gateway health check built on the gateway probe.
+///
+/// The probe is built without its netstack checks, since they would link a second Go runtime into
+/// the daemon. WireGuard gateways are therefore checked up to the registration with their
+/// authenticator, without a handshake. The probes never use credentials, so that checking gateways
+/// doesn't acquire or spend ticketbooks of the account.
+#[derive(Clone)]
+pub(crate) struct GatewayProber {
+    gateway_config: gateway_directory::Config,
+}
+
+impl fmt::Debug for GatewayProber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GatewayProber")
+            .field("gateway_config", &self.gateway_config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GatewayProber {
+    pub(crate) fn new(gateway_config: gateway_directory::Config) -> Self {
+        Self { gateway_config }
+    }
+
+    /// Probe the gateway with the given identity as its own entry, running every check.
+    pub(crate) async fn probe_gateway(
+        &self,
+        identity: &str,
+        custom_gateways: Vec<Gateway>,
+        user_agent: UserAgent,
+    ) -> Result<GatewayHealth, ProbeGatewayError> {
+        let gateway = match custom_gateways
+            .into_iter()
+            .find(|gateway| gateway.identity().to_base58_string() == identity)
+        {
+            Some(gateway) => gateway,
+            None => {
+                let identity = gateway_directory::NodeIdentity::from_base58_string(identity)
+                    .map_err(|_| ProbeGatewayError::InvalidIdentity(identity.to_owned()))?;
+                GatewayClient::new(self.gateway_config.clone(), user_agent)
+                    .map_err(ProbeGatewayError::LookupGateways)?
+                    .lookup_all_gateways()
+                    .await
+                    .map_err(ProbeGatewayError::LookupGateways)?
+                    .gateway_with_identity(&identity)
+                    .cloned()
+                    .ok_or_else(|| ProbeGatewayError::NotFound(identity.to_base58_string()))?
+            }
+        };
+
+        self.probe(&gateway, &gateway, None)
+            .await
+            .map_err(ProbeGatewayError::Probe)
+    }
+
+    /// Probe `gateway` through `entry`. `tunnel_type` limits the probe to the checks needed by
+    /// that tunnel type.
+    async fn probe(
+        &self,
+        entry: &Gateway,
+        gateway: &Gateway,
+        tunnel_type: Option<TunnelType>,
+    ) -> Result<GatewayHealth, String> {
+        let tested_node = if entry.identity() == gateway.identity() {
+            TestedNode::SameAsEntry
+        } else {
+            TestedNode::Node(Box::new(gateway.clone()))
+        };
+        let mut probe = Probe::new(
+            EntryPoint::Gateway {
+                identity: entry.identity(),
+            },
+            tested_node,
+            NetstackArgs::default(),
+            CredentialArgs::default(),
+        );
+        if tunnel_type == Some(TunnelType::Mixnet) {
+            probe.without_wireguard();
+        }
+        let only_wireguard = tunnel_type == Some(TunnelType::Wireguard);
+
+        tracing::info!(
+            "Probing gateway {} via entry {}",
+            gateway.identity(),
+            entry.identity()
+        );
+        let gateways = GatewayList::new(vec![entry.clone()]);
+        let result = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            probe.probe_with_gateways(
+                self.gateway_config.clone(),
+                &gateways,
+                false,
+                only_wireguard,
+            ),
+        )
+        .await
+        .map_err(|_| "timed out".to_owned())?
+        .map_err(|err| err.to_string())?;
+
+        let health = GatewayHealth::new(gateway, &result.outcome);
+        tracing::info!("Gateway {} probed: {health:?}", gateway.identity());
+        Ok(health)
+    }
+}
+
+#[tonic::async_trait]
+impl GatewayPrecheck for GatewayProber {
+    async fn check(
+        &self,
+        tunnel_type: TunnelType,
+        entry: &Gateway,
+        exit: &Gateway,
+    ) -> Result<(), GatewayPrecheckError> {
+        let entry_failure = |reason: &str| GatewayPrecheckError::Entry {
+            identity: entry.identity().to_base58_string(),
+            reason: reason.to_owned(),
+        };
+        let exit_failure = |reason: &str| GatewayPrecheckError::Exit {
+            identity: exit.identity().to_base58_string(),
+            reason: reason.to_owned(),
+        };
+
+        // The entry has to register a WireGuard peer of its own in two-hop mode.
+        if tunnel_type == TunnelType::Wireguard {
+            let health = self
+                .probe(entry, entry, Some(tunnel_type))
+                .await
+                .map_err(|err| entry_failure(&err))?;
+            if let Some(reason) = health.failure() {
+                return Err(entry_failure(reason));
+            }
+        }
+
+        let health = self
+            .probe(entry, exit, Some(tunnel_type))
+            .await
+            .map_err(|err| exit_failure(&err))?;
+        if !health.can_connect {
+            return Err(entry_failure("mixnet client could not connect"));
+        }
+        match health.failure() {
+            Some(reason) => Err(exit_failure(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nym_gateway_directory::test_utils::{self, ADDRESS, IDENTITY};
+    use nym_gateway_probe::{EntryTestResult, Exit, WgProbeResults};
+
+    use super::*;
+
+    fn gateway(ipr: bool) -> Gateway {
+        Gateway::try_from(gateway_directory::GatewayDescriptor {
+            ipr_address: ipr.then(|| ADDRESS.to_owned()),
+            ..test_utils::descriptor(IDENTITY)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn health_from_outcome() {
+        let outcome = ProbeOutcome {
+            as_entry: EntryTestResult {
+                can_connect: true,
+                can_route: true,
+            }
+            .into(),
+            as_exit: Some(Exit::fail_to_connect()),
+            wg: Some(WgProbeResults {
+                can_register: true,
+                ..Default::default()
+            }),
+        };
+
+        let health = GatewayHealth::new(&gateway(true), &outcome);
+        assert_eq!(health.can_connect_ipr, Some(false));
+        assert_eq!(health.can_register_wireguard, Some(true));
+        assert_eq!(
+            health.failure(),
+            Some("ip packet router did not accept the connection")
+        );
+
+        let health = GatewayHealth::new(&gateway(false), &outcome);
+        assert_eq!(health.can_connect_ipr, None);
+        assert_eq!(health.failure(), None);
+    }
+
+    #[test]
+    fn entry_failure_skips_other_checks() {
+        let outcome = ProbeOutcome {
+            as_entry: Entry::EntryFailure,
+            as_exit: None,
+            wg: Some(WgProbeResults::default()),
+        };
+
+        let health = GatewayHealth::new(&gateway(true), &outcome);
+        assert!(!health.can_connect);
+        assert_eq!(health.can_register_wireguard, None);
+        assert_eq!(health.failure(), Some("mixnet client could not connect"));
+    }
+}
@@ -1,8 +1,12 @@
 // Copyright 2024 - Nym Technologies SA <contact@nymtech.net>
 // SPDX-License-Identifier: GPL-3.0-only
 
+mod auto_connect;
 mod config;
 mod error;
+mod gateway_probe;
+mod network_identity;
+mod network_rules;
 mod vpn_service;
 #[cfg(windows)]
 pub mod windows_service;
@@ -12,9 +16,13 @@ pub use config::{
     DEFAULT_GLOBAL_CONFIG_FILE, DEFAULT_LOG_FILE,
 };
 pub use error::{
-    AccountError, SetNetworkError, VpnServiceConnectError, VpnServiceDeleteLogFileError,
+    AccountError, CustomGatewayError, FavouriteError, NetworkRuleError, ProbeGatewayError,
+    SetNetworkError, SetTunnelRoutesError, VpnServiceConnectError, VpnServiceDeleteLogFileError,
     VpnServiceDisconnectError,
 };
+pub use gateway_probe::GatewayHealth;
+pub use network_identity::{format_mac_address, NetworkIdentity, NetworkKind};
+pub use network_rules::{NetworkRule, NetworkRuleAction};
 pub use vpn_service::{
     ConnectArgs, ConnectOptions, NymVpnService, VpnServiceCommand, VpnServiceInfo,
 };
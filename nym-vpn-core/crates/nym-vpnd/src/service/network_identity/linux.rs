@@ -0,0 +1,55 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::Arc;
+
+use ipnetwork::IpNetwork;
+use nym_dbus::network_manager::{self, NetworkManager};
+
+use super::{NetworkIdentity, NetworkIdentityError, NetworkKind};
+
+const NM_CONNECTION_TYPE_WIFI: &str = "802-11-wireless";
+const NM_CONNECTION_TYPE_ETHERNET: &str = "802-3-ethernet";
+
+pub struct NetworkIdentifier {
+    // Kept for the lifetime of the monitor so polling reuses the same D-Bus connection.
+    network_manager: Arc<NetworkManager>,
+}
+
+impl NetworkIdentifier {
+    pub async fn new() -> Result<Self, NetworkIdentityError> {
+        let network_manager = tokio::task::spawn_blocking(|| {
+            let network_manager = NetworkManager::new()?;
+            network_manager.ensure_network_manager_exists()?;
+            network_manager::Result::Ok(network_manager)
+        })
+        .await??;
+        Ok(Self {
+            network_manager: Arc::new(network_manager),
+        })
+    }
+
+    pub async fn current(&mut self) -> Result<Option<NetworkIdentity>, NetworkIdentityError> {
+        let network_manager = self.network_manager.clone();
+        let network =
+            tokio::task::spawn_blocking(move || network_manager.active_network()).await??;
+
+        Ok(network.map(|network| NetworkIdentity {
+            kind: match network.connection_type.as_str() {
+                NM_CONNECTION_TYPE_WIFI => NetworkKind::Wifi,
+                NM_CONNECTION_TYPE_ETHERNET => NetworkKind::Wired,
+                _ => NetworkKind::Other,
+            },
+            interface: Some(network.interface),
+            ssid: network.ssid,
+            addresses: network
+                .addresses
+                .into_iter()
+                .filter_map(|(address, prefix)| IpNetwork::new(address, prefix).ok())
+                .collect(),
+            gateway_mac: None,
+        }))
+    }
+
+    pub async fn stop(self) {}
+}
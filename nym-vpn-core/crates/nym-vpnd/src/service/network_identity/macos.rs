@@ -0,0 +1,45 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use ipnetwork::IpNetwork;
+use nym_routing::RouteManagerHandle;
+
+use super::{format_mac_address, NetworkIdentity, NetworkIdentityError, NetworkKind};
+
+pub struct NetworkIdentifier {
+    // Only used to read the default routes, it never applies any route.
+    route_manager: RouteManagerHandle,
+}
+
+impl NetworkIdentifier {
+    pub async fn new() -> Result<Self, NetworkIdentityError> {
+        Ok(Self {
+            route_manager: RouteManagerHandle::spawn().await?,
+        })
+    }
+
+    pub async fn current(&mut self) -> Result<Option<NetworkIdentity>, NetworkIdentityError> {
+        let (v4_route, v6_route) = self.route_manager.get_default_routes().await?;
+        let (v4_gateway, v6_gateway) = self.route_manager.get_default_gateway().await?;
+
+        let (route, gateway) = match (v4_route, v6_route) {
+            (Some(route), _) => (route, v4_gateway),
+            (None, Some(route)) => (route, v6_gateway),
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(NetworkIdentity {
+            // The interface type isn't known from the routing table.
+            kind: NetworkKind::Other,
+            interface: Some(route.interface),
+            ssid: None,
+            addresses: vec![IpNetwork::from(route.ip)],
+            gateway_mac: gateway
+                .map(|gateway| format_mac_address(gateway.mac_address.into_bytes())),
+        }))
+    }
+
+    pub async fn stop(self) {
+        self.route_manager.stop().await;
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Identification of the network the host is connected to, for the network rules.
+//!
+//! NetworkManager describes the active connection on Linux. Elsewhere the network is identified
+//! by the default route, its interface and the MAC address of its gateway.
+
+use std::{fmt, time::Duration};
+
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use tokio::{sync::mpsc, time::MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+use super::error::NetworkIdentityError;
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod imp;
+
+#[cfg(target_os = "macos")]
+#[path = "macos.rs"]
+mod imp;
+
+#[cfg(windows)]
+#[path = "windows.rs"]
+mod imp;
+
+/// There is no common notification for all the properties, so the network is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkKind {
+    Wifi,
+    Wired,
+    Other,
+}
+
+/// The network the host is connected to, outside of the tunnel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkIdentity {
+    pub kind: NetworkKind,
+    pub interface: Option<String>,
+    /// SSID of Wi-Fi networks, only known on Linux.
+    pub ssid: Option<String>,
+    pub addresses: Vec<IpNetwork>,
+    /// MAC address of the default gateway, only known on macOS and Windows.
+    pub gateway_mac: Option<String>,
+}
+
+impl NetworkIdentity {
+    /// Whether both describe the same network, regardless of the addresses the host got on it.
+    pub fn is_same_network(&self, other: &NetworkIdentity) -> bool {
+        self.kind == other.kind
+            && self.interface == other.interface
+            && self.ssid == other.ssid
+            && self.gateway_mac == other.gateway_mac
+    }
+}
+
+/// Format a MAC address the way network rules store them, e.g. `3c:22:fb:00:11:22`.
+pub fn format_mac_address(bytes: [u8; 6]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Spawn a task identifying the network the host is connected to, sending it whenever it
+/// changes. `None` is sent when the host isn't connected to any network.
+pub fn spawn_network_monitor(
+    shutdown_token: CancellationToken,
+) -> mpsc::UnboundedReceiver<Option<NetworkIdentity>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut identifier = match imp::NetworkIdentifier::new().await {
+            Ok(identifier) => identifier,
+            Err(err) => {
+                tracing::warn!(
+                    "Network identification is unavailable, network rules are disabled: {err}"
+                );
+                return;
+            }
+        };
+
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut current = None;
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => break,
+                _ = interval.tick() => {
+                    match identifier.current().await {
+                        Ok(network) if current.as_ref() != Some(&network) => {
+                            current = Some(network.clone());
+                            if tx.send(network).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(err) => tracing::debug!("Failed to identify the network: {err}"),
+                    }
+                }
+            }
+        }
+        identifier.stop().await;
+    });
+    rx
+}
+
+impl fmt::Display for NetworkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkKind::Wifi => write!(f, "wifi"),
+            NetworkKind::Wired => write!(f, "wired"),
+            NetworkKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl fmt::Display for NetworkIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(interface) = &self.interface {
+            write!(f, " on {interface}")?;
+        }
+        if let Some(ssid) = &self.ssid {
+            write!(f, ", ssid {ssid}")?;
+        }
+        if let Some(mac) = &self.gateway_mac {
+            write!(f, ", gateway {mac}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use ipnetwork::IpNetwork;
+use nym_routing::{get_best_default_route, get_gateway_mac_address, InterfaceAndGateway};
+use nym_windows::net::{
+    alias_from_luid, get_unicast_table, try_socketaddr_from_inet_sockaddr, AddressFamily,
+};
+use windows::Win32::NetworkManagement::IpHelper::{
+    GetIfEntry2, IF_TYPE_ETHERNET_CSMACD, IF_TYPE_IEEE80211, MIB_IF_ROW2,
+};
+
+use super::{format_mac_address, NetworkIdentity, NetworkIdentityError, NetworkKind};
+
+pub struct NetworkIdentifier;
+
+impl NetworkIdentifier {
+    pub async fn new() -> Result<Self, NetworkIdentityError> {
+        Ok(Self)
+    }
+
+    pub async fn current(&mut self) -> Result<Option<NetworkIdentity>, NetworkIdentityError> {
+        tokio::task::spawn_blocking(identify).await?
+    }
+
+    pub async fn stop(self) {}
+}
+
+fn identify() -> Result<Option<NetworkIdentity>, NetworkIdentityError> {
+    let route = match get_best_default_route(AddressFamily::Ipv4)? {
+        Some(route) => route,
+        None => match get_best_default_route(AddressFamily::Ipv6)? {
+            Some(route) => route,
+            None => return Ok(None),
+        },
+    };
+
+    let gateway_mac = get_gateway_mac_address(&route)
+        .inspect_err(|err| tracing::debug!("Failed to get the gateway MAC address: {err}"))
+        .ok()
+        .flatten()
+        .map(format_mac_address);
+
+    Ok(Some(NetworkIdentity {
+        kind: interface_kind(&route),
+        interface: alias_from_luid(&route.iface)
+            .ok()
+            .map(|alias| alias.to_string_lossy().into_owned()),
+        ssid: None,
+        addresses: interface_addresses(&route),
+        gateway_mac,
+    }))
+}
+
+fn interface_kind(route: &InterfaceAndGateway) -> NetworkKind {
+    // SAFETY: We are allowed to initialize MIB_IF_ROW2 with zeroed because it is made up entirely
+    // of types for which the zero pattern (all zeros) is valid.
+    let mut row: MIB_IF_ROW2 = unsafe { std::mem::zeroed() };
+    row.InterfaceLuid = route.iface;
+
+    // SAFETY: GetIfEntry2 reads the interface LUID set above and populates the rest of the row.
+    if unsafe { GetIfEntry2(&mut row) }.ok().is_err() {
+        return NetworkKind::Other;
+    }
+    match row.Type {
+        IF_TYPE_IEEE80211 => NetworkKind::Wifi,
+        IF_TYPE_ETHERNET_CSMACD => NetworkKind::Wired,
+        _ => NetworkKind::Other,
+    }
+}
+
+fn interface_addresses(route: &InterfaceAndGateway) -> Vec<IpNetwork> {
+    let rows = get_unicast_table(None)
+        .inspect_err(|err| tracing::debug!("Failed to get the interface addresses: {err}"))
+        .unwrap_or_default();
+    rows.into_iter()
+        // SAFETY: Both variants of the LUID union are a u64.
+        .filter(|row| unsafe { row.InterfaceLuid.Value == route.iface.Value })
+        .filter_map(|row| {
+            let address = try_socketaddr_from_inet_sockaddr(row.Address).ok()?.ip();
+            IpNetwork::new(address, row.OnLinkPrefixLength).ok()
+        })
+        .collect()
+}
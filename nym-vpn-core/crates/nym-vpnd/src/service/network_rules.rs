@@ -0,0 +1,213 @@
+// Copyright 2025 - Nym Technologies SA <contact@nymtech.net>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt;
+
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+
+use super::network_identity::{NetworkIdentity, NetworkKind};
+
+/// What to do when the host joins a network matching a rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkRuleAction {
+    /// Connect unless already connected, for untrusted networks.
+    Connect,
+    /// Disconnect unless already disconnected, for trusted networks.
+    Disconnect,
+}
+
+/// Rule matching the networks which fulfil all its conditions. A rule without conditions
+/// matches any network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkRule {
+    pub name: String,
+    pub action: NetworkRuleAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<NetworkKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// Matches when one of the addresses of the interface is in the subnet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subnet: Option<IpNetwork>,
+    /// Lowercase and colon separated, e.g. `3c:22:fb:00:11:22`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_mac: Option<String>,
+}
+
+impl NetworkRule {
+    pub fn matches(&self, network: &NetworkIdentity) -> bool {
+        self.kind.is_none_or(|kind| network.kind == kind)
+            && self
+                .ssid
+                .as_ref()
+                .is_none_or(|ssid| network.ssid.as_ref() == Some(ssid))
+            && self
+                .interface
+                .as_ref()
+                .is_none_or(|interface| network.interface.as_ref() == Some(interface))
+            && self.subnet.is_none_or(|subnet| {
+                network
+                    .addresses
+                    .iter()
+                    .any(|address| subnet.contains(address.ip()))
+            })
+            && self
+                .gateway_mac
+                .as_ref()
+                .is_none_or(|mac| network.gateway_mac.as_ref() == Some(mac))
+    }
+}
+
+/// The rules are evaluated in order and the first matching one applies.
+pub fn first_match<'a>(
+    rules: &'a [NetworkRule],
+    network: &NetworkIdentity,
+) -> Option<&'a NetworkRule> {
+    rules.iter().find(|rule| rule.matches(network))
+}
+
+impl fmt::Display for NetworkRuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkRuleAction::Connect => write!(f, "connect"),
+            NetworkRuleAction::Disconnect => write!(f, "disconnect"),
+        }
+    }
+}
+
+impl fmt::Display for NetworkRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conditions = [
+            self.kind.map(|kind| kind.to_string()),
+            self.ssid.as_ref().map(|ssid| format!("ssid {ssid}")),
+            self.interface
+                .as_ref()
+                .map(|interface| format!("interface {interface}")),
+            self.subnet.map(|subnet| format!("subnet {subnet}")),
+            self.gateway_mac
+                .as_ref()
+                .map(|mac| format!("gateway {mac}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        if conditions.is_empty() {
+            write!(f, "{}: {} on any network", self.name, self.action)
+        } else {
+            write!(
+                f,
+                "{}: {} on {}",
+                self.name,
+                self.action,
+                conditions.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, action: NetworkRuleAction) -> NetworkRule {
+        NetworkRule {
+            name: name.to_owned(),
+            action,
+            kind: None,
+            ssid: None,
+            interface: None,
+            subnet: None,
+            gateway_mac: None,
+        }
+    }
+
+    fn wifi(ssid: &str) -> NetworkIdentity {
+        NetworkIdentity {
+            kind: NetworkKind::Wifi,
+            interface: Some("wlan0".to_owned()),
+            ssid: Some(ssid.to_owned()),
+            addresses: vec!["192.168.1.20/24".parse().unwrap()],
+            gateway_mac: None,
+        }
+    }
+
+    #[test]
+    fn any_wifi_except_trusted_ssid() {
+        let rules = vec![
+            NetworkRule {
+                ssid: Some("HomeNet".to_owned()),
+                ..rule("home", NetworkRuleAction::Disconnect)
+            },
+            NetworkRule {
+                kind: Some(NetworkKind::Wifi),
+                ..rule("wifi", NetworkRuleAction::Connect)
+            },
+        ];
+
+        let matched = first_match(&rules, &wifi("HomeNet")).unwrap();
+        assert_eq!(matched.name, "home");
+        let matched = first_match(&rules, &wifi("CoffeeShop")).unwrap();
+        assert_eq!(matched.name, "wifi");
+
+        let wired = NetworkIdentity {
+            kind: NetworkKind::Wired,
+            ssid: None,
+            ..wifi("")
+        };
+        assert!(first_match(&rules, &wired).is_none());
+    }
+
+    #[test]
+    fn interface_and_subnet() {
+        let office = NetworkRule {
+            interface: Some("eth0".to_owned()),
+            subnet: Some("10.1.0.0/16".parse().unwrap()),
+            ..rule("office", NetworkRuleAction::Disconnect)
+        };
+        let network = NetworkIdentity {
+            kind: NetworkKind::Wired,
+            interface: Some("eth0".to_owned()),
+            ssid: None,
+            addresses: vec![
+                "fe80::1/64".parse().unwrap(),
+                "10.1.4.2/16".parse().unwrap(),
+            ],
+            gateway_mac: None,
+        };
+        assert!(office.matches(&network));
+
+        let other_subnet = NetworkIdentity {
+            addresses: vec!["10.2.4.2/16".parse().unwrap()],
+            ..network.clone()
+        };
+        assert!(!office.matches(&other_subnet));
+
+        let other_interface = NetworkIdentity {
+            interface: Some("eth1".to_owned()),
+            ..network
+        };
+        assert!(!office.matches(&other_interface));
+    }
+
+    #[test]
+    fn new_addresses_keep_the_network() {
+        let renewed = NetworkIdentity {
+            addresses: vec!["10.1.4.7/16".parse().unwrap()],
+            ..wifi("Office")
+        };
+        assert!(wifi("Office").is_same_network(&renewed));
+        assert!(!wifi("Office").is_same_network(&wifi("Guest")));
+
+        // but they can still change the matching rule
+        let rules = vec![NetworkRule {
+            subnet: Some("10.1.0.0/16".parse().unwrap()),
+            ..rule("office", NetworkRuleAction::Disconnect)
+        }];
+        assert!(first_match(&rules, &wifi("Office")).is_none());
+        assert!(first_match(&rules, &renewed).is_some());
+    }
+}
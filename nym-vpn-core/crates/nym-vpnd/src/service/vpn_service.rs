@@ -14,7 +14,7 @@ use tokio_util::sync::CancellationToken;
 
 use nym_vpn_account_controller::{
     AccountCommand, AccountController, AccountControllerCommander, AccountStateSummary,
-    AvailableTicketbooks, SharedAccountState,
+    AvailableTicketbooks, CredentialSpend, SharedAccountState, TicketbookImportSummary,
 };
 use nym_vpn_api_client::{
     response::{NymVpnDevice, NymVpnUsage},
@@ -22,10 +22,12 @@ use nym_vpn_api_client::{
     NetworkCompatibility,
 };
 use nym_vpn_lib::{
-    gateway_directory::{self, EntryPoint, ExitPoint},
+    dns::{DnsStatus, DnsVerification},
+    gateway_directory::{self, EntryPoint, ExitPoint, Gateway, GatewayDescriptor},
     tunnel_state_machine::{
-        DnsOptions, GatewayPerformanceOptions, MixnetTunnelOptions, NymConfig, TunnelCommand,
-        TunnelSettings, TunnelStateMachine, WireguardMultihopMode, WireguardTunnelOptions,
+        DnsFilter, DnsFilterStats, DnsOptions, GatewayPerformanceOptions, IpFamily, LeakReport,
+        LeakTestError, MixnetTunnelOptions, NymConfig, TunnelCommand, TunnelRoutes, TunnelSettings,
+        TunnelStateMachine, WireguardMtu, WireguardMultihopMode, WireguardTunnelOptions,
     },
     MixnetClientConfig, Recipient, UserAgent,
 };
@@ -33,14 +35,23 @@ use nym_vpn_lib_types::{TunnelEvent, TunnelState, TunnelType};
 use nym_vpn_network_config::{
     FeatureFlags, Network, NymNetwork, NymVpnNetwork, ParsedAccountLinks, SystemMessages,
 };
+use nym_vpnd_types::favourite::{FavouriteGateway, FavouriteTarget};
 use zeroize::Zeroizing;
 
 use super::{
+    auto_connect,
     config::{NetworkEnvironments, NymVpnServiceConfig, DEFAULT_CONFIG_FILE},
-    error::{AccountError, Error, Result, SetNetworkError, VpnServiceDeleteLogFileError},
+    error::{
+        AccountError, CustomGatewayError, Error, FavouriteError, NetworkRuleError,
+        ProbeGatewayError, Result, SetNetworkError, SetTunnelRoutesError,
+        VpnServiceDeleteLogFileError,
+    },
+    gateway_probe::{GatewayHealth, GatewayProber},
+    network_identity::{self, NetworkIdentity},
+    network_rules::{self, NetworkRule, NetworkRuleAction},
     VpnServiceConnectError, VpnServiceDisconnectError,
 };
-use crate::config::GlobalConfigFile;
+use crate::config::{GlobalConfigFile, TunnelRoutesConfig};
 use crate::logging::LogPath;
 
 // Seed used to generate device identity keys
@@ -91,6 +102,47 @@ pub enum VpnServiceCommand {
         oneshot::Sender<Result<AvailableTicketbooks, AccountError>>,
         (),
     ),
+    ExportTicketbooks(oneshot::Sender<Result<Vec<u8>, AccountError>>, ()),
+    ConfirmTicketbooksExported(oneshot::Sender<Result<(), AccountError>>, ()),
+    ImportTicketbooks(
+        oneshot::Sender<Result<TicketbookImportSummary, AccountError>>,
+        Vec<u8>,
+    ),
+    GetCredentialSpendHistory(
+        oneshot::Sender<Result<Vec<CredentialSpend>, AccountError>>,
+        Option<u32>,
+    ),
+    GetDnsStatus(oneshot::Sender<(DnsStatus, DnsVerification)>, ()),
+    DiagnoseLeaks(oneshot::Sender<Result<LeakReport, LeakTestError>>, ()),
+    GetDnsFilterStats(oneshot::Sender<Option<DnsFilterStats>>, ()),
+    SetTunnelRoutes(
+        oneshot::Sender<Result<(), SetTunnelRoutesError>>,
+        TunnelRoutes,
+    ),
+    GetTunnelRoutes(oneshot::Sender<TunnelRoutes>, ()),
+    AddCustomGateway(
+        oneshot::Sender<Result<(), CustomGatewayError>>,
+        GatewayDescriptor,
+    ),
+    RemoveCustomGateway(oneshot::Sender<Result<(), CustomGatewayError>>, String),
+    ListCustomGateways(oneshot::Sender<Vec<GatewayDescriptor>>, ()),
+    SetFavourite(
+        oneshot::Sender<Result<(), FavouriteError>>,
+        FavouriteGateway,
+    ),
+    RemoveFavourite(oneshot::Sender<Result<(), FavouriteError>>, FavouriteTarget),
+    ListFavourites(oneshot::Sender<Vec<FavouriteGateway>>, ()),
+    SetNetworkRule(oneshot::Sender<Result<(), NetworkRuleError>>, NetworkRule),
+    RemoveNetworkRule(oneshot::Sender<Result<(), NetworkRuleError>>, String),
+    ListNetworkRules(oneshot::Sender<Vec<NetworkRule>>, ()),
+    GetCurrentNetwork(
+        oneshot::Sender<(Option<NetworkIdentity>, Option<NetworkRule>)>,
+        (),
+    ),
+    ProbeGateway(
+        oneshot::Sender<Result<GatewayHealth, ProbeGatewayError>>,
+        String,
+    ),
     GetLogPath(oneshot::Sender<Option<LogPath>>, ()),
     DeleteLogFile(
         oneshot::Sender<Result<(), VpnServiceDeleteLogFileError>>,
@@ -106,6 +158,7 @@ pub struct ConnectArgs {
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConnectOptions {
     pub dns: Option<IpAddr>,
     pub enable_two_hop: bool,
@@ -117,6 +170,32 @@ pub struct ConnectOptions {
     pub min_gateway_mixnet_performance: Option<Percent>,
     pub min_gateway_vpn_performance: Option<Percent>,
     pub user_agent: Option<UserAgent>,
+    #[serde(default, with = "IpFamilyDef")]
+    pub ip_family: IpFamily,
+    #[serde(default, with = "WireguardMtuDef")]
+    pub wireguard_mtu: WireguardMtu,
+    pub precheck: bool,
+}
+
+// nym-vpn-lib doesn't depend on serde, so the tunnel settings are mirrored here
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "IpFamily", rename_all = "snake_case")]
+enum IpFamilyDef {
+    DualStack,
+    Ipv4Only,
+    Ipv6Preferred,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "WireguardMtu", rename_all = "snake_case")]
+enum WireguardMtuDef {
+    Default,
+    Manual {
+        entry: Option<u16>,
+        exit: Option<u16>,
+    },
+    Auto,
 }
 
 #[derive(Clone, Debug)]
@@ -180,6 +259,42 @@ where
     // Event channel for receiving events from state machine
     event_receiver: mpsc::UnboundedReceiver<TunnelEvent>,
 
+    // DNS configuration currently applied by the state machine
+    dns_status: watch::Receiver<DnsStatus>,
+
+    // Domain filter shared with the local resolver of the state machine
+    dns_filter: Option<Arc<DnsFilter>>,
+
+    // Destinations routed through the tunnel, applied on the next connect
+    tunnel_routes: TunnelRoutes,
+
+    // Gateways added to the directory gateways in WireGuard mode
+    custom_gateways: Vec<GatewayDescriptor>,
+
+    // Gateways and countries marked as favourite or blocked
+    favourites: Vec<FavouriteGateway>,
+
+    // Rules connecting or disconnecting when the host joins a matching network
+    network_rules: Vec<NetworkRule>,
+
+    // Changes of the network the host is connected to
+    network_rx: mpsc::UnboundedReceiver<Option<NetworkIdentity>>,
+
+    // The network the host is connected to, if known
+    current_network: Option<NetworkIdentity>,
+
+    // Options of the last connect request, reused when a network rule connects
+    last_connect_options: Option<ConnectOptions>,
+
+    // Pending auto-connect on start, handed to the auto-connect task on the first tunnel state
+    auto_connect_tx: Option<mpsc::UnboundedSender<()>>,
+
+    // Signalled once the host is online and the account is ready to auto-connect
+    auto_connect_rx: mpsc::UnboundedReceiver<()>,
+
+    // Health check of gateways, also used by the state machine before connecting
+    gateway_prober: GatewayProber,
+
     // Service shutdown token.
     shutdown_token: CancellationToken,
 
@@ -196,6 +311,7 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
         network_env: Network,
         user_agent: UserAgent,
         log_path: Option<LogPath>,
+        global_config: GlobalConfigFile,
     ) -> JoinHandle<()> {
         tracing::trace!("Starting VPN service");
         tokio::spawn(async {
@@ -207,6 +323,7 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
                 network_env,
                 user_agent,
                 log_path,
+                global_config,
             )
             .await
             {
@@ -237,12 +354,39 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
         network_env: Network,
         user_agent: UserAgent,
         log_path: Option<LogPath>,
+        global_config: GlobalConfigFile,
     ) -> Result<Self> {
+        let tunnel_routes = TunnelRoutes::from(&global_config.tunnel_routes);
+        let custom_gateways = global_config
+            .custom_gateways
+            .into_iter()
+            .filter(|descriptor| match Gateway::try_from(descriptor.clone()) {
+                Ok(_) => true,
+                Err(err) => {
+                    tracing::warn!("Skipping invalid custom gateway {descriptor}: {err}");
+                    false
+                }
+            })
+            .collect();
+        let favourites = global_config.favourites;
+        let network_rules = global_config.network_rules;
+        let (auto_connect_tx, auto_connect_rx) = mpsc::unbounded_channel();
+        let auto_connect_tx = global_config.auto_connect.then_some(auto_connect_tx);
+        let harbour_master_url = global_config.harbour_master_url.as_deref().and_then(|url| {
+            url.parse()
+                .inspect_err(|err| tracing::error!("Ignoring harbour master url {url}: {err}"))
+                .ok()
+        });
+
         let network_name = network_env.nym_network_details().network_name.clone();
 
         let config_dir = super::config::config_dir().join(&network_name);
         let config_file = config_dir.join(DEFAULT_CONFIG_FILE);
         let data_dir = super::config::data_dir().join(&network_name);
+        let last_connect_options =
+            super::config::read_config_file::<NymVpnServiceConfig>(&config_file)
+                .ok()
+                .map(|config| config.connect_options);
 
         let storage = Arc::new(tokio::sync::Mutex::new(
             nym_vpn_lib::storage::VpnClientOnDiskStorage::new(data_dir.clone()),
@@ -286,13 +430,24 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
             min_gateway_performance: None,
             mix_score_thresholds: None,
             wg_score_thresholds: None,
+            custom_gateways: Vec::new(),
+            harbour_master_url,
         };
+        let dns_filter = global_config.dns_filter.build();
+        let gateway_prober = GatewayProber::new(gateway_config.clone());
         let nym_config = NymConfig {
             config_path: Some(config_dir),
             data_path: Some(data_dir.clone()),
             gateway_config,
             network_env: network_env.clone(),
+            dns_backend: global_config.dns_backend,
+            leak_test_ip_url: global_config.leak_test_ip_url,
+            dns_filter: dns_filter.clone(),
+            dns_routes: global_config.dns_routes,
+            gateway_precheck: Some(Arc::new(gateway_prober.clone())),
         };
+        let (dns_status_tx, dns_status) = watch::channel(DnsStatus::default());
+        let network_rx = network_identity::spawn_network_monitor(shutdown_token.child_token());
 
         let state_machine_handle = TunnelStateMachine::spawn(
             command_receiver,
@@ -300,6 +455,7 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
             nym_config,
             tunnel_settings,
             account_command_tx.clone(),
+            dns_status_tx,
             shutdown_token.child_token(),
         )
         .await
@@ -321,6 +477,18 @@ impl NymVpnService<nym_vpn_lib::storage::VpnClientOnDiskStorage> {
             state_machine_handle,
             command_sender,
             event_receiver,
+            dns_status,
+            dns_filter,
+            tunnel_routes,
+            custom_gateways,
+            favourites,
+            network_rules,
+            network_rx,
+            current_network: None,
+            last_connect_options,
+            auto_connect_tx,
+            auto_connect_rx,
+            gateway_prober,
             shutdown_token,
             statistics_recipient,
         })
@@ -347,10 +515,21 @@ where
                         TunnelEvent::NewState(new_state) => {
                             // Replace value even when there are no receivers.
                             let _ = self.tunnel_state.send_replace(new_state.clone());
+
+                            // The first state tells whether the host starts offline
+                            if let Some(auto_connect_tx) = self.auto_connect_tx.take() {
+                                self.spawn_auto_connect(auto_connect_tx);
+                            }
                         }
                         TunnelEvent::MixnetState(_) => {}
                     }
                 }
+                Some(network) = self.network_rx.recv() => {
+                    self.handle_network_change(network).await;
+                }
+                Some(()) = self.auto_connect_rx.recv() => {
+                    self.handle_auto_connect().await;
+                }
                 _ = self.shutdown_token.cancelled() => {
                     tracing::info!("Received shutdown signal");
                     break;
@@ -495,6 +674,81 @@ where
                 let result = self.handle_get_available_tickets().await;
                 let _ = tx.send(result);
             }
+            VpnServiceCommand::ExportTicketbooks(tx, ()) => {
+                let result = self.handle_export_ticketbooks().await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::ConfirmTicketbooksExported(tx, ()) => {
+                let result = self.handle_confirm_ticketbooks_exported().await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::ImportTicketbooks(tx, bundle) => {
+                let result = self.handle_import_ticketbooks(bundle).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::GetCredentialSpendHistory(tx, limit) => {
+                let result = self.handle_get_credential_spend_history(limit).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::GetDnsStatus(tx, ()) => {
+                let result = self.handle_get_dns_status().await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::DiagnoseLeaks(tx, ()) => {
+                self.handle_diagnose_leaks(tx);
+            }
+            VpnServiceCommand::GetDnsFilterStats(tx, ()) => {
+                let _ = tx.send(self.dns_filter.as_ref().map(|filter| filter.stats()));
+            }
+            VpnServiceCommand::SetTunnelRoutes(tx, tunnel_routes) => {
+                let result = self.handle_set_tunnel_routes(tunnel_routes).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::GetTunnelRoutes(tx, ()) => {
+                let _ = tx.send(self.tunnel_routes.clone());
+            }
+            VpnServiceCommand::AddCustomGateway(tx, descriptor) => {
+                let result = self.handle_add_custom_gateway(descriptor).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::RemoveCustomGateway(tx, identity) => {
+                let result = self.handle_remove_custom_gateway(identity).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::ListCustomGateways(tx, ()) => {
+                let _ = tx.send(self.custom_gateways.clone());
+            }
+            VpnServiceCommand::SetFavourite(tx, favourite) => {
+                let result = self.handle_set_favourite(favourite).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::RemoveFavourite(tx, target) => {
+                let result = self.handle_remove_favourite(target).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::ListFavourites(tx, ()) => {
+                let _ = tx.send(self.favourites.clone());
+            }
+            VpnServiceCommand::SetNetworkRule(tx, rule) => {
+                let result = self.handle_set_network_rule(rule).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::RemoveNetworkRule(tx, name) => {
+                let result = self.handle_remove_network_rule(name).await;
+                let _ = tx.send(result);
+            }
+            VpnServiceCommand::ListNetworkRules(tx, ()) => {
+                let _ = tx.send(self.network_rules.clone());
+            }
+            VpnServiceCommand::GetCurrentNetwork(tx, ()) => {
+                let matching_rule = self.current_network.as_ref().and_then(|network| {
+                    network_rules::first_match(&self.network_rules, network).cloned()
+                });
+                let _ = tx.send((self.current_network.clone(), matching_rule));
+            }
+            VpnServiceCommand::ProbeGateway(tx, identity) => {
+                self.handle_probe_gateway(tx, identity);
+            }
             VpnServiceCommand::GetLogPath(tx, ()) => {
                 let _ = tx.send(self.log_path.clone());
             }
@@ -509,7 +763,13 @@ where
         &self,
         entry: Option<gateway_directory::EntryPoint>,
         exit: Option<gateway_directory::ExitPoint>,
+        options: &ConnectOptions,
     ) -> Result<NymVpnServiceConfig> {
+        // The user agent belongs to the client making the request
+        let connect_options = ConnectOptions {
+            user_agent: None,
+            ..options.clone()
+        };
         // If the config file does not exit, create it
         let config = if self.config_file.exists() {
             let mut read_config: NymVpnServiceConfig =
@@ -523,6 +783,7 @@ where
                     .unwrap_or_default();
             read_config.entry_point = entry.unwrap_or(read_config.entry_point);
             read_config.exit_point = exit.unwrap_or(read_config.exit_point);
+            read_config.connect_options = connect_options;
             super::config::write_config_file(&self.config_file, &read_config)
                 .map_err(Error::ConfigSetup)?;
             read_config
@@ -530,6 +791,7 @@ where
             let config = NymVpnServiceConfig {
                 entry_point: entry.unwrap_or(EntryPoint::Random),
                 exit_point: exit.unwrap_or(ExitPoint::Random),
+                connect_options,
             };
             super::config::create_config_file(&self.config_file, config)
                 .map_err(Error::ConfigSetup)?
@@ -546,6 +808,9 @@ where
             exit,
             mut options,
         } = connect_args;
+        // Persisted before the feature flags apply, for the next automatic connection
+        let requested_options = options.clone();
+        self.last_connect_options = Some(options.clone());
 
         // Get feature flag
         let enable_credentials_mode = self
@@ -573,7 +838,7 @@ where
         tracing::debug!("Using options: {:?}", options);
 
         let config = self
-            .try_setup_config(entry, exit)
+            .try_setup_config(entry, exit, &requested_options)
             .map_err(|err| VpnServiceConnectError::Internal(err.to_string()))?;
         tracing::info!("Using config: {}", config);
 
@@ -624,12 +889,17 @@ where
                 } else {
                     WireguardMultihopMode::TunTun
                 },
+                mtu: options.wireguard_mtu,
             },
             gateway_performance_options: gateway_options,
             mixnet_client_config: Some(mixnet_client_config),
             entry_point: Box::new(config.entry_point),
             exit_point: Box::new(config.exit_point),
+            custom_gateways: self.custom_gateways.clone(),
+            precheck_gateways: options.precheck,
             dns,
+            tunnel_routes: self.tunnel_routes.clone(),
+            ip_family: options.ip_family,
             user_agent: options.user_agent,
         };
 
@@ -707,6 +977,310 @@ where
         Ok(())
     }
 
+    async fn handle_set_tunnel_routes(
+        &mut self,
+        tunnel_routes: TunnelRoutes,
+    ) -> Result<(), SetTunnelRoutesError> {
+        let mut global_config = GlobalConfigFile::read_from_file().map_err(|source| {
+            SetTunnelRoutesError::ReadConfig {
+                source: source.into(),
+            }
+        })?;
+
+        global_config.tunnel_routes = TunnelRoutesConfig::from(&tunnel_routes);
+
+        global_config
+            .write_to_file()
+            .map_err(|source| SetTunnelRoutesError::WriteConfig {
+                source: source.into(),
+            })?;
+
+        tracing::info!(
+            "Tunnel routes updated to: {} (applied on the next connect)",
+            tunnel_routes
+        );
+        self.tunnel_routes = tunnel_routes;
+        Ok(())
+    }
+
+    async fn handle_add_custom_gateway(
+        &mut self,
+        descriptor: GatewayDescriptor,
+    ) -> Result<(), CustomGatewayError> {
+        Gateway::try_from(descriptor.clone()).map_err(CustomGatewayError::InvalidGateway)?;
+
+        let mut custom_gateways = self.custom_gateways.clone();
+        custom_gateways.retain(|gateway| gateway.identity != descriptor.identity);
+        custom_gateways.push(descriptor.clone());
+        self.write_custom_gateways(custom_gateways)?;
+
+        tracing::info!("Custom gateway added: {descriptor} (applied on the next connect)");
+        Ok(())
+    }
+
+    async fn handle_remove_custom_gateway(
+        &mut self,
+        identity: String,
+    ) -> Result<(), CustomGatewayError> {
+        let mut custom_gateways = self.custom_gateways.clone();
+        custom_gateways.retain(|gateway| gateway.identity != identity);
+        if custom_gateways.len() == self.custom_gateways.len() {
+            return Err(CustomGatewayError::NotFound(identity));
+        }
+        self.write_custom_gateways(custom_gateways)?;
+
+        tracing::info!("Custom gateway removed: {identity} (applied on the next connect)");
+        Ok(())
+    }
+
+    fn write_custom_gateways(
+        &mut self,
+        custom_gateways: Vec<GatewayDescriptor>,
+    ) -> Result<(), CustomGatewayError> {
+        let mut global_config = GlobalConfigFile::read_from_file().map_err(|source| {
+            CustomGatewayError::ReadConfig {
+                source: source.into(),
+            }
+        })?;
+
+        global_config.custom_gateways = custom_gateways.clone();
+
+        global_config
+            .write_to_file()
+            .map_err(|source| CustomGatewayError::WriteConfig {
+                source: source.into(),
+            })?;
+
+        self.custom_gateways = custom_gateways;
+        Ok(())
+    }
+
+    async fn handle_set_favourite(
+        &mut self,
+        favourite: FavouriteGateway,
+    ) -> Result<(), FavouriteError> {
+        let mut favourites = self.favourites.clone();
+        favourites.retain(|existing| existing.target != favourite.target);
+        favourites.push(favourite.clone());
+        self.write_favourites(favourites)?;
+
+        tracing::info!("Favourite set: {favourite}");
+        Ok(())
+    }
+
+    async fn handle_remove_favourite(
+        &mut self,
+        target: FavouriteTarget,
+    ) -> Result<(), FavouriteError> {
+        let mut favourites = self.favourites.clone();
+        favourites.retain(|existing| existing.target != target);
+        if favourites.len() == self.favourites.len() {
+            return Err(FavouriteError::NotFound(target));
+        }
+        self.write_favourites(favourites)?;
+
+        tracing::info!("Favourite removed: {target}");
+        Ok(())
+    }
+
+    fn write_favourites(
+        &mut self,
+        favourites: Vec<FavouriteGateway>,
+    ) -> Result<(), FavouriteError> {
+        let mut global_config =
+            GlobalConfigFile::read_from_file().map_err(|source| FavouriteError::ReadConfig {
+                source: source.into(),
+            })?;
+
+        global_config.favourites = favourites.clone();
+
+        global_config
+            .write_to_file()
+            .map_err(|source| FavouriteError::WriteConfig {
+                source: source.into(),
+            })?;
+
+        self.favourites = favourites;
+        Ok(())
+    }
+
+    async fn handle_set_network_rule(&mut self, rule: NetworkRule) -> Result<(), NetworkRuleError> {
+        let mut network_rules = self.network_rules.clone();
+        // Keep the position of a replaced rule, since the first matching rule applies.
+        match network_rules
+            .iter_mut()
+            .find(|existing| existing.name == rule.name)
+        {
+            Some(existing) => *existing = rule.clone(),
+            None => network_rules.push(rule.clone()),
+        }
+        self.write_network_rules(network_rules)?;
+
+        tracing::info!("Network rule set: {rule}");
+        Ok(())
+    }
+
+    async fn handle_remove_network_rule(&mut self, name: String) -> Result<(), NetworkRuleError> {
+        let mut network_rules = self.network_rules.clone();
+        network_rules.retain(|existing| existing.name != name);
+        if network_rules.len() == self.network_rules.len() {
+            return Err(NetworkRuleError::NotFound(name));
+        }
+        self.write_network_rules(network_rules)?;
+
+        tracing::info!("Network rule removed: {name}");
+        Ok(())
+    }
+
+    fn write_network_rules(
+        &mut self,
+        network_rules: Vec<NetworkRule>,
+    ) -> Result<(), NetworkRuleError> {
+        let mut global_config =
+            GlobalConfigFile::read_from_file().map_err(|source| NetworkRuleError::ReadConfig {
+                source: source.into(),
+            })?;
+
+        global_config.network_rules = network_rules.clone();
+
+        global_config
+            .write_to_file()
+            .map_err(|source| NetworkRuleError::WriteConfig {
+                source: source.into(),
+            })?;
+
+        self.network_rules = network_rules;
+        Ok(())
+    }
+
+    fn spawn_auto_connect(&self, ready_tx: mpsc::UnboundedSender<()>) {
+        let options = self.last_connect_options.clone().unwrap_or_default();
+        let credential_mode = options.enable_credentials_mode
+            || self
+                .network_env
+                .get_feature_flag_credential_mode()
+                .unwrap_or(false);
+        auto_connect::spawn(
+            ready_tx,
+            self.tunnel_state.subscribe(),
+            self.account_command_tx.clone(),
+            credential_mode,
+            self.shutdown_token.child_token(),
+        );
+    }
+
+    async fn handle_auto_connect(&mut self) {
+        // Connecting or disconnecting by hand in the meantime takes precedence
+        if !matches!(*self.tunnel_state.borrow(), TunnelState::Disconnected) {
+            tracing::info!("Not connecting on start, the tunnel state changed");
+            return;
+        }
+        if let Some(rule) = self
+            .current_network
+            .as_ref()
+            .and_then(|network| network_rules::first_match(&self.network_rules, network))
+            .filter(|rule| rule.action == NetworkRuleAction::Disconnect)
+        {
+            tracing::info!("Not connecting on start, network rule {}", rule.name);
+            return;
+        }
+
+        tracing::info!("Connecting on start");
+        let options = self.last_connect_options.clone().unwrap_or_default();
+        let result = self
+            .handle_connect(ConnectArgs {
+                entry: None,
+                exit: None,
+                options,
+            })
+            .await;
+        if let Err(err) = result {
+            tracing::error!("Failed to connect on start: {err}");
+        }
+    }
+
+    // Rules only apply when joining a network, so connecting or disconnecting by hand sticks
+    // until the network changes.
+    async fn handle_network_change(&mut self, network: Option<NetworkIdentity>) {
+        let previous = std::mem::replace(&mut self.current_network, network);
+        let Some(network) = &self.current_network else {
+            tracing::info!("Not connected to any network");
+            return;
+        };
+
+        let rule = network_rules::first_match(&self.network_rules, network).cloned();
+        // New addresses on the same network, e.g. after a DHCP renewal, only matter when they
+        // change the matching rule through its subnet.
+        if let Some(previous) = previous.filter(|previous| previous.is_same_network(network)) {
+            let previous_rule = network_rules::first_match(&self.network_rules, &previous);
+            if previous_rule.map(|rule| &rule.name) == rule.as_ref().map(|rule| &rule.name) {
+                tracing::debug!("Addresses changed on network: {network}");
+                return;
+            }
+        }
+        tracing::info!("Connected to network: {network}");
+
+        let Some(rule) = rule else {
+            return;
+        };
+        let tunnel_state = self.tunnel_state.borrow().clone();
+        let result = match rule.action {
+            NetworkRuleAction::Connect => match tunnel_state {
+                TunnelState::Disconnected
+                | TunnelState::Error(_)
+                | TunnelState::Offline { reconnect: false } => {
+                    tracing::info!("Connecting, network rule {}", rule.name);
+                    let options = self.last_connect_options.clone().unwrap_or_default();
+                    self.handle_connect(ConnectArgs {
+                        entry: None,
+                        exit: None,
+                        options,
+                    })
+                    .await
+                    .map_err(|err| err.to_string())
+                }
+                _ => Ok(()),
+            },
+            NetworkRuleAction::Disconnect => match tunnel_state {
+                TunnelState::Disconnected
+                | TunnelState::Disconnecting { .. }
+                | TunnelState::Offline { reconnect: false } => Ok(()),
+                _ => {
+                    tracing::info!("Disconnecting, network rule {}", rule.name);
+                    self.handle_disconnect()
+                        .await
+                        .map_err(|err| err.to_string())
+                }
+            },
+        };
+        if let Err(err) = result {
+            tracing::error!("Failed to apply network rule: {err}");
+        }
+    }
+
+    // Probing takes a while, so it runs in its own task instead of blocking the service loop.
+    fn handle_probe_gateway(
+        &self,
+        tx: oneshot::Sender<Result<GatewayHealth, ProbeGatewayError>>,
+        identity: String,
+    ) {
+        let gateway_prober = self.gateway_prober.clone();
+        let custom_gateways = self
+            .custom_gateways
+            .iter()
+            .cloned()
+            .filter_map(|descriptor| Gateway::try_from(descriptor).ok())
+            .collect();
+        let user_agent = self.user_agent.clone();
+
+        tokio::spawn(async move {
+            let result = gateway_prober
+                .probe_gateway(&identity, custom_gateways, user_agent)
+                .await;
+            let _ = tx.send(result);
+        });
+    }
+
     async fn handle_get_system_messages(&self) -> SystemMessages {
         self.network_env.nym_vpn_network.system_messages.clone()
     }
@@ -886,6 +1460,71 @@ where
             .map_err(|source| AccountError::AccountCommandError { source })
     }
 
+    async fn handle_export_ticketbooks(&self) -> Result<Vec<u8>, AccountError> {
+        // Reading the ticketbooks briefly withdraws them from the credential storage, so don't
+        // do it under an active tunnel
+        if *self.tunnel_state.borrow() != TunnelState::Disconnected {
+            return Err(AccountError::IsConnected);
+        }
+
+        self.account_command_tx
+            .export_ticketbooks()
+            .await
+            .map_err(|source| AccountError::AccountCommandError { source })
+    }
+
+    async fn handle_confirm_ticketbooks_exported(&self) -> Result<(), AccountError> {
+        // The exported tickets are no longer available locally after this, so don't pull them
+        // out from under an active tunnel
+        if *self.tunnel_state.borrow() != TunnelState::Disconnected {
+            return Err(AccountError::IsConnected);
+        }
+
+        self.account_command_tx
+            .confirm_ticketbooks_exported()
+            .await
+            .map_err(|source| AccountError::AccountCommandError { source })
+    }
+
+    async fn handle_import_ticketbooks(
+        &self,
+        bundle: Vec<u8>,
+    ) -> Result<TicketbookImportSummary, AccountError> {
+        self.account_command_tx
+            .import_ticketbooks(bundle)
+            .await
+            .map_err(|source| AccountError::AccountCommandError { source })
+    }
+
+    async fn handle_get_credential_spend_history(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<CredentialSpend>, AccountError> {
+        self.account_command_tx
+            .get_credential_spend_history(limit)
+            .await
+            .map_err(|source| AccountError::AccountCommandError { source })
+    }
+
+    async fn handle_get_dns_status(&self) -> (DnsStatus, DnsVerification) {
+        let status = self.dns_status.borrow().clone();
+        let verification = nym_vpn_lib::dns::verify(&status).await;
+        (status, verification)
+    }
+
+    // The leak test takes a few seconds, so the state machine replies directly to the caller
+    // instead of holding up the service.
+    fn handle_diagnose_leaks(&self, tx: oneshot::Sender<Result<LeakReport, LeakTestError>>) {
+        if let Err(mpsc::error::SendError(command)) =
+            self.command_sender.send(TunnelCommand::DiagnoseLeaks(tx))
+        {
+            tracing::error!("Failed to send command to diagnose leaks");
+            if let TunnelCommand::DiagnoseLeaks(tx) = command {
+                let _ = tx.send(Err(LeakTestError::StateMachineDown));
+            }
+        }
+    }
+
     async fn handle_delete_log_file(&self) -> Result<(), VpnServiceDeleteLogFileError> {
         match self.file_logging_event_tx.try_send(()) {
             Ok(_) => {}
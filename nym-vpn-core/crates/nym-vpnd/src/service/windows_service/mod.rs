@@ -164,17 +164,18 @@ async fn run_service_inner() -> anyhow::Result<()> {
     let network_env_result = tokio::task::spawn(async move {
         let global_config_file =
             crate::setup_global_config(cloned_network_config.network.as_deref())?;
-        crate::environment::setup_environment(
+        let network_env = crate::environment::setup_environment(
             &global_config_file,
             cloned_network_config.config_env_file.as_deref(),
         )
-        .await
+        .await?;
+        anyhow::Ok((network_env, global_config_file))
     })
     .await;
-    let network_env = match network_env_result {
-        Ok(Ok(network_env)) => {
+    let (network_env, global_config_file) = match network_env_result {
+        Ok(Ok((network_env, global_config_file))) => {
             network_env.export_to_env();
-            network_env
+            (network_env, global_config_file)
         }
         Ok(Err(err)) => {
             persistent_status.set_stopped(ServiceExitCode::ServiceSpecific(
@@ -216,6 +217,7 @@ async fn run_service_inner() -> anyhow::Result<()> {
     let (command_handle, vpn_command_rx) = command_interface::start_command_interface(
         tunnel_event_rx,
         network_env.clone(),
+        global_config_file.command_interface_options(),
         shutdown_token.child_token(),
     );
 
@@ -230,6 +232,7 @@ async fn run_service_inner() -> anyhow::Result<()> {
         network_env,
         user_agent,
         log_path,
+        global_config_file,
     );
 
     tracing::info!("Service has started");
@@ -9,6 +9,9 @@ pub enum Error {
     #[error("received invalid response from gateway authenticator")]
     InvalidGatewayAuthResponse,
 
+    #[error("received invalid response from gateway authenticator after sending a credential")]
+    InvalidCredentialResponse,
+
     #[error("unknown authenticator version number")]
     UnsupportedAuthenticatorVersion,
 
@@ -35,6 +38,26 @@ pub enum Error {
     },
 }
 
+impl Error {
+    // The request carried a credential that the gateway might have consumed, even though we
+    // didn't get a successful response back.
+    pub fn is_wasteful(&self) -> bool {
+        matches!(
+            self,
+            Error::NoRetry { .. } | Error::InvalidCredentialResponse
+        )
+    }
+
+    // The ticket was sent to the gateway, which might have accepted it. Any other error happened
+    // before a ticket left the client.
+    pub fn is_ticket_spent(&self) -> bool {
+        matches!(
+            self,
+            Error::NoRetry { .. } | Error::InvalidCredentialResponse
+        )
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ErrorMessage {
     #[error("out of bandwidth for gateway: `{gateway_id}`")]
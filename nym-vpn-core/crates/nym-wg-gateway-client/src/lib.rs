@@ -181,7 +181,7 @@ impl WgGatewayLightClient {
             AuthenticatorResponse::TopUpBandwidth(top_up_bandwidth_response) => {
                 top_up_bandwidth_response.available_bandwidth()
             }
-            _ => return Err(Error::InvalidGatewayAuthResponse),
+            _ => return Err(Error::InvalidCredentialResponse),
         };
 
         Ok(remaining_bandwidth)
@@ -460,7 +460,7 @@ impl WgGatewayClient {
 
                 let response = self.light_client().send(finalized_message).await?;
                 let AuthenticatorResponse::Registered(registered_response) = response else {
-                    return Err(Error::InvalidGatewayAuthResponse);
+                    return Err(Error::InvalidCredentialResponse);
                 };
                 registered_response
             }